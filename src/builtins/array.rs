@@ -1,8 +1,16 @@
+use builtins::iterator::create_iter_result_object;
 use gc;
 use vm::{
     error::RuntimeError,
     frame::Frame,
-    jsvalue::{object::Property, prototype::ObjectPrototypes, value::Value},
+    jsvalue::{
+        array::{join_element_to_string, MAX_DENSE_LENGTH},
+        iterator::ArrayIterationKind,
+        object::{ObjectKind2, Property},
+        prototype::ObjectPrototypes,
+        string::JSString,
+        value::Value,
+    },
     vm::{VMResult, VM2},
 };
 
@@ -16,64 +24,1072 @@ pub fn array(
         "Array".to_string(),
         array_constructor,
     );
+
+    // Array.isArray
+    ary.set_property_by_string_key("isArray".to_string(), {
+        Value::builtin_function(
+            memory_allocator,
+            object_prototypes,
+            "isArray".to_string(),
+            array_is_array,
+        )
+    });
+    // Array.from
+    ary.set_property_by_string_key("from".to_string(), {
+        Value::builtin_function(
+            memory_allocator,
+            object_prototypes,
+            "from".to_string(),
+            array_from,
+        )
+    });
+    // Array.of
+    ary.set_property_by_string_key("of".to_string(), {
+        Value::builtin_function(
+            memory_allocator,
+            object_prototypes,
+            "of".to_string(),
+            array_of,
+        )
+    });
+
     ary.set_property_by_string_key("prototype".to_string(), object_prototypes.array);
     ary.get_property_by_str_key("prototype")
         .set_constructor(ary);
     ary
 }
 
-pub fn array_constructor(vm: &mut VM2, _args: &[Value], _cur_frame: &Frame) -> VMResult {
-    vm.stack.push(Value::undefined().into());
+/// https://tc39.github.io/ecma262/#sec-array-constructor
+pub fn array_constructor(vm: &mut VM2, args: &[Value], _cur_frame: &Frame) -> VMResult {
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, vec![]);
+
+    match args {
+        [Value::Number(len)] => match Value::Number(*len).is_array_index() {
+            Some(len) if len > MAX_DENSE_LENGTH => {
+                return Err(RuntimeError::Range(format!(
+                    "Array length {} is too large",
+                    len
+                )))
+            }
+            Some(len) => ary.as_array_mut().set_length(len),
+            None => return Err(RuntimeError::Range("Invalid array length".to_string())),
+        },
+        args => {
+            ary.as_array_mut().elems = args
+                .iter()
+                .map(|arg| Property::new_data_simple(*arg))
+                .collect();
+        }
+    }
+
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.isarray
+pub fn array_is_array(vm: &mut VM2, args: &[Value], _cur_frame: &Frame) -> VMResult {
+    let arg = args.get(0).map(|arg| *arg).unwrap_or(Value::undefined());
+    vm.stack.push(Value::bool(arg.is_array_object()).into());
     Ok(())
 }
 
+/// https://tc39.github.io/ecma262/#sec-array.from
+pub fn array_from(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let items = args.get(0).map(|arg| *arg).unwrap_or(Value::undefined());
+    let map_fn = args.get(1).map(|arg| *arg).unwrap_or(Value::undefined());
+    let this_arg = args.get(2).map(|arg| *arg).unwrap_or(Value::undefined());
+
+    if !map_fn.is_undefined() && !map_fn.is_function_object() {
+        return Err(RuntimeError::Type(format!(
+            "{} is not a function",
+            map_fn.debug_string(true)
+        )));
+    }
+
+    if items.is_undefined() || items.is_null() {
+        return Err(RuntimeError::Type(format!(
+            "{} is not iterable",
+            items.debug_string(true)
+        )));
+    }
+
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, vec![]);
+    vm.memory_allocator.lock(ary);
+    let result = array_from_items(vm, ary, items, map_fn, this_arg, cur_frame);
+    vm.memory_allocator.unlock(ary);
+    result?;
+
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// Append the values of the iterable or array-like items to the array, mapped by map_fn if it is
+/// not undefined.
+fn array_from_items(
+    vm: &mut VM2,
+    ary: Value,
+    items: Value,
+    map_fn: Value,
+    this_arg: Value,
+    cur_frame: &Frame,
+) -> VMResult {
+    let sym_iterator = vm.global_symbol_registry.well_known.iterator;
+    let using_iterator = vm.get_property(items, sym_iterator, cur_frame)?;
+
+    if !using_iterator.is_undefined() && !using_iterator.is_null() {
+        let iterator = vm.get_iterator(items, cur_frame)?;
+        let mut k = 0;
+        while let Some(val) = vm.iterator_step(iterator, cur_frame)? {
+            let val = if map_fn.is_undefined() {
+                val
+            } else {
+                let args_for_map_fn = [val, Value::Number(k as f64)];
+                if let Err(err) = vm.call_function(map_fn, &args_for_map_fn, this_arg, cur_frame) {
+                    vm.iterator_close(iterator, cur_frame)?;
+                    return Err(err);
+                }
+                vm.stack.pop().unwrap().into(): Value
+            };
+            push_element(vm, ary, val);
+            k += 1;
+        }
+        return Ok(());
+    }
+
+    let len = length_of_array_like(vm, items, cur_frame)?;
+    for k in 0..len {
+        let val = get_element(vm, items, k, cur_frame)?;
+        let val = if map_fn.is_undefined() {
+            val
+        } else {
+            vm.call_function(map_fn, &[val, Value::Number(k as f64)], this_arg, cur_frame)?;
+            vm.stack.pop().unwrap().into(): Value
+        };
+        push_element(vm, ary, val);
+    }
+
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.of
+pub fn array_of(vm: &mut VM2, args: &[Value], _cur_frame: &Frame) -> VMResult {
+    let elems = args
+        .iter()
+        .map(|arg| Property::new_data_simple(*arg))
+        .collect();
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, elems);
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.push
 pub fn array_prototype_push(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
-    if !cur_frame.this.is_array_object() {
-        return Err(RuntimeError::Unknown);
+    let obj = this_object(cur_frame, "push")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+
+    for (i, arg) in args.iter().enumerate() {
+        set_element(vm, obj, len + i, *arg, cur_frame)?;
     }
 
-    let ary_info = cur_frame.this.as_array_mut();
+    let new_len = len + args.len();
+    set_length(vm, obj, new_len, cur_frame)?;
+    vm.stack.push(Value::Number(new_len as f64).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.pop
+pub fn array_prototype_pop(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "pop")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+
+    if len == 0 {
+        set_length(vm, obj, 0, cur_frame)?;
+        vm.stack.push(Value::undefined().into());
+        return Ok(());
+    }
+
+    let elem = get_element(vm, obj, len - 1, cur_frame)?;
+    delete_element(vm, obj, len - 1)?;
+    set_length(vm, obj, len - 1, cur_frame)?;
+    vm.stack.push(elem.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.shift
+pub fn array_prototype_shift(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "shift")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+
+    if len == 0 {
+        set_length(vm, obj, 0, cur_frame)?;
+        vm.stack.push(Value::undefined().into());
+        return Ok(());
+    }
+
+    let first = get_element(vm, obj, 0, cur_frame)?;
+    for k in 1..len {
+        move_element(vm, obj, k, k - 1, cur_frame)?;
+    }
+    delete_element(vm, obj, len - 1)?;
+    set_length(vm, obj, len - 1, cur_frame)?;
+    vm.stack.push(first.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.unshift
+pub fn array_prototype_unshift(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "unshift")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
 
-    for arg in args {
-        ary_info.elems.push(Property::new_data_simple(*arg));
+    if args.len() > 0 {
+        for k in (0..len).rev() {
+            move_element(vm, obj, k, k + args.len(), cur_frame)?;
+        }
+        for (j, arg) in args.iter().enumerate() {
+            set_element(vm, obj, j, *arg, cur_frame)?;
+        }
     }
 
-    vm.stack
-        .push(Value::Number(ary_info.get_length() as f64).into());
+    let new_len = len + args.len();
+    set_length(vm, obj, new_len, cur_frame)?;
+    vm.stack.push(Value::Number(new_len as f64).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.slice
+pub fn array_prototype_slice(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "slice")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let start = relative_index(vm, args.get(0), len, 0);
+    let end = relative_index(vm, args.get(1), len, len);
+
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, vec![]);
+    vm.memory_allocator.lock(ary);
+    let result = copy_elements(vm, obj, ary, start, end, cur_frame);
+    vm.memory_allocator.unlock(ary);
+    result?;
 
+    vm.stack.push(ary.into());
     Ok(())
 }
 
+/// https://tc39.github.io/ecma262/#sec-array.prototype.splice
+pub fn array_prototype_splice(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "splice")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let start = relative_index(vm, args.get(0), len, 0);
+    let delete_count = match args.len() {
+        0 => 0,
+        1 => len - start,
+        _ => {
+            let count = args[1].to_integer(&mut vm.memory_allocator);
+            count.max(0.0).min((len - start) as f64) as usize
+        }
+    };
+    let items = if args.len() > 2 { &args[2..] } else { &[] };
+
+    let removed = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, vec![]);
+    vm.memory_allocator.lock(removed);
+    let result = copy_elements(vm, obj, removed, start, start + delete_count, cur_frame);
+    vm.memory_allocator.unlock(removed);
+    result?;
+
+    if items.len() < delete_count {
+        for k in start..len - delete_count {
+            move_element(vm, obj, k + delete_count, k + items.len(), cur_frame)?;
+        }
+        for k in (len - delete_count + items.len()..len).rev() {
+            delete_element(vm, obj, k)?;
+        }
+    } else if items.len() > delete_count {
+        for k in (start..len - delete_count).rev() {
+            move_element(vm, obj, k + delete_count, k + items.len(), cur_frame)?;
+        }
+    }
+
+    for (i, item) in items.iter().enumerate() {
+        set_element(vm, obj, start + i, *item, cur_frame)?;
+    }
+    set_length(vm, obj, len - delete_count + items.len(), cur_frame)?;
+
+    vm.stack.push(removed.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.concat
+/// Primitive receivers are concatenated as they are since wrapper objects are not supported.
+pub fn array_prototype_concat(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = cur_frame.this;
+    if obj.is_undefined() || obj.is_null() {
+        return Err(RuntimeError::Type(
+            "Array.prototype.concat called on null or undefined".to_string(),
+        ));
+    }
+
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, vec![]);
+    vm.memory_allocator.lock(ary);
+    let result = (|| {
+        for item in ::std::iter::once(&obj).chain(args.iter()) {
+            if is_concat_spreadable(vm, *item, cur_frame)? {
+                let len = length_of_array_like(vm, *item, cur_frame)?;
+                if ary.as_array_mut().get_length() + len > MAX_DENSE_LENGTH {
+                    return Err(RuntimeError::Range("Invalid array length".to_string()));
+                }
+                copy_elements(vm, *item, ary, 0, len, cur_frame)?;
+            } else {
+                push_element(vm, ary, *item);
+            }
+        }
+        Ok(())
+    })();
+    vm.memory_allocator.unlock(ary);
+    result?;
+
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.join
+pub fn array_prototype_join(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "join")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let separator = match args.get(0) {
+        Some(sep) if !sep.is_undefined() => sep.to_js_string(),
+        _ => JSString::from(","),
+    };
+
+    let mut joined = vec![];
+    for k in 0..len {
+        if k > 0 {
+            joined.extend_from_slice(&separator.units());
+        }
+        let elem = get_element(vm, obj, k, cur_frame)?;
+        joined.extend_from_slice(&join_element_to_string(elem, &mut vec![obj]).units());
+    }
+
+    let joined = Value::js_string(&mut vm.memory_allocator, JSString::new(joined));
+    vm.stack.push(joined.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.tostring
+pub fn array_prototype_to_string(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "toString")?;
+    let join_key = Value::string(&mut vm.memory_allocator, "join".to_string());
+    let join = vm.get_property(obj, join_key, cur_frame)?;
+
+    if join.is_function_object() {
+        return vm.call_function(join, &[], obj, cur_frame);
+    }
+
+    let tag = if obj.is_array_object() {
+        "[object Array]"
+    } else {
+        "[object Object]"
+    };
+    let tag = Value::string(&mut vm.memory_allocator, tag.to_string());
+    vm.stack.push(tag.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.reverse
+pub fn array_prototype_reverse(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "reverse")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+
+    for lower in 0..len / 2 {
+        let upper = len - lower - 1;
+        let lower_exists = has_element(vm, obj, lower);
+        let lower_val = if lower_exists {
+            get_element(vm, obj, lower, cur_frame)?
+        } else {
+            Value::undefined()
+        };
+        let upper_exists = has_element(vm, obj, upper);
+        let upper_val = if upper_exists {
+            get_element(vm, obj, upper, cur_frame)?
+        } else {
+            Value::undefined()
+        };
+
+        if upper_exists {
+            set_element(vm, obj, lower, upper_val, cur_frame)?;
+        } else if lower_exists {
+            delete_element(vm, obj, lower)?;
+        }
+
+        if lower_exists {
+            set_element(vm, obj, upper, lower_val, cur_frame)?;
+        } else if upper_exists {
+            delete_element(vm, obj, upper)?;
+        }
+    }
+
+    vm.stack.push(obj.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.indexof
+pub fn array_prototype_index_of(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "indexOf")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let search = args.get(0).map(|arg| *arg).unwrap_or(Value::undefined());
+    let start = relative_index(vm, args.get(1), len, 0);
+
+    for k in start..len {
+        if has_element(vm, obj, k) {
+            let elem = get_element(vm, obj, k, cur_frame)?;
+            if elem.strict_eq(search).into_bool() {
+                vm.stack.push(Value::Number(k as f64).into());
+                return Ok(());
+            }
+        }
+    }
+
+    vm.stack.push(Value::Number(-1.0).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.lastindexof
+pub fn array_prototype_last_index_of(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "lastIndexOf")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let search = args.get(0).map(|arg| *arg).unwrap_or(Value::undefined());
+    // The index to start searching backwards from, plus one.
+    let end = match args.get(1) {
+        Some(from_index) => {
+            let n = from_index.to_integer(&mut vm.memory_allocator);
+            if n >= 0.0 {
+                (n + 1.0).min(len as f64) as usize
+            } else {
+                (len as f64 + n + 1.0).max(0.0) as usize
+            }
+        }
+        None => len,
+    };
+
+    for k in (0..end).rev() {
+        if has_element(vm, obj, k) {
+            let elem = get_element(vm, obj, k, cur_frame)?;
+            if elem.strict_eq(search).into_bool() {
+                vm.stack.push(Value::Number(k as f64).into());
+                return Ok(());
+            }
+        }
+    }
+
+    vm.stack.push(Value::Number(-1.0).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.includes
+pub fn array_prototype_includes(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "includes")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let search = args.get(0).map(|arg| *arg).unwrap_or(Value::undefined());
+    let start = relative_index(vm, args.get(1), len, 0);
+
+    for k in start..len {
+        let elem = get_element(vm, obj, k, cur_frame)?;
+        if same_value_zero(elem, search) {
+            vm.stack.push(Value::bool(true).into());
+            return Ok(());
+        }
+    }
+
+    vm.stack.push(Value::bool(false).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.foreach
+pub fn array_prototype_for_each(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "forEach")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let (callback, this_arg) = callback_args(args)?;
+
+    for k in 0..len {
+        if has_element(vm, obj, k) {
+            let elem = get_element(vm, obj, k, cur_frame)?;
+            call_callback(vm, callback, this_arg, elem, k, obj, cur_frame)?;
+        }
+    }
+
+    vm.stack.push(Value::undefined().into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.map
 pub fn array_prototype_map(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
-    if !cur_frame.this.is_array_object() {
-        return Err(RuntimeError::Unknown);
+    let obj = this_object(cur_frame, "map")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let (callback, this_arg) = callback_args(args)?;
+
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, vec![]);
+    ary.as_array_mut().set_length(len);
+    vm.memory_allocator.lock(ary);
+    let result = (|| {
+        for k in 0..len {
+            if has_element(vm, obj, k) {
+                let elem = get_element(vm, obj, k, cur_frame)?;
+                let mapped = call_callback(vm, callback, this_arg, elem, k, obj, cur_frame)?;
+                ary.as_array_mut().set_element(k, mapped);
+                vm.memory_allocator.gray2(mapped);
+            }
+        }
+        Ok(())
+    })();
+    vm.memory_allocator.unlock(ary);
+    result?;
+
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.filter
+pub fn array_prototype_filter(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "filter")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let (callback, this_arg) = callback_args(args)?;
+
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, vec![]);
+    vm.memory_allocator.lock(ary);
+    let result = (|| {
+        for k in 0..len {
+            if has_element(vm, obj, k) {
+                let elem = get_element(vm, obj, k, cur_frame)?;
+                let selected = call_callback(vm, callback, this_arg, elem, k, obj, cur_frame)?;
+                if selected.to_boolean() {
+                    push_element(vm, ary, elem);
+                }
+            }
+        }
+        Ok(())
+    })();
+    vm.memory_allocator.unlock(ary);
+    result?;
+
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.some
+pub fn array_prototype_some(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "some")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let (callback, this_arg) = callback_args(args)?;
+
+    for k in 0..len {
+        if has_element(vm, obj, k) {
+            let elem = get_element(vm, obj, k, cur_frame)?;
+            if call_callback(vm, callback, this_arg, elem, k, obj, cur_frame)?.to_boolean() {
+                vm.stack.push(Value::bool(true).into());
+                return Ok(());
+            }
+        }
+    }
+
+    vm.stack.push(Value::bool(false).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.every
+pub fn array_prototype_every(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "every")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let (callback, this_arg) = callback_args(args)?;
+
+    for k in 0..len {
+        if has_element(vm, obj, k) {
+            let elem = get_element(vm, obj, k, cur_frame)?;
+            if !call_callback(vm, callback, this_arg, elem, k, obj, cur_frame)?.to_boolean() {
+                vm.stack.push(Value::bool(false).into());
+                return Ok(());
+            }
+        }
     }
 
-    let ary_info = cur_frame.this.as_array_mut();
+    vm.stack.push(Value::bool(true).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.find
+pub fn array_prototype_find(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "find")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let (callback, this_arg) = callback_args(args)?;
+
+    for k in 0..len {
+        // Holes are visited as undefined
+        let elem = get_element(vm, obj, k, cur_frame)?;
+        if call_callback(vm, callback, this_arg, elem, k, obj, cur_frame)?.to_boolean() {
+            vm.stack.push(elem.into());
+            return Ok(());
+        }
+    }
 
-    let callback = args[0];
-    let mut args_for_callback = [
-        /* value = */ Value::undefined(),
-        /* nth element = */ Value::Number(0.0),
-        /* array itself = */ cur_frame.this,
-    ];
+    vm.stack.push(Value::undefined().into());
+    Ok(())
+}
 
-    let mut new_ary = vec![];
+/// https://tc39.github.io/ecma262/#sec-array.prototype.findindex
+pub fn array_prototype_find_index(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "findIndex")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let (callback, this_arg) = callback_args(args)?;
 
-    for i in 0..ary_info.get_length() {
-        args_for_callback[0] =
-            vm.get_property(cur_frame.this, Value::Number(i as f64), cur_frame)?; // 'i'th element may be getter
-        args_for_callback[1] = Value::Number(i as f64);
+    for k in 0..len {
+        // Holes are visited as undefined
+        let elem = get_element(vm, obj, k, cur_frame)?;
+        if call_callback(vm, callback, this_arg, elem, k, obj, cur_frame)?.to_boolean() {
+            vm.stack.push(Value::Number(k as f64).into());
+            return Ok(());
+        }
+    }
+
+    vm.stack.push(Value::Number(-1.0).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.reduce
+pub fn array_prototype_reduce(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "reduce")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let indices: Vec<usize> = (0..len).collect();
+    reduce(vm, args, obj, indices.into_iter(), cur_frame)
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.reduceright
+pub fn array_prototype_reduce_right(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "reduceRight")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let indices: Vec<usize> = (0..len).rev().collect();
+    reduce(vm, args, obj, indices.into_iter(), cur_frame)
+}
+
+/// The common part of reduce and reduceRight. The elements are visited in the order of indices.
+fn reduce<I: Iterator<Item = usize>>(
+    vm: &mut VM2,
+    args: &[Value],
+    obj: Value,
+    mut indices: I,
+    cur_frame: &Frame,
+) -> VMResult {
+    let callback = args.get(0).map(|arg| *arg).unwrap_or(Value::undefined());
+    if !callback.is_function_object() {
+        return Err(RuntimeError::Type(format!(
+            "{} is not a function",
+            callback.debug_string(true)
+        )));
+    }
+
+    let mut accumulator = match args.get(1) {
+        Some(initial_value) => *initial_value,
+        None => loop {
+            match indices.next() {
+                Some(k) if has_element(vm, obj, k) => break get_element(vm, obj, k, cur_frame)?,
+                Some(_) => {}
+                None => {
+                    return Err(RuntimeError::Type(
+                        "Reduce of empty array with no initial value".to_string(),
+                    ))
+                }
+            }
+        },
+    };
+
+    for k in indices {
+        if has_element(vm, obj, k) {
+            let elem = get_element(vm, obj, k, cur_frame)?;
+            let args_for_callback = [accumulator, elem, Value::Number(k as f64), obj];
+            vm.call_function(callback, &args_for_callback, Value::undefined(), cur_frame)?;
+            accumulator = vm.stack.pop().unwrap().into();
+        }
+    }
+
+    vm.stack.push(accumulator.into());
+    Ok(())
+}
 
-        vm.call_function(callback, &args_for_callback, Value::undefined(), cur_frame)?;
+/// https://tc39.github.io/ecma262/#sec-array.prototype.fill
+pub fn array_prototype_fill(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let obj = this_object(cur_frame, "fill")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+    let val = args.get(0).map(|arg| *arg).unwrap_or(Value::undefined());
+    let start = relative_index(vm, args.get(1), len, 0);
+    let end = relative_index(vm, args.get(2), len, len);
 
-        new_ary.push(Property::new_data_simple(
-            vm.stack.pop().unwrap().into(): Value,
+    for k in start..end {
+        set_element(vm, obj, k, val, cur_frame)?;
+    }
+
+    vm.stack.push(obj.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-array.prototype.sort
+/// The sort is stable. Undefined values are moved to the end, followed by holes.
+pub fn array_prototype_sort(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let comparefn = args.get(0).map(|arg| *arg).unwrap_or(Value::undefined());
+    if !comparefn.is_undefined() && !comparefn.is_function_object() {
+        return Err(RuntimeError::Type(
+            "The comparison function must be either a function or undefined".to_string(),
         ));
     }
 
-    vm.stack
-        .push(Value::array(&mut vm.memory_allocator, &vm.object_prototypes, new_ary).into());
+    let obj = this_object(cur_frame, "sort")?;
+    let len = length_of_array_like(vm, obj, cur_frame)?;
+
+    let mut items = vec![];
+    let mut undefined_count = 0;
+    for k in 0..len {
+        if has_element(vm, obj, k) {
+            let elem = get_element(vm, obj, k, cur_frame)?;
+            if elem.is_undefined() {
+                undefined_count += 1;
+            } else {
+                items.push(elem);
+            }
+        }
+    }
+
+    let items = merge_sort(vm, items, comparefn, cur_frame)?;
+
+    let item_count = items.len();
+    for (k, item) in items.into_iter().enumerate() {
+        set_element(vm, obj, k, item, cur_frame)?;
+    }
+    for k in item_count..item_count + undefined_count {
+        set_element(vm, obj, k, Value::undefined(), cur_frame)?;
+    }
+    for k in item_count + undefined_count..len {
+        delete_element(vm, obj, k)?;
+    }
+
+    vm.stack.push(obj.into());
+    Ok(())
+}
+
+fn merge_sort(
+    vm: &mut VM2,
+    mut items: Vec<Value>,
+    comparefn: Value,
+    cur_frame: &Frame,
+) -> Result<Vec<Value>, RuntimeError> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort(vm, items, comparefn, cur_frame)?;
+    let right = merge_sort(vm, right, comparefn, cur_frame)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    loop {
+        let take_right = match (left.peek(), right.peek()) {
+            (Some(x), Some(y)) => sort_compare(vm, *x, *y, comparefn, cur_frame)? > 0.0,
+            (Some(_), None) => false,
+            (None, Some(_)) => true,
+            (None, None) => break,
+        };
+        let next = if take_right {
+            right.next()
+        } else {
+            left.next()
+        };
+        merged.push(next.unwrap());
+    }
+
+    Ok(merged)
+}
+
+/// https://tc39.github.io/ecma262/#sec-sortcompare
+fn sort_compare(
+    vm: &mut VM2,
+    x: Value,
+    y: Value,
+    comparefn: Value,
+    cur_frame: &Frame,
+) -> Result<f64, RuntimeError> {
+    // Strings are compared by UTF-16 code units.
+    if comparefn.is_undefined() {
+        let (x, y) = (x.to_js_string(), y.to_js_string());
+        return Ok(if x < y {
+            -1.0
+        } else if x > y {
+            1.0
+        } else {
+            0.0
+        });
+    }
+
+    vm.call_function(comparefn, &[x, y], Value::undefined(), cur_frame)?;
+    let ret: Value = vm.stack.pop().unwrap().into();
+    let ret = ret.to_number(&mut vm.memory_allocator);
+    Ok(if ret.is_nan() { 0.0 } else { ret })
+}
+
+/// Array.prototype.keys
+pub fn array_prototype_keys(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    create_array_iterator(vm, cur_frame, "keys", ArrayIterationKind::Key)
+}
+
+/// Array.prototype.values, which is also Array.prototype\[@@iterator\]
+pub fn array_prototype_values(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    create_array_iterator(vm, cur_frame, "values", ArrayIterationKind::Value)
+}
 
+/// Array.prototype.entries
+pub fn array_prototype_entries(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    create_array_iterator(vm, cur_frame, "entries", ArrayIterationKind::KeyAndValue)
+}
+
+/// https://tc39.github.io/ecma262/#sec-createarrayiterator
+fn create_array_iterator(
+    vm: &mut VM2,
+    cur_frame: &Frame,
+    name: &str,
+    kind: ArrayIterationKind,
+) -> VMResult {
+    if !cur_frame.this.is_object() {
+        return Err(RuntimeError::Type(format!(
+            "Array.prototype.{} called on non-object",
+            name
+        )));
+    }
+
+    let iter = Value::array_iterator(
+        &mut vm.memory_allocator,
+        &vm.object_prototypes,
+        cur_frame.this,
+        kind,
+    );
+    vm.stack.push(iter.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-%arrayiteratorprototype%.next
+pub fn array_iterator_prototype_next(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    let info = match cur_frame.this {
+        Value::Object(obj) => match unsafe { &mut *obj }.kind {
+            ObjectKind2::ArrayIterator(ref mut info) => info,
+            _ => return Err(RuntimeError::Type("not an Array Iterator".to_string())),
+        },
+        _ => return Err(RuntimeError::Type("not an Array Iterator".to_string())),
+    };
+
+    let iterated = info.iterated;
+    if iterated.is_undefined() {
+        let result = create_iter_result_object(vm, Value::undefined(), true);
+        vm.stack.push(result.into());
+        return Ok(());
+    }
+
+    let len = length_of_array_like(vm, iterated, cur_frame)?;
+
+    let index = info.next_index;
+    if index >= len {
+        info.iterated = Value::undefined();
+        let result = create_iter_result_object(vm, Value::undefined(), true);
+        vm.stack.push(result.into());
+        return Ok(());
+    }
+
+    info.next_index = index + 1;
+
+    let result = match info.kind {
+        ArrayIterationKind::Key => Value::Number(index as f64),
+        ArrayIterationKind::Value => get_element(vm, iterated, index, cur_frame)?,
+        ArrayIterationKind::KeyAndValue => {
+            let elem = get_element(vm, iterated, index, cur_frame)?;
+            Value::array(
+                &mut vm.memory_allocator,
+                &vm.object_prototypes,
+                vec![
+                    Property::new_data_simple(Value::Number(index as f64)),
+                    Property::new_data_simple(elem),
+                ],
+            )
+        }
+    };
+    let result = create_iter_result_object(vm, result, false);
+    vm.stack.push(result.into());
+    Ok(())
+}
+
+// Helpers for the generic array methods. They work on any object, going through the ordinary
+// property access so that holes, getters and setters are handled.
+
+fn this_object(cur_frame: &Frame, name: &str) -> Result<Value, RuntimeError> {
+    if !cur_frame.this.is_object() {
+        return Err(RuntimeError::Type(format!(
+            "Array.prototype.{} called on non-object",
+            name
+        )));
+    }
+    Ok(cur_frame.this)
+}
+
+/// Return the callback and thisArg given to the methods like forEach.
+fn callback_args(args: &[Value]) -> Result<(Value, Value), RuntimeError> {
+    let callback = args.get(0).map(|arg| *arg).unwrap_or(Value::undefined());
+    if !callback.is_function_object() {
+        return Err(RuntimeError::Type(format!(
+            "{} is not a function",
+            callback.debug_string(true)
+        )));
+    }
+    let this_arg = args.get(1).map(|arg| *arg).unwrap_or(Value::undefined());
+    Ok((callback, this_arg))
+}
+
+/// Call the callback with (element, index, object) and return its result.
+fn call_callback(
+    vm: &mut VM2,
+    callback: Value,
+    this_arg: Value,
+    elem: Value,
+    k: usize,
+    obj: Value,
+    cur_frame: &Frame,
+) -> Result<Value, RuntimeError> {
+    let args_for_callback = [elem, Value::Number(k as f64), obj];
+    vm.call_function(callback, &args_for_callback, this_arg, cur_frame)?;
+    Ok(vm.stack.pop().unwrap().into())
+}
+
+/// https://tc39.github.io/ecma262/#sec-lengthofarraylike
+/// https://tc39.github.io/ecma262/#sec-isconcatspreadable
+fn is_concat_spreadable(vm: &mut VM2, val: Value, cur_frame: &Frame) -> Result<bool, RuntimeError> {
+    if !val.is_object() || val.is_symbol() {
+        return Ok(false);
+    }
+    let sym_is_concat_spreadable = vm.global_symbol_registry.well_known.is_concat_spreadable;
+    let spreadable = vm.get_property(val, sym_is_concat_spreadable, cur_frame)?;
+    if !spreadable.is_undefined() {
+        return Ok(spreadable.to_boolean());
+    }
+    Ok(val.is_array_object())
+}
+
+fn length_of_array_like(
+    vm: &mut VM2,
+    obj: Value,
+    cur_frame: &Frame,
+) -> Result<usize, RuntimeError> {
+    if obj.is_array_object() {
+        return Ok(obj.as_array_mut().get_length());
+    }
+
+    let length_key = Value::string(&mut vm.memory_allocator, "length".to_string());
+    let len = vm
+        .get_property(obj, length_key, cur_frame)?
+        .to_number(&mut vm.memory_allocator);
+    // ToLength
+    Ok(if len.is_nan() || len <= 0.0 {
+        0
+    } else {
+        len.trunc().min(9007199254740991.0) as usize
+    })
+}
+
+/// Convert the relative index argument into an index clamped to 0..=len. A negative index counts
+/// from the end. Return default if the argument is absent or undefined.
+pub fn relative_index(vm: &mut VM2, arg: Option<&Value>, len: usize, default: usize) -> usize {
+    let arg = match arg {
+        Some(arg) if !arg.is_undefined() => *arg,
+        _ => return default,
+    };
+    let relative = arg.to_integer(&mut vm.memory_allocator);
+    if relative < 0.0 {
+        (len as f64 + relative).max(0.0) as usize
+    } else {
+        relative.min(len as f64) as usize
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-samevaluezero
+fn same_value_zero(x: Value, y: Value) -> bool {
+    match (x, y) {
+        (Value::Number(x), Value::Number(y)) if x.is_nan() && y.is_nan() => true,
+        (x, y) => x.strict_eq(y).into_bool(),
+    }
+}
+
+fn has_element(vm: &mut VM2, obj: Value, k: usize) -> bool {
+    obj.has_property(&mut vm.memory_allocator, Value::Number(k as f64))
+}
+
+fn get_element(
+    vm: &mut VM2,
+    obj: Value,
+    k: usize,
+    cur_frame: &Frame,
+) -> Result<Value, RuntimeError> {
+    vm.get_property(obj, Value::Number(k as f64), cur_frame) // May be getter
+}
+
+fn set_element(vm: &mut VM2, obj: Value, k: usize, val: Value, cur_frame: &Frame) -> VMResult {
+    vm.set_property_or_throw(obj, Value::Number(k as f64), val, cur_frame)?; // May be setter
+    vm.memory_allocator.gray2(val);
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-deletepropertyorthrow
+fn delete_element(vm: &mut VM2, obj: Value, k: usize) -> VMResult {
+    if !obj.delete_property(&mut vm.memory_allocator, Value::Number(k as f64)) {
+        return Err(RuntimeError::Type(format!(
+            "Cannot delete property '{}' of {}",
+            k,
+            obj.debug_string(true)
+        )));
+    }
+    Ok(())
+}
+
+/// Move the element at from to to. If from is a hole, to becomes a hole.
+fn move_element(vm: &mut VM2, obj: Value, from: usize, to: usize, cur_frame: &Frame) -> VMResult {
+    if has_element(vm, obj, from) {
+        let elem = get_element(vm, obj, from, cur_frame)?;
+        set_element(vm, obj, to, elem, cur_frame)
+    } else {
+        delete_element(vm, obj, to)
+    }
+}
+
+fn set_length(vm: &mut VM2, obj: Value, len: usize, cur_frame: &Frame) -> VMResult {
+    let length_key = Value::string(&mut vm.memory_allocator, "length".to_string());
+    vm.set_property_or_throw(obj, length_key, Value::Number(len as f64), cur_frame)
+}
+
+/// Append the value to the array created by the array methods.
+fn push_element(vm: &mut VM2, ary: Value, val: Value) {
+    ary.as_array_mut()
+        .elems
+        .push(Property::new_data_simple(val));
+    vm.memory_allocator.gray2(val);
+}
+
+/// Append the elements of obj in start..end to the array created by the array methods. Holes
+/// are preserved. Nothing is appended if end is not greater than start.
+fn copy_elements(
+    vm: &mut VM2,
+    obj: Value,
+    ary: Value,
+    start: usize,
+    end: usize,
+    cur_frame: &Frame,
+) -> VMResult {
+    let base = ary.as_array_mut().get_length();
+    let end = end.max(start);
+    for k in start..end {
+        if has_element(vm, obj, k) {
+            let elem = get_element(vm, obj, k, cur_frame)?;
+            ary.as_array_mut().set_element(base + k - start, elem);
+            vm.memory_allocator.gray2(elem);
+        }
+    }
+    ary.as_array_mut().set_length(base + end - start);
     Ok(())
 }
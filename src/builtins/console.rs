@@ -65,7 +65,7 @@ pub fn debug_print(val: &Value, nest: bool) {
         Value::Number(n) if n.is_infinite() => print!("Infinity"),
         Value::Number(n) => print!("{}", *n),
         Value::String(ref s) => {
-            let s = unsafe { &**s }.to_str().unwrap();
+            let s = unsafe { &**s };
             if nest {
                 print!("'{}'", s)
             } else {
@@ -92,6 +92,40 @@ pub fn debug_print(val: &Value, nest: bool) {
                     "Symbol({})",
                     info.description.as_ref().unwrap_or(&"".to_string())
                 ),
+                ObjectKind2::ArrayIterator(_) => print!("Object [Array Iterator] {{}}"),
+                ObjectKind2::StringIterator(_) => print!("Object [String Iterator] {{}}"),
+                ObjectKind2::Generator(_) => print!("Object [Generator] {{}}"),
+                ObjectKind2::Arguments(ref info) => {
+                    print!("[Arguments] {{ ");
+
+                    let key_val = (&obj_info.property)
+                        .iter()
+                        .filter(|(_, prop)| prop.is_enumerable())
+                        .map(|(key, prop)| (key, info.reflect_parameter(key, *prop)))
+                        .collect::<Vec<(&String, Property)>>();
+                    let mut sorted_key_val = key_val
+                        .iter()
+                        .map(|(key, prop)| (*key, prop))
+                        .collect::<Vec<(&String, &Property)>>();
+                    sorted_key_val.sort_by(|(key1, _), (key2, _)| key1.as_str().cmp(key2.as_str()));
+
+                    show_obj(sorted_key_val);
+
+                    print!("}}");
+                }
+                ObjectKind2::RegExp(ref regexp) => print!("{}", regexp),
+                ObjectKind2::Promise(ref info) => {
+                    print!("Promise {{ ");
+                    match info.state {
+                        PromiseState::Pending => print!("<pending>"),
+                        PromiseState::Fulfilled => debug_print(&info.result, true),
+                        PromiseState::Rejected => {
+                            print!("<rejected> ");
+                            debug_print(&info.result, true)
+                        }
+                    }
+                    print!(" }}");
+                }
                 ObjectKind2::Function(ref func_info) => {
                     if let Some(ref name) = func_info.name {
                         print!("[Function: {}]", name);
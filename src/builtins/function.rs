@@ -35,3 +35,15 @@ pub fn function_prototype_call(
     let func = cur_frame.this;
     vm.call_function(func, args.get(1..).unwrap_or(&[]), this_arg, cur_frame)
 }
+
+/// https://tc39.github.io/ecma262/#sec-function.prototype-@@hasinstance
+pub fn function_prototype_has_instance(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let val = *args.get(0).unwrap_or(&Value::undefined());
+    let result = vm.ordinary_has_instance(cur_frame.this, val)?;
+    vm.stack.push(Value::bool(result).into());
+    Ok(())
+}
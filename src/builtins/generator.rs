@@ -0,0 +1,23 @@
+use vm::{
+    frame::Frame,
+    jsvalue::value::*,
+    vm::{VMResult, VM2},
+};
+
+/// https://tc39.github.io/ecma262/#sec-generator.prototype.next
+pub fn generator_prototype_next(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let val = *args.get(0).unwrap_or(&Value::undefined());
+    vm.resume_generator(cur_frame.this, val, ResumeKind::Next)
+}
+
+/// https://tc39.github.io/ecma262/#sec-generator.prototype.return
+pub fn generator_prototype_return(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let val = *args.get(0).unwrap_or(&Value::undefined());
+    vm.resume_generator(cur_frame.this, val, ResumeKind::Return)
+}
+
+/// https://tc39.github.io/ecma262/#sec-generator.prototype.throw
+pub fn generator_prototype_throw(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let val = *args.get(0).unwrap_or(&Value::undefined());
+    vm.resume_generator(cur_frame.this, val, ResumeKind::Throw)
+}
@@ -0,0 +1,19 @@
+use vm::{
+    frame::Frame,
+    jsvalue::value::*,
+    vm::{VMResult, VM2},
+};
+
+/// %IteratorPrototype%\[@@iterator\]
+pub fn iterator_prototype_iterator(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    vm.stack.push(cur_frame.this.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-createiterresultobject
+pub fn create_iter_result_object(vm: &mut VM2, value: Value, done: bool) -> Value {
+    make_normal_object!(vm.memory_allocator, vm.object_prototypes,
+        value => true, true, true: value,
+        done  => true, true, true: Value::bool(done)
+    )
+}
@@ -5,7 +5,7 @@ use vm::{
     frame,
     jsvalue::prototype::ObjectPrototypes,
     jsvalue::{
-        object::{DataProperty, ObjectInfo, ObjectKind2, Property},
+        object::{DataProperty, ObjectInfo, ObjectKind2, Property, PropertyMap},
         value::Value,
     },
     vm,
@@ -1,6 +1,11 @@
+use builtin::BuiltinFuncTy2;
 use gc;
-use rustc_hash::FxHashMap;
-use vm::{frame, jsvalue::value::*, vm};
+use vm::{
+    error::RuntimeError,
+    frame,
+    jsvalue::{iterator::ArrayIterationKind, value::*},
+    vm,
+};
 
 pub fn object(
     memory_allocator: &mut gc::MemoryAllocator,
@@ -12,6 +17,48 @@ pub fn object(
         "Object".to_string(),
         object_constructor,
     );
+
+    let statics: [(&str, BuiltinFuncTy2); 19] = [
+        ("keys", object_keys),
+        ("values", object_values),
+        ("entries", object_entries),
+        ("assign", object_assign),
+        ("create", object_create),
+        ("getPrototypeOf", object_get_prototype_of),
+        ("setPrototypeOf", object_set_prototype_of),
+        ("defineProperty", object_define_property),
+        ("defineProperties", object_define_properties),
+        (
+            "getOwnPropertyDescriptor",
+            object_get_own_property_descriptor,
+        ),
+        (
+            "getOwnPropertyDescriptors",
+            object_get_own_property_descriptors,
+        ),
+        ("getOwnPropertyNames", object_get_own_property_names),
+        ("getOwnPropertySymbols", object_get_own_property_symbols),
+        ("freeze", object_freeze),
+        ("isFrozen", object_is_frozen),
+        ("seal", object_seal),
+        ("isSealed", object_is_sealed),
+        ("preventExtensions", object_prevent_extensions),
+        ("isExtensible", object_is_extensible),
+    ];
+    for (name, func) in &statics {
+        let func =
+            Value::builtin_function(memory_allocator, object_prototypes, name.to_string(), *func);
+        obj.get_object_info().property.insert(
+            name.to_string(),
+            Property::Data(DataProperty {
+                val: func,
+                writable: true,
+                enumerable: false,
+                configurable: true,
+            }),
+        );
+    }
+
     obj.set_property_by_string_key("prototype".to_string(), object_prototypes.object);
     obj.get_property_by_str_key("prototype")
         .set_constructor(obj);
@@ -27,7 +74,7 @@ pub fn object_constructor(
         let empty_obj = Value::object(
             &mut vm.memory_allocator,
             &vm.object_prototypes,
-            FxHashMap::default(),
+            PropertyMap::default(),
         );
         vm.stack.push(empty_obj.into());
         return Ok(());
@@ -38,7 +85,7 @@ pub fn object_constructor(
             let empty_obj = Value::object(
                 &mut vm.memory_allocator,
                 &vm.object_prototypes,
-                FxHashMap::default(),
+                PropertyMap::default(),
             );
             vm.stack.push(empty_obj.into());
         }
@@ -51,3 +98,782 @@ pub fn object_constructor(
 
     Ok(())
 }
+
+/// https://tc39.github.io/ecma262/#sec-object.keys
+pub fn object_keys(vm: &mut vm::VM2, args: &[Value], cur_frame: &frame::Frame) -> vm::VMResult {
+    let obj = require_object_coercible(arg(args, 0))?;
+    let ary = enumerable_own_property_names(vm, obj, ArrayIterationKind::Key, cur_frame)?;
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.values
+pub fn object_values(vm: &mut vm::VM2, args: &[Value], cur_frame: &frame::Frame) -> vm::VMResult {
+    let obj = require_object_coercible(arg(args, 0))?;
+    let ary = enumerable_own_property_names(vm, obj, ArrayIterationKind::Value, cur_frame)?;
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.entries
+pub fn object_entries(vm: &mut vm::VM2, args: &[Value], cur_frame: &frame::Frame) -> vm::VMResult {
+    let obj = require_object_coercible(arg(args, 0))?;
+    let ary = enumerable_own_property_names(vm, obj, ArrayIterationKind::KeyAndValue, cur_frame)?;
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-enumerableownpropertynames
+fn enumerable_own_property_names(
+    vm: &mut vm::VM2,
+    obj: Value,
+    kind: ArrayIterationKind,
+    cur_frame: &frame::Frame,
+) -> Result<Value, RuntimeError> {
+    let keys = own_property_keys(vm, obj)
+        .into_iter()
+        .filter(|(key, _)| key.is_string())
+        .map(|(key, _)| key)
+        .collect::<Vec<Value>>();
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, vec![]);
+
+    let mut locked = keys.clone();
+    locked.push(ary);
+    with_locked(vm, &locked, |vm| {
+        for key in keys {
+            // Getters called so far may have deleted the property or changed its attributes
+            match get_own_property(vm, obj, key) {
+                Some(prop) if prop.is_enumerable() => {}
+                _ => continue,
+            }
+
+            let elem = match kind {
+                ArrayIterationKind::Key => key,
+                ArrayIterationKind::Value => vm.get_property(obj, key, cur_frame)?,
+                ArrayIterationKind::KeyAndValue => {
+                    let val = vm.get_property(obj, key, cur_frame)?;
+                    Value::array(
+                        &mut vm.memory_allocator,
+                        &vm.object_prototypes,
+                        vec![
+                            Property::new_data_simple(key),
+                            Property::new_data_simple(val),
+                        ],
+                    )
+                }
+            };
+            ary.as_array_mut()
+                .elems
+                .push(Property::new_data_simple(elem));
+            vm.memory_allocator.gray2(elem);
+        }
+        Ok(ary)
+    })
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.assign
+/// TODO: A primitive target is returned as it is since wrapper objects are not supported.
+pub fn object_assign(vm: &mut vm::VM2, args: &[Value], cur_frame: &frame::Frame) -> vm::VMResult {
+    let target = require_object_coercible(arg(args, 0))?;
+    for &source in args.iter().skip(1) {
+        if source.is_undefined() || source.is_null() {
+            continue;
+        }
+
+        let keys = own_property_keys(vm, source)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect::<Vec<Value>>();
+        with_locked(vm, &keys, |vm| {
+            for &key in &keys {
+                match get_own_property(vm, source, key) {
+                    Some(prop) if prop.is_enumerable() => {}
+                    _ => continue,
+                }
+                let val = vm.get_property(source, key, cur_frame)?;
+                vm.set_property_or_throw(target, key, val, cur_frame)?;
+                vm.memory_allocator.gray2(val);
+            }
+            Ok(())
+        })?;
+    }
+    vm.stack.push(target.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.create
+pub fn object_create(vm: &mut vm::VM2, args: &[Value], cur_frame: &frame::Frame) -> vm::VMResult {
+    let proto = arg(args, 0);
+    if !is_object(proto) && !proto.is_null() {
+        return Err(RuntimeError::Type(format!(
+            "Object prototype may only be an Object or null: {}",
+            proto.debug_string(true)
+        )));
+    }
+
+    let obj = Value::object(
+        &mut vm.memory_allocator,
+        &vm.object_prototypes,
+        PropertyMap::default(),
+    );
+    obj.get_object_info().prototype = proto;
+
+    let properties = arg(args, 1);
+    if !properties.is_undefined() {
+        with_locked(vm, &[obj], |vm| {
+            define_properties(vm, obj, properties, cur_frame)
+        })?;
+    }
+
+    vm.stack.push(obj.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.getprototypeof
+pub fn object_get_prototype_of(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    _cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = require_object_coercible(arg(args, 0))?;
+    let proto = match obj {
+        Value::Object(_) => obj.get_prototype(),
+        Value::String(_) => vm.object_prototypes.string,
+        // TODO: Number.prototype and Boolean.prototype are not implemented.
+        _ => vm.object_prototypes.object,
+    };
+    vm.stack.push(proto.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.setprototypeof
+pub fn object_set_prototype_of(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    _cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = require_object_coercible(arg(args, 0))?;
+    let proto = arg(args, 1);
+    if !is_object(proto) && !proto.is_null() {
+        return Err(RuntimeError::Type(format!(
+            "Object prototype may only be an Object or null: {}",
+            proto.debug_string(true)
+        )));
+    }
+
+    if is_object(obj) {
+        let info = obj.get_object_info();
+        if !info.set_prototype_of(proto) {
+            return Err(RuntimeError::Type(if info.extensible {
+                "Cyclic __proto__ value".to_string()
+            } else {
+                format!("{} is not extensible", obj.debug_string(true))
+            }));
+        }
+    }
+    vm.stack.push(obj.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.defineproperty
+pub fn object_define_property(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = arg(args, 0);
+    if !is_object(obj) {
+        return Err(RuntimeError::Type(
+            "Object.defineProperty called on non-object".to_string(),
+        ));
+    }
+
+    let desc = to_property_descriptor(vm, arg(args, 2), cur_frame)?;
+    let key = to_property_key(vm, arg(args, 1));
+    define_property_or_throw(vm, obj, key, desc)?;
+    vm.stack.push(obj.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.defineproperties
+pub fn object_define_properties(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = arg(args, 0);
+    if !is_object(obj) {
+        return Err(RuntimeError::Type(
+            "Object.defineProperties called on non-object".to_string(),
+        ));
+    }
+
+    define_properties(vm, obj, arg(args, 1), cur_frame)?;
+    vm.stack.push(obj.into());
+    Ok(())
+}
+
+/// All the descriptors are read before any property is defined.
+/// https://tc39.github.io/ecma262/#sec-objectdefineproperties
+fn define_properties(
+    vm: &mut vm::VM2,
+    obj: Value,
+    properties: Value,
+    cur_frame: &frame::Frame,
+) -> Result<(), RuntimeError> {
+    let properties = require_object_coercible(properties)?;
+    let keys = own_property_keys(vm, properties)
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect::<Vec<Value>>();
+
+    let descriptors = with_locked(vm, &keys, |vm| {
+        let mut descriptors = vec![];
+        for &key in &keys {
+            match get_own_property(vm, properties, key) {
+                Some(prop) if prop.is_enumerable() => {}
+                _ => continue,
+            }
+            let desc_obj = vm.get_property(properties, key, cur_frame)?;
+            descriptors.push((key, to_property_descriptor(vm, desc_obj, cur_frame)?));
+        }
+        Ok(descriptors)
+    })?;
+
+    for (key, desc) in descriptors {
+        define_property_or_throw(vm, obj, key, desc)?;
+    }
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.getownpropertydescriptor
+pub fn object_get_own_property_descriptor(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    _cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = require_object_coercible(arg(args, 0))?;
+    let key = to_property_key(vm, arg(args, 1));
+    let desc = match get_own_property(vm, obj, key) {
+        Some(prop) => from_property_descriptor(vm, prop.into()),
+        None => Value::undefined(),
+    };
+    vm.stack.push(desc.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.getownpropertydescriptors
+pub fn object_get_own_property_descriptors(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    _cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = require_object_coercible(arg(args, 0))?;
+    let descriptors = Value::object(
+        &mut vm.memory_allocator,
+        &vm.object_prototypes,
+        PropertyMap::default(),
+    );
+    for (key, _) in own_property_keys(vm, obj) {
+        if let Some(prop) = get_own_property(vm, obj, key) {
+            let desc = from_property_descriptor(vm, prop.into());
+            descriptors.get_object_info().define_own_property(
+                &mut vm.memory_allocator,
+                key,
+                PropertyDescriptor {
+                    value: Some(desc),
+                    writable: Some(true),
+                    enumerable: Some(true),
+                    configurable: Some(true),
+                    ..PropertyDescriptor::default()
+                },
+            )?;
+        }
+    }
+    vm.stack.push(descriptors.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.getownpropertynames
+pub fn object_get_own_property_names(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    _cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = require_object_coercible(arg(args, 0))?;
+    let names = own_property_keys(vm, obj)
+        .into_iter()
+        .filter(|(key, _)| key.is_string())
+        .map(|(key, _)| Property::new_data_simple(key))
+        .collect::<Vec<Property>>();
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, names);
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.getownpropertysymbols
+pub fn object_get_own_property_symbols(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    _cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = require_object_coercible(arg(args, 0))?;
+    let symbols = own_property_keys(vm, obj)
+        .into_iter()
+        .filter(|(key, _)| key.is_symbol())
+        .map(|(key, _)| Property::new_data_simple(key))
+        .collect::<Vec<Property>>();
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, symbols);
+    vm.stack.push(ary.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.freeze
+pub fn object_freeze(vm: &mut vm::VM2, args: &[Value], _cur_frame: &frame::Frame) -> vm::VMResult {
+    let obj = arg(args, 0);
+    if is_object(obj) {
+        set_integrity_level(vm, obj, IntegrityLevel::Frozen)?;
+    }
+    vm.stack.push(obj.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.isfrozen
+pub fn object_is_frozen(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    _cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = arg(args, 0);
+    let frozen = !is_object(obj) || test_integrity_level(vm, obj, IntegrityLevel::Frozen);
+    vm.stack.push(Value::bool(frozen).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.seal
+pub fn object_seal(vm: &mut vm::VM2, args: &[Value], _cur_frame: &frame::Frame) -> vm::VMResult {
+    let obj = arg(args, 0);
+    if is_object(obj) {
+        set_integrity_level(vm, obj, IntegrityLevel::Sealed)?;
+    }
+    vm.stack.push(obj.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.issealed
+pub fn object_is_sealed(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    _cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = arg(args, 0);
+    let sealed = !is_object(obj) || test_integrity_level(vm, obj, IntegrityLevel::Sealed);
+    vm.stack.push(Value::bool(sealed).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.preventextensions
+pub fn object_prevent_extensions(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    _cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = arg(args, 0);
+    if is_object(obj) {
+        obj.get_object_info().prevent_extensions();
+    }
+    vm.stack.push(obj.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.isextensible
+pub fn object_is_extensible(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    _cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = arg(args, 0);
+    let extensible = is_object(obj) && obj.get_object_info().extensible;
+    vm.stack.push(Value::bool(extensible).into());
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum IntegrityLevel {
+    Sealed,
+    Frozen,
+}
+
+/// https://tc39.github.io/ecma262/#sec-setintegritylevel
+fn set_integrity_level(
+    vm: &mut vm::VM2,
+    obj: Value,
+    level: IntegrityLevel,
+) -> Result<(), RuntimeError> {
+    obj.get_object_info().prevent_extensions();
+    for (key, _) in own_property_keys(vm, obj) {
+        let current = match get_own_property(vm, obj, key) {
+            Some(current) => current,
+            None => continue,
+        };
+        let desc = match current {
+            Property::Data(_) if level == IntegrityLevel::Frozen => PropertyDescriptor {
+                configurable: Some(false),
+                writable: Some(false),
+                ..PropertyDescriptor::default()
+            },
+            _ => PropertyDescriptor {
+                configurable: Some(false),
+                ..PropertyDescriptor::default()
+            },
+        };
+        define_property_or_throw(vm, obj, key, desc)?;
+    }
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-testintegritylevel
+fn test_integrity_level(vm: &mut vm::VM2, obj: Value, level: IntegrityLevel) -> bool {
+    if obj.get_object_info().extensible {
+        return false;
+    }
+    own_property_keys(vm, obj)
+        .into_iter()
+        .all(|(key, _)| match get_own_property(vm, obj, key) {
+            Some(Property::Data(DataProperty { writable: true, .. }))
+                if level == IntegrityLevel::Frozen =>
+            {
+                false
+            }
+            Some(prop) => !prop.is_configurable(),
+            None => true,
+        })
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.prototype.hasownproperty
+pub fn object_prototype_has_own_property(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let key = to_property_key(vm, arg(args, 0));
+    let obj = require_object_coercible(cur_frame.this)?;
+    let has = get_own_property(vm, obj, key).is_some();
+    vm.stack.push(Value::bool(has).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-get-object.prototype.__proto__
+pub fn object_prototype_get_proto(
+    vm: &mut vm::VM2,
+    _args: &[Value],
+    cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    object_get_prototype_of(vm, &[cur_frame.this], cur_frame)
+}
+
+/// https://tc39.github.io/ecma262/#sec-set-object.prototype.__proto__
+pub fn object_prototype_set_proto(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let obj = require_object_coercible(cur_frame.this)?;
+    let proto = arg(args, 0);
+    if is_object(obj) && (is_object(proto) || proto.is_null()) {
+        let info = obj.get_object_info();
+        if !info.set_prototype_of(proto) {
+            return Err(RuntimeError::Type(if info.extensible {
+                "Cyclic __proto__ value".to_string()
+            } else {
+                format!("{} is not extensible", obj.debug_string(true))
+            }));
+        }
+    }
+    vm.stack.push(Value::undefined().into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.prototype.isprototypeof
+pub fn object_prototype_is_prototype_of(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let val = arg(args, 0);
+    if !is_object(val) {
+        vm.stack.push(Value::bool(false).into());
+        return Ok(());
+    }
+
+    let obj = require_object_coercible(cur_frame.this)?;
+    let mut proto = val.get_prototype();
+    let mut found = false;
+    while is_object(proto) {
+        if proto.strict_eq(obj).into_bool() {
+            found = true;
+            break;
+        }
+        proto = proto.get_prototype();
+    }
+    vm.stack.push(Value::bool(found).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.prototype.propertyisenumerable
+pub fn object_prototype_property_is_enumerable(
+    vm: &mut vm::VM2,
+    args: &[Value],
+    cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let key = to_property_key(vm, arg(args, 0));
+    let obj = require_object_coercible(cur_frame.this)?;
+    let enumerable = get_own_property(vm, obj, key).map_or(false, |prop| prop.is_enumerable());
+    vm.stack.push(Value::bool(enumerable).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-object.prototype.tostring
+/// A string @@toStringTag property takes precedence. The builtin objects which would define it
+/// on their prototypes are given tags here instead.
+pub fn object_prototype_to_string(
+    vm: &mut vm::VM2,
+    _args: &[Value],
+    cur_frame: &frame::Frame,
+) -> vm::VMResult {
+    let tag = match cur_frame.this {
+        Value::Other(UNDEFINED) => "Undefined",
+        Value::Other(NULL) => "Null",
+        Value::String(_) => "String",
+        Value::Number(_) => "Number",
+        Value::Bool(_) => "Boolean",
+        Value::Object(info) => match unsafe { &*info }.kind {
+            ObjectKind2::Array(_) => "Array",
+            ObjectKind2::Arguments(_) => "Arguments",
+            ObjectKind2::Function(_) => "Function",
+            ObjectKind2::RegExp(_) => "RegExp",
+            ObjectKind2::Symbol(_) => "Symbol",
+            ObjectKind2::Generator(_) => "Generator",
+            ObjectKind2::Promise(_) => "Promise",
+            ObjectKind2::ArrayIterator(_) => "Array Iterator",
+            ObjectKind2::StringIterator(_) => "String Iterator",
+            ObjectKind2::Ordinary => "Object",
+        },
+        _ => "Object",
+    };
+    let tag = if is_object(cur_frame.this) {
+        let sym_to_string_tag = vm.global_symbol_registry.well_known.to_string_tag;
+        match vm.get_property(cur_frame.this, sym_to_string_tag, cur_frame)? {
+            Value::String(s) => unsafe { &*s }.to_string(),
+            _ => tag.to_string(),
+        }
+    } else {
+        tag.to_string()
+    };
+    let string = Value::string(&mut vm.memory_allocator, format!("[object {}]", tag));
+    vm.stack.push(string.into());
+    Ok(())
+}
+
+fn arg(args: &[Value], idx: usize) -> Value {
+    args.get(idx).map(|arg| *arg).unwrap_or(Value::undefined())
+}
+
+/// Symbols are represented as objects, but they are not objects in the language.
+fn is_object(val: Value) -> bool {
+    val.is_object() && !val.is_symbol()
+}
+
+/// Throw a TypeError for null and undefined. This is used in place of ToObject since wrapper
+/// objects for primitives are not supported.
+/// https://tc39.github.io/ecma262/#sec-requireobjectcoercible
+fn require_object_coercible(val: Value) -> Result<Value, RuntimeError> {
+    if val.is_undefined() || val.is_null() {
+        return Err(RuntimeError::Type(
+            "Cannot convert undefined or null to object".to_string(),
+        ));
+    }
+    Ok(val)
+}
+
+/// https://tc39.github.io/ecma262/#sec-topropertykey
+fn to_property_key(vm: &mut vm::VM2, val: Value) -> Value {
+    if val.is_symbol() || val.is_string() {
+        return val;
+    }
+    Value::string(&mut vm.memory_allocator, val.to_string())
+}
+
+/// Return the own property of obj. Strings have their code units and length as own properties.
+fn get_own_property(vm: &mut vm::VM2, obj: Value, key: Value) -> Option<Property> {
+    match obj {
+        Value::Object(_) => obj
+            .get_object_info()
+            .get_own_property(&mut vm.memory_allocator, key),
+        Value::String(_) => {
+            let string = obj.as_string();
+            let idx = key.is_canonical_numeric_index_string(&mut vm.memory_allocator);
+            let val = match idx {
+                Some(idx) if idx < string.len() => {
+                    Value::js_string(&mut vm.memory_allocator, string.substring(idx, idx + 1))
+                }
+                _ if key.is_string() && *key.as_string() == *"length" => {
+                    Value::Number(string.len() as f64)
+                }
+                _ => return None,
+            };
+            Some(Property::Data(DataProperty {
+                val,
+                writable: false,
+                enumerable: idx.is_some(),
+                configurable: false,
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// Return the own property keys of obj with their enumerability. String keys come before symbol
+/// keys.
+/// https://tc39.github.io/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-ownpropertykeys
+fn own_property_keys(vm: &mut vm::VM2, obj: Value) -> Vec<(Value, bool)> {
+    match obj {
+        Value::Object(_) => {
+            let info = obj.get_object_info();
+            let mut keys = info
+                .own_property_keys()
+                .into_iter()
+                .map(|(key, enumerable)| {
+                    (
+                        Value::string_from_key(&mut vm.memory_allocator, &key),
+                        enumerable,
+                    )
+                })
+                .collect::<Vec<(Value, bool)>>();
+            keys.extend(info.own_symbol_keys());
+            keys
+        }
+        Value::String(_) => {
+            let len = obj.as_string().len();
+            let mut keys = (0..len)
+                .map(|i| (Value::string(&mut vm.memory_allocator, i.to_string()), true))
+                .collect::<Vec<(Value, bool)>>();
+            keys.push((
+                Value::string(&mut vm.memory_allocator, "length".to_string()),
+                false,
+            ));
+            keys
+        }
+        _ => vec![],
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-definepropertyorthrow
+fn define_property_or_throw(
+    vm: &mut vm::VM2,
+    obj: Value,
+    key: Value,
+    desc: PropertyDescriptor,
+) -> Result<(), RuntimeError> {
+    if !obj
+        .get_object_info()
+        .define_own_property(&mut vm.memory_allocator, key, desc)?
+    {
+        return Err(RuntimeError::Type(format!(
+            "Cannot redefine property: {}",
+            key.debug_string(false)
+        )));
+    }
+
+    for val in [desc.value, desc.get, desc.set]
+        .iter()
+        .filter_map(|val| *val)
+    {
+        vm.memory_allocator.gray2(val);
+    }
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-topropertydescriptor
+fn to_property_descriptor(
+    vm: &mut vm::VM2,
+    obj: Value,
+    cur_frame: &frame::Frame,
+) -> Result<PropertyDescriptor, RuntimeError> {
+    if !is_object(obj) {
+        return Err(RuntimeError::Type(format!(
+            "Property description must be an object: {}",
+            obj.debug_string(true)
+        )));
+    }
+
+    let field = |vm: &mut vm::VM2, name: &str| -> Result<Option<Value>, RuntimeError> {
+        let key = Value::string(&mut vm.memory_allocator, name.to_string());
+        if !obj.has_property(&mut vm.memory_allocator, key) {
+            return Ok(None);
+        }
+        Ok(Some(vm.get_property(obj, key, cur_frame)?))
+    };
+
+    let desc = PropertyDescriptor {
+        enumerable: field(vm, "enumerable")?.map(|val| val.to_boolean()),
+        configurable: field(vm, "configurable")?.map(|val| val.to_boolean()),
+        value: field(vm, "value")?,
+        writable: field(vm, "writable")?.map(|val| val.to_boolean()),
+        get: field(vm, "get")?,
+        set: field(vm, "set")?,
+    };
+
+    for (name, accessor) in &[("Getter", desc.get), ("Setter", desc.set)] {
+        match accessor {
+            Some(f) if !f.is_undefined() && !f.is_function_object() => {
+                return Err(RuntimeError::Type(format!(
+                    "{} must be a function: {}",
+                    name,
+                    f.debug_string(true)
+                )))
+            }
+            _ => {}
+        }
+    }
+
+    if desc.is_accessor_descriptor() && desc.is_data_descriptor() {
+        return Err(RuntimeError::Type(
+            "Invalid property descriptor. Cannot both specify accessors and a value or writable attribute"
+                .to_string(),
+        ));
+    }
+
+    Ok(desc)
+}
+
+/// https://tc39.github.io/ecma262/#sec-frompropertydescriptor
+fn from_property_descriptor(vm: &mut vm::VM2, desc: PropertyDescriptor) -> Value {
+    let mut property = PropertyMap::default();
+    let fields = [
+        ("value", desc.value),
+        ("writable", desc.writable.map(Value::bool)),
+        ("get", desc.get),
+        ("set", desc.set),
+        ("enumerable", desc.enumerable.map(Value::bool)),
+        ("configurable", desc.configurable.map(Value::bool)),
+    ];
+    for (name, val) in &fields {
+        if let Some(val) = val {
+            property.insert(name.to_string(), Property::new_data_simple(*val));
+        }
+    }
+    Value::object(&mut vm.memory_allocator, &vm.object_prototypes, property)
+}
+
+/// Run f with the values locked, so that GC triggered by getters and setters doesn't free them.
+fn with_locked<T, F: FnOnce(&mut vm::VM2) -> T>(vm: &mut vm::VM2, vals: &[Value], f: F) -> T {
+    for val in vals {
+        vm.memory_allocator.lock(*val)
+    }
+    let ret = f(vm);
+    for val in vals {
+        vm.memory_allocator.unlock(*val)
+    }
+    ret
+}
@@ -0,0 +1,597 @@
+use gc;
+use std::cell::Cell;
+use std::mem;
+use std::rc::Rc;
+use vm::{
+    error::RuntimeError,
+    frame::Frame,
+    jsvalue::value::*,
+    vm::{VMResult, VM2},
+};
+
+pub fn promise(
+    memory_allocator: &mut gc::MemoryAllocator,
+    object_prototypes: &ObjectPrototypes,
+) -> Value {
+    let obj = Value::builtin_function(
+        memory_allocator,
+        object_prototypes,
+        "Promise".to_string(),
+        promise_constructor,
+    );
+    obj.set_property_by_string_key("prototype".to_string(), object_prototypes.promise);
+    obj.get_property_by_str_key("prototype")
+        .set_constructor(obj);
+
+    // Promise.resolve
+    obj.set_property_by_string_key("resolve".to_string(), {
+        Value::builtin_function(
+            memory_allocator,
+            object_prototypes,
+            "resolve".to_string(),
+            promise_resolve,
+        )
+    });
+    // Promise.reject
+    obj.set_property_by_string_key("reject".to_string(), {
+        Value::builtin_function(
+            memory_allocator,
+            object_prototypes,
+            "reject".to_string(),
+            promise_reject,
+        )
+    });
+    // Promise.all
+    obj.set_property_by_string_key("all".to_string(), {
+        Value::builtin_function(
+            memory_allocator,
+            object_prototypes,
+            "all".to_string(),
+            promise_all,
+        )
+    });
+    // Promise.race
+    obj.set_property_by_string_key("race".to_string(), {
+        Value::builtin_function(
+            memory_allocator,
+            object_prototypes,
+            "race".to_string(),
+            promise_race,
+        )
+    });
+
+    obj
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise-executor
+pub fn promise_constructor(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    if !cur_frame.constructor_call {
+        return Err(RuntimeError::Type(
+            "Promise constructor cannot be invoked without 'new'".to_string(),
+        ));
+    }
+
+    let executor = *args.get(0).unwrap_or(&Value::undefined());
+    if !executor.is_function_object() {
+        return Err(RuntimeError::Type(format!(
+            "Promise resolver {} is not a function",
+            executor.debug_string(true)
+        )));
+    }
+
+    let promise = Value::promise(&mut vm.memory_allocator, &vm.object_prototypes);
+    let (resolve, reject) = create_resolving_functions(vm, promise);
+
+    vm.memory_allocator.lock(promise);
+    let result = vm.call_function(executor, &[resolve, reject], Value::undefined(), cur_frame);
+    vm.memory_allocator.unlock(promise);
+
+    if let Err(err) = result {
+        let reason = err.to_value2(&mut vm.memory_allocator);
+        vm.call_function(reject, &[reason], Value::undefined(), cur_frame)?;
+    }
+    vm.stack.pop(); // Pop the return value of executor or reject
+
+    vm.stack.push(promise.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise.resolve
+pub fn promise_resolve(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let val = *args.get(0).unwrap_or(&Value::undefined());
+    let promise = to_promise(vm, val, cur_frame)?;
+    vm.stack.push(promise.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise.reject
+pub fn promise_reject(vm: &mut VM2, args: &[Value], _cur_frame: &Frame) -> VMResult {
+    let reason = *args.get(0).unwrap_or(&Value::undefined());
+    let promise = Value::promise(&mut vm.memory_allocator, &vm.object_prototypes);
+    reject_promise(vm, promise, reason);
+    vm.stack.push(promise.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise.all
+pub fn promise_all(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let iterable = *args.get(0).unwrap_or(&Value::undefined());
+    let promise = Value::promise(&mut vm.memory_allocator, &vm.object_prototypes);
+    let values = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, vec![]);
+
+    vm.memory_allocator.lock(promise);
+    vm.memory_allocator.lock(values);
+    let result = perform_promise_all(vm, iterable, promise, values, cur_frame);
+    vm.memory_allocator.unlock(values);
+    vm.memory_allocator.unlock(promise);
+
+    if let Err(err) = result {
+        let reason = err.to_value2(&mut vm.memory_allocator);
+        reject_promise(vm, promise, reason);
+    }
+
+    vm.stack.push(promise.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-performpromiseall
+fn perform_promise_all(
+    vm: &mut VM2,
+    iterable: Value,
+    promise: Value,
+    values: Value,
+    cur_frame: &Frame,
+) -> VMResult {
+    let iterator = vm.get_iterator(iterable, cur_frame)?;
+    // The count of the elements not yet fulfilled plus one, which is decremented after the
+    // iteration so that the promise is not resolved during the iteration.
+    let remaining = Rc::new(Cell::new(1));
+
+    vm.memory_allocator.lock(iterator);
+    let result: VMResult = (|| {
+        let mut index = 0;
+        while let Some(next_value) = vm.iterator_step(iterator, cur_frame)? {
+            values
+                .as_array_mut()
+                .elems
+                .push(Property::new_data_simple(Value::undefined()));
+            let next_promise = to_promise(vm, next_value, cur_frame)?;
+            remaining.set(remaining.get() + 1);
+            perform_promise_then(
+                vm,
+                next_promise,
+                PromiseReactionHandler::AllResolveElement {
+                    index,
+                    values,
+                    remaining: remaining.clone(),
+                    promise,
+                },
+                PromiseReactionHandler::Function(Value::undefined()),
+                Some(promise),
+            );
+            index += 1;
+        }
+        Ok(())
+    })();
+    vm.memory_allocator.unlock(iterator);
+    result?;
+
+    remaining.set(remaining.get() - 1);
+    if remaining.get() == 0 {
+        resolve_promise(vm, promise, values, cur_frame)?;
+    }
+
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise.race
+pub fn promise_race(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let iterable = *args.get(0).unwrap_or(&Value::undefined());
+    let promise = Value::promise(&mut vm.memory_allocator, &vm.object_prototypes);
+
+    vm.memory_allocator.lock(promise);
+    let result = perform_promise_race(vm, iterable, promise, cur_frame);
+    vm.memory_allocator.unlock(promise);
+
+    if let Err(err) = result {
+        let reason = err.to_value2(&mut vm.memory_allocator);
+        reject_promise(vm, promise, reason);
+    }
+
+    vm.stack.push(promise.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-performpromiserace
+fn perform_promise_race(
+    vm: &mut VM2,
+    iterable: Value,
+    promise: Value,
+    cur_frame: &Frame,
+) -> VMResult {
+    let iterator = vm.get_iterator(iterable, cur_frame)?;
+
+    vm.memory_allocator.lock(iterator);
+    let result: VMResult = (|| {
+        while let Some(next_value) = vm.iterator_step(iterator, cur_frame)? {
+            let next_promise = to_promise(vm, next_value, cur_frame)?;
+            perform_promise_then(
+                vm,
+                next_promise,
+                PromiseReactionHandler::Function(Value::undefined()),
+                PromiseReactionHandler::Function(Value::undefined()),
+                Some(promise),
+            );
+        }
+        Ok(())
+    })();
+    vm.memory_allocator.unlock(iterator);
+
+    result
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise.prototype.then
+pub fn promise_prototype_then(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let promise = cur_frame.this;
+    if !promise.is_promise() {
+        return Err(RuntimeError::Type(
+            "Promise.prototype.then called on incompatible receiver".to_string(),
+        ));
+    }
+
+    let handler = |arg: Option<&Value>| match arg {
+        Some(f) if f.is_function_object() => PromiseReactionHandler::Function(*f),
+        _ => PromiseReactionHandler::Function(Value::undefined()),
+    };
+
+    let derived = Value::promise(&mut vm.memory_allocator, &vm.object_prototypes);
+    perform_promise_then(
+        vm,
+        promise,
+        handler(args.get(0)),
+        handler(args.get(1)),
+        Some(derived),
+    );
+
+    vm.stack.push(derived.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise.prototype.catch
+pub fn promise_prototype_catch(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let on_rejected = *args.get(0).unwrap_or(&Value::undefined());
+    let then_key = Value::string(&mut vm.memory_allocator, "then".to_string());
+    let then = vm.get_property(cur_frame.this, then_key, cur_frame)?;
+    vm.call_function(
+        then,
+        &[Value::undefined(), on_rejected],
+        cur_frame.this,
+        cur_frame,
+    )
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise.prototype.finally
+pub fn promise_prototype_finally(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let promise = cur_frame.this;
+    if !promise.is_promise() {
+        return Err(RuntimeError::Type(
+            "Promise.prototype.finally called on incompatible receiver".to_string(),
+        ));
+    }
+
+    let on_finally = *args.get(0).unwrap_or(&Value::undefined());
+    let handler = if on_finally.is_function_object() {
+        PromiseReactionHandler::Finally(on_finally)
+    } else {
+        PromiseReactionHandler::Function(Value::undefined())
+    };
+
+    let derived = Value::promise(&mut vm.memory_allocator, &vm.object_prototypes);
+    perform_promise_then(vm, promise, handler.clone(), handler, Some(derived));
+
+    vm.stack.push(derived.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-createresolvingfunctions
+fn create_resolving_functions(vm: &mut VM2, promise: Value) -> (Value, Value) {
+    let already_resolved = Rc::new(Cell::new(false));
+    let resolve = Value::promise_resolving_function(
+        &mut vm.memory_allocator,
+        &vm.object_prototypes,
+        PromiseResolvingFunctionInfo {
+            promise,
+            kind: PromiseReactionKind::Fulfill,
+            already_resolved: already_resolved.clone(),
+        },
+    );
+    let reject = Value::promise_resolving_function(
+        &mut vm.memory_allocator,
+        &vm.object_prototypes,
+        PromiseResolvingFunctionInfo {
+            promise,
+            kind: PromiseReactionKind::Reject,
+            already_resolved,
+        },
+    );
+    (resolve, reject)
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise-resolve-functions
+/// https://tc39.github.io/ecma262/#sec-promise-reject-functions
+pub fn call_promise_resolving_function(
+    vm: &mut VM2,
+    info: &PromiseResolvingFunctionInfo,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> VMResult {
+    let resolution = *args.get(0).unwrap_or(&Value::undefined());
+    if !info.already_resolved.get() {
+        info.already_resolved.set(true);
+        match info.kind {
+            PromiseReactionKind::Fulfill => resolve_promise(vm, info.promise, resolution, cur_frame)?,
+            PromiseReactionKind::Reject => reject_promise(vm, info.promise, resolution),
+        }
+    }
+    vm.stack.push(Value::undefined().into());
+    Ok(())
+}
+
+/// Resolve the promise with the value, following it if it is a thenable. Promises already
+/// settled are left as they are, which may happen to the promise of Promise.race().
+/// https://tc39.github.io/ecma262/#sec-promise-resolve-functions
+pub fn resolve_promise(
+    vm: &mut VM2,
+    promise: Value,
+    resolution: Value,
+    cur_frame: &Frame,
+) -> VMResult {
+    if promise.as_promise_mut().state != PromiseState::Pending {
+        return Ok(());
+    }
+
+    if resolution == promise {
+        let reason = Value::string(
+            &mut vm.memory_allocator,
+            "Chaining cycle detected for promise".to_string(),
+        );
+        reject_promise(vm, promise, reason);
+        return Ok(());
+    }
+
+    if !resolution.is_object() {
+        settle_promise(vm, promise, PromiseReactionKind::Fulfill, resolution);
+        return Ok(());
+    }
+
+    let then_key = Value::string(&mut vm.memory_allocator, "then".to_string());
+    let then = match vm.get_property(resolution, then_key, cur_frame) {
+        Ok(then) => then,
+        Err(err) => {
+            let reason = err.to_value2(&mut vm.memory_allocator);
+            reject_promise(vm, promise, reason);
+            return Ok(());
+        }
+    };
+
+    if !then.is_function_object() {
+        settle_promise(vm, promise, PromiseReactionKind::Fulfill, resolution);
+        return Ok(());
+    }
+
+    vm.enqueue_job(Job::PromiseResolveThenable {
+        promise,
+        thenable: resolution,
+        then,
+    });
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-rejectpromise
+pub fn reject_promise(vm: &mut VM2, promise: Value, reason: Value) {
+    settle_promise(vm, promise, PromiseReactionKind::Reject, reason)
+}
+
+/// https://tc39.github.io/ecma262/#sec-fulfillpromise
+/// https://tc39.github.io/ecma262/#sec-rejectpromise
+fn settle_promise(vm: &mut VM2, promise: Value, kind: PromiseReactionKind, val: Value) {
+    let info = promise.as_promise_mut();
+    if info.state != PromiseState::Pending {
+        return;
+    }
+
+    let fulfill_reactions = mem::replace(&mut info.fulfill_reactions, vec![]);
+    let reject_reactions = mem::replace(&mut info.reject_reactions, vec![]);
+    let reactions = match kind {
+        PromiseReactionKind::Fulfill => {
+            info.state = PromiseState::Fulfilled;
+            fulfill_reactions
+        }
+        PromiseReactionKind::Reject => {
+            info.state = PromiseState::Rejected;
+            reject_reactions
+        }
+    };
+    info.result = val;
+
+    // https://tc39.github.io/ecma262/#sec-host-promise-rejection-tracker
+    if kind == PromiseReactionKind::Reject && !info.is_handled {
+        vm.rejected_promises.push(promise);
+    }
+
+    // https://tc39.github.io/ecma262/#sec-triggerpromisereactions
+    for reaction in reactions {
+        vm.enqueue_job(Job::PromiseReaction {
+            reaction,
+            argument: val,
+        });
+    }
+}
+
+/// Return the value if it is a promise, otherwise a new promise resolved with the value.
+/// https://tc39.github.io/ecma262/#sec-promise-resolve
+pub fn to_promise(vm: &mut VM2, val: Value, cur_frame: &Frame) -> Result<Value, RuntimeError> {
+    if val.is_promise() {
+        return Ok(val);
+    }
+
+    let promise = Value::promise(&mut vm.memory_allocator, &vm.object_prototypes);
+    resolve_promise(vm, promise, val, cur_frame)?;
+    Ok(promise)
+}
+
+/// https://tc39.github.io/ecma262/#sec-performpromisethen
+pub fn perform_promise_then(
+    vm: &mut VM2,
+    promise: Value,
+    on_fulfilled: PromiseReactionHandler,
+    on_rejected: PromiseReactionHandler,
+    capability: Option<Value>,
+) {
+    let fulfill_reaction = PromiseReaction {
+        capability,
+        kind: PromiseReactionKind::Fulfill,
+        handler: on_fulfilled,
+    };
+    let reject_reaction = PromiseReaction {
+        capability,
+        kind: PromiseReactionKind::Reject,
+        handler: on_rejected,
+    };
+
+    let info = promise.as_promise_mut();
+    if info.state == PromiseState::Rejected && !info.is_handled {
+        vm.rejected_promises.retain(|rejected| *rejected != promise);
+    }
+    info.is_handled = true;
+
+    match info.state {
+        PromiseState::Pending => {
+            info.fulfill_reactions.push(fulfill_reaction);
+            info.reject_reactions.push(reject_reaction);
+        }
+        PromiseState::Fulfilled => vm.enqueue_job(Job::PromiseReaction {
+            reaction: fulfill_reaction,
+            argument: info.result,
+        }),
+        PromiseState::Rejected => vm.enqueue_job(Job::PromiseReaction {
+            reaction: reject_reaction,
+            argument: info.result,
+        }),
+    }
+}
+
+pub fn run_job(vm: &mut VM2, job: Job, cur_frame: &Frame) -> VMResult {
+    match job {
+        Job::PromiseReaction { reaction, argument } => {
+            promise_reaction_job(vm, reaction, argument, cur_frame)
+        }
+        Job::PromiseResolveThenable {
+            promise,
+            thenable,
+            then,
+        } => promise_resolve_thenable_job(vm, promise, thenable, then, cur_frame),
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-promisereactionjob
+fn promise_reaction_job(
+    vm: &mut VM2,
+    reaction: PromiseReaction,
+    argument: Value,
+    cur_frame: &Frame,
+) -> VMResult {
+    let handler_result = match reaction.handler {
+        PromiseReactionHandler::Function(handler) if handler.is_undefined() => {
+            match reaction.kind {
+                PromiseReactionKind::Fulfill => Ok(argument),
+                PromiseReactionKind::Reject => Err(argument),
+            }
+        }
+        PromiseReactionHandler::Function(handler) => call(vm, handler, &[argument], cur_frame),
+        PromiseReactionHandler::ValueThunk(val) => Ok(val),
+        PromiseReactionHandler::Thrower(val) => Err(val),
+        PromiseReactionHandler::Finally(on_finally) => {
+            let promise = match call(vm, on_finally, &[], cur_frame) {
+                Ok(result) => match to_promise(vm, result, cur_frame) {
+                    Ok(promise) => Ok(promise),
+                    Err(err) => Err(err.to_value2(&mut vm.memory_allocator)),
+                },
+                Err(err) => Err(err),
+            };
+            match promise {
+                // The original value or reason is passed through after the promise returned
+                // by onFinally is fulfilled.
+                Ok(promise) => {
+                    let on_fulfilled = match reaction.kind {
+                        PromiseReactionKind::Fulfill => PromiseReactionHandler::ValueThunk(argument),
+                        PromiseReactionKind::Reject => PromiseReactionHandler::Thrower(argument),
+                    };
+                    perform_promise_then(
+                        vm,
+                        promise,
+                        on_fulfilled,
+                        PromiseReactionHandler::Function(Value::undefined()),
+                        reaction.capability,
+                    );
+                    return Ok(());
+                }
+                Err(err) => Err(err),
+            }
+        }
+        PromiseReactionHandler::Await { generator, promise } => {
+            let kind = match reaction.kind {
+                PromiseReactionKind::Fulfill => ResumeKind::Next,
+                PromiseReactionKind::Reject => ResumeKind::Throw,
+            };
+            return vm.resume_async_function(generator, promise, argument, kind);
+        }
+        PromiseReactionHandler::AllResolveElement {
+            index,
+            values,
+            remaining,
+            promise,
+        } => {
+            values.as_array_mut().elems[index] = Property::new_data_simple(argument);
+            remaining.set(remaining.get() - 1);
+            if remaining.get() == 0 {
+                resolve_promise(vm, promise, values, cur_frame)?;
+            }
+            return Ok(());
+        }
+    };
+
+    if let Some(capability) = reaction.capability {
+        match handler_result {
+            Ok(val) => resolve_promise(vm, capability, val, cur_frame)?,
+            Err(reason) => reject_promise(vm, capability, reason),
+        }
+    }
+
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-promiseresolvethenablejob
+fn promise_resolve_thenable_job(
+    vm: &mut VM2,
+    promise: Value,
+    thenable: Value,
+    then: Value,
+    cur_frame: &Frame,
+) -> VMResult {
+    let (resolve, reject) = create_resolving_functions(vm, promise);
+    if let Err(err) = vm.call_function(then, &[resolve, reject], thenable, cur_frame) {
+        let reason = err.to_value2(&mut vm.memory_allocator);
+        vm.call_function(reject, &[reason], Value::undefined(), cur_frame)?;
+    }
+    vm.stack.pop(); // Pop the return value of then or reject
+    Ok(())
+}
+
+/// Call the function and return the thrown value as Err if an exception is thrown.
+fn call(vm: &mut VM2, callee: Value, args: &[Value], cur_frame: &Frame) -> Result<Value, Value> {
+    match vm.call_function(callee, args, Value::undefined(), cur_frame) {
+        Ok(()) => Ok(vm.stack.pop().unwrap().into()),
+        Err(err) => Err(err.to_value2(&mut vm.memory_allocator)),
+    }
+}
@@ -0,0 +1,292 @@
+use gc::MemoryAllocator;
+use regexp::{Captures, Flags, RegExp};
+use vm::{
+    error::RuntimeError,
+    frame::Frame,
+    jsvalue::value::*,
+    vm::{VMResult, VM2},
+};
+
+pub fn regexp(
+    memory_allocator: &mut MemoryAllocator,
+    object_prototypes: &ObjectPrototypes,
+) -> Value {
+    let obj = Value::builtin_function(
+        memory_allocator,
+        object_prototypes,
+        "RegExp".to_string(),
+        regexp_constructor,
+    );
+    obj.set_property_by_string_key("prototype".to_string(), object_prototypes.regexp);
+    obj.get_property_by_str_key("prototype")
+        .set_constructor(obj);
+    obj
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexp-pattern-flags
+pub fn regexp_constructor(vm: &mut VM2, args: &[Value], _cur_frame: &Frame) -> VMResult {
+    let pattern = args.get(0).map(|x| *x).unwrap_or(Value::undefined());
+    let flags = args.get(1).map(|x| *x).unwrap_or(Value::undefined());
+    let (source, flags) = if pattern.is_regexp() {
+        let regexp = pattern.as_regexp();
+        let flags = if flags.is_undefined() {
+            regexp.flags.to_string()
+        } else {
+            flags.to_string()
+        };
+        (regexp.source.clone(), flags)
+    } else {
+        let source = if pattern.is_undefined() {
+            vec![]
+        } else {
+            pattern.to_js_string().units().to_vec()
+        };
+        let flags = if flags.is_undefined() {
+            "".to_string()
+        } else {
+            flags.to_string()
+        };
+        (source, flags)
+    };
+    let regexp = create_regexp(vm, source, &flags)?;
+    vm.stack.push(regexp.into());
+    Ok(())
+}
+
+/// Create a RegExp object, or return an error if the pattern or flags are invalid.
+pub fn create_regexp(vm: &mut VM2, source: Vec<u16>, flags: &str) -> Result<Value, RuntimeError> {
+    let pattern = String::from_utf16_lossy(&source);
+    match RegExp::from_units(source, flags) {
+        Ok(regexp) => Ok(Value::regexp(
+            &mut vm.memory_allocator,
+            &vm.object_prototypes,
+            regexp,
+        )),
+        Err(msg) => Err(RuntimeError::General(format!(
+            "Invalid regular expression: /{}/: {}",
+            pattern, msg
+        ))),
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexpbuiltinexec
+/// Return the captures if matched. Indices in them are in UTF-16 code units.
+pub fn regexp_builtin_exec(vm: &mut VM2, regexp_obj: Value, input: &JSString) -> Option<Captures> {
+    let regexp = regexp_obj.as_regexp();
+    let global_or_sticky = regexp.flags.global || regexp.flags.sticky;
+    let mut last_index = if global_or_sticky {
+        let last_index = regexp_obj
+            .get_property_by_str_key("lastIndex")
+            .to_number(&mut vm.memory_allocator);
+        if last_index.is_nan() || last_index <= 0.0 {
+            0
+        } else {
+            last_index as usize
+        }
+    } else {
+        0
+    };
+
+    loop {
+        if last_index > input.len() {
+            if global_or_sticky {
+                set_last_index(regexp_obj, 0);
+            }
+            return None;
+        }
+        if let Some(captures) = regexp.match_at(&input.units(), last_index) {
+            if global_or_sticky {
+                set_last_index(regexp_obj, captures[0].unwrap().1);
+            }
+            return Some(captures);
+        }
+        if regexp.flags.sticky {
+            set_last_index(regexp_obj, 0);
+            return None;
+        }
+        last_index = advance_string_index(input, last_index, regexp.flags.unicode);
+    }
+}
+
+/// Return the index next to `index`, skipping a surrogate pair if `unicode` is true.
+/// https://tc39.github.io/ecma262/#sec-advancestringindex
+pub fn advance_string_index(input: &JSString, index: usize, unicode: bool) -> usize {
+    match input.code_point_at(index) {
+        Some((_, len)) if unicode => index + len,
+        _ => index + 1,
+    }
+}
+
+pub fn set_last_index(regexp_obj: Value, last_index: usize) {
+    regexp_obj.set_property_by_string_key("lastIndex".to_string(), Value::Number(last_index as f64))
+}
+
+/// Create the array returned by RegExp.prototype.exec.
+pub fn create_match_array(
+    vm: &mut VM2,
+    regexp: &RegExp,
+    input: &JSString,
+    input_val: Value,
+    captures: &Captures,
+) -> Value {
+    let elems = captures
+        .iter()
+        .map(|capture| Property::new_data_simple(capture_to_value(vm, input, *capture)))
+        .collect::<Vec<Property>>();
+    let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, elems);
+    ary.set_property_by_string_key(
+        "index".to_string(),
+        Value::Number(captures[0].unwrap().0 as f64),
+    );
+    ary.set_property_by_string_key("input".to_string(), input_val);
+    let groups = create_groups_object(vm, regexp, input, captures);
+    ary.set_property_by_string_key("groups".to_string(), groups);
+    ary
+}
+
+/// Create the object which maps the names of the named capturing groups to their captures.
+/// Return undefined if there are no named groups.
+pub fn create_groups_object(
+    vm: &mut VM2,
+    regexp: &RegExp,
+    input: &JSString,
+    captures: &Captures,
+) -> Value {
+    if regexp.group_names.is_empty() {
+        return Value::undefined();
+    }
+    let groups = Value::object(
+        &mut vm.memory_allocator,
+        &vm.object_prototypes,
+        PropertyMap::default(),
+    );
+    groups.get_object_info().prototype = Value::null();
+    for (name, index) in &regexp.group_names {
+        let capture = capture_to_value(vm, input, captures[*index]);
+        groups.set_property_by_string_key(name.clone(), capture);
+    }
+    groups
+}
+
+pub fn capture_to_value(vm: &mut VM2, input: &JSString, capture: Option<(usize, usize)>) -> Value {
+    match capture {
+        Some((start, end)) => {
+            Value::js_string(&mut vm.memory_allocator, input.substring(start, end))
+        }
+        None => Value::undefined(),
+    }
+}
+
+fn this_regexp(cur_frame: &Frame, method_name: &str) -> Result<Value, RuntimeError> {
+    if cur_frame.this.is_regexp() {
+        Ok(cur_frame.this)
+    } else {
+        Err(RuntimeError::Type(format!(
+            "RegExp.prototype.{} called on incompatible receiver",
+            method_name
+        )))
+    }
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexp.prototype.exec
+pub fn regexp_prototype_exec(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let regexp_obj = this_regexp(cur_frame, "exec")?;
+    let input_val = args.get(0).map(|x| *x).unwrap_or(Value::undefined());
+    let input = input_val.to_js_string();
+    let input_val = if input_val.is_string() {
+        input_val
+    } else {
+        Value::js_string(&mut vm.memory_allocator, input.clone())
+    };
+    let result = match regexp_builtin_exec(vm, regexp_obj, &input) {
+        Some(captures) => {
+            create_match_array(vm, regexp_obj.as_regexp(), &input, input_val, &captures)
+        }
+        None => Value::null(),
+    };
+    vm.stack.push(result.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexp.prototype.test
+pub fn regexp_prototype_test(vm: &mut VM2, args: &[Value], cur_frame: &Frame) -> VMResult {
+    let regexp_obj = this_regexp(cur_frame, "test")?;
+    let input = args.get(0).unwrap_or(&Value::undefined()).to_js_string();
+    let matched = regexp_builtin_exec(vm, regexp_obj, &input).is_some();
+    vm.stack.push(Value::bool(matched).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexp.prototype.tostring
+pub fn regexp_prototype_to_string(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    let regexp_obj = this_regexp(cur_frame, "toString")?;
+    let string = JSString::new(regexp_obj.as_regexp().to_units());
+    let string = Value::js_string(&mut vm.memory_allocator, string);
+    vm.stack.push(string.into());
+    Ok(())
+}
+
+/// get RegExp.prototype.source
+pub fn regexp_prototype_source(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    let source = if cur_frame.this.is_regexp() {
+        JSString::new(cur_frame.this.as_regexp().escaped_source())
+    } else {
+        JSString::from("(?:)")
+    };
+    let source = Value::js_string(&mut vm.memory_allocator, source);
+    vm.stack.push(source.into());
+    Ok(())
+}
+
+/// get RegExp.prototype.flags
+pub fn regexp_prototype_flags(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    let flags = if cur_frame.this.is_regexp() {
+        cur_frame.this.as_regexp().flags.to_string()
+    } else {
+        "".to_string()
+    };
+    let flags = Value::string(&mut vm.memory_allocator, flags);
+    vm.stack.push(flags.into());
+    Ok(())
+}
+
+/// Push the flag of this value, or undefined if this value is not a RegExp object.
+fn push_flag(vm: &mut VM2, cur_frame: &Frame, flag: fn(&Flags) -> bool) -> VMResult {
+    let val = if cur_frame.this.is_regexp() {
+        Value::bool(flag(&cur_frame.this.as_regexp().flags))
+    } else {
+        Value::undefined()
+    };
+    vm.stack.push(val.into());
+    Ok(())
+}
+
+/// get RegExp.prototype.global
+pub fn regexp_prototype_global(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    push_flag(vm, cur_frame, |flags| flags.global)
+}
+
+/// get RegExp.prototype.ignoreCase
+pub fn regexp_prototype_ignore_case(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    push_flag(vm, cur_frame, |flags| flags.ignore_case)
+}
+
+/// get RegExp.prototype.multiline
+pub fn regexp_prototype_multiline(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    push_flag(vm, cur_frame, |flags| flags.multiline)
+}
+
+/// get RegExp.prototype.dotAll
+pub fn regexp_prototype_dot_all(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    push_flag(vm, cur_frame, |flags| flags.dot_all)
+}
+
+/// get RegExp.prototype.unicode
+pub fn regexp_prototype_unicode(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    push_flag(vm, cur_frame, |flags| flags.unicode)
+}
+
+/// get RegExp.prototype.sticky
+pub fn regexp_prototype_sticky(vm: &mut VM2, _args: &[Value], cur_frame: &Frame) -> VMResult {
+    push_flag(vm, cur_frame, |flags| flags.sticky)
+}
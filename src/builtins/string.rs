@@ -1,44 +1,1092 @@
+use builtins::array::relative_index;
+use builtins::iterator::create_iter_result_object;
+use builtins::regexp::{
+    advance_string_index, capture_to_value, create_groups_object, create_match_array,
+    create_regexp, regexp_builtin_exec, set_last_index,
+};
+use gc::MemoryAllocator;
+use regexp::Captures;
+use unicode_normalization::UnicodeNormalization;
 use vm::{error::RuntimeError, frame::Frame, jsvalue::value::*, vm::VM2};
 
+pub fn string(
+    memory_allocator: &mut MemoryAllocator,
+    object_prototypes: &ObjectPrototypes,
+) -> Value {
+    let obj = Value::builtin_function(
+        memory_allocator,
+        object_prototypes,
+        "String".to_string(),
+        string_constructor,
+    );
+
+    // String.fromCharCode
+    obj.set_property_by_string_key("fromCharCode".to_string(), {
+        Value::builtin_function(
+            memory_allocator,
+            object_prototypes,
+            "fromCharCode".to_string(),
+            string_from_char_code,
+        )
+    });
+    // String.fromCodePoint
+    obj.set_property_by_string_key("fromCodePoint".to_string(), {
+        Value::builtin_function(
+            memory_allocator,
+            object_prototypes,
+            "fromCodePoint".to_string(),
+            string_from_code_point,
+        )
+    });
+
+    obj.set_property_by_string_key("prototype".to_string(), object_prototypes.string);
+    obj.get_property_by_str_key("prototype")
+        .set_constructor(obj);
+    obj
+}
+
+/// https://tc39.github.io/ecma262/#sec-string-constructor-string-value
+/// TODO: String objects are not supported, so `new String(value)` also returns a primitive.
+pub fn string_constructor(
+    vm: &mut VM2,
+    args: &[Value],
+    _cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = match args.get(0) {
+        None => JSString::from(""),
+        Some(val) if val.is_symbol() => JSString::from(format!(
+            "Symbol({})",
+            val.get_symbol_info().get_description_string()
+        )),
+        Some(val) => val.to_js_string(),
+    };
+    let string = Value::js_string(&mut vm.memory_allocator, string);
+    vm.stack.push(string.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.fromcharcode
+pub fn string_from_char_code(
+    vm: &mut VM2,
+    args: &[Value],
+    _cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let units = args
+        .iter()
+        .map(|arg| to_uint16(arg.to_number(&mut vm.memory_allocator)))
+        .collect::<Vec<u16>>();
+    let string = Value::js_string(&mut vm.memory_allocator, JSString::new(units));
+    vm.stack.push(string.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.fromcodepoint
+pub fn string_from_code_point(
+    vm: &mut VM2,
+    args: &[Value],
+    _cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let mut units = vec![];
+    for arg in args {
+        let code_point = arg.to_number(&mut vm.memory_allocator);
+        if !is_integer(code_point) || code_point < 0.0 || code_point > 1114111.0 {
+            return Err(RuntimeError::General(format!(
+                "Invalid code point {}",
+                Value::Number(code_point).to_string()
+            )));
+        }
+        let code_point = code_point as u32;
+        if code_point < 0x10000 {
+            units.push(code_point as u16);
+        } else {
+            let code_point = code_point - 0x10000;
+            units.push(0xD800 + (code_point >> 10) as u16);
+            units.push(0xDC00 + (code_point & 0x3FF) as u16);
+        }
+    }
+    let string = Value::js_string(&mut vm.memory_allocator, JSString::new(units));
+    vm.stack.push(string.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.split
 pub fn string_prototype_split(
     vm: &mut VM2,
     args: &[Value],
     cur_frame: &Frame,
 ) -> Result<(), RuntimeError> {
-    let string = cur_frame.this.into_str();
+    let string = this_string(cur_frame, "split")?;
     let separator_ = args.get(0).map(|x| *x).unwrap_or(Value::undefined());
+    let limit = match args.get(1) {
+        Some(limit) if !limit.is_undefined() => {
+            let limit = limit.to_number(&mut vm.memory_allocator);
+            if limit.is_nan() {
+                0
+            } else {
+                limit as i64 as u32 as usize
+            }
+        }
+        _ => ::std::u32::MAX as usize,
+    };
+    if limit == 0 {
+        let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, vec![]);
+        vm.stack.push(ary.into());
+        return Ok(());
+    }
     if separator_.is_undefined() {
+        let string = Value::js_string(&mut vm.memory_allocator, string);
         let ary = Value::array(
             &mut vm.memory_allocator,
             &vm.object_prototypes,
-            vec![Property::new_data_simple(cur_frame.this)],
+            vec![Property::new_data_simple(string)],
         );
         vm.stack.push(ary.into());
         return Ok(());
     }
-    let separator = separator_.to_string();
-    let elems = string
-        .split(separator.as_str())
-        .collect::<Vec<&str>>()
-        .iter()
-        .map(|s| Property::new_data_simple(Value::string(&mut vm.memory_allocator, s.to_string())))
+    if separator_.is_regexp() {
+        let elems = split_by_regexp(vm, separator_, &string, limit);
+        let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, elems);
+        vm.stack.push(ary.into());
+        return Ok(());
+    }
+    let separator = separator_.to_js_string();
+    let ranges = split_by_string(&string.units(), &separator.units(), limit);
+    let elems = ranges
+        .into_iter()
+        .map(|(start, end)| {
+            let substring =
+                Value::js_string(&mut vm.memory_allocator, string.substring(start, end));
+            Property::new_data_simple(substring)
+        })
         .collect::<Vec<Property>>();
     let ary = Value::array(&mut vm.memory_allocator, &vm.object_prototypes, elems);
     vm.stack.push(ary.into());
     Ok(())
 }
 
+/// Split the input by the separator in UTF-16 code units, and return the ranges of the
+/// substrings. An empty separator splits the input into code units.
+fn split_by_string(input: &[u16], separator: &[u16], limit: usize) -> Vec<(usize, usize)> {
+    let size = input.len();
+    if size == 0 {
+        return if separator.is_empty() {
+            vec![]
+        } else {
+            vec![(0, 0)]
+        };
+    }
+
+    let mut substrings = vec![];
+    let mut p = 0;
+    let mut q = 0;
+    while q < size {
+        if !input[q..].starts_with(separator) {
+            q += 1;
+            continue;
+        }
+        let e = q + separator.len();
+        if e == p {
+            q += 1;
+            continue;
+        }
+        substrings.push((p, q));
+        if substrings.len() == limit {
+            return substrings;
+        }
+        p = e;
+        q = p;
+    }
+    substrings.push((p, size));
+    substrings
+}
+
+/// https://tc39.github.io/ecma262/#sec-regexp.prototype-@@split
+/// Captures in the separator are also included in the result.
+fn split_by_regexp(
+    vm: &mut VM2,
+    regexp_obj: Value,
+    input: &JSString,
+    limit: usize,
+) -> Vec<Property> {
+    let regexp = regexp_obj.as_regexp();
+    let unicode = regexp.flags.unicode;
+    let size = input.len();
+    let mut elems = vec![];
+    if size == 0 {
+        if regexp.match_at(&input.units(), 0).is_none() {
+            elems.push(Property::new_data_simple(capture_to_value(
+                vm,
+                input,
+                Some((0, 0)),
+            )));
+        }
+        return elems;
+    }
+
+    let mut p = 0;
+    let mut q = 0;
+    while q < size {
+        let captures = match regexp.match_at(&input.units(), q) {
+            Some(captures) => captures,
+            None => {
+                q = advance_string_index(input, q, unicode);
+                continue;
+            }
+        };
+        let e = captures[0].unwrap().1.min(size);
+        if e == p {
+            q = advance_string_index(input, q, unicode);
+            continue;
+        }
+        for capture in ::std::iter::once(Some((p, q))).chain(captures[1..].iter().cloned()) {
+            elems.push(Property::new_data_simple(capture_to_value(
+                vm, input, capture,
+            )));
+            if elems.len() == limit {
+                return elems;
+            }
+        }
+        p = e;
+        q = p;
+    }
+    elems.push(Property::new_data_simple(capture_to_value(
+        vm,
+        input,
+        Some((p, size)),
+    )));
+    elems
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.indexof
 pub fn string_prototype_index_of(
     vm: &mut VM2,
     args: &[Value],
     cur_frame: &Frame,
 ) -> Result<(), RuntimeError> {
-    let string = cur_frame.this.into_str();
-    let search_string = args.get(0).unwrap_or(&Value::undefined()).to_string();
-    let position = args.get(1).unwrap_or(&Value::Number(0.0)).into_number() as usize;
-    let found_pos = string[position..]
-        .find(search_string.as_str())
-        .map_or(-1.0, |p| p as f64);
+    let string = this_string(cur_frame, "indexOf")?;
+    let search = args.get(0).unwrap_or(&Value::undefined()).to_js_string();
+    let start = position_arg(vm, args.get(1), string.len());
+    let found_pos = string.index_of(&search, start).map_or(-1.0, |k| k as f64);
+    vm.stack.push(Value::Number(found_pos).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.lastindexof
+pub fn string_prototype_last_index_of(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "lastIndexOf")?;
+    let search = args.get(0).unwrap_or(&Value::undefined()).to_js_string();
+    let position = args.get(1).map_or(::std::f64::NAN, |pos| {
+        pos.to_number(&mut vm.memory_allocator)
+    });
+    let start = if position.is_nan() {
+        string.len()
+    } else {
+        position.trunc().max(0.0).min(string.len() as f64) as usize
+    };
+    let found_pos = string
+        .last_index_of(&search, start)
+        .map_or(-1.0, |k| k as f64);
     vm.stack.push(Value::Number(found_pos).into());
     Ok(())
 }
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.includes
+pub fn string_prototype_includes(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "includes")?;
+    let search = search_string_arg(args, "includes")?;
+    let start = position_arg(vm, args.get(1), string.len());
+    let found = string.index_of(&search, start).is_some();
+    vm.stack.push(Value::bool(found).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.startswith
+pub fn string_prototype_starts_with(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "startsWith")?;
+    let search = search_string_arg(args, "startsWith")?;
+    let start = position_arg(vm, args.get(1), string.len());
+    let found = string.units()[start..].starts_with(&search.units());
+    vm.stack.push(Value::bool(found).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.endswith
+pub fn string_prototype_ends_with(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "endsWith")?;
+    let search = search_string_arg(args, "endsWith")?;
+    let end = match args.get(1) {
+        Some(end) if !end.is_undefined() => position_arg(vm, Some(end), string.len()),
+        _ => string.len(),
+    };
+    let found = string.units()[..end].ends_with(&search.units());
+    vm.stack.push(Value::bool(found).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.charat
+pub fn string_prototype_char_at(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "charAt")?;
+    let char = match code_unit_index(vm, args.get(0), string.len()) {
+        Some(position) => string.substring(position, position + 1),
+        None => JSString::from(""),
+    };
+    let char = Value::js_string(&mut vm.memory_allocator, char);
+    vm.stack.push(char.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.charcodeat
+pub fn string_prototype_char_code_at(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "charCodeAt")?;
+    let code = match code_unit_index(vm, args.get(0), string.len()) {
+        Some(position) => string.code_unit_at(position).unwrap() as f64,
+        None => ::std::f64::NAN,
+    };
+    vm.stack.push(Value::Number(code).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.codepointat
+pub fn string_prototype_code_point_at(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "codePointAt")?;
+    let code_point = match code_unit_index(vm, args.get(0), string.len()) {
+        Some(position) => Value::Number(string.code_point_at(position).unwrap().0 as f64),
+        None => Value::undefined(),
+    };
+    vm.stack.push(code_point.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.slice
+pub fn string_prototype_slice(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "slice")?;
+    let len = string.len();
+    let from = relative_index(vm, args.get(0), len, 0);
+    let to = relative_index(vm, args.get(1), len, len);
+    let sliced = string.substring(from, to.max(from));
+    let sliced = Value::js_string(&mut vm.memory_allocator, sliced);
+    vm.stack.push(sliced.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.substring
+pub fn string_prototype_substring(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "substring")?;
+    let len = string.len();
+    let start = position_arg(vm, args.get(0), len);
+    let end = match args.get(1) {
+        Some(end) if !end.is_undefined() => position_arg(vm, Some(end), len),
+        _ => len,
+    };
+    let substring = string.substring(start.min(end), start.max(end));
+    let substring = Value::js_string(&mut vm.memory_allocator, substring);
+    vm.stack.push(substring.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.substr
+pub fn string_prototype_substr(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "substr")?;
+    let len = string.len();
+    let start = relative_index(vm, args.get(0), len, 0);
+    let length = match args.get(1) {
+        Some(length) if !length.is_undefined() => {
+            let length = length.to_integer(&mut vm.memory_allocator);
+            length.max(0.0).min((len - start) as f64) as usize
+        }
+        _ => len - start,
+    };
+    let substring = string.substring(start, start + length);
+    let substring = Value::js_string(&mut vm.memory_allocator, substring);
+    vm.stack.push(substring.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.touppercase
+pub fn string_prototype_to_upper_case(
+    vm: &mut VM2,
+    _args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "toUpperCase")?;
+    let string = map_well_formed(&string, str::to_uppercase);
+    let string = Value::js_string(&mut vm.memory_allocator, string);
+    vm.stack.push(string.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.tolowercase
+pub fn string_prototype_to_lower_case(
+    vm: &mut VM2,
+    _args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "toLowerCase")?;
+    let string = map_well_formed(&string, str::to_lowercase);
+    let string = Value::js_string(&mut vm.memory_allocator, string);
+    vm.stack.push(string.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.trim
+pub fn string_prototype_trim(
+    vm: &mut VM2,
+    _args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "trim")?;
+    let trimmed = trim_string(&string, true, true);
+    let trimmed = Value::js_string(&mut vm.memory_allocator, trimmed);
+    vm.stack.push(trimmed.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.trimstart
+pub fn string_prototype_trim_start(
+    vm: &mut VM2,
+    _args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "trimStart")?;
+    let trimmed = trim_string(&string, true, false);
+    let trimmed = Value::js_string(&mut vm.memory_allocator, trimmed);
+    vm.stack.push(trimmed.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.trimend
+pub fn string_prototype_trim_end(
+    vm: &mut VM2,
+    _args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "trimEnd")?;
+    let trimmed = trim_string(&string, false, true);
+    let trimmed = Value::js_string(&mut vm.memory_allocator, trimmed);
+    vm.stack.push(trimmed.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.padstart
+pub fn string_prototype_pad_start(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "padStart")?;
+    let padded = match filler(vm, args, string.len())? {
+        Some(filler) => JSString::new(filler).concat(&string),
+        None => string,
+    };
+    let padded = Value::js_string(&mut vm.memory_allocator, padded);
+    vm.stack.push(padded.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.padend
+pub fn string_prototype_pad_end(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "padEnd")?;
+    let padded = match filler(vm, args, string.len())? {
+        Some(filler) => string.concat(&JSString::new(filler)),
+        None => string,
+    };
+    let padded = Value::js_string(&mut vm.memory_allocator, padded);
+    vm.stack.push(padded.into());
+    Ok(())
+}
+
+/// Return the padding for padStart and padEnd, or None if the string needs no padding.
+/// https://tc39.github.io/ecma262/#sec-stringpad
+fn filler(vm: &mut VM2, args: &[Value], len: usize) -> Result<Option<Vec<u16>>, RuntimeError> {
+    let max_length = args.get(0).map_or(0.0, |max_length| {
+        max_length.to_integer(&mut vm.memory_allocator)
+    });
+    if max_length <= len as f64 {
+        return Ok(None);
+    }
+
+    let fill_string = match args.get(1) {
+        Some(fill_string) if !fill_string.is_undefined() => {
+            fill_string.to_js_string().units().to_vec()
+        }
+        _ => vec![' ' as u16],
+    };
+    if fill_string.is_empty() {
+        return Ok(None);
+    }
+    if max_length > MAX_STRING_LENGTH as f64 {
+        return Err(RuntimeError::Range("Invalid string length".to_string()));
+    }
+
+    let fill_len = max_length as usize - len;
+    Ok(Some(
+        fill_string.into_iter().cycle().take(fill_len).collect(),
+    ))
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.repeat
+pub fn string_prototype_repeat(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "repeat")?;
+    let count = args
+        .get(0)
+        .map_or(0.0, |count| count.to_integer(&mut vm.memory_allocator));
+    if count < 0.0 || count.is_infinite() {
+        return Err(RuntimeError::Range(format!(
+            "Invalid count value: {}",
+            Value::Number(count).to_string()
+        )));
+    }
+    if string.len() as f64 * count > MAX_STRING_LENGTH as f64 {
+        return Err(RuntimeError::Range("Invalid string length".to_string()));
+    }
+    let repeated = JSString::new(string.units().repeat(count as usize));
+    let repeated = Value::js_string(&mut vm.memory_allocator, repeated);
+    vm.stack.push(repeated.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.concat
+pub fn string_prototype_concat(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let mut string = this_string(cur_frame, "concat")?;
+    for arg in args {
+        string = string.concat(&arg.to_js_string());
+    }
+    let string = Value::js_string(&mut vm.memory_allocator, string);
+    vm.stack.push(string.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.localecompare
+/// Locale-sensitive collation is not supported. The strings are compared by code units.
+pub fn string_prototype_locale_compare(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "localeCompare")?;
+    let that = args.get(0).unwrap_or(&Value::undefined()).to_js_string();
+    let order = match string.cmp(&that) {
+        ::std::cmp::Ordering::Less => -1.0,
+        ::std::cmp::Ordering::Equal => 0.0,
+        ::std::cmp::Ordering::Greater => 1.0,
+    };
+    vm.stack.push(Value::Number(order).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.normalize
+pub fn string_prototype_normalize(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let string = this_string(cur_frame, "normalize")?;
+    let form = match args.get(0) {
+        Some(form) if !form.is_undefined() => form.to_string(),
+        _ => "NFC".to_string(),
+    };
+    match form.as_str() {
+        "NFC" | "NFD" | "NFKC" | "NFKD" => {}
+        _ => {
+            return Err(RuntimeError::Range(format!(
+                "The normalization form should be one of NFC, NFD, NFKC, NFKD: {}",
+                form
+            )))
+        }
+    }
+
+    // Lone surrogates are not normalized. Each run of code points between them is.
+    let mut normalized = vec![];
+    let mut run = String::new();
+    for c in ::std::char::decode_utf16(string.units().iter().cloned()) {
+        match c {
+            Ok(c) => run.push(c),
+            Err(err) => {
+                normalize_run(&run, form.as_str(), &mut normalized);
+                run.clear();
+                normalized.push(err.unpaired_surrogate());
+            }
+        }
+    }
+    normalize_run(&run, form.as_str(), &mut normalized);
+
+    let string = Value::js_string(&mut vm.memory_allocator, JSString::new(normalized));
+    vm.stack.push(string.into());
+    Ok(())
+}
+
+fn normalize_run(run: &str, form: &str, units: &mut Vec<u16>) {
+    let normalized: String = match form {
+        "NFC" => run.nfc().collect(),
+        "NFD" => run.nfd().collect(),
+        "NFKC" => run.nfkc().collect(),
+        "NFKD" => run.nfkd().collect(),
+        _ => unreachable!(),
+    };
+    units.extend(normalized.encode_utf16());
+}
+
+/// Return `val` if it is a RegExp object, or create a RegExp object from it.
+fn to_regexp(vm: &mut VM2, val: Value) -> Result<Value, RuntimeError> {
+    if val.is_regexp() {
+        return Ok(val);
+    }
+    let pattern = if val.is_undefined() {
+        vec![]
+    } else {
+        val.to_js_string().units().to_vec()
+    };
+    create_regexp(vm, pattern, "")
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.match
+pub fn string_prototype_match(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let input = cur_frame.this.to_js_string();
+    let regexp_obj = to_regexp(vm, args.get(0).map(|x| *x).unwrap_or(Value::undefined()))?;
+    let regexp = regexp_obj.as_regexp();
+
+    if !regexp.flags.global {
+        let result = match regexp_builtin_exec(vm, regexp_obj, &input) {
+            Some(captures) => {
+                let input_val = Value::js_string(&mut vm.memory_allocator, input.clone());
+                create_match_array(vm, regexp, &input, input_val, &captures)
+            }
+            None => Value::null(),
+        };
+        vm.stack.push(result.into());
+        return Ok(());
+    }
+
+    set_last_index(regexp_obj, 0);
+    let mut matches = vec![];
+    while let Some(captures) = regexp_builtin_exec(vm, regexp_obj, &input) {
+        let (start, end) = captures[0].unwrap();
+        matches.push(Property::new_data_simple(capture_to_value(
+            vm,
+            &input,
+            captures[0],
+        )));
+        if start == end {
+            set_last_index(
+                regexp_obj,
+                advance_string_index(&input, end, regexp.flags.unicode),
+            );
+        }
+    }
+    let result = if matches.is_empty() {
+        Value::null()
+    } else {
+        Value::array(&mut vm.memory_allocator, &vm.object_prototypes, matches)
+    };
+    vm.stack.push(result.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.search
+pub fn string_prototype_search(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let input = cur_frame.this.to_js_string();
+    let regexp_obj = to_regexp(vm, args.get(0).map(|x| *x).unwrap_or(Value::undefined()))?;
+    let previous_last_index = regexp_obj.get_property_by_str_key("lastIndex");
+    set_last_index(regexp_obj, 0);
+    let result = regexp_builtin_exec(vm, regexp_obj, &input);
+    regexp_obj.set_property_by_string_key("lastIndex".to_string(), previous_last_index);
+    let index = result.map_or(-1.0, |captures| captures[0].unwrap().0 as f64);
+    vm.stack.push(Value::Number(index).into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-string.prototype.replace
+pub fn string_prototype_replace(
+    vm: &mut VM2,
+    args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let input = cur_frame.this.to_js_string();
+    let search_value = args.get(0).map(|x| *x).unwrap_or(Value::undefined());
+    let replace_value = args.get(1).map(|x| *x).unwrap_or(Value::undefined());
+
+    let mut matches = vec![];
+    if search_value.is_regexp() {
+        let flags = search_value.as_regexp().flags;
+        if flags.global {
+            set_last_index(search_value, 0);
+        }
+        while let Some(captures) = regexp_builtin_exec(vm, search_value, &input) {
+            let (start, end) = captures[0].unwrap();
+            matches.push(captures);
+            if !flags.global {
+                break;
+            }
+            if start == end {
+                set_last_index(
+                    search_value,
+                    advance_string_index(&input, end, flags.unicode),
+                );
+            }
+        }
+    } else {
+        let search = search_value.to_js_string();
+        if let Some(pos) = input.index_of(&search, 0) {
+            matches.push(vec![Some((pos, pos + search.len()))]);
+        }
+    }
+
+    let group_names = if search_value.is_regexp() {
+        search_value.as_regexp().group_names.clone()
+    } else {
+        vec![]
+    };
+    let template = if replace_value.is_function_object() {
+        None
+    } else {
+        Some(replace_value.to_js_string())
+    };
+
+    let mut result = vec![];
+    let mut next_source_pos = 0;
+    for captures in &matches {
+        let (start, end) = captures[0].unwrap();
+        let replacement = match template {
+            Some(ref template) => get_substitution(&input, captures, &group_names, template),
+            None => {
+                let mut replacer_args = captures
+                    .iter()
+                    .map(|capture| capture_to_value(vm, &input, *capture))
+                    .collect::<Vec<Value>>();
+                replacer_args.push(Value::Number(start as f64));
+                replacer_args.push(Value::js_string(&mut vm.memory_allocator, input.clone()));
+                if !group_names.is_empty() {
+                    let groups =
+                        create_groups_object(vm, search_value.as_regexp(), &input, captures);
+                    replacer_args.push(groups);
+                }
+                vm.call_function(replace_value, &replacer_args, Value::undefined(), cur_frame)?;
+                let replacement: Value = vm.stack.pop().unwrap().into();
+                replacement.to_js_string().units().to_vec()
+            }
+        };
+        if start >= next_source_pos {
+            result.extend_from_slice(&input.units()[next_source_pos..start]);
+            result.extend(replacement);
+            next_source_pos = end;
+        }
+    }
+    result.extend_from_slice(&input.units()[next_source_pos..]);
+
+    let result = Value::js_string(&mut vm.memory_allocator, JSString::new(result));
+    vm.stack.push(result.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-getsubstitution
+/// Expand '$$', '$&', '$`', "$'", '$n', '$nn' and '$<name>' in the replacement template.
+fn get_substitution(
+    input: &JSString,
+    captures: &Captures,
+    group_names: &[(String, usize)],
+    template: &JSString,
+) -> Vec<u16> {
+    let input = input.units();
+    let capture_units = |capture: Option<(usize, usize)>| {
+        capture.map_or(vec![], |(start, end)| input[start..end].to_vec())
+    };
+    let (start, end) = captures[0].unwrap();
+    let group_count = captures.len() - 1;
+    let template = template.units();
+    let char_at = |i: usize| {
+        template
+            .get(i)
+            .and_then(|&unit| ::std::char::from_u32(unit as u32))
+    };
+    let digit = |i: usize| char_at(i).and_then(|c| c.to_digit(10)).map(|d| d as usize);
+
+    let mut result = vec![];
+    let mut i = 0;
+    while i < template.len() {
+        if char_at(i) != Some('$') || i + 1 == template.len() {
+            result.push(template[i]);
+            i += 1;
+            continue;
+        }
+        match char_at(i + 1) {
+            Some('$') => result.push('$' as u16),
+            Some('&') => result.extend(capture_units(captures[0])),
+            Some('`') => result.extend_from_slice(&input[..start]),
+            Some('\'') => result.extend_from_slice(&input[end..]),
+            Some('0'...'9') => {
+                let one = digit(i + 1).unwrap();
+                match digit(i + 2).map(|d| one * 10 + d) {
+                    Some(two) if 1 <= two && two <= group_count => {
+                        result.extend(capture_units(captures[two]));
+                        i += 3;
+                    }
+                    _ if 1 <= one && one <= group_count => {
+                        result.extend(capture_units(captures[one]));
+                        i += 2;
+                    }
+                    _ => {
+                        result.push('$' as u16);
+                        i += 1;
+                    }
+                }
+                continue;
+            }
+            Some('<') if !group_names.is_empty() => {
+                match template[i + 2..]
+                    .iter()
+                    .position(|&unit| unit == '>' as u16)
+                {
+                    Some(len) => {
+                        let name = String::from_utf16_lossy(&template[i + 2..i + 2 + len]);
+                        if let Some((_, index)) = group_names.iter().find(|(n, _)| n == &name) {
+                            result.extend(capture_units(captures[*index]));
+                        }
+                        i += len + 3;
+                    }
+                    None => {
+                        result.push('$' as u16);
+                        i += 1;
+                    }
+                }
+                continue;
+            }
+            _ => {
+                result.push('$' as u16);
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+    result
+}
+
+/// String.prototype\[@@iterator\]
+pub fn string_prototype_iterator(
+    vm: &mut VM2,
+    _args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let this = cur_frame.this;
+    if this.is_undefined() || this.is_null() {
+        return Err(RuntimeError::Type(
+            "String.prototype[Symbol.iterator] called on null or undefined".to_string(),
+        ));
+    }
+
+    let string = if this.is_string() {
+        this
+    } else {
+        Value::string(&mut vm.memory_allocator, this.to_string())
+    };
+    let iter = Value::string_iterator(&mut vm.memory_allocator, &vm.object_prototypes, string);
+    vm.stack.push(iter.into());
+    Ok(())
+}
+
+/// https://tc39.github.io/ecma262/#sec-%stringiteratorprototype%.next
+/// Iterates over the string by code points. Lone surrogates are yielded as they are.
+pub fn string_iterator_prototype_next(
+    vm: &mut VM2,
+    _args: &[Value],
+    cur_frame: &Frame,
+) -> Result<(), RuntimeError> {
+    let info = match cur_frame.this {
+        Value::Object(obj) => match unsafe { &mut *obj }.kind {
+            ObjectKind2::StringIterator(ref mut info) => info,
+            _ => return Err(RuntimeError::Type("not a String Iterator".to_string())),
+        },
+        _ => return Err(RuntimeError::Type("not a String Iterator".to_string())),
+    };
+
+    let next_char = if info.iterated.is_undefined() {
+        None
+    } else {
+        let string = info.iterated.as_string();
+        string.code_point_at(info.next_index).map(|(_, len)| {
+            let start = info.next_index;
+            string.substring(start, start + len)
+        })
+    };
+
+    let result = match next_char {
+        Some(c) => {
+            info.next_index += c.len();
+            let val = Value::js_string(&mut vm.memory_allocator, c);
+            create_iter_result_object(vm, val, false)
+        }
+        None => {
+            info.iterated = Value::undefined();
+            create_iter_result_object(vm, Value::undefined(), true)
+        }
+    };
+    vm.stack.push(result.into());
+    Ok(())
+}
+
+/// Return the this value converted to a string. Throw a TypeError for null and undefined.
+fn this_string(cur_frame: &Frame, name: &str) -> Result<JSString, RuntimeError> {
+    let this = cur_frame.this;
+    if this.is_undefined() || this.is_null() {
+        return Err(RuntimeError::Type(format!(
+            "String.prototype.{} called on null or undefined",
+            name
+        )));
+    }
+    Ok(this.to_js_string())
+}
+
+/// Return the search string given to includes, startsWith and endsWith, which must not be a
+/// RegExp.
+fn search_string_arg(args: &[Value], name: &str) -> Result<JSString, RuntimeError> {
+    let search = args.get(0).map(|x| *x).unwrap_or(Value::undefined());
+    if search.is_regexp() {
+        return Err(RuntimeError::Type(format!(
+            "First argument to String.prototype.{} must not be a regular expression",
+            name
+        )));
+    }
+    Ok(search.to_js_string())
+}
+
+/// Convert the position argument into an index clamped to 0..=len.
+fn position_arg(vm: &mut VM2, arg: Option<&Value>, len: usize) -> usize {
+    arg.map_or(0.0, |arg| arg.to_integer(&mut vm.memory_allocator))
+        .max(0.0)
+        .min(len as f64) as usize
+}
+
+/// Convert the position argument into the index of a code unit. Return None if it is out of
+/// range.
+fn code_unit_index(vm: &mut VM2, arg: Option<&Value>, len: usize) -> Option<usize> {
+    let position = arg.map_or(0.0, |arg| arg.to_integer(&mut vm.memory_allocator));
+    if position < 0.0 || position >= len as f64 {
+        None
+    } else {
+        Some(position as usize)
+    }
+}
+
+/// Apply f to each well-formed part of the string, leaving lone surrogates as they are.
+fn map_well_formed<F: Fn(&str) -> String>(string: &JSString, f: F) -> JSString {
+    let mut units = vec![];
+    let mut part = String::new();
+    for c in ::std::char::decode_utf16(string.units().iter().cloned()) {
+        match c {
+            Ok(c) => part.push(c),
+            Err(e) => {
+                units.extend(f(&part).encode_utf16());
+                units.push(e.unpaired_surrogate());
+                part.clear();
+            }
+        }
+    }
+    units.extend(f(&part).encode_utf16());
+    JSString::new(units)
+}
+
+/// https://tc39.github.io/ecma262/#sec-trimstring
+fn trim_string(string: &JSString, start: bool, end: bool) -> JSString {
+    let (from, to) = {
+        let units = string.units();
+        let from = if start {
+            units
+                .iter()
+                .position(|&u| !is_js_whitespace(u))
+                .unwrap_or(units.len())
+        } else {
+            0
+        };
+        let to = if end {
+            units
+                .iter()
+                .rposition(|&u| !is_js_whitespace(u))
+                .map_or(from, |k| k + 1)
+        } else {
+            units.len()
+        };
+        (from, to.max(from))
+    };
+    string.substring(from, to)
+}
+
+/// https://tc39.github.io/ecma262/#sec-touint16
+fn to_uint16(number: f64) -> u16 {
+    if number.is_nan() || number.is_infinite() {
+        return 0;
+    }
+    (number.trunc() % 65536.0 + 65536.0) as u32 as u16
+}
+
+/// WhiteSpace and LineTerminator, which String.prototype.trim removes. All of them are in the
+/// BMP, so a code unit is enough to tell.
+/// https://tc39.github.io/ecma262/#sec-white-space
+fn is_js_whitespace(unit: u16) -> bool {
+    match unit {
+        0x9 | 0xB | 0xC | 0x20 | 0xA0 | 0xFEFF => true,
+        0xA | 0xD | 0x2028 | 0x2029 => true,
+        // Zs
+        0x1680 | 0x2000...0x200A | 0x202F | 0x205F | 0x3000 => true,
+        _ => false,
+    }
+}
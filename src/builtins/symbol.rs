@@ -9,6 +9,7 @@ use vm::{
 pub fn symbol(
     memory_allocator: &mut MemoryAllocator,
     object_prototypes: &ObjectPrototypes,
+    well_known_symbols: &WellKnownSymbols,
 ) -> Value {
     let obj = Value::builtin_function(
         memory_allocator,
@@ -36,6 +37,50 @@ pub fn symbol(
         )
     });
 
+    // Symbol.hasInstance
+    obj.get_object_info().property.insert(
+        "hasInstance".to_string(),
+        Property::Data(DataProperty {
+            val: well_known_symbols.has_instance,
+            writable: false,
+            enumerable: false,
+            configurable: false,
+        }),
+    );
+
+    // Symbol.isConcatSpreadable
+    obj.get_object_info().property.insert(
+        "isConcatSpreadable".to_string(),
+        Property::Data(DataProperty {
+            val: well_known_symbols.is_concat_spreadable,
+            writable: false,
+            enumerable: false,
+            configurable: false,
+        }),
+    );
+
+    // Symbol.iterator
+    obj.get_object_info().property.insert(
+        "iterator".to_string(),
+        Property::Data(DataProperty {
+            val: well_known_symbols.iterator,
+            writable: false,
+            enumerable: false,
+            configurable: false,
+        }),
+    );
+
+    // Symbol.toStringTag
+    obj.get_object_info().property.insert(
+        "toStringTag".to_string(),
+        Property::Data(DataProperty {
+            val: well_known_symbols.to_string_tag,
+            writable: false,
+            enumerable: false,
+            configurable: false,
+        }),
+    );
+
     obj.set_property_by_string_key("prototype".to_string(), object_prototypes.symbol);
     obj.get_property_by_str_key("prototype")
         .set_constructor(obj);
@@ -31,6 +31,16 @@ impl<'a> ByteCodeGenerator<'a> {
         self.append_int32(len as i32, iseq);
     }
 
+    pub fn append_create_class(&self, id: usize, iseq: &mut ByteCode) {
+        iseq.push(VMInst::CREATE_CLASS);
+        self.append_int32(id as i32, iseq);
+    }
+
+    pub fn append_create_regexp(&self, id: usize, iseq: &mut ByteCode) {
+        iseq.push(VMInst::CREATE_REGEXP);
+        self.append_int32(id as i32, iseq);
+    }
+
     pub fn append_create_array(&self, len: usize, iseq: &mut ByteCode) {
         iseq.push(VMInst::CREATE_ARRAY);
         self.append_int32(len as i32, iseq);
@@ -116,6 +126,9 @@ impl<'a> ByteCodeGenerator<'a> {
     pub fn append_rem(&self, iseq: &mut ByteCode) {
         iseq.push(VMInst::REM);
     }
+    pub fn append_exp(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::EXP);
+    }
     pub fn append_lt(&self, iseq: &mut ByteCode) {
         iseq.push(VMInst::LT);
     }
@@ -170,6 +183,26 @@ impl<'a> ByteCodeGenerator<'a> {
     pub fn append_double(&self, iseq: &mut ByteCode) {
         iseq.push(VMInst::DOUBLE);
     }
+    pub fn append_double2(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::DOUBLE2);
+    }
+    /// Move the value on the stack top under the `depth` values below it.
+    pub fn append_rotate(&self, depth: i8, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ROTATE);
+        self.append_int8(depth, iseq);
+    }
+    pub fn append_is_nullish(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::IS_NULLISH);
+    }
+    pub fn append_instance_of(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::INSTANCE_OF);
+    }
+    pub fn append_in(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::IN);
+    }
+    pub fn append_delete_member(&self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::DELETE_MEMBER);
+    }
     pub fn append_pop(&self, iseq: &mut ByteCode) {
         iseq.push(VMInst::POP);
     }
@@ -227,11 +260,6 @@ impl<'a> ByteCodeGenerator<'a> {
         iseq.push(VMInst::RETURN);
     }
 
-    pub fn append_return_try(&self, iseq: &mut ByteCode) {
-        iseq.push(VMInst::RETURN_TRY);
-        self.append_int32(0, iseq);
-    }
-
     pub fn append_push_scope(&self, iseq: &mut ByteCode) {
         iseq.push(VMInst::PUSH_SCOPE);
     }
@@ -250,6 +278,12 @@ impl<'a> ByteCodeGenerator<'a> {
         self.append_int32(id, iseq);
     }
 
+    pub fn append_delete_binding(&mut self, name: &String, iseq: &mut ByteCode) {
+        let id = self.constant_table.add_string(name.clone()) as i32;
+        iseq.push(VMInst::DELETE_BINDING);
+        self.append_int32(id, iseq);
+    }
+
     pub fn append_set_value(&mut self, name: &String, iseq: &mut ByteCode) {
         let id = self.constant_table.add_string(name.clone()) as i32;
         iseq.push(VMInst::SET_VALUE);
@@ -323,6 +357,98 @@ impl<'a> ByteCodeGenerator<'a> {
         iseq.push(VMInst::TYPEOF);
     }
 
+    pub fn append_for_in_init(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::FOR_IN_INIT);
+    }
+
+    pub fn append_for_in_next(&mut self, dst: i32, iseq: &mut ByteCode) {
+        iseq.push(VMInst::FOR_IN_NEXT);
+        self.append_int32(dst, iseq);
+    }
+
+    pub fn append_get_iterator(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::GET_ITERATOR);
+    }
+
+    pub fn append_iter_next(&mut self, dst: i32, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ITER_NEXT);
+        self.append_int32(dst, iseq);
+    }
+
+    pub fn append_iter_close(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ITER_CLOSE);
+    }
+
+    pub fn append_iter_close_throw(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ITER_CLOSE_THROW);
+    }
+
+    pub fn append_yield(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::YIELD);
+    }
+
+    pub fn append_yield_delegate(&mut self, dst: i32, iseq: &mut ByteCode) {
+        iseq.push(VMInst::YIELD_DELEGATE);
+        self.append_int32(dst, iseq);
+    }
+
+    pub fn append_get_super(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::GET_SUPER);
+    }
+
+    pub fn append_call_super(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::CALL_SUPER);
+    }
+
+    pub fn append_enter_function_body(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ENTER_FUNCTION_BODY);
+    }
+
+    pub fn append_require_object_coercible(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::REQUIRE_OBJECT_COERCIBLE);
+    }
+
+    pub fn append_bind_this(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::BIND_THIS);
+    }
+
+    pub fn append_get_super_member(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::GET_SUPER_MEMBER);
+    }
+
+    pub fn append_iter_step(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ITER_STEP);
+    }
+
+    pub fn append_iter_rest(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ITER_REST);
+    }
+
+    pub fn append_object_rest(&mut self, len: usize, iseq: &mut ByteCode) {
+        iseq.push(VMInst::OBJECT_REST);
+        self.append_uint32(len as u32, iseq);
+    }
+
+    pub fn append_array_push(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ARRAY_PUSH);
+    }
+
+    pub fn append_array_spread(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::ARRAY_SPREAD);
+    }
+
+    pub fn append_call_spread(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::CALL_SPREAD);
+    }
+
+    pub fn append_call_method_spread(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::CALL_METHOD_SPREAD);
+    }
+
+    pub fn append_construct_spread(&mut self, iseq: &mut ByteCode) {
+        iseq.push(VMInst::CONSTRUCT_SPREAD);
+    }
+
     // Utils
 
     pub fn append_int8(&self, n: i8, iseq: &mut ByteCode) {
@@ -422,6 +548,7 @@ pub fn show_inst2(code: &ByteCode, i: usize, const_table: &constant::ConstantTab
             VMInst::MUL => format!("Mul"),
             VMInst::DIV => format!("Div"),
             VMInst::REM => format!("Rem"),
+            VMInst::EXP => format!("Exp"),
             VMInst::LT => format!("Lt"),
             VMInst::GT => format!("Gt"),
             VMInst::LE => format!("Le"),
@@ -461,6 +588,20 @@ pub fn show_inst2(code: &ByteCode, i: usize, const_table: &constant::ConstantTab
             }
             VMInst::RETURN => format!("Return"),
             VMInst::DOUBLE => format!("Double"),
+            VMInst::DOUBLE2 => format!("Double2"),
+            VMInst::ROTATE => {
+                let int8 = code[i + 1] as i32;
+                format!("Rotate {}", int8)
+            }
+            VMInst::IS_NULLISH => format!("IsNullish"),
+            VMInst::INSTANCE_OF => format!("InstanceOf"),
+            VMInst::IN => format!("In"),
+            VMInst::DELETE_MEMBER => format!("DeleteMember"),
+            VMInst::DELETE_BINDING => {
+                let int32 = read_int32(code, i + 1);
+                let name = const_table.get(int32 as usize).as_string();
+                format!("DeleteBinding '{}'", name)
+            }
             VMInst::POP => format!("Pop"),
             VMInst::LAND => format!("LogAnd"),
             VMInst::LOR => format!("LogOr"),
@@ -503,7 +644,6 @@ pub fn show_inst2(code: &ByteCode, i: usize, const_table: &constant::ConstantTab
             VMInst::LEAVE_TRY => format!("LeaveTry"),
             VMInst::CATCH => format!("Catch"),
             VMInst::FINALLY => format!("Finally"),
-            VMInst::RETURN_TRY => format!("ReturnTry"),
             VMInst::PUSH_SCOPE => format!("PushScope"),
             VMInst::POP_SCOPE => format!("PopScope"),
             VMInst::NOT => format!("BitwiseNot"),
@@ -514,6 +654,48 @@ pub fn show_inst2(code: &ByteCode, i: usize, const_table: &constant::ConstantTab
             }
             VMInst::RETURN_SUB => format!("ReturnSub"),
             VMInst::TYPEOF => format!("Typeof"),
+            VMInst::FOR_IN_INIT => format!("ForInInit"),
+            VMInst::FOR_IN_NEXT => {
+                let int32 = read_int32(code, i + 1);
+                format!("ForInNext {:04x}", i as i32 + int32 + 5)
+            }
+            VMInst::GET_ITERATOR => format!("GetIterator"),
+            VMInst::ITER_NEXT => {
+                let int32 = read_int32(code, i + 1);
+                format!("IterNext {:04x}", i as i32 + int32 + 5)
+            }
+            VMInst::ITER_CLOSE => format!("IterClose"),
+            VMInst::ITER_CLOSE_THROW => format!("IterCloseThrow"),
+            VMInst::YIELD => format!("Yield"),
+            VMInst::YIELD_DELEGATE => {
+                let int32 = read_int32(code, i + 1);
+                format!("YieldDelegate {:04x}", i as i32 + int32 + 5)
+            }
+            VMInst::CREATE_CLASS => {
+                let int32 = read_int32(code, i + 1);
+                format!("CreateClass {}", int32)
+            }
+            VMInst::GET_SUPER => format!("GetSuper"),
+            VMInst::CALL_SUPER => format!("CallSuper"),
+            VMInst::BIND_THIS => format!("BindThis"),
+            VMInst::ENTER_FUNCTION_BODY => format!("EnterFunctionBody"),
+            VMInst::REQUIRE_OBJECT_COERCIBLE => format!("RequireObjectCoercible"),
+            VMInst::GET_SUPER_MEMBER => format!("GetSuperMember"),
+            VMInst::ITER_STEP => format!("IterStep"),
+            VMInst::ITER_REST => format!("IterRest"),
+            VMInst::OBJECT_REST => {
+                let int32 = read_int32(code, i + 1);
+                format!("ObjectRest {}", int32)
+            }
+            VMInst::ARRAY_PUSH => format!("ArrayPush"),
+            VMInst::ARRAY_SPREAD => format!("ArraySpread"),
+            VMInst::CALL_SPREAD => format!("CallSpread"),
+            VMInst::CALL_METHOD_SPREAD => format!("CallMethodSpread"),
+            VMInst::CONSTRUCT_SPREAD => format!("ConstructSpread"),
+            VMInst::CREATE_REGEXP => {
+                let int32 = read_int32(code, i + 1);
+                format!("CreateRegExp {}", int32)
+            }
             _ => unreachable!("sorry. need to implement more opcodes"),
         }
     );
@@ -577,7 +759,6 @@ pub mod VMInst {
     pub const LEAVE_TRY: u8 = 0x35;
     pub const CATCH: u8 = 0x36;
     pub const FINALLY: u8 = 0x37;
-    pub const RETURN_TRY: u8 = 0x38;
     pub const PUSH_SCOPE: u8 = 0x39;
     pub const POP_SCOPE: u8 = 0x3a;
     pub const DECL_CONST: u8 = 0x3b;
@@ -592,15 +773,55 @@ pub mod VMInst {
     pub const RETURN_SUB: u8 = 0x44;
     pub const TYPEOF: u8 = 0x45;
     pub const PUSH_NULL: u8 = 0x46;
+    pub const FOR_IN_INIT: u8 = 0x47;
+    pub const FOR_IN_NEXT: u8 = 0x48;
+    pub const GET_ITERATOR: u8 = 0x49;
+    pub const ITER_NEXT: u8 = 0x4a;
+    pub const ITER_CLOSE: u8 = 0x4b;
+    pub const YIELD: u8 = 0x4c;
+    pub const YIELD_DELEGATE: u8 = 0x4d;
+    pub const CREATE_CLASS: u8 = 0x4e;
+    pub const GET_SUPER: u8 = 0x4f;
+    pub const CALL_SUPER: u8 = 0x50;
+    pub const GET_SUPER_MEMBER: u8 = 0x51;
+    pub const ITER_STEP: u8 = 0x52;
+    pub const ITER_REST: u8 = 0x53;
+    pub const OBJECT_REST: u8 = 0x54;
+    pub const ARRAY_PUSH: u8 = 0x55;
+    pub const ARRAY_SPREAD: u8 = 0x56;
+    pub const CALL_SPREAD: u8 = 0x57;
+    pub const CALL_METHOD_SPREAD: u8 = 0x58;
+    pub const CONSTRUCT_SPREAD: u8 = 0x59;
+    pub const CREATE_REGEXP: u8 = 0x5a;
+    pub const EXP: u8 = 0x5b;
+    pub const DOUBLE2: u8 = 0x5c;
+    pub const ROTATE: u8 = 0x5d;
+    pub const IS_NULLISH: u8 = 0x5e;
+    pub const INSTANCE_OF: u8 = 0x5f;
+    pub const IN: u8 = 0x60;
+    pub const DELETE_MEMBER: u8 = 0x61;
+    pub const DELETE_BINDING: u8 = 0x62;
+    pub const BIND_THIS: u8 = 0x63;
+    pub const ITER_CLOSE_THROW: u8 = 0x64;
+    pub const ENTER_FUNCTION_BODY: u8 = 0x65;
+    pub const REQUIRE_OBJECT_COERCIBLE: u8 = 0x66;
 
     pub fn get_inst_size(inst: u8) -> Option<usize> {
         match inst {
             CREATE_CONTEXT | THROW | LEAVE_TRY | CATCH | FINALLY | POP_SCOPE | PUSH_SCOPE
-            | RETURN_SUB | SET_OUTER_ENV | POP_ENV | TYPEOF | PUSH_NULL => Some(1),
+            | RETURN_SUB | SET_OUTER_ENV | POP_ENV | TYPEOF | PUSH_NULL | FOR_IN_INIT
+            | GET_ITERATOR | ITER_CLOSE | YIELD | GET_SUPER | CALL_SUPER | GET_SUPER_MEMBER
+            | ITER_STEP | ITER_REST | ARRAY_PUSH | ARRAY_SPREAD | CALL_SPREAD
+            | CALL_METHOD_SPREAD | CONSTRUCT_SPREAD | EXP | DOUBLE2 | IS_NULLISH | INSTANCE_OF
+            | IN | DELETE_MEMBER | BIND_THIS | ITER_CLOSE_THROW | ENTER_FUNCTION_BODY => Some(1),
+            REQUIRE_OBJECT_COERCIBLE => Some(1),
             CONSTRUCT | CREATE_OBJECT | PUSH_CONST | PUSH_INT32 | CREATE_ARRAY | JMP_IF_FALSE
-            | RETURN_TRY | DECL_VAR | LOOP_START | JMP | SET_VALUE | GET_VALUE | CALL | JMP_SUB
-            | CALL_METHOD | PUSH_ENV | DECL_LET | DECL_CONST => Some(5),
-            PUSH_INT8 => Some(2),
+            | DECL_VAR | LOOP_START | JMP | SET_VALUE | GET_VALUE | CALL | JMP_SUB
+            | CALL_METHOD | PUSH_ENV | DECL_LET | DECL_CONST | FOR_IN_NEXT | ITER_NEXT
+            | YIELD_DELEGATE | CREATE_CLASS | OBJECT_REST | CREATE_REGEXP | DELETE_BINDING => {
+                Some(5)
+            }
+            PUSH_INT8 | ROTATE => Some(2),
             PUSH_FALSE | END | PUSH_TRUE | PUSH_THIS | ADD | SUB | MUL | DIV | REM | LT
             | PUSH_ARGUMENTS | NEG | POSI | GT | LE | GE | EQ | NE | GET_MEMBER | RETURN | SNE
             | ZFSHR | POP | DOUBLE | AND | COND_OP | OR | SEQ | SET_MEMBER | LNOT
@@ -1,10 +1,11 @@
 use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::VecDeque;
 use std::hash::{Hash, Hasher};
 use std::mem;
 use vm::{
     constant, frame,
     jsvalue::{
-        function, object, prototype,
+        function, object, promise, prototype, string,
         value::{BoxedValue, Value},
     },
 };
@@ -42,7 +43,7 @@ pub struct MemoryAllocator {
     white: MarkState,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum GCState {
     Initial,
     Marking,
@@ -98,9 +99,16 @@ impl MemoryAllocator {
         stack: &Vec<BoxedValue>,
         cur_frame: &frame::Frame,
         saved_frame: &Vec<frame::Frame>,
+        job_queue: &VecDeque<promise::Job>,
+        rejected_promises: &Vec<Value>,
     ) {
         let mut markset = MarkSet::default();
 
+        // Values may be grayed after all the objects are marked.
+        if self.state == GCState::ReadyToSweep && self.roots.len() > 0 {
+            self.state = GCState::Marking;
+        }
+
         self.state = match self.state {
             GCState::Initial => {
                 // println!("initial");
@@ -116,6 +124,14 @@ impl MemoryAllocator {
                 object_prototypes.function.initial_trace(&mut markset);
                 object_prototypes.string.initial_trace(&mut markset);
                 object_prototypes.array.initial_trace(&mut markset);
+                object_prototypes.iterator.initial_trace(&mut markset);
+                object_prototypes.array_iterator.initial_trace(&mut markset);
+                object_prototypes
+                    .string_iterator
+                    .initial_trace(&mut markset);
+                object_prototypes.generator.initial_trace(&mut markset);
+                object_prototypes.promise.initial_trace(&mut markset);
+                object_prototypes.regexp.initial_trace(&mut markset);
 
                 constant_table.initial_trace(&mut markset);
 
@@ -129,6 +145,14 @@ impl MemoryAllocator {
                     frame.this.initial_trace(&mut markset);
                 }
 
+                for job in job_queue {
+                    job.initial_trace(&mut markset);
+                }
+
+                for promise in rejected_promises {
+                    promise.initial_trace(&mut markset);
+                }
+
                 // println!("initial mark: {:?}", markset);
 
                 self.white = self.white.flip_white();
@@ -261,12 +285,22 @@ impl GcTarget for frame::LexicalEnvironment {
     fn initial_trace(&self, markset: &mut MarkSet) {
         fn trace_record(record: &frame::EnvironmentRecord, markset: &mut MarkSet) {
             match record {
-                frame::EnvironmentRecord::Declarative(record)
-                | frame::EnvironmentRecord::Function { record, .. } => {
+                frame::EnvironmentRecord::Declarative(record) => {
                     for (_, val) in record {
                         val.initial_trace(markset);
                     }
                 }
+                frame::EnvironmentRecord::Function {
+                    record,
+                    this,
+                    new_target,
+                } => {
+                    for (_, val) in record {
+                        val.initial_trace(markset);
+                    }
+                    this.initial_trace(markset);
+                    new_target.initial_trace(markset);
+                }
                 frame::EnvironmentRecord::Object(obj) | frame::EnvironmentRecord::Global(obj) => {
                     obj.initial_trace(markset)
                 }
@@ -287,11 +321,21 @@ impl GcTarget for frame::LexicalEnvironment {
             markset: &mut MarkSet,
         ) {
             match record {
-                frame::EnvironmentRecord::Declarative(record)
-                | frame::EnvironmentRecord::Function { record, .. } => {
+                frame::EnvironmentRecord::Declarative(record) => {
+                    for (_, val) in record {
+                        val.trace(allocator, markset);
+                    }
+                }
+                frame::EnvironmentRecord::Function {
+                    record,
+                    this,
+                    new_target,
+                } => {
                     for (_, val) in record {
                         val.trace(allocator, markset);
                     }
+                    this.trace(allocator, markset);
+                    new_target.trace(allocator, markset);
                 }
                 frame::EnvironmentRecord::Object(obj) | frame::EnvironmentRecord::Global(obj) => {
                     obj.trace(allocator, markset)
@@ -311,12 +355,11 @@ impl GcTarget for frame::LexicalEnvironment {
     }
 }
 
-impl GcTarget for ::std::ffi::CString {
+impl GcTarget for string::JSString {
     fn initial_trace(&self, _markset: &mut MarkSet) {}
     fn trace(&self, _allocator: &mut MemoryAllocator, _markset: &mut MarkSet) {}
     fn free(&self) -> usize {
-        // mem::drop(self);
-        mem::size_of::<::std::ffi::CString>()
+        mem::size_of::<string::JSString>()
     }
 }
 
@@ -349,16 +392,26 @@ impl GcTarget for Value {
 impl GcTarget for object::ObjectInfo {
     fn initial_trace(&self, markset: &mut MarkSet) {
         self.kind.initial_trace(markset);
+        self.prototype.initial_trace(markset);
         for (_, property) in &self.property {
             property.initial_trace(markset)
         }
+        for (key, property) in &self.sym_property {
+            key.0.initial_trace(markset);
+            property.initial_trace(markset)
+        }
     }
 
     fn trace(&self, allocator: &mut MemoryAllocator, markset: &mut MarkSet) {
         self.kind.trace(allocator, markset);
+        self.prototype.trace(allocator, markset);
         for (_, property) in &self.property {
             property.trace(allocator, markset)
         }
+        for (key, property) in &self.sym_property {
+            key.0.trace(allocator, markset);
+            property.trace(allocator, markset)
+        }
     }
 
     fn free(&self) -> usize {
@@ -415,12 +468,35 @@ impl GcTarget for object::ObjectKind2 {
                     trace_user_function_info(markset, user_func_info)
                 }
                 function::FunctionObjectKind::Builtin(_) => {}
+                function::FunctionObjectKind::PromiseResolving(ref info) => {
+                    info.promise.initial_trace(markset)
+                }
             },
             object::ObjectKind2::Array(ary_info) => {
                 for elem in &ary_info.elems {
                     elem.initial_trace(markset)
                 }
             }
+            object::ObjectKind2::ArrayIterator(info) => info.iterated.initial_trace(markset),
+            object::ObjectKind2::StringIterator(info) => info.iterated.initial_trace(markset),
+            object::ObjectKind2::Generator(info) => {
+                if let Some(ref frame) = info.frame {
+                    frame.execution_context.initial_trace(markset);
+                    frame.this.initial_trace(markset);
+                }
+                for val_boxed in &info.stack {
+                    let val: Value = (*val_boxed).into();
+                    val.initial_trace(markset);
+                }
+            }
+            object::ObjectKind2::Promise(info) => {
+                info.result.initial_trace(markset);
+                for reaction in info.fulfill_reactions.iter().chain(&info.reject_reactions) {
+                    reaction.initial_trace(markset);
+                }
+            }
+            object::ObjectKind2::Arguments(info) => mark!(markset, info.env.as_ptr()),
+            object::ObjectKind2::RegExp(_) => {}
             object::ObjectKind2::Symbol(_) => {}
             object::ObjectKind2::Ordinary => {}
         }
@@ -447,12 +523,37 @@ impl GcTarget for object::ObjectKind2 {
                     trace_user_function_info(allocator, markset, user_func_info)
                 }
                 function::FunctionObjectKind::Builtin(_) => {}
+                function::FunctionObjectKind::PromiseResolving(ref info) => {
+                    info.promise.trace(allocator, markset)
+                }
             },
             object::ObjectKind2::Array(ary_info) => {
                 for elem in &ary_info.elems {
                     elem.trace(allocator, markset)
                 }
             }
+            object::ObjectKind2::ArrayIterator(info) => info.iterated.trace(allocator, markset),
+            object::ObjectKind2::StringIterator(info) => info.iterated.trace(allocator, markset),
+            object::ObjectKind2::Generator(info) => {
+                if let Some(ref frame) = info.frame {
+                    frame.execution_context.trace(allocator, markset);
+                    frame.this.trace(allocator, markset);
+                }
+                for val_boxed in &info.stack {
+                    let val: Value = (*val_boxed).into();
+                    val.trace(allocator, markset);
+                }
+            }
+            object::ObjectKind2::Promise(info) => {
+                info.result.trace(allocator, markset);
+                for reaction in info.fulfill_reactions.iter().chain(&info.reject_reactions) {
+                    reaction.trace(allocator, markset);
+                }
+            }
+            object::ObjectKind2::Arguments(info) => {
+                mark_if_white!(allocator, markset, info.env.as_ptr())
+            }
+            object::ObjectKind2::RegExp(_) => {}
             object::ObjectKind2::Symbol(_) => {}
             object::ObjectKind2::Ordinary => {}
         }
@@ -463,6 +564,84 @@ impl GcTarget for object::ObjectKind2 {
     }
 }
 
+impl GcTarget for promise::PromiseReaction {
+    fn initial_trace(&self, markset: &mut MarkSet) {
+        if let Some(capability) = self.capability {
+            capability.initial_trace(markset);
+        }
+        match self.handler {
+            promise::PromiseReactionHandler::Function(val)
+            | promise::PromiseReactionHandler::Finally(val)
+            | promise::PromiseReactionHandler::ValueThunk(val)
+            | promise::PromiseReactionHandler::Thrower(val) => val.initial_trace(markset),
+            promise::PromiseReactionHandler::Await { generator, promise } => {
+                generator.initial_trace(markset);
+                promise.initial_trace(markset);
+            }
+            promise::PromiseReactionHandler::AllResolveElement {
+                values, promise, ..
+            } => {
+                values.initial_trace(markset);
+                promise.initial_trace(markset);
+            }
+        }
+    }
+
+    fn trace(&self, allocator: &mut MemoryAllocator, markset: &mut MarkSet) {
+        if let Some(capability) = self.capability {
+            capability.trace(allocator, markset);
+        }
+        match self.handler {
+            promise::PromiseReactionHandler::Function(val)
+            | promise::PromiseReactionHandler::Finally(val)
+            | promise::PromiseReactionHandler::ValueThunk(val)
+            | promise::PromiseReactionHandler::Thrower(val) => val.trace(allocator, markset),
+            promise::PromiseReactionHandler::Await { generator, promise } => {
+                generator.trace(allocator, markset);
+                promise.trace(allocator, markset);
+            }
+            promise::PromiseReactionHandler::AllResolveElement {
+                values, promise, ..
+            } => {
+                values.trace(allocator, markset);
+                promise.trace(allocator, markset);
+            }
+        }
+    }
+
+    fn free(&self) -> usize {
+        mem::size_of::<promise::PromiseReaction>()
+    }
+}
+
+impl GcTarget for promise::Job {
+    fn initial_trace(&self, markset: &mut MarkSet) {
+        match self {
+            promise::Job::PromiseReaction { reaction, argument } => {
+                reaction.initial_trace(markset);
+                argument.initial_trace(markset);
+            }
+            promise::Job::PromiseResolveThenable {
+                promise,
+                thenable,
+                then,
+            } => {
+                promise.initial_trace(markset);
+                thenable.initial_trace(markset);
+                then.initial_trace(markset);
+            }
+        }
+    }
+
+    fn trace(&self, _allocator: &mut MemoryAllocator, _markset: &mut MarkSet) {
+        panic!()
+    }
+
+    fn free(&self) -> usize {
+        panic!()
+    }
+}
+
 impl GcTarget for constant::ConstantTable {
     fn initial_trace(&self, markset: &mut MarkSet) {
         for const_ in &self.table {
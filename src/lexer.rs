@@ -1,11 +1,8 @@
 use parser::Error;
-use token::{convert_reserved_keyword, Kind, Symbol, Token};
+use token::{convert_reserved_keyword, Kind, Symbol, TemplatePart, Token};
 
 use std::collections::VecDeque;
 
-use encoding::all::UTF_16BE;
-use encoding::{DecoderTrap, Encoding};
-
 #[derive(Clone, Debug)]
 pub struct Lexer {
     pub code: String,
@@ -32,6 +29,17 @@ pub struct Lexer {
 
     /// Saved states
     pub states: Vec<usize>,
+
+    /// Number of unclosed braces in each substitution of template literals being read.
+    /// A closing brace without unclosed ones resumes the template literal.
+    pub template_braces: Vec<usize>,
+
+    /// Represent if each unclosed '(' begins the head of if, while, for or with statement.
+    pub parens: Vec<bool>,
+
+    /// Represent if the last ')' closed the head of if, while, for or with statement, after
+    /// which '/' begins a regular expression literal.
+    pub paren_closed_head: bool,
 }
 
 impl Lexer {
@@ -45,6 +53,9 @@ impl Lexer {
             token_pos: 0,
             prev_token_pos: 0,
             states: vec![],
+            template_braces: vec![],
+            parens: vec![],
+            paren_closed_head: false,
         }
     }
 
@@ -235,6 +246,9 @@ impl Lexer {
             'a'...'z' | 'A'...'Z' | '_' | '$' => self.read_identifier(),
             '0'...'9' => self.read_number(),
             '\'' | '\"' => self.read_string_literal(),
+            '`' => self.read_template_part(),
+            '}' if self.template_braces.last() == Some(&0) => self.read_template_part(),
+            '/' if self.regexp_allowed() => self.read_regexp_literal(),
             '\n' => self.read_line_terminator(),
             c if c.is_whitespace() => {
                 self.skip_whitespace()?;
@@ -395,75 +409,221 @@ impl Lexer {
     }
 }
 
+impl Lexer {
+    /// Return true if '/' at the current position begins a regular expression literal rather
+    /// than a division operator, judging from the previous significant token.
+    fn regexp_allowed(&self) -> bool {
+        use token::Keyword;
+        match self.prev_significant_token() {
+            None => true,
+            Some(Kind::Symbol(Symbol::ClosingParen)) => self.paren_closed_head,
+            Some(Kind::Identifier(_))
+            | Some(Kind::Number(_))
+            | Some(Kind::String(_))
+            | Some(Kind::RegExp(_, _))
+            | Some(Kind::Keyword(Keyword::This))
+            | Some(Kind::Keyword(Keyword::Super))
+            | Some(Kind::Keyword(Keyword::Arguments))
+            | Some(Kind::Symbol(Symbol::ClosingBoxBracket))
+            | Some(Kind::Symbol(Symbol::Inc))
+            | Some(Kind::Symbol(Symbol::Dec)) => false,
+            Some(Kind::Template(part)) => !part.tail,
+            _ => true,
+        }
+    }
+
+    /// Return the kind of the last token except line terminators.
+    fn prev_significant_token(&self) -> Option<&Kind> {
+        self.buf
+            .iter()
+            .rev()
+            .find(|tok| tok.kind != Kind::LineTerminator)
+            .map(|tok| &tok.kind)
+    }
+
+    fn read_regexp_literal(&mut self) -> Result<Token, Error> {
+        let pos = self.pos;
+        assert_eq!(self.take_char()?, '/');
+        let mut pattern = "".to_string();
+        let mut in_class = false;
+        let mut escaped = false;
+        loop {
+            let c = self.take_char().unwrap_or('\n');
+            if c == '\n' || c == '\r' {
+                return Err(Error::General(
+                    pos,
+                    "unterminated regular expression literal.".to_string(),
+                ));
+            }
+            if escaped {
+                escaped = false
+            } else {
+                match c {
+                    '/' if !in_class => break,
+                    '[' => in_class = true,
+                    ']' => in_class = false,
+                    '\\' => escaped = true,
+                    _ => {}
+                }
+            }
+            pattern.push(c)
+        }
+        let flags = self.take_char_while(|c| c.is_alphanumeric() || c == '_' || c == '$')?;
+        Ok(Token::new_regexp(pattern, flags, pos))
+    }
+}
+
 impl Lexer {
     fn read_string_literal(&mut self) -> Result<Token, Error> {
         let pos = self.pos;
         let quote = self.take_char()?;
-        let mut s = "".to_string();
+        let mut s = vec![];
         loop {
             match self.take_char()? {
                 q if q == quote => break,
-                '\\' => {
-                    for c in self.read_escaped_char()? {
-                        s.push(c)
-                    }
+                '\\' => s.extend(self.read_escaped_char()?),
+                c => {
+                    let mut buf = [0; 2];
+                    s.extend_from_slice(c.encode_utf16(&mut buf));
                 }
-                c => s.push(c),
             }
         }
         Ok(Token::new_string(s, pos))
     }
 
-    // TODO: Support more escape sequences
-    fn read_escaped_char(&mut self) -> Result<Vec<char>, Error> {
-        let c = self.take_char()?;
-        Ok(match c {
-            '\'' | '"' | '?' | '\\' => vec![c],
-            'a' => vec!['\x07'],
-            'b' => vec!['\x08'],
-            'f' => vec!['\x0c'],
-            'n' => vec!['\x0a'],
-            'r' => vec!['\x0d'],
-            't' => vec!['\x09'],
-            'v' => vec!['\x0b'],
-            'x' => {
-                let hex = self.take_char_while(|c| c.is_alphanumeric())?;
-                vec![self.read_hex_num(hex.as_str()) as u8 as char]
+    /// Read a part of template literals, which begins with '`' or '}' closing a substitution.
+    /// Line terminators in the part are normalized to LF. The cooked string is None if the part
+    /// has an invalid escape sequence, which is allowed only in tagged templates.
+    fn read_template_part(&mut self) -> Result<Token, Error> {
+        let pos = self.pos;
+        let head = self.take_char()? == '`';
+        if !head {
+            self.template_braces.pop();
+        }
+
+        let mut cooked = Some(vec![]);
+        let mut raw = "".to_string();
+        let tail = loop {
+            if self.take_line_terminator_in_template()? {
+                cooked.as_mut().map(|cooked| cooked.push('\n' as u16));
+                raw.push('\n');
+                continue;
             }
-            'u' => {
-                let mut u8s = vec![];
-                loop {
-                    let hex = self.take_char_while(|c| c.is_alphanumeric())?;
-                    let mut i = 0;
-                    while i < hex.len() {
-                        u8s.push(
-                            self.read_hex_num(&hex[i..i + if i + 2 > hex.len() { 1 } else { 2 }])
-                                as u8,
-                        );
-                        i += 2;
-                    }
-                    if u8s.len() % 2 != 0 {
-                        // TODO: Support \u{X..X}
-                        unimplemented!("unsupported escape sequence");
-                    }
-                    let save_pos = self.pos;
-                    // TODO: Error handling
-                    if self.take_char()? == '\\' && self.take_char()? == 'u' {
-                        continue;
+
+            let c = match self.take_char() {
+                Ok(c) => c,
+                Err(_) => return Err(Error::UnexpectedEOF("expected '`'.".to_string())),
+            };
+            match c {
+                '`' => break true,
+                '$' if self.take_char_if('{')? => {
+                    self.template_braces.push(0);
+                    break false;
+                }
+                '\\' => {
+                    raw.push('\\');
+                    if self.take_line_terminator_in_template()? {
+                        // Line continuation
+                        raw.push('\n');
                     } else {
-                        self.pos = save_pos;
-                        break;
+                        let start = self.pos;
+                        match self.read_escaped_char() {
+                            Ok(units) => {
+                                cooked.as_mut().map(|cooked| cooked.extend(units));
+                                raw += &self.code[start..self.pos];
+                            }
+                            Err(Error::General(_, _)) => {
+                                // The rest of the escape sequence is read as it is in raw.
+                                cooked = None;
+                                self.pos = start;
+                            }
+                            Err(_) => {
+                                return Err(Error::UnexpectedEOF("expected '`'.".to_string()))
+                            }
+                        }
                     }
                 }
-                UTF_16BE
-                    .decode(u8s.as_slice(), DecoderTrap::Strict)
-                    .unwrap()
-                    .chars()
-                    .collect::<Vec<char>>()
+                c => {
+                    let mut buf = [0; 2];
+                    cooked
+                        .as_mut()
+                        .map(|cooked| cooked.extend_from_slice(c.encode_utf16(&mut buf)));
+                    raw.push(c);
+                }
+            }
+        };
+
+        Ok(Token::new_template(
+            TemplatePart {
+                cooked,
+                raw,
+                head,
+                tail,
+            },
+            pos,
+        ))
+    }
+
+    /// Take LF, CR or CRLF if the next char is a line terminator.
+    fn take_line_terminator_in_template(&mut self) -> Result<bool, Error> {
+        let cr = self.take_char_if('\r')?;
+        if !self.take_char_if('\n')? && !cr {
+            return Ok(false);
+        }
+        self.line += 1;
+        self.pos_line_list.push((self.pos, self.line));
+        Ok(true)
+    }
+
+    /// Read the escape sequence after '\\' as UTF-16 code units, which may be a lone surrogate.
+    // TODO: Support more escape sequences
+    fn read_escaped_char(&mut self) -> Result<Vec<u16>, Error> {
+        let pos = self.pos - 1;
+        let c = self.take_char()?;
+        let code_point = match c {
+            'a' => 0x07,
+            'b' => 0x08,
+            'f' => 0x0c,
+            'n' => 0x0a,
+            'r' => 0x0d,
+            't' => 0x09,
+            'v' => 0x0b,
+            'x' => self.read_hex_digits(2, pos)?,
+            'u' if self.take_char_if('{')? => {
+                let hex = self.take_char_while(|c| c.is_digit(16))?;
+                let code_point = u32::from_str_radix(hex.as_str(), 16).unwrap_or(0x110000);
+                if code_point > 0x10ffff || !self.take_char_if('}')? {
+                    return Err(Error::General(pos, "invalid escape sequence".to_string()));
+                }
+                code_point
             }
-            _ => vec![c],
+            'u' => self.read_hex_digits(4, pos)?,
+            c => c as u32,
+        };
+
+        Ok(if code_point < 0x10000 {
+            vec![code_point as u16]
+        } else {
+            let offset = code_point - 0x10000;
+            vec![
+                (0xD800 + (offset >> 10)) as u16,
+                (0xDC00 + (offset & 0x3ff)) as u16,
+            ]
         })
     }
+
+    /// Read exactly len hex digits of an escape sequence beginning at pos.
+    fn read_hex_digits(&mut self, len: usize, pos: usize) -> Result<u32, Error> {
+        let mut count = 0;
+        let hex = self.take_char_while(|c| {
+            count += 1;
+            count <= len && c.is_digit(16)
+        })?;
+        if hex.len() != len {
+            return Err(Error::General(pos, "invalid escape sequence".to_string()));
+        }
+        Ok(self.read_hex_num(hex.as_str()) as u32)
+    }
 }
 
 impl Lexer {
@@ -511,7 +671,11 @@ impl Lexer {
                 if self.take_char_if('=')? {
                     symbol = Symbol::AssignMul
                 } else if self.take_char_if('*')? {
-                    symbol = Symbol::Exp
+                    symbol = if self.take_char_if('=')? {
+                        Symbol::AssignExp
+                    } else {
+                        Symbol::Exp
+                    }
                 } else {
                     symbol = Symbol::Asterisk
                 }
@@ -584,6 +748,7 @@ impl Lexer {
                         ('<', Symbol::Shl) => Symbol::AssignShl,
                         ('<', _) => Symbol::Le,
                         ('>', Symbol::Shr) => Symbol::AssignShr,
+                        ('>', Symbol::ZFShr) => Symbol::AssignZFShr,
                         ('>', _) => Symbol::Ge,
                         ('&', Symbol::LAnd) => Symbol::AssignLAnd,
                         ('&', _) => Symbol::AssignAnd,
@@ -603,17 +768,55 @@ impl Lexer {
                     };
                 }
             }
-            '(' => symbol = Symbol::OpeningParen,
-            ')' => symbol = Symbol::ClosingParen,
+            '(' => {
+                use token::Keyword;
+                let head = match self.prev_significant_token() {
+                    Some(Kind::Keyword(Keyword::If))
+                    | Some(Kind::Keyword(Keyword::While))
+                    | Some(Kind::Keyword(Keyword::For))
+                    | Some(Kind::Keyword(Keyword::With)) => true,
+                    _ => false,
+                };
+                self.parens.push(head);
+                symbol = Symbol::OpeningParen
+            }
+            ')' => {
+                self.paren_closed_head = self.parens.pop().unwrap_or(false);
+                symbol = Symbol::ClosingParen
+            }
             '[' => symbol = Symbol::OpeningBoxBracket,
             ']' => symbol = Symbol::ClosingBoxBracket,
-            '{' => symbol = Symbol::OpeningBrace,
-            '}' => symbol = Symbol::ClosingBrace,
+            '{' => {
+                if let Some(braces) = self.template_braces.last_mut() {
+                    *braces += 1;
+                }
+                symbol = Symbol::OpeningBrace
+            }
+            '}' => {
+                if let Some(braces) = self.template_braces.last_mut() {
+                    *braces -= 1;
+                }
+                symbol = Symbol::ClosingBrace
+            }
             ',' => symbol = Symbol::Comma,
             ';' => symbol = Symbol::Semicolon,
             ':' => symbol = Symbol::Colon,
             '~' => symbol = Symbol::BitwiseNot,
-            '?' => symbol = Symbol::Question,
+            '?' => {
+                if self.starts_with("?=") {
+                    assert_eq!(self.take_char()?, '?');
+                    assert_eq!(self.take_char()?, '=');
+                    symbol = Symbol::AssignNullish
+                } else if self.take_char_if('?')? {
+                    symbol = Symbol::Nullish
+                } else if self.starts_with(".") && !self.starts_with_digit_at(self.pos + 1) {
+                    // '?.' followed by a digit is '?' and a number, as in 'a?.5:b'.
+                    assert_eq!(self.take_char()?, '.');
+                    symbol = Symbol::OptionalChaining
+                } else {
+                    symbol = Symbol::Question
+                }
+            }
             '#' => symbol = Symbol::Hash,
             '.' => {
                 if self.take_char_if('.')? {
@@ -699,6 +902,13 @@ impl Lexer {
     }
 
     /// peek next char. if eof, raise Err(Error::NormalEOF)
+    fn starts_with_digit_at(&self, pos: usize) -> bool {
+        self.code[pos..]
+            .chars()
+            .next()
+            .map_or(false, |c| c.is_ascii_digit())
+    }
+
     fn peek_char(&self) -> Result<char, Error> {
         self.code[self.pos..].chars().next().ok_or(Error::NormalEOF)
     }
@@ -766,8 +976,8 @@ fn identifier() {
 fn string() {
     let mut lexer = Lexer::new("'aaa' \"bbb\"".to_string());
     lexer.tokenize_all().unwrap();
-    assert_eq!(lexer.next().unwrap().kind, Kind::String("aaa".to_string()));
-    assert_eq!(lexer.next().unwrap().kind, Kind::String("bbb".to_string()));
+    assert_eq!(lexer.next().unwrap().kind, string_kind("aaa"));
+    assert_eq!(lexer.next().unwrap().kind, string_kind("bbb"));
 }
 
 #[test]
@@ -775,9 +985,9 @@ fn keyword() {
     use token::Keyword;
 
     let mut lexer = Lexer::new(
-        "break case catch continue debugger default \
-         delete do else finally for function if in instanceof \
-         new return switch this throw try typeof \
+        "break case catch class continue debugger default \
+         delete do else extends finally for function if in instanceof \
+         new return super switch this throw try typeof \
          var void while with"
             .to_string(),
     );
@@ -785,6 +995,7 @@ fn keyword() {
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Break,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Case,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Catch,));
+    assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Class,));
     assert_eq!(
         lexer.next().unwrap().kind,
         Kind::Keyword(Keyword::Continue,)
@@ -797,6 +1008,7 @@ fn keyword() {
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Delete,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Do,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Else,));
+    assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Extends,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Finally,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::For,));
     assert_eq!(
@@ -811,6 +1023,7 @@ fn keyword() {
     );
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::New,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Return,));
+    assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Super,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Switch,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::This,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Keyword(Keyword::Throw,));
@@ -825,10 +1038,10 @@ fn keyword() {
 #[test]
 fn symbol() {
     let mut lexer = Lexer::new(
-        "() {} [] , ; : . -> ++ -- + - * / % **\
+        "() {} [] , ; : . -> ++ -- + - * x / % **\
          ! ~ << >> >>> < <= > >= == != === !== & | ^ && || \
-         ? = += -= *= /= %= <<= >>= &= |= ^= \
-         &&= ||= #"
+         ? = += -= *= x /= %= <<= >>= &= |= ^= \
+         &&= ||= **= >>>= ??= ?. ?? #"
             .to_string(),
     );
     lexer.tokenize_all().unwrap();
@@ -867,6 +1080,10 @@ fn symbol() {
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Add,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Sub,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Asterisk,));
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::Identifier("x".to_string())
+    );
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Div,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Mod,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Exp,));
@@ -896,6 +1113,10 @@ fn symbol() {
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignAdd,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignSub,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignMul,));
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::Identifier("x".to_string())
+    );
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignDiv,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignMod,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignShl,));
@@ -908,6 +1129,20 @@ fn symbol() {
         Kind::Symbol(Symbol::AssignLAnd,)
     );
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignLOr,));
+    assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::AssignExp,));
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::Symbol(Symbol::AssignZFShr,)
+    );
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::Symbol(Symbol::AssignNullish,)
+    );
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::Symbol(Symbol::OptionalChaining,)
+    );
+    assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Nullish,));
     assert_eq!(lexer.next().unwrap().kind, Kind::Symbol(Symbol::Hash,));
     use token::get_string_for_symbol;
     assert_eq!(
@@ -950,7 +1185,132 @@ fn escape_seq() {
     lexer.tokenize_all().unwrap();
     assert_eq!(
         lexer.next().unwrap().kind,
-        Kind::String("\' \" \\ \x07 \x08 \x0c \n \r \t \x0b \x12 𩸽".to_string())
+        string_kind("\' \" \\ \x07 \x08 \x0c \n \r \t \x0b \x12 𩸽")
+    );
+
+    let mut lexer = Lexer::new("'\\uD800\\x41\\u{1F600}\\u{41}'".to_string());
+    lexer.tokenize_all().unwrap();
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::String(vec![0xD800, 0x41, 0xD83D, 0xDE00, 0x41])
+    );
+
+    for code in &["'\\u{}'", "'\\u{110000}'", "'\\uD8'", "'\\xZ'"] {
+        let mut lexer = Lexer::new(code.to_string());
+        assert!(lexer.tokenize_all().is_err(), "{}", code);
+    }
+}
+
+#[cfg(test)]
+fn string_kind(s: &str) -> Kind {
+    Kind::String(s.encode_utf16().collect())
+}
+
+#[test]
+fn template() {
+    use token::TemplatePart;
+
+    let mut lexer = Lexer::new("`a${ {} }b${c}`".to_string());
+    lexer.tokenize_all().unwrap();
+    let part = |s: &str, head: bool, tail: bool| {
+        Kind::Template(TemplatePart {
+            cooked: Some(s.encode_utf16().collect()),
+            raw: s.to_string(),
+            head,
+            tail,
+        })
+    };
+    assert_eq!(lexer.next().unwrap().kind, part("a", true, false));
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::Symbol(Symbol::OpeningBrace)
+    );
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::Symbol(Symbol::ClosingBrace)
+    );
+    assert_eq!(lexer.next().unwrap().kind, part("b", false, false));
+    assert_eq!(
+        lexer.next().unwrap().kind,
+        Kind::Identifier("c".to_string())
+    );
+    assert_eq!(lexer.next().unwrap().kind, part("", false, true));
+}
+
+#[test]
+fn regexp() {
+    let mut lexer = Lexer::new("a / b / c; (1) / /=\\//gi.x;\n/[/]/".to_string());
+    lexer.tokenize_all().unwrap();
+    let kinds = lexer
+        .buf
+        .iter()
+        .map(|tok| tok.kind.clone())
+        .collect::<Vec<Kind>>();
+    assert_eq!(
+        kinds,
+        vec![
+            Kind::Identifier("a".to_string()),
+            Kind::Symbol(Symbol::Div),
+            Kind::Identifier("b".to_string()),
+            Kind::Symbol(Symbol::Div),
+            Kind::Identifier("c".to_string()),
+            Kind::Symbol(Symbol::Semicolon),
+            Kind::Symbol(Symbol::OpeningParen),
+            Kind::Number(1.0),
+            Kind::Symbol(Symbol::ClosingParen),
+            Kind::Symbol(Symbol::Div),
+            Kind::RegExp("=\\/".to_string(), "gi".to_string()),
+            Kind::Symbol(Symbol::Point),
+            Kind::Identifier("x".to_string()),
+            Kind::Symbol(Symbol::Semicolon),
+            Kind::LineTerminator,
+            Kind::RegExp("[/]".to_string(), "".to_string()),
+        ]
+    );
+
+    assert!(Lexer::new("/a\n/".to_string()).tokenize_all().is_err());
+
+    // '/' after the head of if, while, for or with statements begins a regular expression.
+    let mut lexer = Lexer::new("if (f(a)) /b/; while (x) /c/g; g(x) / 2".to_string());
+    lexer.tokenize_all().unwrap();
+    let regexps = lexer
+        .buf
+        .iter()
+        .filter(|tok| match tok.kind {
+            Kind::RegExp(_, _) => true,
+            _ => false,
+        })
+        .count();
+    assert_eq!(regexps, 2);
+    assert_eq!(
+        lexer.buf[lexer.buf.len() - 2].kind,
+        Kind::Symbol(Symbol::Div)
+    );
+}
+
+#[test]
+fn optional_chaining() {
+    let mut lexer = Lexer::new("a?.b ?? c?.5:d".to_string());
+    lexer.tokenize_all().unwrap();
+    let kinds = lexer
+        .buf
+        .iter()
+        .map(|tok| tok.kind.clone())
+        .collect::<Vec<Kind>>();
+    assert_eq!(
+        kinds,
+        vec![
+            Kind::Identifier("a".to_string()),
+            Kind::Symbol(Symbol::OptionalChaining),
+            Kind::Identifier("b".to_string()),
+            Kind::Symbol(Symbol::Nullish),
+            Kind::Identifier("c".to_string()),
+            Kind::Symbol(Symbol::Question),
+            Kind::Symbol(Symbol::Point),
+            Kind::Number(5.0),
+            Kind::Symbol(Symbol::Colon),
+            Kind::Identifier("d".to_string()),
+        ]
     );
 }
 
@@ -14,12 +14,14 @@ pub mod id;
 pub mod lexer;
 pub mod node;
 pub mod parser;
+pub mod regexp;
 pub mod test;
 pub mod token;
 
 extern crate ansi_term;
 extern crate chrono;
 extern crate encoding;
+extern crate indexmap;
 extern crate libc;
 extern crate libloading;
 extern crate llvm_sys as llvm;
@@ -28,6 +30,7 @@ extern crate rand;
 extern crate rustc_hash;
 extern crate rustyline;
 extern crate stopwatch;
+extern crate unicode_normalization;
 #[macro_use]
 extern crate nanbox;
 // extern crate cpuprofiler;
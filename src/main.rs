@@ -143,6 +143,11 @@ fn repl() {
                         break;
                     }
 
+                    if let Err(e) = vm.run_jobs() {
+                        e.show_error_message(None);
+                        break;
+                    }
+
                     if vm.stack.len() != 0 {
                         let val: Value = vm.stack[0].into();
                         println!("{}", val.debug_string(true));
@@ -191,21 +196,12 @@ mod tests {
         // );
         // test_code("'true'*3".to_string(), "'truetruetrue'".to_string());
         // test_code("(100).toString(15)".to_string(), "'6a'".to_string());
-        // test_file(
-        //     "label".to_string(),
-        //     "[ 0, 0, 0, 1, 0, 2, 1, 0, 2, 0, 3, 0, 3, 1, 4, 1, 4, 2, 0 ]".to_string(),
-        // );
 
         // test_file("trycatch".to_string(), "[ 0, 2, 123, 10110 ]".to_string());
         //test_file(
         //    "qsort".to_string(),
         //    "[ 0, 0, 1, 3, 5, 7, 7, 10, 11, 12, 14, 14, 16, 17, 19 ]".to_string(),
         //);
-        // test_file("arguments1".to_string(), "[[1,2,3,4,4],[1,2,[3,4]],[5,6,7,undefined,3],[5,6,[7]],[8,9,undefined,undefined,2],[8,9,undefined],[10,undefined,undefined,undefined,1],[10,undefined,undefined]]".to_string());
-        // test_file(
-        //     "arguments2".to_string(),
-        //     "[10,15,20,25,15,10,'OK',20,25,'OK',10,'NG',20,25,'NG']".to_string(),
-        // );
     }
 
     #[test]
@@ -276,6 +272,121 @@ mod tests {
         assert_file("while")
     }
 
+    #[test]
+    fn for_in() {
+        assert_file("for_in")
+    }
+
+    #[test]
+    fn for_of() {
+        assert_file("for_of")
+    }
+
+    #[test]
+    fn generator() {
+        assert_file("generator")
+    }
+
+    #[test]
+    fn promise() {
+        assert_file("promise")
+    }
+
+    #[test]
+    fn async_function() {
+        assert_file("async")
+    }
+
+    #[test]
+    fn class() {
+        assert_file("class")
+    }
+
+    #[test]
+    fn destructuring() {
+        assert_file("destructuring")
+    }
+
+    #[test]
+    fn spread() {
+        assert_file("spread")
+    }
+
+    #[test]
+    fn default_parameters() {
+        assert_file("default_parameters")
+    }
+
+    #[test]
+    fn arguments() {
+        test_file("arguments1", "[[1,2,3,4,4],[1,2,[3,4]],[5,6,7,undefined,3],[5,6,[7]],[8,9,undefined,undefined,2],[8,9,[]],[10,undefined,undefined,undefined,1],[10,undefined,[]]]".to_string());
+        test_file(
+            "arguments2",
+            "[10,15,20,25,15,10,'OK',20,25,'OK',10,'NG',20,25,'NG']".to_string(),
+        );
+        assert_file("arguments")
+    }
+
+    #[test]
+    fn template_literal() {
+        assert_file("template_literal")
+    }
+
+    #[test]
+    fn regexp() {
+        assert_file("regexp")
+    }
+
+    #[test]
+    fn compound_assignment() {
+        assert_file("compound_assignment")
+    }
+
+    #[test]
+    fn optional_chaining() {
+        assert_file("optional_chaining")
+    }
+
+    #[test]
+    fn instanceof_in() {
+        assert_file("instanceof_in")
+    }
+
+    #[test]
+    fn delete() {
+        assert_file("delete")
+    }
+
+    #[test]
+    fn object_literal() {
+        assert_file("object_literal")
+    }
+
+    #[test]
+    fn array_methods() {
+        assert_file("array_methods")
+    }
+
+    #[test]
+    fn string_methods() {
+        assert_file("string_methods")
+    }
+
+    #[test]
+    fn object_methods() {
+        assert_file("object_methods")
+    }
+
+    #[test]
+    fn set_property() {
+        assert_file("set_property")
+    }
+
+    #[test]
+    fn do_while() {
+        assert_file("do_while")
+    }
+
     #[test]
     fn r#for() {
         assert_file("for")
@@ -286,6 +397,26 @@ mod tests {
         assert_file("if")
     }
 
+    #[test]
+    fn label() {
+        test_file(
+            "label",
+            "[0,0,0,1,0,2,1,0,2,0,3,0,3,1,4,1,4,2,0]".to_string(),
+        );
+        assert_file("labelled_statement");
+    }
+
+    #[test]
+    #[should_panic]
+    fn undefined_label() {
+        execute_script("for (;;) { break label }".to_string());
+    }
+
+    #[test]
+    fn switch() {
+        assert_file("switch")
+    }
+
     #[test]
     fn arrow_function() {
         test_code(
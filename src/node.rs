@@ -5,6 +5,8 @@ pub struct FormalParameter {
     pub name: String,
     pub init: Option<Node>,
     pub is_rest_param: bool,
+    /// Binding pattern which destructures the argument. `name` is empty then.
+    pub pattern: Option<Node>,
 }
 
 pub type FormalParameters = Vec<FormalParameter>;
@@ -15,6 +17,7 @@ impl FormalParameter {
             name: name,
             init: init,
             is_rest_param: is_rest_param,
+            pattern: None,
         }
     }
 }
@@ -23,8 +26,32 @@ impl FormalParameter {
 #[derive(Clone, Debug, PartialEq)]
 pub enum PropertyDefinition {
     IdentifierReference(String),
-    Property(String, Node),
-    MethodDefinition(MethodDefinitionKind, String, Node),
+    Property(PropertyName, Node),
+    MethodDefinition(MethodDefinitionKind, PropertyName, Node),
+    /// {...obj}
+    Spread(Node),
+    /// {__proto__: obj}, which sets \[\[Prototype\]\] instead of defining a property
+    Proto(Node),
+}
+
+/// https://tc39.github.io/ecma262/#prod-PropertyName
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyName {
+    /// Identifier, string literal or numeric literal as a key of property maps. See
+    /// JSString::to_property_key.
+    Literal(String),
+    /// [expr]
+    Computed(Node),
+}
+
+/// Kind of function declarations and function expressions.
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum FunctionKind {
+    Ordinary,
+    /// function* f() {}
+    Generator,
+    /// async function f() {}
+    Async,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -34,6 +61,42 @@ pub enum MethodDefinitionKind {
     Ordinary,
 }
 
+/// A property of object patterns. `value` is the target, or `Assign(target, default)` if it has
+/// a default value.
+/// https://tc39.github.io/ecma262/#prod-BindingProperty
+/// https://tc39.github.io/ecma262/#prod-AssignmentProperty
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyPattern {
+    pub key: PropertyName,
+    pub value: Node,
+}
+
+/// A method of class. Constructors are not included.
+/// https://tc39.github.io/ecma262/#prod-ClassElement
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassElement {
+    pub is_static: bool,
+    pub kind: MethodDefinitionKind,
+    pub name: PropertyName,
+    pub func: Node, // FunctionExpr
+}
+
+/// A clause of switch statement. `test` is None for `default`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaseClause {
+    pub test: Option<Node>,
+    pub body: Vec<Node>,
+}
+
+impl CaseClause {
+    pub fn new(test: Option<Node>, body: Vec<Node>) -> CaseClause {
+        CaseClause {
+            test: test,
+            body: body,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Node {
     pub base: NodeBase,
@@ -51,22 +114,35 @@ pub enum VarKind {
 pub enum NodeBase {
     StatementList(Vec<Node>),
     Block(Vec<Node>),
-    FunctionDecl(String, FormalParameters, Box<Node>), // name, params, body
-    FunctionExpr(Option<String>, FormalParameters, Box<Node>), // Name, params, body
-    ArrowFunction(FormalParameters, Box<Node>),
+    FunctionDecl(String, FormalParameters, Box<Node>, FunctionKind), // name, params, body, kind
+    FunctionExpr(Option<String>, FormalParameters, Box<Node>, FunctionKind), // Name, params, body, kind
+    ArrowFunction(FormalParameters, Box<Node>, FunctionKind),                // params, body, kind
+    Class(Option<String>, Box<Node>, Box<Node>, Vec<ClassElement>), // name, heritage, ctor, methods
     VarDecl(String, Option<Box<Node>>, VarKind),
+    PatternDecl(Box<Node>, Option<Box<Node>>, VarKind), // Pattern, Init, Kind
+    ObjectPattern(Vec<PropertyPattern>, Option<Box<Node>>), // Properties, Rest
+    ArrayPattern(Vec<Node>, Option<Box<Node>>),         // Elements (Nope for holes), Rest
     Member(Box<Node>, String),
     Index(Box<Node>, Box<Node>),
     New(Box<Node>),
     Call(Box<Node>, Vec<Node>),
+    OptionalChain(Box<Node>),            // Chain containing Optional
+    Optional(Box<Node>),                 // Expression before '?.'
     If(Box<Node>, Box<Node>, Box<Node>), // Cond, Then, Else
     While(Box<Node>, Box<Node>),         // Cond, Body
+    DoWhile(Box<Node>, Box<Node>),       // Body, Cond
     For(Box<Node>, Box<Node>, Box<Node>, Box<Node>), // Init, Cond, Step, Body
+    ForIn(Box<Node>, Box<Node>, Box<Node>), // Binding (VarDecl or LHS), Object, Body
+    ForOf(Box<Node>, Box<Node>, Box<Node>), // Binding (VarDecl or LHS), Iterable, Body
+    Switch(Box<Node>, Vec<CaseClause>),  // Discriminant, Clauses
     Assign(Box<Node>, Box<Node>),
+    CompoundAssign(Box<Node>, Box<Node>, BinOp), // Target, Value, Operator
     UnaryOp(Box<Node>, UnaryOp),
     BinaryOp(Box<Node>, Box<Node>, BinOp),
     TernaryOp(Box<Node>, Box<Node>, Box<Node>),
     Return(Option<Box<Node>>),
+    Yield(Option<Box<Node>>, bool), // Operand, Delegate (yield*)
+    Await(Box<Node>),
     Label(String, Box<Node>),
     Break(Option<String>),
     Continue(Option<String>),
@@ -74,12 +150,18 @@ pub enum NodeBase {
     Throw(Box<Node>),
     Array(Vec<Node>),
     Object(Vec<PropertyDefinition>),
+    Spread(Box<Node>), // Element of arguments and array literals
+    Template(Vec<Option<Vec<u16>>>, Vec<String>, Vec<Node>), // Cooked, Raw, Substitutions
+    TaggedTemplate(Box<Node>, Box<Node>), // Tag, Template
+    RegExp(String, String), // Pattern, Flags
     Identifier(String),
     This,
-    // Arguments,
+    Super,
+    SuperCall(Box<Node>), // Array of arguments
+    Arguments,
     // Undefined,
     Null,
-    String(String),
+    String(Vec<u16>), // UTF-16 code units
     Boolean(bool),
     Number(f64),
     Nope,
@@ -92,6 +174,51 @@ impl Node {
             pos: pos,
         }
     }
+
+    /// Return true if the node is while, do-while, for, for-in or for-of statement.
+    pub fn is_iteration_statement(&self) -> bool {
+        match self.base {
+            NodeBase::While(_, _)
+            | NodeBase::DoWhile(_, _)
+            | NodeBase::For(_, _, _, _)
+            | NodeBase::ForIn(_, _, _)
+            | NodeBase::ForOf(_, _, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Return the names bound by the identifier or the pattern.
+    /// https://tc39.github.io/ecma262/#sec-destructuring-binding-patterns-static-semantics-boundnames
+    pub fn bound_names(&self) -> Vec<String> {
+        fn collect(node: &Node, names: &mut Vec<String>) {
+            match node.base {
+                NodeBase::Identifier(ref name) => names.push(name.clone()),
+                NodeBase::Assign(ref target, _) => collect(target, names),
+                NodeBase::ObjectPattern(ref properties, ref rest) => {
+                    for property in properties {
+                        collect(&property.value, names)
+                    }
+                    if let Some(rest) = rest {
+                        collect(rest, names)
+                    }
+                }
+                NodeBase::ArrayPattern(ref elements, ref rest) => {
+                    for element in elements {
+                        collect(element, names)
+                    }
+                    if let Some(rest) = rest {
+                        collect(rest, names)
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut names = vec![];
+        collect(self, &mut names);
+        names
+    }
+
     /*
     pub fn definitely_returns(&self) -> bool {
         match self.base {
@@ -192,7 +319,7 @@ impl NodeBase {
                         _ => None,
                     },
                     (NodeBase::String(l), NodeBase::String(r)) => match op {
-                        BinOp::Add => Some(NodeBase::String(l + r.as_str())),
+                        BinOp::Add => Some(NodeBase::String([&l[..], &r[..]].concat())),
                         _ => None,
                     },
                     _ => None,
@@ -245,6 +372,7 @@ pub enum BinOp {
     Xor,
     LAnd,
     LOr,
+    Nullish,
     Eq,
     Ne,
     SEq, // Strict Eq
@@ -253,6 +381,8 @@ pub enum BinOp {
     Gt,
     Le,
     Ge,
+    InstanceOf,
+    In,
     Shl,
     Shr,
     ZFShr,
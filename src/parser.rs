@@ -1,9 +1,13 @@
 pub use lexer;
 use node::{
-    BinOp, FormalParameter, FormalParameters, MethodDefinitionKind, Node, NodeBase,
-    PropertyDefinition, UnaryOp, VarKind,
+    BinOp, CaseClause, ClassElement, FormalParameter, FormalParameters, FunctionKind,
+    MethodDefinitionKind, Node, NodeBase, PropertyDefinition, PropertyName, PropertyPattern,
+    UnaryOp, VarKind,
 };
-use token::{get_string_for_symbol, Keyword, Kind, Symbol, Token};
+use regexp;
+use std::mem;
+use token::{get_string_for_symbol, Keyword, Kind, Symbol, TemplatePart, Token};
+use vm::jsvalue::string::JSString;
 
 use ansi_term::Colour;
 
@@ -40,12 +44,16 @@ pub enum Error {
 #[derive(Clone, Debug)]
 pub struct Parser {
     pub lexer: lexer::Lexer,
+    /// Kind of the function whose body is being read. 'yield' is an operator in generator
+    /// functions, and so is 'await' in async functions.
+    function_kind: FunctionKind,
 }
 
 impl Parser {
     pub fn new(code: String) -> Parser {
         Parser {
             lexer: lexer::Lexer::new(code),
+            function_kind: FunctionKind::Ordinary,
         }
     }
 
@@ -178,8 +186,17 @@ impl Parser {
         if let Ok(tok) = self.lexer.peek_skip_lineterminator() {
             match tok.kind {
                 Kind::Keyword(Keyword::Function) => self.read_declaration(),
+                Kind::Keyword(Keyword::Class) => self.read_declaration(),
                 Kind::Keyword(Keyword::Const) => self.read_declaration(),
                 Kind::Keyword(Keyword::Let) => self.read_declaration(),
+                Kind::Identifier(ref name) if name == "async" => {
+                    self.lexer.skip_lineterminator()?;
+                    if self.is_async_function() {
+                        self.read_declaration()
+                    } else {
+                        self.read_statement()
+                    }
+                }
                 _ => self.read_statement(),
             }
         } else {
@@ -203,8 +220,11 @@ impl Parser {
                     self.lexer.next_skip_lineterminator()?.kind,
                     Kind::Symbol(Symbol::Colon)
                 );
-                // TODO: https://tc39.github.io/ecma262/#prod-LabelledStatement
-                let labeled_item = self.read_statement_list_item()?;
+                // https://tc39.github.io/ecma262/#prod-LabelledItem
+                let labeled_item = match self.lexer.peek_skip_lineterminator()?.kind {
+                    Kind::Keyword(Keyword::Function) => self.read_declaration()?,
+                    _ => self.read_statement()?,
+                };
                 return Ok(Node::new(
                     NodeBase::Label(name.clone(), Box::new(labeled_item)),
                     tok.pos,
@@ -216,7 +236,9 @@ impl Parser {
             Kind::Keyword(Keyword::If) => self.read_if_statement(),
             Kind::Keyword(Keyword::Var) => self.read_variable_statement(),
             Kind::Keyword(Keyword::While) => self.read_while_statement(),
+            Kind::Keyword(Keyword::Do) => self.read_do_while_statement(),
             Kind::Keyword(Keyword::For) => self.read_for_statement(),
+            Kind::Keyword(Keyword::Switch) => self.read_switch_statement(),
             Kind::Keyword(Keyword::Return) => self.read_return_statement(),
             Kind::Keyword(Keyword::Break) => self.read_break_statement(),
             Kind::Keyword(Keyword::Continue) => self.read_continue_statement(),
@@ -307,6 +329,9 @@ impl Parser {
     /// https://tc39.github.io/ecma262/#prod-VariableDeclaration
     fn read_variable_declaration(&mut self) -> Result<Node, Error> {
         let pos = self.lexer.get_current_pos();
+        if self.is_pattern_start()? {
+            return self.read_pattern_declaration(VarKind::Var);
+        }
         let name = match self.lexer.next_skip_lineterminator()?.kind {
             Kind::Identifier(name) => name,
             _ => {
@@ -330,6 +355,22 @@ impl Parser {
         }
     }
 
+    /// Read a declaration with a binding pattern, which must have an initializer.
+    fn read_pattern_declaration(&mut self, var_kind: VarKind) -> Result<Node, Error> {
+        let pattern = self.read_pattern(false)?;
+        expect!(
+            self,
+            Kind::Symbol(Symbol::Assign),
+            "Missing initializer in destructuring declaration"
+        );
+        let init = self.read_initializer()?;
+        let pos = pattern.pos;
+        Ok(Node::new(
+            NodeBase::PatternDecl(Box::new(pattern), Some(Box::new(init)), var_kind),
+            pos,
+        ))
+    }
+
     /// https://tc39.github.io/ecma262/#prod-Initializer
     fn read_initializer(&mut self) -> Result<Node, Error> {
         self.read_assignment_expression()
@@ -393,11 +434,34 @@ impl Parser {
         ))
     }
 
+    /// https://tc39.github.io/ecma262/#prod-IterationStatement
+    fn read_do_while_statement(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_prev_pos();
+
+        let body = self.read_statement()?;
+
+        expect!(self, Kind::Keyword(Keyword::While), "expect 'while'");
+        expect!(self, Kind::Symbol(Symbol::OpeningParen), "expect '('");
+
+        let cond = self.read_expression()?;
+
+        expect!(self, Kind::Symbol(Symbol::ClosingParen), "expect ')'");
+
+        Ok(Node::new(
+            NodeBase::DoWhile(Box::new(body), Box::new(cond)),
+            pos,
+        ))
+    }
+
     fn read_for_statement(&mut self) -> Result<Node, Error> {
         let pos = self.lexer.get_prev_pos();
 
         expect!(self, Kind::Symbol(Symbol::OpeningParen), "expect '('");
 
+        if let Some(for_in_of) = self.read_for_in_of_statement(pos)? {
+            return Ok(for_in_of);
+        }
+
         let init = match self.lexer.peek(0)?.kind {
             Kind::Keyword(Keyword::Var) => {
                 assert_eq!(self.lexer.next()?.kind, Kind::Keyword(Keyword::Var));
@@ -429,7 +493,7 @@ impl Parser {
 
         let body = self.read_statement()?;
 
-        let for_node = Node::new(
+        Ok(Node::new(
             NodeBase::For(
                 Box::new(init),
                 Box::new(cond),
@@ -437,9 +501,165 @@ impl Parser {
                 Box::new(body),
             ),
             pos,
-        );
+        ))
+    }
+}
+
+impl Parser {
+    /// Read the rest of for-in or for-of statement after 'for ('.
+    /// Return None without consuming any tokens if it is neither for-in nor for-of statement.
+    fn read_for_in_of_statement(&mut self, pos: usize) -> Result<Option<Node>, Error> {
+        let save_pos = self.lexer.token_pos;
+
+        let var_kind = match self.lexer.peek_skip_lineterminator()?.kind {
+            Kind::Keyword(Keyword::Var) => Some(VarKind::Var),
+            Kind::Keyword(Keyword::Let) => Some(VarKind::Let),
+            Kind::Keyword(Keyword::Const) => Some(VarKind::Const),
+            _ => None,
+        };
+
+        let binding = if let Some(var_kind) = var_kind {
+            self.lexer.next_skip_lineterminator()?;
+            if self.is_pattern_start()? {
+                let pattern = self.read_pattern(false)?;
+                let pos = pattern.pos;
+                Node::new(
+                    NodeBase::PatternDecl(Box::new(pattern), None, var_kind),
+                    pos,
+                )
+            } else {
+                let tok = self.lexer.next_skip_lineterminator()?;
+                match tok.kind {
+                    Kind::Identifier(name) => {
+                        Node::new(NodeBase::VarDecl(name, None, var_kind), tok.pos)
+                    }
+                    _ => {
+                        self.lexer.token_pos = save_pos;
+                        return Ok(None);
+                    }
+                }
+            }
+        } else if self.is_pattern_start()? {
+            match self.read_pattern(true) {
+                Ok(pattern) => pattern,
+                Err(_) => {
+                    self.lexer.token_pos = save_pos;
+                    return Ok(None);
+                }
+            }
+        } else {
+            match self.read_left_hand_side_expression() {
+                Ok(lhs) => lhs,
+                Err(_) => {
+                    self.lexer.token_pos = save_pos;
+                    return Ok(None);
+                }
+            }
+        };
+
+        let is_for_of = match self.lexer.next_skip_lineterminator()?.kind {
+            Kind::Keyword(Keyword::In) => false,
+            Kind::Identifier(ref name) if name == "of" => true,
+            _ => {
+                self.lexer.token_pos = save_pos;
+                return Ok(None);
+            }
+        };
+
+        let obj = if is_for_of {
+            self.read_assignment_expression()?
+        } else {
+            self.read_expression()?
+        };
+
+        expect!(self, Kind::Symbol(Symbol::ClosingParen), "expect ')'");
+
+        let body = self.read_statement()?;
+
+        Ok(Some(Node::new(
+            if is_for_of {
+                NodeBase::ForOf(Box::new(binding), Box::new(obj), Box::new(body))
+            } else {
+                NodeBase::ForIn(Box::new(binding), Box::new(obj), Box::new(body))
+            },
+            pos,
+        )))
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-SwitchStatement
+    fn read_switch_statement(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_prev_pos();
+
+        expect!(self, Kind::Symbol(Symbol::OpeningParen), "expect '('");
+
+        let discriminant = self.read_expression()?;
+
+        expect!(self, Kind::Symbol(Symbol::ClosingParen), "expect ')'");
+        expect!(self, Kind::Symbol(Symbol::OpeningBrace), "expect '{'");
+
+        let mut clauses = vec![];
+        let mut found_default = false;
+
+        loop {
+            let tok = self.lexer.next_skip_lineterminator()?;
+            let test = match tok.kind {
+                Kind::Symbol(Symbol::ClosingBrace) => break,
+                Kind::Keyword(Keyword::Case) => Some(self.read_expression()?),
+                Kind::Keyword(Keyword::Default) => {
+                    if found_default {
+                        return Err(Error::General(
+                            tok.pos,
+                            "more than one default clause in switch statement".to_string(),
+                        ));
+                    }
+                    found_default = true;
+                    None
+                }
+                _ => {
+                    return Err(Error::UnexpectedToken(
+                        tok.pos,
+                        "expected 'case', 'default' or '}'".to_string(),
+                    ));
+                }
+            };
+
+            expect!(self, Kind::Symbol(Symbol::Colon), "expect ':'");
+
+            clauses.push(CaseClause::new(test, self.read_case_clause_body()?));
+        }
+
+        Ok(Node::new(
+            NodeBase::Switch(Box::new(discriminant), clauses),
+            pos,
+        ))
+    }
+
+    /// Read statements until the next 'case', 'default' or '}'.
+    fn read_case_clause_body(&mut self) -> Result<Vec<Node>, Error> {
+        let mut body = vec![];
+
+        loop {
+            match self.lexer.peek_skip_lineterminator() {
+                Ok(tok) => match tok.kind {
+                    Kind::Keyword(Keyword::Case)
+                    | Kind::Keyword(Keyword::Default)
+                    | Kind::Symbol(Symbol::ClosingBrace) => break,
+                    Kind::Symbol(Symbol::Semicolon) => {
+                        self.lexer.next_skip_lineterminator()?;
+                        continue;
+                    }
+                    _ => {}
+                },
+                Err(Error::NormalEOF) => {
+                    return Err(Error::UnexpectedEOF("expected '}'.".to_string()))
+                }
+                Err(e) => return Err(e),
+            }
+
+            body.push(self.read_statement_list_item()?);
+        }
 
-        Ok(Node::new(NodeBase::Block(vec![for_node]), pos))
+        Ok(body)
     }
 }
 
@@ -516,28 +736,81 @@ impl Parser {
         self.lexer.skip_lineterminator()?;
         let pos = self.lexer.get_current_pos();
 
+        if self.function_kind == FunctionKind::Generator
+            && self.lexer.peek(0)?.kind == Kind::Identifier("yield".to_string())
+        {
+            return self.read_yield_expression();
+        }
+
+        // Error to report if an object or array literal, which is not a valid pattern, turns
+        // out to be the target of '='.
+        let mut pattern_error = None;
+
         // Arrow function
         let next_token = self.lexer.peek(0)?;
         match next_token.kind {
             // (a,b)=>{}
             Kind::Symbol(Symbol::OpeningParen) => {
                 let save_pos = self.lexer.token_pos;
-                let f = self.read_arrow_function(true);
+                let f = self.read_arrow_function(true, FunctionKind::Ordinary);
                 if f.is_err() {
                     self.lexer.token_pos = save_pos;
                 } else {
                     return f;
                 }
             }
+            // async (a,b)=>{}, async a=>{}
+            Kind::Identifier(ref name) if name == "async" => {
+                let save_pos = self.lexer.token_pos;
+                // no LineTerminator here
+                self.lexer.next()?; // async
+                let is_parenthesized_param = match self.lexer.peek(0) {
+                    Ok(ref tok) if tok.kind == Kind::Symbol(Symbol::OpeningParen) => Some(true),
+                    Ok(Token {
+                        kind: Kind::Identifier(_),
+                        ..
+                    }) => match self.lexer.peek(1) {
+                        Ok(ref tok) if tok.kind == Kind::Symbol(Symbol::FatArrow) => Some(false),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if let Some(is_parenthesized_param) = is_parenthesized_param {
+                    let f = self.read_arrow_function(is_parenthesized_param, FunctionKind::Async);
+                    if f.is_ok() {
+                        return f;
+                    }
+                }
+                self.lexer.token_pos = save_pos;
+            }
             // a=>{}
             Kind::Identifier(_) => match self.lexer.peek(1) {
                 Ok(tok) => {
                     if tok.kind == Kind::Symbol(Symbol::FatArrow) {
-                        return self.read_arrow_function(false);
+                        return self.read_arrow_function(false, FunctionKind::Ordinary);
                     }
                 }
                 _ => {}
             },
+            // [a, b] = [b, a]
+            Kind::Symbol(Symbol::OpeningBoxBracket) | Kind::Symbol(Symbol::OpeningBrace) => {
+                let save_pos = self.lexer.token_pos;
+                match self.read_pattern(true) {
+                    Ok(pattern) if self.lexer.next_if(Kind::Symbol(Symbol::Assign)) => {
+                        let src = self.read_assignment_expression()?;
+                        return Ok(Node::new(
+                            NodeBase::Assign(Box::new(pattern), Box::new(src)),
+                            pos,
+                        ));
+                    }
+                    // Array or object literal
+                    Ok(_) => self.lexer.token_pos = save_pos,
+                    Err(err) => {
+                        self.lexer.token_pos = save_pos;
+                        pattern_error = Some(err);
+                    }
+                }
+            }
             _ => {}
         }
 
@@ -546,18 +819,23 @@ impl Parser {
         if let Ok(tok) = self.lexer.next() {
             macro_rules! assignop {
                 ($op:ident) => {{
+                    match lhs.base {
+                        NodeBase::Identifier(_)
+                        | NodeBase::Member(_, _)
+                        | NodeBase::Index(_, _) => {}
+                        _ => {
+                            return Err(Error::General(
+                                lhs.pos,
+                                "Invalid left-hand side in assignment".to_string(),
+                            ))
+                        }
+                    }
                     let lhs_pos = lhs.pos;
                     lhs = Node::new(
-                        NodeBase::Assign(
-                            Box::new(lhs.clone()),
-                            Box::new(Node::new(
-                                NodeBase::BinaryOp(
-                                    Box::new(lhs),
-                                    Box::new(self.read_assignment_expression()?),
-                                    BinOp::$op,
-                                ),
-                                pos,
-                            )),
+                        NodeBase::CompoundAssign(
+                            Box::new(lhs),
+                            Box::new(self.read_assignment_expression()?),
+                            BinOp::$op,
                         ),
                         lhs_pos,
                     );
@@ -565,6 +843,18 @@ impl Parser {
             }
             match tok.kind {
                 Kind::Symbol(Symbol::Assign) => {
+                    match (pattern_error, &lhs.base) {
+                        (Some(err), NodeBase::Object(_)) | (Some(err), NodeBase::Array(_)) => {
+                            return Err(err)
+                        }
+                        (_, NodeBase::OptionalChain(_)) => {
+                            return Err(Error::General(
+                                lhs.pos,
+                                "Invalid left-hand side in assignment".to_string(),
+                            ))
+                        }
+                        _ => {}
+                    }
                     let lhs_pos = lhs.pos;
                     lhs = Node::new(
                         NodeBase::Assign(
@@ -579,17 +869,65 @@ impl Parser {
                 Kind::Symbol(Symbol::AssignMul) => assignop!(Mul),
                 Kind::Symbol(Symbol::AssignDiv) => assignop!(Div),
                 Kind::Symbol(Symbol::AssignMod) => assignop!(Rem),
+                Kind::Symbol(Symbol::AssignExp) => assignop!(Exp),
+                Kind::Symbol(Symbol::AssignShl) => assignop!(Shl),
+                Kind::Symbol(Symbol::AssignShr) => assignop!(Shr),
+                Kind::Symbol(Symbol::AssignZFShr) => assignop!(ZFShr),
+                Kind::Symbol(Symbol::AssignAnd) => assignop!(And),
+                Kind::Symbol(Symbol::AssignOr) => assignop!(Or),
+                Kind::Symbol(Symbol::AssignXor) => assignop!(Xor),
+                Kind::Symbol(Symbol::AssignLAnd) => assignop!(LAnd),
+                Kind::Symbol(Symbol::AssignLOr) => assignop!(LOr),
+                Kind::Symbol(Symbol::AssignNullish) => assignop!(Nullish),
                 _ => self.lexer.unget(),
             }
         }
         Ok(lhs)
     }
 
+    /// https://tc39.github.io/ecma262/#prod-YieldExpression
+    fn read_yield_expression(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_current_pos();
+        self.lexer.next()?; // yield
+
+        // no LineTerminator here
+        if self.lexer.next_if(Kind::Symbol(Symbol::Asterisk)) {
+            let operand = self.read_assignment_expression()?;
+            return Ok(Node::new(
+                NodeBase::Yield(Some(Box::new(operand)), true),
+                pos,
+            ));
+        }
+
+        let has_operand = match self.lexer.peek(0) {
+            Ok(tok) => match tok.kind {
+                Kind::LineTerminator
+                | Kind::EOF
+                | Kind::Symbol(Symbol::ClosingParen)
+                | Kind::Symbol(Symbol::ClosingBrace)
+                | Kind::Symbol(Symbol::ClosingBoxBracket)
+                | Kind::Symbol(Symbol::Comma)
+                | Kind::Symbol(Symbol::Semicolon)
+                | Kind::Symbol(Symbol::Colon) => false,
+                _ => true,
+            },
+            Err(_) => false,
+        };
+
+        let operand = if has_operand {
+            Some(Box::new(self.read_assignment_expression()?))
+        } else {
+            None
+        };
+
+        Ok(Node::new(NodeBase::Yield(operand, false), pos))
+    }
+
     /// https://tc39.github.io/ecma262/#prod-ConditionalExpression
     fn read_conditional_expression(&mut self) -> Result<Node, Error> {
         let pos = self.lexer.get_current_pos();
 
-        let lhs = self.read_logical_or_expression()?;
+        let lhs = self.read_short_circuit_expression()?;
 
         if let Ok(tok) = self.lexer.next() {
             match tok.kind {
@@ -609,19 +947,60 @@ impl Parser {
         Ok(lhs)
     }
 
-    /// https://tc39.github.io/ecma262/#prod-LogicalORExpression
-    expression!(
-        read_logical_or_expression,
-        read_logical_and_expression,
-        [Symbol::LOr]
-    );
+    /// https://tc39.github.io/ecma262/#prod-ShortCircuitExpression
+    /// '??' can't be mixed with '&&' and '||' without parentheses, so the first operand decides
+    /// which of them the expression consists of.
+    fn read_short_circuit_expression(&mut self) -> Result<Node, Error> {
+        let first = self.read_bitwise_or_expression()?;
+        let is_coalesce = match self.lexer.peek_skip_lineterminator() {
+            Ok(tok) => tok.kind == Kind::Symbol(Symbol::Nullish),
+            Err(_) => false,
+        };
+        let expr = if is_coalesce {
+            self.read_binary_expression_from(first, Symbol::Nullish)?
+        } else {
+            let lhs = self.read_binary_expression_from(first, Symbol::LAnd)?;
+            self.read_binary_expression_from(lhs, Symbol::LOr)?
+        };
+        if let Ok(tok) = self.lexer.peek_skip_lineterminator() {
+            let mixed = match tok.kind {
+                Kind::Symbol(Symbol::Nullish) => !is_coalesce,
+                Kind::Symbol(Symbol::LAnd) | Kind::Symbol(Symbol::LOr) => is_coalesce,
+                _ => false,
+            };
+            if mixed {
+                return Err(Error::General(
+                    tok.pos,
+                    "'??' can't be mixed with '&&' or '||' without parentheses".to_string(),
+                ));
+            }
+        }
+        Ok(expr)
+    }
 
-    /// https://tc39.github.io/ecma262/#prod-LogicalANDExpression
-    expression!(
-        read_logical_and_expression,
-        read_bitwise_or_expression,
-        [Symbol::LAnd]
-    );
+    /// Read the rest of LogicalANDExpression, LogicalORExpression or CoalesceExpression, whose
+    /// first operand is already read. The operands of '||' are LogicalANDExpression, and those of
+    /// the others are BitwiseORExpression.
+    /// https://tc39.github.io/ecma262/#prod-LogicalORExpression
+    /// https://tc39.github.io/ecma262/#prod-CoalesceExpression
+    fn read_binary_expression_from(&mut self, first: Node, op: Symbol) -> Result<Node, Error> {
+        let mut lhs = first;
+        while let Ok(true) = self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(op.clone()))
+        {
+            let pos = self.lexer.get_current_pos();
+            let mut rhs = self.read_bitwise_or_expression()?;
+            if op == Symbol::LOr {
+                rhs = self.read_binary_expression_from(rhs, Symbol::LAnd)?;
+            }
+            lhs = Node::new(
+                NodeBase::BinaryOp(Box::new(lhs), Box::new(rhs), op.as_binop().unwrap()),
+                pos,
+            );
+        }
+        Ok(lhs)
+    }
 
     /// https://tc39.github.io/ecma262/#prod-BitwiseORExpression
     expression!(
@@ -652,11 +1031,27 @@ impl Parser {
     );
 
     /// https://tc39.github.io/ecma262/#prod-RelationalExpression
-    expression!(
-        read_relational_expression,
-        read_shift_expression,
-        [Symbol::Lt, Symbol::Gt, Symbol::Le, Symbol::Ge]
-    );
+    fn read_relational_expression(&mut self) -> Result<Node, Error> {
+        let mut lhs = self.read_shift_expression()?;
+        while let Ok(tok) = self.lexer.peek_skip_lineterminator() {
+            let op = match tok.kind {
+                Kind::Symbol(Symbol::Lt) => BinOp::Lt,
+                Kind::Symbol(Symbol::Gt) => BinOp::Gt,
+                Kind::Symbol(Symbol::Le) => BinOp::Le,
+                Kind::Symbol(Symbol::Ge) => BinOp::Ge,
+                Kind::Keyword(Keyword::Instanceof) => BinOp::InstanceOf,
+                Kind::Keyword(Keyword::In) => BinOp::In,
+                _ => break,
+            };
+            self.lexer.next_skip_lineterminator().unwrap();
+            let pos = self.lexer.get_current_pos();
+            lhs = Node::new(
+                NodeBase::BinaryOp(Box::new(lhs), Box::new(self.read_shift_expression()?), op),
+                pos,
+            );
+        }
+        Ok(lhs)
+    }
 
     /// https://tc39.github.io/ecma262/#prod-ShiftExpression
     expression!(
@@ -713,6 +1108,9 @@ impl Parser {
                 | Kind::Symbol(Symbol::Sub)
                 | Kind::Symbol(Symbol::BitwiseNot)
                 | Kind::Symbol(Symbol::Not) => true,
+                Kind::Identifier(ref name) if name == "await" => {
+                    self.function_kind == FunctionKind::Async
+                }
                 _ => false,
             },
             Err(_) => false,
@@ -752,6 +1150,15 @@ impl Parser {
                 NodeBase::UnaryOp(Box::new(self.read_unary_expression()?), UnaryOp::Not),
                 pos,
             )),
+            // https://tc39.github.io/ecma262/#prod-AwaitExpression
+            Kind::Identifier(ref name)
+                if name == "await" && self.function_kind == FunctionKind::Async =>
+            {
+                Ok(Node::new(
+                    NodeBase::Await(Box::new(self.read_unary_expression()?)),
+                    pos,
+                ))
+            }
             _ => {
                 self.lexer.unget();
                 self.read_update_expression()
@@ -818,12 +1225,90 @@ impl Parser {
     /// TODO: Implement NewExpression: new MemberExpression
     fn read_left_hand_side_expression(&mut self) -> Result<Node, Error> {
         let lhs = self.read_member_expression()?;
-        match self.lexer.peek_skip_lineterminator() {
+        let lhs = match self.lexer.peek_skip_lineterminator() {
             Ok(ref tok) if tok.kind == Kind::Symbol(Symbol::OpeningParen) => {
-                self.read_call_expression(lhs)
+                self.read_call_expression(lhs)?
+            }
+            _ => self.read_new_expression(lhs)?,
+        };
+        match self.lexer.peek_skip_lineterminator() {
+            Ok(ref tok) if tok.kind == Kind::Symbol(Symbol::OptionalChaining) => {
+                self.read_optional_expression(lhs)
+            }
+            _ => Ok(lhs),
+        }
+    }
+
+    /// Read the optional chain following `base`. The expression checked whether it's null or
+    /// undefined at each '?.' is wrapped in NodeBase::Optional, and the whole chain, which all of
+    /// them skip to the end of, is wrapped in NodeBase::OptionalChain.
+    /// https://tc39.github.io/ecma262/#prod-OptionalExpression
+    fn read_optional_expression(&mut self, base: Node) -> Result<Node, Error> {
+        let pos = base.pos;
+        let mut lhs = base;
+        while let Ok(tok) = self.lexer.next_skip_lineterminator() {
+            let pos_ = self.lexer.get_current_pos();
+            match tok.kind {
+                Kind::Symbol(Symbol::OptionalChaining) => {
+                    lhs = Node::new(NodeBase::Optional(Box::new(lhs)), pos);
+                    let tok = self.lexer.next_skip_lineterminator()?;
+                    match tok.kind {
+                        Kind::Symbol(Symbol::OpeningParen) => {
+                            let args = self.read_arguments()?;
+                            lhs = Node::new(NodeBase::Call(Box::new(lhs), args), pos)
+                        }
+                        Kind::Symbol(Symbol::OpeningBoxBracket) => {
+                            let idx = self.read_expression()?;
+                            expect!(self, Kind::Symbol(Symbol::ClosingBoxBracket), "expect ']'");
+                            lhs = Node::new(NodeBase::Index(Box::new(lhs), Box::new(idx)), pos);
+                        }
+                        Kind::Identifier(name) => {
+                            lhs = Node::new(NodeBase::Member(Box::new(lhs), name), pos)
+                        }
+                        Kind::Keyword(kw) => {
+                            lhs = Node::new(
+                                NodeBase::Member(Box::new(lhs), kw.to_str().to_owned()),
+                                pos,
+                            )
+                        }
+                        _ => return Err(Error::Expect(tok.pos, "expect identifier".to_string())),
+                    }
+                }
+                Kind::Symbol(Symbol::OpeningParen) => {
+                    let args = self.read_arguments()?;
+                    lhs = Node::new(NodeBase::Call(Box::new(lhs), args), pos)
+                }
+                Kind::Symbol(Symbol::Point) => match self.lexer.next_skip_lineterminator()?.kind {
+                    Kind::Identifier(name) => {
+                        lhs = Node::new(NodeBase::Member(Box::new(lhs), name), pos)
+                    }
+                    Kind::Keyword(kw) => {
+                        lhs =
+                            Node::new(NodeBase::Member(Box::new(lhs), kw.to_str().to_owned()), pos)
+                    }
+                    _ => {
+                        return Err(Error::Expect(pos_, "expect identifier".to_string()));
+                    }
+                },
+                Kind::Symbol(Symbol::OpeningBoxBracket) => {
+                    let idx = self.read_expression()?;
+                    expect!(self, Kind::Symbol(Symbol::ClosingBoxBracket), "expect ']'");
+                    lhs = Node::new(NodeBase::Index(Box::new(lhs), Box::new(idx)), pos);
+                }
+                Kind::Template(_) => {
+                    return Err(Error::General(
+                        tok.pos,
+                        "Invalid tagged template on optional chain".to_string(),
+                    ));
+                }
+                _ => {
+                    self.lexer.unget();
+                    break;
+                }
             }
-            _ => self.read_new_expression(lhs),
         }
+
+        Ok(Node::new(NodeBase::OptionalChain(Box::new(lhs)), pos))
     }
 
     /// https://tc39.github.io/ecma262/#prod-NewExpression
@@ -879,6 +1364,13 @@ impl Parser {
                     }
                     lhs = Node::new(NodeBase::Index(Box::new(lhs), Box::new(idx)), pos);
                 }
+                Kind::Template(ref part) if part.head => {
+                    let template = self.read_template_literal(part.clone(), tok.pos, true)?;
+                    lhs = Node::new(
+                        NodeBase::TaggedTemplate(Box::new(lhs), Box::new(template)),
+                        pos,
+                    );
+                }
                 _ => {
                     self.lexer.unget();
                     break;
@@ -931,6 +1423,13 @@ impl Parser {
                     }
                     lhs = Node::new(NodeBase::Index(Box::new(lhs), Box::new(idx)), pos);
                 }
+                Kind::Template(ref part) if part.head => {
+                    let template = self.read_template_literal(part.clone(), tok.pos, true)?;
+                    lhs = Node::new(
+                        NodeBase::TaggedTemplate(Box::new(lhs), Box::new(template)),
+                        pos,
+                    );
+                }
                 _ => {
                     self.lexer.unget();
                     break;
@@ -941,8 +1440,50 @@ impl Parser {
         Ok(lhs)
     }
 
-    fn read_arguments(&mut self) -> Result<Vec<Node>, Error> {
-        let mut args = vec![];
+    /// Read the rest of a template literal. `head` is the first part, which is already read.
+    /// Invalid escape sequences are errors unless the template is tagged.
+    /// https://tc39.github.io/ecma262/#prod-TemplateLiteral
+    fn read_template_literal(
+        &mut self,
+        head: TemplatePart,
+        pos: usize,
+        tagged: bool,
+    ) -> Result<Node, Error> {
+        let invalid_escape = |part: &TemplatePart, pos: usize| {
+            if !tagged && part.cooked.is_none() {
+                Err(Error::General(pos, "invalid escape sequence".to_string()))
+            } else {
+                Ok(())
+            }
+        };
+
+        invalid_escape(&head, pos)?;
+        let mut cooked_strings = vec![head.cooked];
+        let mut raw_strings = vec![head.raw];
+        let mut substitutions = vec![];
+        let mut tail = head.tail;
+        while !tail {
+            substitutions.push(self.read_expression()?);
+            let tok = self.lexer.next_skip_lineterminator()?;
+            match tok.kind {
+                Kind::Template(part) if !part.head => {
+                    invalid_escape(&part, tok.pos)?;
+                    cooked_strings.push(part.cooked);
+                    raw_strings.push(part.raw);
+                    tail = part.tail;
+                }
+                _ => return Err(Error::Expect(tok.pos, "expect '}'".to_string())),
+            }
+        }
+
+        Ok(Node::new(
+            NodeBase::Template(cooked_strings, raw_strings, substitutions),
+            pos,
+        ))
+    }
+
+    fn read_arguments(&mut self) -> Result<Vec<Node>, Error> {
+        let mut args = vec![];
         loop {
             match self.lexer.next_skip_lineterminator() {
                 Ok(ref tok) if tok.kind == Kind::Symbol(Symbol::ClosingParen) => break,
@@ -974,20 +1515,34 @@ impl Parser {
                     return Err(Error::UnexpectedEOF("".to_string()));
                 }
             }
-            args.push(self.read_assignment_expression()?);
+            args.push(self.read_element()?);
         }
 
         Ok(args)
     }
 
+    /// Read an element of arguments or array literals, which may be spread.
+    /// https://tc39.github.io/ecma262/#prod-SpreadElement
+    fn read_element(&mut self) -> Result<Node, Error> {
+        self.lexer.skip_lineterminator()?;
+        let pos = self.lexer.get_current_pos();
+        if self.lexer.next_if(Kind::Symbol(Symbol::Rest)) {
+            let expr = self.read_assignment_expression()?;
+            return Ok(Node::new(NodeBase::Spread(Box::new(expr)), pos));
+        }
+        self.read_assignment_expression()
+    }
+
     /// https://tc39.github.io/ecma262/#prod-PrimaryExpression
     fn read_primary_expression(&mut self) -> Result<Node, Error> {
         let tok = self.lexer.next_skip_lineterminator()?;
 
         match tok.kind {
             Kind::Keyword(Keyword::This) => Ok(Node::new(NodeBase::This, tok.pos)),
-            // Kind::Keyword(Keyword::Arguments) => Ok(Node::new(NodeBase::Arguments, tok.pos)),
-            Kind::Keyword(Keyword::Function) => self.read_function_expression(),
+            Kind::Keyword(Keyword::Arguments) => Ok(Node::new(NodeBase::Arguments, tok.pos)),
+            Kind::Keyword(Keyword::Function) => self.read_function_expression(false),
+            Kind::Keyword(Keyword::Class) => self.read_class_expression(),
+            Kind::Keyword(Keyword::Super) => self.read_super(tok.pos),
             Kind::Symbol(Symbol::OpeningParen) => {
                 let expr = self.read_expression();
                 expect!(self, Kind::Symbol(Symbol::ClosingParen), "expect ')'");
@@ -995,6 +1550,9 @@ impl Parser {
             }
             Kind::Symbol(Symbol::OpeningBoxBracket) => self.read_array_literal(),
             Kind::Symbol(Symbol::OpeningBrace) => self.read_object_literal(),
+            Kind::Template(ref part) if part.head => {
+                self.read_template_literal(part.clone(), tok.pos, false)
+            }
             Kind::Identifier(ref i) if i == "true" => {
                 Ok(Node::new(NodeBase::Boolean(true), tok.pos))
             }
@@ -1005,8 +1563,28 @@ impl Parser {
             //     Ok(Node::new(NodeBase::Undefined, tok.pos))
             // }
             Kind::Identifier(ref i) if i == "null" => Ok(Node::new(NodeBase::Null, tok.pos)),
+            Kind::Identifier(ref i) if i == "async" => {
+                self.lexer.unget();
+                if self.is_async_function() {
+                    self.lexer.next()?; // async
+                    self.lexer.next()?; // function
+                    self.read_function_expression(true)
+                } else {
+                    self.lexer.next()?;
+                    Ok(Node::new(NodeBase::Identifier(i.clone()), tok.pos))
+                }
+            }
             Kind::Identifier(ident) => Ok(Node::new(NodeBase::Identifier(ident), tok.pos)),
             Kind::String(s) => Ok(Node::new(NodeBase::String(s), tok.pos)),
+            Kind::RegExp(pattern, flags) => {
+                if let Err(msg) = regexp::RegExp::new(&pattern, &flags) {
+                    return Err(Error::General(
+                        tok.pos,
+                        format!("invalid regular expression: /{}/: {}", pattern, msg),
+                    ));
+                }
+                Ok(Node::new(NodeBase::RegExp(pattern, flags), tok.pos))
+            }
             Kind::Number(num) => Ok(Node::new(NodeBase::Number(num), tok.pos)),
             _ => Err(Error::UnexpectedToken(
                 tok.pos,
@@ -1016,7 +1594,11 @@ impl Parser {
     }
 
     /// https://www.ecma-international.org/ecma-262/6.0/#sec-arrow-function-definitions
-    fn read_arrow_function(&mut self, is_parenthesized_param: bool) -> Result<Node, Error> {
+    fn read_arrow_function(
+        &mut self,
+        is_parenthesized_param: bool,
+        kind: FunctionKind,
+    ) -> Result<Node, Error> {
         let params;
         let params_pos = self.lexer.get_current_pos();
         if is_parenthesized_param {
@@ -1031,30 +1613,32 @@ impl Parser {
                 init: None,
                 name: param_name,
                 is_rest_param: false,
+                pattern: None,
             }];
         }
         expect_no_lineterminator!(self, Kind::Symbol(Symbol::FatArrow), "expect '=>'");
+        let function_kind = mem::replace(&mut self.function_kind, kind);
         let body = if self
             .lexer
             .next_if_skip_lineterminator(Kind::Symbol(Symbol::OpeningBrace))?
         {
-            self.read_block()?
+            self.read_block()
         } else {
             let pos = self.lexer.get_current_pos();
-            Node::new(
-                NodeBase::Return(Some(Box::new(self.read_assignment_expression()?))),
-                pos,
-            )
+            self.read_assignment_expression()
+                .map(|expr| Node::new(NodeBase::Return(Some(Box::new(expr))), pos))
         };
+        self.function_kind = function_kind;
         Ok(Node::new(
-            NodeBase::ArrowFunction(params, Box::new(body)),
+            NodeBase::ArrowFunction(params, Box::new(body?), kind),
             params_pos,
         ))
     }
 
     /// https://tc39.github.io/ecma262/#prod-FunctionDeclaration
-    fn read_function_expression(&mut self) -> Result<Node, Error> {
+    fn read_function_expression(&mut self, is_async: bool) -> Result<Node, Error> {
         let pos = self.lexer.get_current_pos();
+        let kind = self.read_function_kind(is_async)?;
         let name = if let Kind::Identifier(name) = self.lexer.peek(0)?.kind {
             self.lexer.next()?;
             Some(name)
@@ -1066,16 +1650,52 @@ impl Parser {
 
         let params = self.read_formal_parameters()?;
 
-        expect!(self, Kind::Symbol(Symbol::OpeningBrace), "expect '{'");
-
-        let body = self.read_block()?;
+        let body = self.read_function_body(kind)?;
 
         Ok(Node::new(
-            NodeBase::FunctionExpr(name, params, Box::new(body)),
+            NodeBase::FunctionExpr(name, params, Box::new(body), kind),
             pos,
         ))
     }
 
+    /// https://tc39.github.io/ecma262/#prod-ClassExpression
+    fn read_class_expression(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_prev_pos();
+        let name = if let Kind::Identifier(name) = self.lexer.peek_skip_lineterminator()?.kind {
+            self.lexer.next_skip_lineterminator()?;
+            Some(name)
+        } else {
+            None
+        };
+        self.read_class_tail(name, pos)
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-SuperProperty
+    /// https://tc39.github.io/ecma262/#prod-SuperCall
+    fn read_super(&mut self, pos: usize) -> Result<Node, Error> {
+        match self.lexer.peek_skip_lineterminator() {
+            Ok(ref tok) if tok.kind == Kind::Symbol(Symbol::OpeningParen) => {
+                self.lexer.next_skip_lineterminator()?;
+                let args_pos = self.lexer.get_prev_pos();
+                let args = self.read_arguments()?;
+                Ok(Node::new(
+                    NodeBase::SuperCall(Box::new(Node::new(NodeBase::Array(args), args_pos))),
+                    pos,
+                ))
+            }
+            Ok(ref tok)
+                if tok.kind == Kind::Symbol(Symbol::Point)
+                    || tok.kind == Kind::Symbol(Symbol::OpeningBoxBracket) =>
+            {
+                Ok(Node::new(NodeBase::Super, pos))
+            }
+            _ => Err(Error::UnexpectedToken(
+                pos,
+                "'super' keyword unexpected here".to_string(),
+            )),
+        }
+    }
+
     /// https://tc39.github.io/ecma262/#prod-ArrayLiteral
     fn read_array_literal(&mut self) -> Result<Node, Error> {
         let pos = self.lexer.get_current_pos();
@@ -1095,7 +1715,7 @@ impl Parser {
                 return Err(Error::UnexpectedEOF("']' may be needed".to_string()));
             }
 
-            if let Ok(elem) = self.read_assignment_expression() {
+            if let Ok(elem) = self.read_element() {
                 elements.push(elem);
             }
 
@@ -1118,7 +1738,19 @@ impl Parser {
                 break;
             }
 
-            elements.push(self.read_property_definition()?);
+            let property = self.read_property_definition()?;
+            if let PropertyDefinition::Proto(_) = property {
+                if elements.iter().any(|property| match property {
+                    PropertyDefinition::Proto(_) => true,
+                    _ => false,
+                }) {
+                    return Err(Error::General(
+                        self.lexer.get_current_pos(),
+                        "Duplicate __proto__ fields are not allowed in object literals".to_string(),
+                    ));
+                }
+            }
+            elements.push(property);
 
             if self
                 .lexer
@@ -1143,54 +1775,70 @@ impl Parser {
 
     /// https://tc39.github.io/ecma262/#prod-PropertyDefinition
     fn read_property_definition(&mut self) -> Result<PropertyDefinition, Error> {
-        fn to_string(kind: Kind) -> String {
-            match kind {
-                Kind::Identifier(name) => name,
-                Kind::Number(n) => format!("{}", n),
-                Kind::String(s) => s,
-                _ => unimplemented!(),
-            }
+        if self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(Symbol::Rest))?
+        {
+            let val = self.read_assignment_expression()?;
+            return Ok(PropertyDefinition::Spread(val));
         }
 
-        let tok = self.lexer.next_skip_lineterminator()?;
+        let (is_async, is_generator, kind) = self.read_method_modifiers()?;
+
+        let pos = self.lexer.get_current_pos();
+        let is_identifier = self.lexer.peek_skip_lineterminator()?.is_identifier();
+        let name = self.read_object_property_name()?;
+
+        if is_async
+            || is_generator
+            || kind != MethodDefinitionKind::Ordinary
+            || self.lexer.peek_skip_lineterminator()?.kind == Kind::Symbol(Symbol::OpeningParen)
+        {
+            let func_name = match name {
+                PropertyName::Literal(ref name) => Some(name.clone()),
+                PropertyName::Computed(_) => None,
+            };
+            let func = self.read_method(func_name, is_async, is_generator, pos)?;
+            return Ok(PropertyDefinition::MethodDefinition(kind, name, func));
+        }
 
         if self
             .lexer
             .next_if_skip_lineterminator(Kind::Symbol(Symbol::Colon))?
         {
             let val = self.read_assignment_expression()?;
-            return Ok(PropertyDefinition::Property(to_string(tok.kind), val));
+            return Ok(match name {
+                PropertyName::Literal(ref name) if name == "__proto__" => {
+                    PropertyDefinition::Proto(val)
+                }
+                name => PropertyDefinition::Property(name, val),
+            });
         }
 
-        if let Kind::Identifier(name) = tok.kind {
-            if name == "get" || name == "set" {
-                let may_identifier = self.lexer.peek_skip_lineterminator();
-                if may_identifier.is_ok() && may_identifier.unwrap().is_identifier() {
-                    let f = self.read_function_expression()?;
-                    let func_name = if let NodeBase::FunctionExpr(ref name, _, _) = f.base {
-                        name.clone().unwrap()
-                    } else {
-                        panic!()
-                    };
-                    return Ok(PropertyDefinition::MethodDefinition(
-                        if name == "get" {
-                            MethodDefinitionKind::Get
-                        } else {
-                            MethodDefinitionKind::Set
-                        },
-                        func_name,
-                        f,
-                    ));
-                }
+        match name {
+            PropertyName::Literal(name) if is_identifier => {
+                Ok(PropertyDefinition::IdentifierReference(name))
             }
+            _ => Err(Error::Expect(
+                self.lexer.get_current_pos(),
+                "Expect property definition.".to_string(),
+            )),
+        }
+    }
 
-            return Ok(PropertyDefinition::IdentifierReference(name));
+    /// Read PropertyName in object literals and classes, which may be computed.
+    /// https://tc39.github.io/ecma262/#prod-PropertyName
+    fn read_object_property_name(&mut self) -> Result<PropertyName, Error> {
+        if self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(Symbol::OpeningBoxBracket))?
+        {
+            let key = self.read_assignment_expression()?;
+            expect!(self, Kind::Symbol(Symbol::ClosingBoxBracket), "expect ']'");
+            return Ok(PropertyName::Computed(key));
         }
 
-        Err(Error::Expect(
-            tok.pos,
-            "Expect property definition.".to_string(),
-        ))
+        Ok(PropertyName::Literal(self.read_property_name()?))
     }
 }
 
@@ -1243,15 +1891,18 @@ impl Parser {
         let pos_catch = self.lexer.get_current_pos();
         let (catch, param) = if is_catch {
             skip_symbol_or_error!(self.lexer, Symbol::OpeningParen);
-            // TODO: should accept BindingPattern
             let pos_param = self.lexer.get_current_pos();
-            let catch_param = match self.lexer.next()?.kind {
-                Kind::Identifier(s) => Node::new(NodeBase::Identifier(s), pos_param),
-                _ => {
-                    return Err(Error::UnexpectedToken(
-                        pos_param,
-                        "expected identifier.".to_string(),
-                    ));
+            let catch_param = if self.is_pattern_start()? {
+                self.read_pattern(false)?
+            } else {
+                match self.lexer.next()?.kind {
+                    Kind::Identifier(s) => Node::new(NodeBase::Identifier(s), pos_param),
+                    _ => {
+                        return Err(Error::UnexpectedToken(
+                            pos_param,
+                            "expected identifier.".to_string(),
+                        ));
+                    }
                 }
             };
             skip_symbol_or_error!(self.lexer, Symbol::ClosingParen);
@@ -1326,7 +1977,12 @@ impl Parser {
     fn read_declaration(&mut self) -> Result<Node, Error> {
         let tok = self.lexer.next_skip_lineterminator()?;
         match tok.kind {
-            Kind::Keyword(Keyword::Function) => self.read_function_declaration(),
+            Kind::Keyword(Keyword::Function) => self.read_function_declaration(false),
+            Kind::Identifier(ref name) if name == "async" => {
+                self.lexer.next()?; // function
+                self.read_function_declaration(true)
+            }
+            Kind::Keyword(Keyword::Class) => self.read_class_declaration(),
             Kind::Keyword(Keyword::Const) => self.read_lexical_declaration(true),
             Kind::Keyword(Keyword::Let) => self.read_lexical_declaration(false),
             _ => unreachable!(),
@@ -1345,6 +2001,14 @@ impl Parser {
         let mut list = vec![];
 
         loop {
+            if self.is_pattern_start()? {
+                list.push(self.read_pattern_declaration(var_kind)?);
+                if !self.variable_declaration_continuation()? {
+                    break;
+                }
+                continue;
+            }
+
             let pos = self.lexer.get_current_pos();
             let name = match self.lexer.next_skip_lineterminator()?.kind {
                 Kind::Identifier(name) => name,
@@ -1376,8 +2040,9 @@ impl Parser {
     }
 
     /// https://tc39.github.io/ecma262/#prod-FunctionDeclaration
-    fn read_function_declaration(&mut self) -> Result<Node, Error> {
+    fn read_function_declaration(&mut self, is_async: bool) -> Result<Node, Error> {
         let pos = self.lexer.get_prev_pos();
+        let kind = self.read_function_kind(is_async)?;
         let name = if let Kind::Identifier(name) = self.lexer.next_skip_lineterminator()?.kind {
             name
         } else {
@@ -1391,16 +2056,248 @@ impl Parser {
 
         let params = self.read_formal_parameters()?;
 
+        let body = self.read_function_body(kind)?;
+
+        Ok(Node::new(
+            NodeBase::FunctionDecl(name, params, Box::new(body), kind),
+            pos,
+        ))
+    }
+
+    /// Read '*' following 'function' if any.
+    fn read_function_kind(&mut self, is_async: bool) -> Result<FunctionKind, Error> {
+        if self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(Symbol::Asterisk))?
+        {
+            if is_async {
+                // TODO: Async generator functions
+                return Err(Error::UnsupportedFeature(self.lexer.get_prev_pos()));
+            }
+            Ok(FunctionKind::Generator)
+        } else if is_async {
+            Ok(FunctionKind::Async)
+        } else {
+            Ok(FunctionKind::Ordinary)
+        }
+    }
+
+    /// Check if 'async function' without a line terminator between them comes next.
+    fn is_async_function(&mut self) -> bool {
+        match (self.lexer.peek(0), self.lexer.peek(1)) {
+            (Ok(ref tok1), Ok(ref tok2)) => {
+                tok1.kind == Kind::Identifier("async".to_string())
+                    && tok2.kind == Kind::Keyword(Keyword::Function)
+            }
+            _ => false,
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-FunctionBody
+    fn read_function_body(&mut self, kind: FunctionKind) -> Result<Node, Error> {
+        expect!(self, Kind::Symbol(Symbol::OpeningBrace), "expect '{'");
+        let function_kind = mem::replace(&mut self.function_kind, kind);
+        let body = self.read_block();
+        self.function_kind = function_kind;
+        body
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-ClassDeclaration
+    /// A class declaration is represented as 'let' declaration initialized by the class.
+    fn read_class_declaration(&mut self) -> Result<Node, Error> {
+        let pos = self.lexer.get_prev_pos();
+        let name = if let Kind::Identifier(name) = self.lexer.next_skip_lineterminator()?.kind {
+            name
+        } else {
+            return Err(Error::Expect(
+                self.lexer.get_prev_pos(),
+                "expect class name".to_string(),
+            ));
+        };
+
+        let class = self.read_class_tail(Some(name.clone()), pos)?;
+
+        Ok(Node::new(
+            NodeBase::VarDecl(name, Some(Box::new(class)), VarKind::Let),
+            pos,
+        ))
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-ClassTail
+    fn read_class_tail(&mut self, name: Option<String>, pos: usize) -> Result<Node, Error> {
+        let heritage = if self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Keyword(Keyword::Extends))?
+        {
+            self.read_left_hand_side_expression()?
+        } else {
+            Node::new(NodeBase::Nope, pos)
+        };
+
         expect!(self, Kind::Symbol(Symbol::OpeningBrace), "expect '{'");
 
-        let body = self.read_block()?;
+        let mut constructor = None;
+        let mut methods = vec![];
+
+        loop {
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingBrace))?
+            {
+                break;
+            }
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Semicolon))?
+            {
+                continue;
+            }
+
+            let element = self.read_class_element()?;
+            if element.is_static || element.name != PropertyName::Literal("constructor".to_string())
+            {
+                methods.push(element);
+                continue;
+            }
+
+            let (params, body, kind) = match element.func.base {
+                NodeBase::FunctionExpr(_, params, body, kind) => (params, body, kind),
+                _ => unreachable!(),
+            };
+            if element.kind != MethodDefinitionKind::Ordinary || kind != FunctionKind::Ordinary {
+                return Err(Error::General(
+                    element.func.pos,
+                    "Class constructor may not be a special method".to_string(),
+                ));
+            }
+            if constructor.is_some() {
+                return Err(Error::General(
+                    element.func.pos,
+                    "A class may only have one constructor".to_string(),
+                ));
+            }
+            constructor = Some(Node::new(
+                NodeBase::FunctionExpr(name.clone(), params, body, kind),
+                element.func.pos,
+            ));
+        }
+
+        let is_derived = heritage.base != NodeBase::Nope;
+        let constructor =
+            constructor.unwrap_or_else(|| default_class_constructor(name.clone(), is_derived, pos));
+
+        Ok(Node::new(
+            NodeBase::Class(name, Box::new(heritage), Box::new(constructor), methods),
+            pos,
+        ))
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-ClassElement
+    fn read_class_element(&mut self) -> Result<ClassElement, Error> {
+        let is_static = self.read_method_modifier("static")?;
+        let (is_async, is_generator, kind) = self.read_method_modifiers()?;
+
+        let pos = self.lexer.get_current_pos();
+        let name = self.read_object_property_name()?;
+        let func_name = match name {
+            PropertyName::Literal(ref name) => Some(name.clone()),
+            PropertyName::Computed(_) => None,
+        };
+        let func = self.read_method(func_name, is_async, is_generator, pos)?;
+
+        Ok(ClassElement {
+            is_static,
+            kind,
+            name,
+            func,
+        })
+    }
+
+    /// Read 'async', '*', 'get' and 'set' placed before a method name.
+    /// Return whether the method is async, whether it is a generator, and its kind.
+    fn read_method_modifiers(&mut self) -> Result<(bool, bool, MethodDefinitionKind), Error> {
+        let is_async = self.read_method_modifier("async")?;
+        let is_generator = self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(Symbol::Asterisk))?;
+        let kind = if is_async || is_generator {
+            MethodDefinitionKind::Ordinary
+        } else if self.read_method_modifier("get")? {
+            MethodDefinitionKind::Get
+        } else if self.read_method_modifier("set")? {
+            MethodDefinitionKind::Set
+        } else {
+            MethodDefinitionKind::Ordinary
+        };
+        Ok((is_async, is_generator, kind))
+    }
+
+    /// Read the parameters and the body of a method after its name.
+    /// https://tc39.github.io/ecma262/#prod-MethodDefinition
+    fn read_method(
+        &mut self,
+        name: Option<String>,
+        is_async: bool,
+        is_generator: bool,
+        pos: usize,
+    ) -> Result<Node, Error> {
+        expect!(self, Kind::Symbol(Symbol::OpeningParen), "expect '('");
+
+        let params = self.read_formal_parameters()?;
+
+        if is_async && is_generator {
+            // TODO: Async generator methods
+            return Err(Error::UnsupportedFeature(pos));
+        }
+        let func_kind = if is_async {
+            FunctionKind::Async
+        } else if is_generator {
+            FunctionKind::Generator
+        } else {
+            FunctionKind::Ordinary
+        };
+
+        let body = self.read_function_body(func_kind)?;
 
         Ok(Node::new(
-            NodeBase::FunctionDecl(name, params, Box::new(body)),
+            NodeBase::FunctionExpr(name, params, Box::new(body), func_kind),
             pos,
         ))
     }
 
+    /// Read the contextual keyword such as 'static' and 'get' placed before a method name.
+    /// Return false if the keyword is the method or property name itself.
+    fn read_method_modifier(&mut self, modifier: &str) -> Result<bool, Error> {
+        self.lexer.skip_lineterminator()?;
+        match (self.lexer.peek(0), self.lexer.peek(1)) {
+            (Ok(ref tok1), Ok(ref tok2))
+                if tok1.kind == Kind::Identifier(modifier.to_string())
+                    && tok2.kind != Kind::Symbol(Symbol::OpeningParen)
+                    && tok2.kind != Kind::Symbol(Symbol::Colon)
+                    && tok2.kind != Kind::Symbol(Symbol::Comma)
+                    && tok2.kind != Kind::Symbol(Symbol::ClosingBrace) =>
+            {
+                self.lexer.next()?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Read LiteralPropertyName. Computed names are read by read_object_property_name.
+    /// https://tc39.github.io/ecma262/#prod-LiteralPropertyName
+    fn read_property_name(&mut self) -> Result<String, Error> {
+        let tok = self.lexer.next_skip_lineterminator()?;
+        match tok.kind {
+            Kind::Identifier(name) => Ok(name),
+            Kind::Keyword(kw) => Ok(kw.to_str().to_string()),
+            Kind::String(s) => Ok(JSString::new(s).to_property_key()),
+            Kind::Number(n) => Ok(format!("{}", n)),
+            _ => Err(Error::Expect(tok.pos, "expect property name".to_string())),
+        }
+    }
+
     fn read_formal_parameters(&mut self) -> Result<FormalParameters, Error> {
         if self
             .lexer
@@ -1445,20 +2342,35 @@ impl Parser {
 
     // TODO: Support all features: https://tc39.github.io/ecma262/#prod-FormalParameter
     fn read_formal_parameter(&mut self) -> Result<FormalParameter, Error> {
-        let pos = self.lexer.get_current_pos();
-        let name = if let Kind::Identifier(name) = self.lexer.next_skip_lineterminator()?.kind {
-            name
+        let mut param = if self.is_pattern_start()? {
+            self.read_pattern_parameter(false)?
         } else {
-            return Err(Error::Expect(
-                pos,
-                "expect identifier (unsupported feature)".to_string(),
-            ));
+            let pos = self.lexer.get_current_pos();
+            let name = if let Kind::Identifier(name) = self.lexer.next_skip_lineterminator()?.kind {
+                name
+            } else {
+                return Err(Error::Expect(
+                    pos,
+                    "expect identifier (unsupported feature)".to_string(),
+                ));
+            };
+            FormalParameter::new(name, None, false)
         };
-        // TODO: Implement initializer.
-        Ok(FormalParameter::new(name, None, false))
+
+        if self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(Symbol::Assign))?
+        {
+            param.init = Some(self.read_assignment_expression()?);
+        }
+
+        Ok(param)
     }
 
     fn read_function_rest_parameter(&mut self) -> Result<FormalParameter, Error> {
+        if self.is_pattern_start()? {
+            return self.read_pattern_parameter(true);
+        }
         let pos = self.lexer.get_current_pos();
         Ok(FormalParameter::new(
             if let Kind::Identifier(name) = self.lexer.next()?.kind {
@@ -1473,35 +2385,255 @@ impl Parser {
             true,
         ))
     }
-}
 
-#[test]
-fn number() {
-    let mut parser = Parser::new("12345".to_string());
-    assert_eq!(
-        parser.parse_all().unwrap(),
-        Node::new(
-            NodeBase::StatementList(vec![Node::new(NodeBase::Number(12345.0), 0)]),
-            0
-        )
-    );
-}
+    fn read_pattern_parameter(&mut self, is_rest_param: bool) -> Result<FormalParameter, Error> {
+        Ok(FormalParameter {
+            name: "".to_string(),
+            init: None,
+            is_rest_param,
+            pattern: Some(self.read_pattern(false)?),
+        })
+    }
 
-#[test]
-fn string() {
-    let mut parser = Parser::new("\"aaa\"".to_string());
-    assert_eq!(
-        parser.parse_all().unwrap(),
-        Node::new(
-            NodeBase::StatementList(vec![Node::new(NodeBase::String("aaa".to_string()), 0)]),
-            0
-        )
-    );
-}
+    fn is_pattern_start(&mut self) -> Result<bool, Error> {
+        match self.lexer.peek_skip_lineterminator() {
+            Ok(tok) => Ok(tok.kind == Kind::Symbol(Symbol::OpeningBrace)
+                || tok.kind == Kind::Symbol(Symbol::OpeningBoxBracket)),
+            Err(Error::NormalEOF) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
 
-#[test]
-fn boolean() {
-    let mut parser = Parser::new("true; false".to_string());
+    /// Read a binding pattern, or an assignment pattern if `is_assignment` is true. The targets
+    /// of assignment patterns may be any references, while those of binding patterns must be
+    /// identifiers.
+    /// https://tc39.github.io/ecma262/#prod-BindingPattern
+    /// https://tc39.github.io/ecma262/#prod-AssignmentPattern
+    fn read_pattern(&mut self, is_assignment: bool) -> Result<Node, Error> {
+        let tok = self.lexer.next_skip_lineterminator()?;
+        match tok.kind {
+            Kind::Symbol(Symbol::OpeningBrace) => self.read_object_pattern(is_assignment, tok.pos),
+            Kind::Symbol(Symbol::OpeningBoxBracket) => {
+                self.read_array_pattern(is_assignment, tok.pos)
+            }
+            _ => Err(Error::Expect(tok.pos, "expect '{' or '['".to_string())),
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-ObjectBindingPattern
+    fn read_object_pattern(&mut self, is_assignment: bool, pos: usize) -> Result<Node, Error> {
+        let mut properties = vec![];
+        let mut rest = None;
+
+        loop {
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingBrace))?
+            {
+                break;
+            }
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Rest))?
+            {
+                if self.is_pattern_start()? {
+                    return Err(Error::UnexpectedToken(
+                        self.lexer.get_current_pos(),
+                        "'...' must be followed by an identifier in object patterns".to_string(),
+                    ));
+                }
+                rest = Some(Box::new(self.read_pattern_target(is_assignment)?));
+                expect!(self, Kind::Symbol(Symbol::ClosingBrace), "expect '}'");
+                break;
+            }
+
+            let tok = self.lexer.peek_skip_lineterminator()?;
+            let key = self.read_object_property_name()?;
+            let value = if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Colon))?
+            {
+                self.read_pattern_element(is_assignment)?
+            } else {
+                // Shorthand property such as {a} and {a = 1}
+                let name = match (tok.kind, &key) {
+                    (Kind::Identifier(_), PropertyName::Literal(name)) => name.clone(),
+                    _ => return Err(Error::Expect(tok.pos, "expect ':'".to_string())),
+                };
+                let target = Node::new(NodeBase::Identifier(name), tok.pos);
+                self.read_default_value(target)?
+            };
+            properties.push(PropertyPattern { key, value });
+
+            if !self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Comma))?
+            {
+                expect!(
+                    self,
+                    Kind::Symbol(Symbol::ClosingBrace),
+                    "expect ',' or '}'"
+                );
+                break;
+            }
+        }
+
+        Ok(Node::new(NodeBase::ObjectPattern(properties, rest), pos))
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-ArrayBindingPattern
+    fn read_array_pattern(&mut self, is_assignment: bool, pos: usize) -> Result<Node, Error> {
+        let mut elements = vec![];
+        let mut rest = None;
+
+        loop {
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::ClosingBoxBracket))?
+            {
+                break;
+            }
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Comma))?
+            {
+                let pos = self.lexer.get_prev_pos();
+                elements.push(Node::new(NodeBase::Nope, pos));
+                continue;
+            }
+
+            if self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Rest))?
+            {
+                rest = Some(Box::new(self.read_pattern_target(is_assignment)?));
+                expect!(self, Kind::Symbol(Symbol::ClosingBoxBracket), "expect ']'");
+                break;
+            }
+
+            elements.push(self.read_pattern_element(is_assignment)?);
+
+            if !self
+                .lexer
+                .next_if_skip_lineterminator(Kind::Symbol(Symbol::Comma))?
+            {
+                expect!(
+                    self,
+                    Kind::Symbol(Symbol::ClosingBoxBracket),
+                    "expect ',' or ']'"
+                );
+                break;
+            }
+        }
+
+        Ok(Node::new(NodeBase::ArrayPattern(elements, rest), pos))
+    }
+
+    /// https://tc39.github.io/ecma262/#prod-BindingElement
+    fn read_pattern_element(&mut self, is_assignment: bool) -> Result<Node, Error> {
+        let target = self.read_pattern_target(is_assignment)?;
+        self.read_default_value(target)
+    }
+
+    fn read_pattern_target(&mut self, is_assignment: bool) -> Result<Node, Error> {
+        if self.is_pattern_start()? {
+            return self.read_pattern(is_assignment);
+        }
+
+        if is_assignment {
+            let target = self.read_left_hand_side_expression()?;
+            return match target.base {
+                NodeBase::Identifier(_) | NodeBase::Member(_, _) | NodeBase::Index(_, _) => {
+                    Ok(target)
+                }
+                _ => Err(Error::General(
+                    target.pos,
+                    "Invalid destructuring assignment target".to_string(),
+                )),
+            };
+        }
+
+        let tok = self.lexer.next_skip_lineterminator()?;
+        match tok.kind {
+            Kind::Identifier(name) => Ok(Node::new(NodeBase::Identifier(name), tok.pos)),
+            _ => Err(Error::Expect(tok.pos, "expect identifier".to_string())),
+        }
+    }
+
+    fn read_default_value(&mut self, target: Node) -> Result<Node, Error> {
+        if !self
+            .lexer
+            .next_if_skip_lineterminator(Kind::Symbol(Symbol::Assign))?
+        {
+            return Ok(target);
+        }
+        let pos = target.pos;
+        let default = self.read_assignment_expression()?;
+        Ok(Node::new(
+            NodeBase::Assign(Box::new(target), Box::new(default)),
+            pos,
+        ))
+    }
+}
+
+/// constructor() {} for base classes, and constructor(...args) { super(...args) } for derived
+/// classes.
+/// https://tc39.github.io/ecma262/#sec-runtime-semantics-classdefinitionevaluation
+fn default_class_constructor(name: Option<String>, is_derived: bool, pos: usize) -> Node {
+    let (params, body) = if is_derived {
+        let args = "args".to_string();
+        let super_call =
+            NodeBase::SuperCall(Box::new(Node::new(NodeBase::Identifier(args.clone()), pos)));
+        (
+            vec![FormalParameter::new(args, None, true)],
+            vec![Node::new(super_call, pos)],
+        )
+    } else {
+        (vec![], vec![])
+    };
+    Node::new(
+        NodeBase::FunctionExpr(
+            name,
+            params,
+            Box::new(Node::new(NodeBase::StatementList(body), pos)),
+            FunctionKind::Ordinary,
+        ),
+        pos,
+    )
+}
+
+#[test]
+fn number() {
+    let mut parser = Parser::new("12345".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(NodeBase::Number(12345.0), 0)]),
+            0
+        )
+    );
+}
+
+#[test]
+fn string() {
+    let mut parser = Parser::new("\"aaa\"".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::String("aaa".encode_utf16().collect()),
+                0
+            )]),
+            0
+        )
+    );
+}
+
+#[test]
+fn boolean() {
+    let mut parser = Parser::new("true; false".to_string());
     assert_eq!(
         parser.parse_all().unwrap(),
         Node::new(
@@ -1593,11 +2725,11 @@ fn object() {
                     Box::new(Node::new(
                         NodeBase::Object(vec![
                             PropertyDefinition::Property(
-                                "x".to_string(),
+                                PropertyName::Literal("x".to_string()),
                                 Node::new(NodeBase::Number(123.0), 8),
                             ),
                             PropertyDefinition::Property(
-                                "1.2".to_string(),
+                                PropertyName::Literal("1.2".to_string()),
                                 Node::new(NodeBase::Number(456.0), 18),
                             ),
                         ]),
@@ -1609,14 +2741,63 @@ fn object() {
             0
         )
     );
-    for input in ["a = {}", "a = {b}"].iter() {
+    for input in [
+        "a = {}",
+        "a = {b}",
+        "a = {[b]: 1}",
+        "a = {'b': 1, 2() {}}",
+        "a = {[b]() {}, get [c]() {}, set [d](v) {}}",
+        "a = {*b() {}, async c() {}}",
+        "a = {get: 1, set, async}",
+        "a = {__proto__: b, ['__proto__']: c}",
+    ]
+    .iter()
+    {
         let mut parser = Parser::new(input.to_string());
         parser.parse_all().unwrap();
     }
-    for input in ["a = {b:6 c}", "a = {b:6, 777}"].iter() {
+    for input in [
+        "a = {b:6 c}",
+        "a = {b:6, 777}",
+        "a = {'b'}",
+        "a = {[b]}",
+        "a = {__proto__: b, '__proto__': c}",
+    ]
+    .iter()
+    {
         let mut parser = Parser::new(input.to_string());
         parser.parse_all().expect_err(input);
     }
+
+    let mut parser = Parser::new("a = {[b]: 1, __proto__: c}".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::Assign(
+                    Box::new(Node::new(NodeBase::Identifier("a".to_string()), 0)),
+                    Box::new(Node::new(
+                        NodeBase::Object(vec![
+                            PropertyDefinition::Property(
+                                PropertyName::Computed(Node::new(
+                                    NodeBase::Identifier("b".to_string()),
+                                    6
+                                )),
+                                Node::new(NodeBase::Number(1.0), 10),
+                            ),
+                            PropertyDefinition::Proto(Node::new(
+                                NodeBase::Identifier("c".to_string()),
+                                24
+                            )),
+                        ]),
+                        5,
+                    )),
+                ),
+                0,
+            )]),
+            0
+        )
+    );
 }
 
 #[test]
@@ -1938,21 +3119,32 @@ fn simple_expr_assign() {
         );
     } }
     f!(Node::new(NodeBase::Number(1.0), 4));
-    parser = Parser::new("v += 1".to_string());
-    f!(Node::new(NodeBase::BinaryOp(Box::new(Node::new(NodeBase::Identifier("v".to_string()), 0)), 
-                                    Box::new(Node::new(NodeBase::Number(1.0), 5)), BinOp::Add), 0));
-    parser = Parser::new("v -= 1".to_string());
-    f!(Node::new(NodeBase::BinaryOp(Box::new(Node::new(NodeBase::Identifier("v".to_string()), 0)), 
-                                    Box::new(Node::new(NodeBase::Number(1.0), 5)), BinOp::Sub), 0));
-    parser = Parser::new("v *= 1".to_string());
-    f!(Node::new(NodeBase::BinaryOp(Box::new(Node::new(NodeBase::Identifier("v".to_string()), 0)), 
-                                    Box::new(Node::new(NodeBase::Number(1.0), 5)), BinOp::Mul), 0));
-    parser = Parser::new("v /= 1".to_string());
-    f!(Node::new(NodeBase::BinaryOp(Box::new(Node::new(NodeBase::Identifier("v".to_string()), 0)), 
-                                    Box::new(Node::new(NodeBase::Number(1.0), 5)), BinOp::Div), 0));
-    parser = Parser::new("v %= 1".to_string());
-    f!(Node::new(NodeBase::BinaryOp(Box::new(Node::new(NodeBase::Identifier("v".to_string()), 0)), 
-                                    Box::new(Node::new(NodeBase::Number(1.0), 5)), BinOp::Rem), 0));
+    macro_rules! g { ($src:expr, $op:ident) => {
+        assert_eq!(
+            Node::new(NodeBase::StatementList(vec![Node::new(NodeBase::CompoundAssign(
+                Box::new(Node::new(NodeBase::Identifier("v".to_string()), 0)),
+                Box::new(Node::new(NodeBase::Number(1.0), $src.len() - 1)), BinOp::$op
+            ), 0)]), 0),
+            Parser::new($src.to_string()).parse_all().unwrap()
+        );
+    } }
+    g!("v += 1", Add);
+    g!("v -= 1", Sub);
+    g!("v *= 1", Mul);
+    g!("v /= 1", Div);
+    g!("v %= 1", Rem);
+    g!("v **= 1", Exp);
+    g!("v <<= 1", Shl);
+    g!("v >>= 1", Shr);
+    g!("v >>>= 1", ZFShr);
+    g!("v &= 1", And);
+    g!("v |= 1", Or);
+    g!("v ^= 1", Xor);
+    g!("v &&= 1", LAnd);
+    g!("v ||= 1", LOr);
+    g!("v ??= 1", Nullish);
+    assert!(Parser::new("f() += 1".to_string()).parse_all().is_err());
+    assert!(Parser::new("[v] ||= 1".to_string()).parse_all().is_err());
 }
 
 #[test]
@@ -2284,6 +3476,74 @@ fn while_() {
     );
 }
 
+#[test]
+fn switch() {
+    let mut parser = Parser::new("switch (x) { case 1: a; break; default: }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::Switch(
+                    Box::new(Node::new(NodeBase::Identifier("x".to_string()), 8)),
+                    vec![
+                        CaseClause::new(
+                            Some(Node::new(NodeBase::Number(1.0), 18)),
+                            vec![
+                                Node::new(NodeBase::Identifier("a".to_string()), 21),
+                                Node::new(NodeBase::Break(None), 24),
+                            ],
+                        ),
+                        CaseClause::new(None, vec![]),
+                    ],
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+    for input in [
+        "switch x {}",
+        "switch (x) { case 1 }",
+        "switch (x) { a }",
+        "switch (x) { default: default: }",
+        "switch (x) { case 1:",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new(input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn do_while() {
+    let mut parser = Parser::new("do { } while (true)".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::DoWhile(
+                    Box::new(Node::new(NodeBase::Block(vec![]), 3)),
+                    Box::new(Node::new(NodeBase::Boolean(true), 14)),
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+    for input in [
+        "do {}",
+        "do {} while",
+        "do {} while (true",
+        "do while (true)",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new(input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
 #[test]
 fn for1() {
     let mut parser = Parser::new("for (;;) { }".to_string());
@@ -2291,15 +3551,12 @@ fn for1() {
         parser.parse_all().unwrap(),
         Node::new(
             NodeBase::StatementList(vec![Node::new(
-                NodeBase::Block(vec![Node::new(
-                    NodeBase::For(
-                        Box::new(Node::new(NodeBase::Nope, 4)),
-                        Box::new(Node::new(NodeBase::Boolean(true), 6)),
-                        Box::new(Node::new(NodeBase::Nope, 7)),
-                        Box::new(Node::new(NodeBase::Block(vec![]), 9)),
-                    ),
-                    0,
-                )]),
+                NodeBase::For(
+                    Box::new(Node::new(NodeBase::Nope, 4)),
+                    Box::new(Node::new(NodeBase::Boolean(true), 6)),
+                    Box::new(Node::new(NodeBase::Nope, 7)),
+                    Box::new(Node::new(NodeBase::Block(vec![]), 9)),
+                ),
                 0,
             )]),
             0
@@ -2323,34 +3580,115 @@ fn for1() {
 }
 
 #[test]
-fn function_decl() {
-    for (input, node) in [
-        (
-            "function
-            f
-            (
-            ) 
-            { 
-            }",
-            Node::new(
-                NodeBase::FunctionDecl(
-                    "f".to_string(),
-                    vec![],
-                    Box::new(Node::new(NodeBase::StatementList(vec![]), 64)),
+fn for_in() {
+    let mut parser = Parser::new("for (let k in obj) { }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::ForIn(
+                    Box::new(Node::new(
+                        NodeBase::VarDecl("k".to_string(), None, VarKind::Let),
+                        9
+                    )),
+                    Box::new(Node::new(NodeBase::Identifier("obj".to_string()), 14)),
+                    Box::new(Node::new(NodeBase::Block(vec![]), 19)),
                 ),
                 0,
-            ),
-        ),
-        (
-            "function f() { return }",
-            Node::new(
-                NodeBase::FunctionDecl(
-                    "f".to_string(),
-                    vec![],
+            )]),
+            0
+        )
+    );
+    let mut parser = Parser::new("for (a.b in obj) ;".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::ForIn(
                     Box::new(Node::new(
-                        NodeBase::StatementList(vec![Node::new(NodeBase::Return(None), 15)]),
-                        13,
+                        NodeBase::Member(
+                            Box::new(Node::new(NodeBase::Identifier("a".to_string()), 5)),
+                            "b".to_string()
+                        ),
+                        5
                     )),
+                    Box::new(Node::new(NodeBase::Identifier("obj".to_string()), 12)),
+                    Box::new(Node::new(NodeBase::Nope, 17)),
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+    for input in [
+        "for (var k in) {}",
+        "for (let k in obj {}",
+        "for (k in obj)",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new(input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn for_of() {
+    let mut parser = Parser::new("for (const x of xs) { }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::ForOf(
+                    Box::new(Node::new(
+                        NodeBase::VarDecl("x".to_string(), None, VarKind::Const),
+                        11
+                    )),
+                    Box::new(Node::new(NodeBase::Identifier("xs".to_string()), 16)),
+                    Box::new(Node::new(NodeBase::Block(vec![]), 20)),
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+    for input in ["for (let x of) {}", "for (x of xs, ys) {}", "for (x of xs"].iter() {
+        let mut parser = Parser::new(input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn function_decl() {
+    for (input, node) in [
+        (
+            "function
+            f
+            (
+            ) 
+            { 
+            }",
+            Node::new(
+                NodeBase::FunctionDecl(
+                    "f".to_string(),
+                    vec![],
+                    Box::new(Node::new(NodeBase::StatementList(vec![]), 64)),
+                    FunctionKind::Ordinary,
+                ),
+                0,
+            ),
+        ),
+        (
+            "function f() { return }",
+            Node::new(
+                NodeBase::FunctionDecl(
+                    "f".to_string(),
+                    vec![],
+                    Box::new(Node::new(
+                        NodeBase::StatementList(vec![Node::new(NodeBase::Return(None), 15)]),
+                        13,
+                    )),
+                    FunctionKind::Ordinary,
                 ),
                 0,
             ),
@@ -2379,6 +3717,7 @@ fn function_decl() {
                         )]),
                         23,
                     )),
+                    FunctionKind::Ordinary,
                 ),
                 0,
             ),
@@ -2414,6 +3753,168 @@ fn function_decl() {
     }
 }
 
+#[test]
+fn generator() {
+    let mut parser = Parser::new("function* g() { yield; yield* x }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::FunctionDecl(
+                    "g".to_string(),
+                    vec![],
+                    Box::new(Node::new(
+                        NodeBase::StatementList(vec![
+                            Node::new(NodeBase::Yield(None, false), 16),
+                            Node::new(
+                                NodeBase::Yield(
+                                    Some(Box::new(Node::new(
+                                        NodeBase::Identifier("x".to_string()),
+                                        30
+                                    ))),
+                                    true
+                                ),
+                                23
+                            ),
+                        ]),
+                        14
+                    )),
+                    FunctionKind::Generator,
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+
+    // 'yield' is an identifier outside generators.
+    let mut parser = Parser::new("function* g() { () => yield }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::FunctionDecl(
+                    "g".to_string(),
+                    vec![],
+                    Box::new(Node::new(
+                        NodeBase::StatementList(vec![Node::new(
+                            NodeBase::ArrowFunction(
+                                vec![],
+                                Box::new(Node::new(
+                                    NodeBase::Return(Some(Box::new(Node::new(
+                                        NodeBase::Identifier("yield".to_string()),
+                                        22
+                                    )))),
+                                    22
+                                )),
+                                FunctionKind::Ordinary,
+                            ),
+                            16
+                        )]),
+                        14
+                    )),
+                    FunctionKind::Generator,
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+
+    for input in ["function* g() { yield* }", "function* () {}"].iter() {
+        let mut parser = Parser::new(input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn async_function() {
+    let mut parser = Parser::new("async function f() { await x }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::FunctionDecl(
+                    "f".to_string(),
+                    vec![],
+                    Box::new(Node::new(
+                        NodeBase::StatementList(vec![Node::new(
+                            NodeBase::Await(Box::new(Node::new(
+                                NodeBase::Identifier("x".to_string()),
+                                27
+                            ))),
+                            21
+                        )]),
+                        19
+                    )),
+                    FunctionKind::Async,
+                ),
+                6,
+            )]),
+            0
+        )
+    );
+
+    let mut parser = Parser::new("async x => await x".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::ArrowFunction(
+                    vec![FormalParameter {
+                        name: "x".to_string(),
+                        init: None,
+                        is_rest_param: false,
+                        pattern: None,
+                    }],
+                    Box::new(Node::new(
+                        NodeBase::Return(Some(Box::new(Node::new(
+                            NodeBase::Await(Box::new(Node::new(
+                                NodeBase::Identifier("x".to_string()),
+                                17
+                            ))),
+                            11
+                        )))),
+                        11
+                    )),
+                    FunctionKind::Async,
+                ),
+                6
+            )]),
+            0
+        )
+    );
+
+    // 'await' is an identifier outside async functions.
+    let mut parser = Parser::new("function f() { await }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::FunctionDecl(
+                    "f".to_string(),
+                    vec![],
+                    Box::new(Node::new(
+                        NodeBase::StatementList(vec![Node::new(
+                            NodeBase::Identifier("await".to_string()),
+                            15
+                        )]),
+                        13
+                    )),
+                    FunctionKind::Ordinary,
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+
+    for input in ["async function* f() {}", "async function f() { await }"].iter() {
+        let mut parser = Parser::new(input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
 #[test]
 fn arrow_function() {
     for (input, node) in [
@@ -2426,11 +3927,13 @@ fn arrow_function() {
                             name: "a".to_string(),
                             init: None,
                             is_rest_param: false,
+                            pattern: None,
                         },
                         FormalParameter {
                             name: "b".to_string(),
                             init: None,
                             is_rest_param: false,
+                            pattern: None,
                         },
                     ],
                     Box::new(Node::new(
@@ -2447,6 +3950,7 @@ fn arrow_function() {
                         )]),
                         10,
                     )),
+                    FunctionKind::Ordinary,
                 ),
                 0,
             ),
@@ -2460,16 +3964,19 @@ fn arrow_function() {
                             name: "a".to_string(),
                             init: None,
                             is_rest_param: false,
+                            pattern: None,
                         },
                         FormalParameter {
                             name: "b".to_string(),
                             init: None,
                             is_rest_param: false,
+                            pattern: None,
                         },
                         FormalParameter {
                             name: "c".to_string(),
                             init: None,
                             is_rest_param: true,
+                            pattern: None,
                         },
                     ],
                     Box::new(Node::new(
@@ -2479,6 +3986,7 @@ fn arrow_function() {
                         )))),
                         16,
                     )),
+                    FunctionKind::Ordinary,
                 ),
                 0,
             ),
@@ -2533,6 +4041,7 @@ fn asi1() {
                         ]),
                         23,
                     )),
+                    FunctionKind::Ordinary,
                 ),
                 0,
             )]),
@@ -2662,3 +4171,421 @@ fn try_catch3() {
         )
     );
 }
+
+#[test]
+fn class() {
+    let mut parser =
+        Parser::new("class A { constructor(x) { super.y } static get m() {} }".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::VarDecl(
+                    "A".to_string(),
+                    Some(Box::new(Node::new(
+                        NodeBase::Class(
+                            Some("A".to_string()),
+                            Box::new(Node::new(NodeBase::Nope, 0)),
+                            Box::new(Node::new(
+                                NodeBase::FunctionExpr(
+                                    Some("A".to_string()),
+                                    vec![FormalParameter {
+                                        name: "x".to_string(),
+                                        init: None,
+                                        is_rest_param: false,
+                                        pattern: None,
+                                    }],
+                                    Box::new(Node::new(
+                                        NodeBase::StatementList(vec![Node::new(
+                                            NodeBase::Member(
+                                                Box::new(Node::new(NodeBase::Super, 27)),
+                                                "y".to_string()
+                                            ),
+                                            27
+                                        )]),
+                                        25
+                                    )),
+                                    FunctionKind::Ordinary,
+                                ),
+                                10
+                            )),
+                            vec![ClassElement {
+                                is_static: true,
+                                kind: MethodDefinitionKind::Get,
+                                name: PropertyName::Literal("m".to_string()),
+                                func: Node::new(
+                                    NodeBase::FunctionExpr(
+                                        Some("m".to_string()),
+                                        vec![],
+                                        Box::new(Node::new(NodeBase::StatementList(vec![]), 52)),
+                                        FunctionKind::Ordinary,
+                                    ),
+                                    48
+                                ),
+                            }],
+                        ),
+                        0
+                    ))),
+                    VarKind::Let,
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+
+    for input in [
+        "class A { constructor() {} constructor() {} }",
+        "class A { get constructor() {} }",
+        "class A extends B { async *m() {} }",
+        "super",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new(input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn destructuring() {
+    let mut parser = Parser::new("let { a, b: [c = 1, , ...d], ...e } = f".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::StatementList(vec![Node::new(
+                    NodeBase::PatternDecl(
+                        Box::new(Node::new(
+                            NodeBase::ObjectPattern(
+                                vec![
+                                    PropertyPattern {
+                                        key: PropertyName::Literal("a".to_string()),
+                                        value: Node::new(NodeBase::Identifier("a".to_string()), 6),
+                                    },
+                                    PropertyPattern {
+                                        key: PropertyName::Literal("b".to_string()),
+                                        value: Node::new(
+                                            NodeBase::ArrayPattern(
+                                                vec![
+                                                    Node::new(
+                                                        NodeBase::Assign(
+                                                            Box::new(Node::new(
+                                                                NodeBase::Identifier(
+                                                                    "c".to_string()
+                                                                ),
+                                                                13
+                                                            )),
+                                                            Box::new(Node::new(
+                                                                NodeBase::Number(1.0),
+                                                                17
+                                                            )),
+                                                        ),
+                                                        13
+                                                    ),
+                                                    Node::new(NodeBase::Nope, 20),
+                                                ],
+                                                Some(Box::new(Node::new(
+                                                    NodeBase::Identifier("d".to_string()),
+                                                    25
+                                                ))),
+                                            ),
+                                            12
+                                        ),
+                                    },
+                                ],
+                                Some(Box::new(Node::new(
+                                    NodeBase::Identifier("e".to_string()),
+                                    32
+                                ))),
+                            ),
+                            4
+                        )),
+                        Some(Box::new(Node::new(
+                            NodeBase::Identifier("f".to_string()),
+                            38
+                        ))),
+                        VarKind::Let,
+                    ),
+                    4,
+                )]),
+                4,
+            )]),
+            0
+        )
+    );
+
+    let mut parser = Parser::new("[a.b, ...c[0]] = d".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::Assign(
+                    Box::new(Node::new(
+                        NodeBase::ArrayPattern(
+                            vec![Node::new(
+                                NodeBase::Member(
+                                    Box::new(Node::new(NodeBase::Identifier("a".to_string()), 1)),
+                                    "b".to_string()
+                                ),
+                                1
+                            )],
+                            Some(Box::new(Node::new(
+                                NodeBase::Index(
+                                    Box::new(Node::new(NodeBase::Identifier("c".to_string()), 9)),
+                                    Box::new(Node::new(NodeBase::Number(0.0), 11)),
+                                ),
+                                9
+                            ))),
+                        ),
+                        0
+                    )),
+                    Box::new(Node::new(NodeBase::Identifier("d".to_string()), 17)),
+                ),
+                0,
+            )]),
+            0
+        )
+    );
+
+    for input in [
+        "let { a }",
+        "let [a.b] = c",
+        "let { ...{ a } } = b",
+        "let [...a, b] = c",
+        "({ a: 1 } = b)",
+        "[a + b] = c",
+        "function f({ a.b }) {}",
+    ]
+    .iter()
+    {
+        let mut parser = Parser::new(input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn spread() {
+    let mut parser = Parser::new("f(...a, [...b], {...c})".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::Call(
+                    Box::new(Node::new(NodeBase::Identifier("f".to_string()), 0)),
+                    vec![
+                        Node::new(
+                            NodeBase::Spread(Box::new(Node::new(
+                                NodeBase::Identifier("a".to_string()),
+                                5
+                            ))),
+                            2
+                        ),
+                        Node::new(
+                            NodeBase::Array(vec![Node::new(
+                                NodeBase::Spread(Box::new(Node::new(
+                                    NodeBase::Identifier("b".to_string()),
+                                    12
+                                ))),
+                                9
+                            )]),
+                            9
+                        ),
+                        Node::new(
+                            NodeBase::Object(vec![PropertyDefinition::Spread(Node::new(
+                                NodeBase::Identifier("c".to_string()),
+                                20
+                            ))]),
+                            17
+                        ),
+                    ]
+                ),
+                0
+            )]),
+            0
+        )
+    );
+}
+
+#[test]
+fn default_parameters() {
+    let mut parser = Parser::new("function f(a, b = a) {}".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::FunctionDecl(
+                    "f".to_string(),
+                    vec![
+                        FormalParameter::new("a".to_string(), None, false),
+                        FormalParameter::new(
+                            "b".to_string(),
+                            Some(Node::new(NodeBase::Identifier("a".to_string()), 18)),
+                            false
+                        ),
+                    ],
+                    Box::new(Node::new(NodeBase::StatementList(vec![]), 21)),
+                    FunctionKind::Ordinary,
+                ),
+                0
+            )]),
+            0
+        )
+    );
+
+    for input in ["function f(...a = []) {}", "function f(a = ) {}"].iter() {
+        let mut parser = Parser::new(input.to_string());
+        parser.parse_all().expect_err("should be error");
+    }
+}
+
+#[test]
+fn template_literal() {
+    let mut parser = Parser::new("tag`\\n${x}`".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::TaggedTemplate(
+                    Box::new(Node::new(NodeBase::Identifier("tag".to_string()), 0)),
+                    Box::new(Node::new(
+                        NodeBase::Template(
+                            vec![Some(vec![0x0a]), Some(vec![])],
+                            vec!["\\n".to_string(), "".to_string()],
+                            vec![Node::new(NodeBase::Identifier("x".to_string()), 8)]
+                        ),
+                        3
+                    ))
+                ),
+                0
+            )]),
+            0
+        )
+    );
+
+    // Invalid escape sequences are allowed only in tagged templates.
+    let mut parser = Parser::new("tag`\\u{}${x}\\xZ`".to_string());
+    match parser.parse_all().unwrap().base {
+        NodeBase::StatementList(ref stmts) => match stmts[0].base {
+            NodeBase::TaggedTemplate(_, ref template) => match template.base {
+                NodeBase::Template(ref cooked, ref raw, _) => {
+                    assert_eq!(cooked, &vec![None, None]);
+                    assert_eq!(raw, &vec!["\\u{}".to_string(), "\\xZ".to_string()]);
+                }
+                _ => panic!(),
+            },
+            _ => panic!(),
+        },
+        _ => panic!(),
+    }
+    assert!(Parser::new("`\\u{}`".to_string()).parse_all().is_err());
+    assert!(Parser::new("`${x}\\xZ`".to_string()).parse_all().is_err());
+}
+
+#[test]
+fn regexp_literal() {
+    let mut parser = Parser::new("x = /[/]+/g".to_string());
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::Assign(
+                    Box::new(Node::new(NodeBase::Identifier("x".to_string()), 0)),
+                    Box::new(Node::new(
+                        NodeBase::RegExp("[/]+".to_string(), "g".to_string()),
+                        4
+                    ))
+                ),
+                0
+            )]),
+            0
+        )
+    );
+
+    for code in &["/(/", "/a/gg", "/a"] {
+        assert!(Parser::new(code.to_string()).parse_all().is_err());
+    }
+}
+
+#[test]
+fn optional_chaining() {
+    let mut parser = Parser::new("a?.b.c(d)".to_string());
+    let a = Node::new(NodeBase::Identifier("a".to_string()), 0);
+    let a_b = Node::new(
+        NodeBase::Member(
+            Box::new(Node::new(NodeBase::Optional(Box::new(a)), 0)),
+            "b".to_string(),
+        ),
+        0,
+    );
+    let a_b_c = Node::new(NodeBase::Member(Box::new(a_b), "c".to_string()), 0);
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::OptionalChain(Box::new(Node::new(
+                    NodeBase::Call(
+                        Box::new(a_b_c),
+                        vec![Node::new(NodeBase::Identifier("d".to_string()), 7)]
+                    ),
+                    0
+                ))),
+                0
+            )]),
+            0
+        )
+    );
+
+    for code in &[
+        "a?.[b]",
+        "a?.(b)",
+        "a?.b?.c",
+        "a ?? b ?? c",
+        "(a || b) ?? c",
+        "a ?? (b && c)",
+    ] {
+        assert!(
+            Parser::new(code.to_string()).parse_all().is_ok(),
+            "{}",
+            code
+        );
+    }
+    for code in &[
+        "a?.b = 1",
+        "a?.b += 1",
+        "a?.b`c`",
+        "a || b ?? c",
+        "a ?? b && c",
+    ] {
+        assert!(
+            Parser::new(code.to_string()).parse_all().is_err(),
+            "{}",
+            code
+        );
+    }
+}
+
+#[test]
+fn instanceof_in() {
+    let mut parser = Parser::new("a instanceof b in c".to_string());
+    let a = Node::new(NodeBase::Identifier("a".to_string()), 0);
+    let b = Node::new(NodeBase::Identifier("b".to_string()), 13);
+    let c = Node::new(NodeBase::Identifier("c".to_string()), 18);
+    assert_eq!(
+        parser.parse_all().unwrap(),
+        Node::new(
+            NodeBase::StatementList(vec![Node::new(
+                NodeBase::BinaryOp(
+                    Box::new(Node::new(
+                        NodeBase::BinaryOp(Box::new(a), Box::new(b), BinOp::InstanceOf),
+                        13
+                    )),
+                    Box::new(c),
+                    BinOp::In
+                ),
+                18
+            )]),
+            0
+        )
+    );
+}
@@ -0,0 +1,522 @@
+//! Backtracking matcher, which follows the pattern semantics in the specification.
+//! https://tc39.github.io/ecma262/#sec-pattern-semantics
+//!
+//! A pattern is compiled to instructions of a backtracking machine. Alternatives to try on
+//! failure and the changes of the state to undo are pushed to an explicit stack rather than kept
+//! in the call stack, so that quantifiers iterated over a long input don't overflow the stack.
+
+use super::parser::{ClassItem, Node, Quantifier};
+use super::{Captures, Flags, RegExp};
+use std::char;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Program {
+    insts: Vec<Inst>,
+    /// Number of the registers, which hold the start positions of groups and iterations, and
+    /// the counts of iterations
+    register_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Inst {
+    /// Read a character, backward in lookbehinds
+    Char(u32, bool),
+    Any(bool),
+    Class(Vec<ClassItem>, bool, bool), // Items, Negated, Backward
+    LineStart,
+    LineEnd,
+    WordBoundary(bool), // \b if true, \B otherwise
+    /// Continue at the first, and at the second on backtracking
+    Split(usize, usize),
+    Jump(usize),
+    /// Save the position where the capturing group begins in the register
+    GroupStart(usize),
+    GroupEnd(usize, usize, bool), // Index of the capturing group, Register, Backward
+    /// Reset the count of iterations in the register
+    RepeatStart(usize),
+    /// Decide whether to iterate once more. The pattern begins at the next instruction.
+    Repeat {
+        count: usize,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+        exit: usize,
+    },
+    /// Clear the capturing groups in the range, and save the position where the iteration
+    /// begins in the register
+    IterationStart(usize, (usize, usize)),
+    /// Count the iteration and go back to the Repeat instruction
+    IterationEnd {
+        count: usize,
+        start: usize,
+        min: usize,
+        repeat: usize,
+    },
+    /// Match the pattern from the next instruction to the Match, which is read backward in
+    /// lookbehinds, and continue at `end`
+    Lookaround {
+        negated: bool,
+        end: usize,
+    },
+    BackReference(usize, bool), // Index of the capturing group, Backward
+    Match,
+}
+
+enum Backtrack {
+    /// Alternative to try, which is the position in the program and the input
+    Branch(usize, usize),
+    Register(usize, usize),
+    Capture(usize, Option<(usize, usize)>),
+    Captures(Captures),
+}
+
+pub fn compile(node: &Node) -> Program {
+    let mut compiler = Compiler {
+        insts: vec![],
+        register_count: 0,
+    };
+    compiler.compile(node, false);
+    compiler.insts.push(Inst::Match);
+    Program {
+        insts: compiler.insts,
+        register_count: compiler.register_count,
+    }
+}
+
+struct Compiler {
+    insts: Vec<Inst>,
+    register_count: usize,
+}
+
+impl Compiler {
+    fn compile(&mut self, node: &Node, backward: bool) {
+        match node {
+            Node::Char(c) => self.insts.push(Inst::Char(*c, backward)),
+            Node::Any => self.insts.push(Inst::Any(backward)),
+            Node::Class(items, negated) => {
+                self.insts
+                    .push(Inst::Class(items.clone(), *negated, backward))
+            }
+            Node::LineStart => self.insts.push(Inst::LineStart),
+            Node::LineEnd => self.insts.push(Inst::LineEnd),
+            Node::WordBoundary(boundary) => self.insts.push(Inst::WordBoundary(*boundary)),
+            Node::Sequence(nodes) => {
+                if backward {
+                    nodes
+                        .iter()
+                        .rev()
+                        .for_each(|node| self.compile(node, backward))
+                } else {
+                    nodes.iter().for_each(|node| self.compile(node, backward))
+                }
+            }
+            Node::Disjunction(alternatives) => {
+                let (last, rest) = alternatives.split_last().unwrap();
+                let mut jumps = vec![];
+                for alternative in rest {
+                    let split = self.insts.len();
+                    self.insts.push(Inst::Split(split + 1, 0));
+                    self.compile(alternative, backward);
+                    jumps.push(self.insts.len());
+                    self.insts.push(Inst::Jump(0));
+                    self.insts[split] = Inst::Split(split + 1, self.insts.len());
+                }
+                self.compile(last, backward);
+                for jump in jumps {
+                    self.insts[jump] = Inst::Jump(self.insts.len());
+                }
+            }
+            Node::Group(node, None) => self.compile(node, backward),
+            Node::Group(node, Some(index)) => {
+                let start = self.new_register();
+                self.insts.push(Inst::GroupStart(start));
+                self.compile(node, backward);
+                self.insts.push(Inst::GroupEnd(*index, start, backward));
+            }
+            Node::Lookaround(node, lookahead, negated) => {
+                let lookaround = self.insts.len();
+                self.insts.push(Inst::Match);
+                self.compile(node, !lookahead);
+                self.insts.push(Inst::Match);
+                self.insts[lookaround] = Inst::Lookaround {
+                    negated: *negated,
+                    end: self.insts.len(),
+                };
+            }
+            Node::Repeat(node, quantifier) => self.compile_repeat(node, quantifier, backward),
+            Node::BackReference(index) => self.insts.push(Inst::BackReference(*index, backward)),
+        }
+    }
+
+    fn compile_repeat(&mut self, node: &Node, quantifier: &Quantifier, backward: bool) {
+        let count = self.new_register();
+        let start = self.new_register();
+        self.insts.push(Inst::RepeatStart(count));
+        let repeat = self.insts.len();
+        self.insts.push(Inst::Match);
+        self.insts
+            .push(Inst::IterationStart(start, quantifier.groups));
+        self.compile(node, backward);
+        self.insts.push(Inst::IterationEnd {
+            count,
+            start,
+            min: quantifier.min,
+            repeat,
+        });
+        self.insts[repeat] = Inst::Repeat {
+            count,
+            min: quantifier.min,
+            max: quantifier.max,
+            greedy: quantifier.greedy,
+            exit: self.insts.len(),
+        };
+    }
+
+    fn new_register(&mut self) -> usize {
+        self.register_count += 1;
+        self.register_count - 1
+    }
+}
+
+struct Matcher<'a> {
+    program: &'a Program,
+    input: &'a [u16],
+    flags: Flags,
+    captures: Captures,
+    registers: Vec<usize>,
+    stack: Vec<Backtrack>,
+}
+
+pub fn match_at(regexp: &RegExp, input: &[u16], start: usize) -> Option<Captures> {
+    let mut matcher = Matcher {
+        program: &regexp.program,
+        input,
+        flags: regexp.flags,
+        captures: vec![None; regexp.group_count + 1],
+        registers: vec![0; regexp.program.register_count],
+        stack: vec![],
+    };
+    let end = matcher.run(0, start)?;
+    matcher.captures[0] = Some((start, end));
+    Some(matcher.captures)
+}
+
+impl<'a> Matcher<'a> {
+    /// Run the program from `pc` at `pos` until a Match instruction. Return the position where
+    /// it matched, leaving the stack as it was at the start.
+    fn run(&mut self, mut pc: usize, mut pos: usize) -> Option<usize> {
+        let base = self.stack.len();
+        loop {
+            let matched = match self.program.insts[pc] {
+                Inst::Char(c, backward) => {
+                    let c = self.canonicalize(c);
+                    self.read_char(&mut pos, backward, |m, ch| m.canonicalize(ch) == c)
+                }
+                Inst::Any(backward) => {
+                    let dot_all = self.flags.dot_all;
+                    self.read_char(&mut pos, backward, |_, ch| {
+                        dot_all || !is_line_terminator(ch)
+                    })
+                }
+                Inst::Class(ref items, negated, backward) => {
+                    self.read_char(&mut pos, backward, |m, ch| {
+                        items.iter().any(|item| m.class_item_matches(*item, ch)) != negated
+                    })
+                }
+                Inst::LineStart => {
+                    pos == 0
+                        || self.flags.multiline && is_line_terminator(self.input[pos - 1] as u32)
+                }
+                Inst::LineEnd => {
+                    pos == self.input.len()
+                        || self.flags.multiline && is_line_terminator(self.input[pos] as u32)
+                }
+                Inst::WordBoundary(boundary) => {
+                    let before = pos > 0 && is_word_char(self.input[pos - 1] as u32);
+                    let after = pos < self.input.len() && is_word_char(self.input[pos] as u32);
+                    (before != after) == boundary
+                }
+                Inst::Split(first, second) => {
+                    self.stack.push(Backtrack::Branch(second, pos));
+                    pc = first;
+                    continue;
+                }
+                Inst::Jump(dst) => {
+                    pc = dst;
+                    continue;
+                }
+                Inst::GroupStart(start) => {
+                    self.set_register(start, pos);
+                    true
+                }
+                Inst::GroupEnd(index, start, backward) => {
+                    let start = self.registers[start];
+                    self.set_capture(
+                        index,
+                        Some(if backward { (pos, start) } else { (start, pos) }),
+                    );
+                    true
+                }
+                Inst::RepeatStart(count) => {
+                    self.set_register(count, 0);
+                    true
+                }
+                Inst::Repeat {
+                    count,
+                    min,
+                    max,
+                    greedy,
+                    exit,
+                } => {
+                    let count = self.registers[count];
+                    pc = if max == Some(count) {
+                        exit
+                    } else if count < min {
+                        pc + 1
+                    } else if greedy {
+                        self.stack.push(Backtrack::Branch(exit, pos));
+                        pc + 1
+                    } else {
+                        self.stack.push(Backtrack::Branch(pc + 1, pos));
+                        exit
+                    };
+                    continue;
+                }
+                Inst::IterationStart(start, (groups_start, groups_end)) => {
+                    for index in groups_start..groups_end {
+                        self.set_capture(index, None);
+                    }
+                    self.set_register(start, pos);
+                    true
+                }
+                Inst::IterationEnd {
+                    count,
+                    start,
+                    min,
+                    repeat,
+                } => {
+                    // Reject an empty iteration after the required ones not to loop forever.
+                    let iterations = self.registers[count];
+                    if pos == self.registers[start] && iterations >= min {
+                        false
+                    } else {
+                        self.set_register(count, iterations + 1);
+                        pc = repeat;
+                        continue;
+                    }
+                }
+                Inst::Lookaround { negated, end } => {
+                    // Lookarounds are atomic. Once matched, they are never backtracked into.
+                    let saved = self.captures.clone();
+                    let matched = self.run(pc + 1, pos).is_some();
+                    let succeeded = if negated {
+                        self.captures = saved;
+                        !matched
+                    } else {
+                        if matched {
+                            self.stack.push(Backtrack::Captures(saved));
+                        }
+                        matched
+                    };
+                    if succeeded {
+                        pc = end;
+                        continue;
+                    }
+                    false
+                }
+                Inst::BackReference(index, backward) => {
+                    self.read_back_reference(index, &mut pos, backward)
+                }
+                Inst::Match => {
+                    self.stack.truncate(base);
+                    return Some(pos);
+                }
+            };
+
+            if matched {
+                pc += 1;
+                continue;
+            }
+
+            // Backtrack to the last alternative, undoing the changes made after it.
+            loop {
+                if self.stack.len() == base {
+                    return None;
+                }
+                match self.stack.pop().unwrap() {
+                    Backtrack::Branch(next_pc, next_pos) => {
+                        pc = next_pc;
+                        pos = next_pos;
+                        break;
+                    }
+                    Backtrack::Register(register, val) => self.registers[register] = val,
+                    Backtrack::Capture(index, capture) => self.captures[index] = capture,
+                    Backtrack::Captures(captures) => self.captures = captures,
+                }
+            }
+        }
+    }
+
+    /// Read a character satisfying `pred` and move `pos` past it. With the u flag, a surrogate
+    /// pair in the input is read as one code point.
+    fn read_char<F>(&self, pos: &mut usize, backward: bool, pred: F) -> bool
+    where
+        F: Fn(&Self, u32) -> bool,
+    {
+        let unicode = self.flags.unicode;
+        let (ch, next) = if backward {
+            if *pos == 0 {
+                return false;
+            }
+            match (
+                pos.checked_sub(2).map(|i| self.input[i]),
+                self.input[*pos - 1],
+            ) {
+                (Some(lead), trail) if unicode && is_surrogate_pair(lead, trail) => {
+                    (code_point(lead, trail), *pos - 2)
+                }
+                (_, unit) => (unit as u32, *pos - 1),
+            }
+        } else {
+            match (self.input.get(*pos), self.input.get(*pos + 1)) {
+                (Some(&lead), Some(&trail)) if unicode && is_surrogate_pair(lead, trail) => {
+                    (code_point(lead, trail), *pos + 2)
+                }
+                (Some(&unit), _) => (unit as u32, *pos + 1),
+                (None, _) => return false,
+            }
+        };
+        if !pred(self, ch) {
+            return false;
+        }
+        *pos = next;
+        true
+    }
+
+    /// Read the string captured by the group and move `pos` past it. A group which didn't
+    /// participate in the match matches the empty string.
+    fn read_back_reference(&self, index: usize, pos: &mut usize, backward: bool) -> bool {
+        let (start, end) = match self.captures[index] {
+            Some(capture) => capture,
+            None => return true,
+        };
+        let len = end - start;
+        let from = if backward {
+            if *pos < len {
+                return false;
+            }
+            *pos - len
+        } else {
+            if *pos + len > self.input.len() {
+                return false;
+            }
+            *pos
+        };
+        for i in 0..len {
+            if self.canonicalize(self.input[start + i] as u32)
+                != self.canonicalize(self.input[from + i] as u32)
+            {
+                return false;
+            }
+        }
+        *pos = if backward { from } else { *pos + len };
+        true
+    }
+
+    fn set_register(&mut self, register: usize, val: usize) {
+        let old = self.registers[register];
+        self.stack.push(Backtrack::Register(register, old));
+        self.registers[register] = val;
+    }
+
+    fn set_capture(&mut self, index: usize, capture: Option<(usize, usize)>) {
+        let old = self.captures[index];
+        if old != capture {
+            self.stack.push(Backtrack::Capture(index, old));
+            self.captures[index] = capture;
+        }
+    }
+
+    fn class_item_matches(&self, item: ClassItem, c: u32) -> bool {
+        match item {
+            ClassItem::Range(from, to) => {
+                let in_range = |c: char| from <= c as u32 && c as u32 <= to;
+                from <= c && c <= to
+                    || self.flags.ignore_case
+                        && char::from_u32(c).map_or(false, |c| {
+                            single_char(c.to_lowercase()).map_or(false, &in_range)
+                                || single_char(c.to_uppercase()).map_or(false, &in_range)
+                        })
+            }
+            ClassItem::Digit(negated) => ('0' as u32 <= c && c <= '9' as u32) != negated,
+            ClassItem::Word(negated) => is_word_char(c) != negated,
+            ClassItem::Space(negated) => is_white_space(c) != negated,
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-runtime-semantics-canonicalize-ch
+    fn canonicalize(&self, c: u32) -> u32 {
+        if !self.flags.ignore_case {
+            return c;
+        }
+        // Surrogates are never changed.
+        let ch = match char::from_u32(c) {
+            Some(ch) => ch,
+            None => return c,
+        };
+        if self.flags.unicode {
+            // Approximate simple case folding
+            return single_char(ch.to_lowercase()).map_or(c, |lower| lower as u32);
+        }
+        match single_char(ch.to_uppercase()) {
+            // A character outside the BMP is not a code unit.
+            Some(upper) if upper > '\u{ffff}' => c,
+            Some(upper) if ch.is_ascii() || !upper.is_ascii() => upper as u32,
+            _ => c,
+        }
+    }
+}
+
+/// Return the char if the case mapping results in a single char.
+fn single_char<I: Iterator<Item = char>>(mut chars: I) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+fn is_surrogate_pair(lead: u16, trail: u16) -> bool {
+    0xd800 <= lead && lead < 0xdc00 && 0xdc00 <= trail && trail < 0xe000
+}
+
+fn code_point(lead: u16, trail: u16) -> u32 {
+    0x10000 + ((lead as u32 - 0xd800) << 10) + (trail as u32 - 0xdc00)
+}
+
+fn is_line_terminator(c: u32) -> bool {
+    match c {
+        0x0a | 0x0d | 0x2028 | 0x2029 => true,
+        _ => false,
+    }
+}
+
+fn is_word_char(c: u32) -> bool {
+    char::from_u32(c).map_or(false, |c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_white_space(c: u32) -> bool {
+    match c {
+        0x09
+        | 0x0b
+        | 0x0c
+        | 0x20
+        | 0xa0
+        | 0xfeff
+        | 0x1680
+        | 0x2000...0x200a
+        | 0x202f
+        | 0x205f
+        | 0x3000 => true,
+        c => is_line_terminator(c),
+    }
+}
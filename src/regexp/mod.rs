@@ -0,0 +1,282 @@
+//! Backtracking regular expression engine for RegExp objects.
+//! https://tc39.github.io/ecma262/#sec-regexp-regular-expression-objects
+//!
+//! Patterns are matched against strings as sequences of UTF-16 code units, so indices of matches
+//! are code unit indices. With the u flag, surrogate pairs are matched as single code points.
+
+mod matcher;
+mod parser;
+
+use std::fmt;
+
+/// Ranges of the input matched by each capturing group. The first element is for the whole match.
+pub type Captures = Vec<Option<(usize, usize)>>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegExp {
+    /// Source text of the pattern in UTF-16 code units
+    pub source: Vec<u16>,
+    pub flags: Flags,
+    program: matcher::Program,
+    /// Number of capturing groups
+    pub group_count: usize,
+    /// Names of the named capturing groups and their indices
+    pub group_names: Vec<(String, usize)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Flags {
+    pub global: bool,
+    pub ignore_case: bool,
+    pub multiline: bool,
+    pub dot_all: bool,
+    pub unicode: bool,
+    pub sticky: bool,
+}
+
+impl RegExp {
+    /// Compile the pattern. Return the error message if the pattern or flags are invalid.
+    pub fn new(source: &str, flags: &str) -> Result<RegExp, String> {
+        RegExp::from_units(source.encode_utf16().collect(), flags)
+    }
+
+    /// Same as new, but the source is given in UTF-16 code units to be kept as it is.
+    pub fn from_units(source: Vec<u16>, flags: &str) -> Result<RegExp, String> {
+        let flags = Flags::parse(flags)?;
+        let pattern = String::from_utf16_lossy(&source);
+        let (node, group_count, group_names) = parser::parse(&pattern, flags.unicode)?;
+        Ok(RegExp {
+            source,
+            flags,
+            program: matcher::compile(&node),
+            group_count,
+            group_names,
+        })
+    }
+
+    /// Try to match the pattern at `start` of `input`, without searching forward.
+    pub fn match_at(&self, input: &[u16], start: usize) -> Option<Captures> {
+        matcher::match_at(self, input, start)
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-escaperegexppattern
+    pub fn escaped_source(&self) -> Vec<u16> {
+        if self.source.is_empty() {
+            return "(?:)".encode_utf16().collect();
+        }
+        let mut escaped = vec![];
+        let mut in_class = false;
+        let mut units = self.source.iter().cloned();
+        while let Some(u) = units.next() {
+            match u {
+                BACKSLASH => {
+                    escaped.push(u);
+                    if let Some(u) = units.next() {
+                        escaped.push(u)
+                    }
+                    continue;
+                }
+                SLASH if !in_class => escaped.push(BACKSLASH),
+                OPENING_BRACKET => in_class = true,
+                CLOSING_BRACKET => in_class = false,
+                _ => {}
+            }
+            match u {
+                0x0A => escaped.extend("\\n".encode_utf16()),
+                0x0D => escaped.extend("\\r".encode_utf16()),
+                0x2028 => escaped.extend("\\u2028".encode_utf16()),
+                0x2029 => escaped.extend("\\u2029".encode_utf16()),
+                u => escaped.push(u),
+            }
+        }
+        escaped
+    }
+
+    /// Return the string of the regular expression such as /a/g in UTF-16 code units.
+    /// https://tc39.github.io/ecma262/#sec-regexp.prototype.tostring
+    pub fn to_units(&self) -> Vec<u16> {
+        let mut units = vec![SLASH];
+        units.extend(self.escaped_source());
+        units.push(SLASH);
+        units.extend(self.flags.to_string().encode_utf16());
+        units
+    }
+}
+
+const BACKSLASH: u16 = b'\\' as u16;
+const SLASH: u16 = b'/' as u16;
+const OPENING_BRACKET: u16 = b'[' as u16;
+const CLOSING_BRACKET: u16 = b']' as u16;
+
+/// Lone surrogates in the source are written as U+FFFD.
+impl fmt::Display for RegExp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", String::from_utf16_lossy(&self.to_units()))
+    }
+}
+
+impl Flags {
+    pub fn parse(flags: &str) -> Result<Flags, String> {
+        let mut parsed = Flags::default();
+        for c in flags.chars() {
+            let flag = match c {
+                'g' => &mut parsed.global,
+                'i' => &mut parsed.ignore_case,
+                'm' => &mut parsed.multiline,
+                's' => &mut parsed.dot_all,
+                'u' => &mut parsed.unicode,
+                'y' => &mut parsed.sticky,
+                _ => return Err(format!("invalid flags '{}'", flags)),
+            };
+            if *flag {
+                return Err(format!("invalid flags '{}'", flags));
+            }
+            *flag = true;
+        }
+        Ok(parsed)
+    }
+}
+
+impl fmt::Display for Flags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (flag, c) in &[
+            (self.global, 'g'),
+            (self.ignore_case, 'i'),
+            (self.multiline, 'm'),
+            (self.dot_all, 's'),
+            (self.unicode, 'u'),
+            (self.sticky, 'y'),
+        ] {
+            if *flag {
+                write!(f, "{}", c)?
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn regexp_match() {
+    let find = |pattern: &str, flags: &str, input: &str| -> Option<Vec<Option<String>>> {
+        let regexp = RegExp::new(pattern, flags).unwrap();
+        let input = input.encode_utf16().collect::<Vec<u16>>();
+        (0..input.len() + 1)
+            .filter_map(|start| regexp.match_at(&input, start))
+            .next()
+            .map(|captures| {
+                captures
+                    .iter()
+                    .map(|c| c.map(|(start, end)| String::from_utf16_lossy(&input[start..end])))
+                    .collect()
+            })
+    };
+    let s = |s: &str| Some(s.to_string());
+
+    assert_eq!(find("b+", "", "abbbc"), Some(vec![s("bbb")]));
+    assert_eq!(find("b+?", "", "abbbc"), Some(vec![s("b")]));
+    assert_eq!(find("a{2,3}", "", "aaaa"), Some(vec![s("aaa")]));
+    assert_eq!(find("x", "", "abc"), None);
+    assert_eq!(
+        find("(\\d+)-(\\d+)", "", "tel: 03-1234"),
+        Some(vec![s("03-1234"), s("03"), s("1234")])
+    );
+    assert_eq!(find("(a)|b", "", "b"), Some(vec![s("b"), None]));
+    assert_eq!(
+        find("(z)((a+)?(b+)?(c))*", "", "zaacbbbcac"),
+        Some(vec![s("zaacbbbcac"), s("z"), s("ac"), s("a"), None, s("c")])
+    );
+    assert_eq!(find("(a*)*", "", "b"), Some(vec![s(""), None]));
+    assert_eq!(find("(a*)b\\1+", "", "baaaac"), Some(vec![s("b"), s("")]));
+    assert_eq!(
+        find("(?<y>\\d{4})\\k<y>", "", "19991999"),
+        Some(vec![s("19991999"), s("1999")])
+    );
+    assert_eq!(find("^b", "", "a\nb"), None);
+    assert_eq!(find("^b", "m", "a\nb"), Some(vec![s("b")]));
+    assert_eq!(find("a.c", "", "a\nc"), None);
+    assert_eq!(find("a.c", "s", "a\nc"), Some(vec![s("a\nc")]));
+    assert_eq!(find("ABC", "i", "xabcx"), Some(vec![s("abc")]));
+    assert_eq!(find("[^a-c]+", "", "abcdefabc"), Some(vec![s("def")]));
+    assert_eq!(find("[\\w-]+", "", "  foo-bar "), Some(vec![s("foo-bar")]));
+    assert_eq!(find("\\bis\\b", "", "this is"), Some(vec![s("is")]));
+    assert_eq!(find("\\d+(?=%)", "", "10 20%"), Some(vec![s("20")]));
+    assert_eq!(find("\\d+(?!\\d|%)", "", "20% 30"), Some(vec![s("30")]));
+    assert_eq!(find("(?<=\\$)\\d+", "", "1 $2"), Some(vec![s("2")]));
+    assert_eq!(find("(?<!\\$)\\b\\d", "", "$1 2"), Some(vec![s("2")]));
+    assert_eq!(find("\\u{1F363}", "u", "🍣"), Some(vec![s("🍣")]));
+    assert_eq!(find("\\x41\\u0042", "", "AB"), Some(vec![s("AB")]));
+    assert_eq!(find("^.$", "", "🍣"), None);
+    assert_eq!(find("^.$", "u", "🍣"), Some(vec![s("🍣")]));
+    assert_eq!(find("^🍣+$", "", "🍣"), Some(vec![s("🍣")]));
+    assert_eq!(find("^[🍣]$", "u", "🍣"), Some(vec![s("🍣")]));
+    assert_eq!(find("^[🍣]$", "", "🍣"), None);
+    assert_eq!(find("\\ud83c\\udf63", "", "🍣"), Some(vec![s("🍣")]));
+    assert_eq!(find("\\ud83c\\udf63", "u", "🍣"), Some(vec![s("🍣")]));
+    assert_eq!(find("(?<=🍣)a", "u", "🍣a"), Some(vec![s("a")]));
+    assert_eq!(find("^(a|ab)*c", "", "abac"), Some(vec![s("abac"), s("a")]));
+    assert_eq!(find("(a+?)+b", "", "aab"), Some(vec![s("aab"), s("a")]));
+    assert_eq!(find("(?:a{2}){2}", "", "aaaaa"), Some(vec![s("aaaa")]));
+    assert_eq!(
+        find("(?<=(\\d)(\\d))x", "", "12x"),
+        Some(vec![s("x"), s("1"), s("2")])
+    );
+    assert_eq!(
+        find("(?=(a+))a*b\\1", "", "baaabac"),
+        Some(vec![s("aba"), s("a")])
+    );
+    assert_eq!(find("(?!(a))\\1b", "", "b"), Some(vec![s("b"), None]));
+}
+
+#[test]
+fn regexp_match_index() {
+    let index = |pattern: &str, flags: &str, input: &str| {
+        let regexp = RegExp::new(pattern, flags).unwrap();
+        let input = input.encode_utf16().collect::<Vec<u16>>();
+        (0..input.len() + 1).find(|&start| regexp.match_at(&input, start).is_some())
+    };
+    assert_eq!(index("a", "", "😀a"), Some(2));
+    assert_eq!(index("a", "u", "😀a"), Some(2));
+    assert_eq!(index("\\udc00", "", "\u{10000}"), Some(1));
+}
+
+#[test]
+fn regexp_match_long_input() {
+    let input = "a".repeat(100_000).encode_utf16().collect::<Vec<u16>>();
+    let end = |pattern: &str, flags: &str| {
+        let regexp = RegExp::new(pattern, flags).unwrap();
+        regexp
+            .match_at(&input, 0)
+            .map(|captures| captures[0].unwrap().1)
+    };
+    assert_eq!(end("a*", ""), Some(100_000));
+    assert_eq!(end("a*?$", ""), Some(100_000));
+    assert_eq!(end(".+a", "u"), Some(100_000));
+    assert_eq!(end("[a-z]{3,}b", "i"), None);
+    assert_eq!(end("(?<=a*)a{2}", ""), Some(2));
+    assert_eq!(end("(a)*", ""), Some(100_000));
+    assert_eq!(end("(?:aa)+?$", ""), Some(100_000));
+    assert_eq!(end("(a|b)*c", ""), None);
+    assert_eq!(end("(?:a(?=a))*", ""), Some(99_999));
+}
+
+#[test]
+fn regexp_syntax_error() {
+    for (pattern, flags) in &[
+        ("a**", ""),
+        ("(", ""),
+        (")", ""),
+        ("[b-a]", ""),
+        ("a{2,1}", ""),
+        ("(?<n>a)(?<n>b)", ""),
+        ("\\k<n>(?<m>)", ""),
+        ("\\p", "u"),
+        ("{", "u"),
+        ("a", "gg"),
+        ("a", "x"),
+    ] {
+        assert!(RegExp::new(pattern, flags).is_err(), "{}", pattern);
+    }
+    for pattern in &["{", "a{,", "]", "\\p", "\\1", "(?=a)*"] {
+        assert!(RegExp::new(pattern, "").is_ok(), "{}", pattern);
+    }
+}
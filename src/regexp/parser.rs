@@ -0,0 +1,560 @@
+//! Parser for regular expression patterns.
+//! https://tc39.github.io/ecma262/#sec-patterns
+//!
+//! Without the u flag, the extensions in Annex B are accepted.
+//! https://tc39.github.io/ecma262/#sec-regular-expressions-patterns
+//!
+//! Characters in the resulting nodes are code points with the u flag, and UTF-16 code units
+//! otherwise.
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Char(u32),
+    Any,
+    Class(Vec<ClassItem>, bool), // Items, Negated
+    LineStart,
+    LineEnd,
+    WordBoundary(bool), // \b if true, \B otherwise
+    Sequence(Vec<Node>),
+    Disjunction(Vec<Node>),
+    Group(Box<Node>, Option<usize>), // Pattern, Index of the capturing group
+    Lookaround(Box<Node>, bool, bool), // Pattern, Lookahead, Negated
+    Repeat(Box<Node>, Quantifier),
+    BackReference(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quantifier {
+    pub min: usize,
+    pub max: Option<usize>,
+    pub greedy: bool,
+    /// Range of the indices of the capturing groups in the quantified pattern, which are
+    /// cleared at each iteration
+    pub groups: (usize, usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClassItem {
+    Range(u32, u32),
+    Digit(bool), // \d if false, \D otherwise
+    Word(bool),  // \w if false, \W otherwise
+    Space(bool), // \s if false, \S otherwise
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    unicode: bool,
+    /// Number of capturing groups read so far
+    group_count: usize,
+    group_names: Vec<(String, usize)>,
+    /// Number of capturing groups in the whole pattern
+    total_group_count: usize,
+    /// Names of the capturing groups in the whole pattern, which may be referred to before they
+    /// appear
+    all_group_names: Vec<(String, usize)>,
+}
+
+/// Parse the pattern. Return the root node, the number of the capturing groups and the names of
+/// the named ones.
+pub fn parse(pattern: &str, unicode: bool) -> Result<(Node, usize, Vec<(String, usize)>), String> {
+    let chars = pattern.chars().collect::<Vec<char>>();
+    let (total_group_count, all_group_names) = scan_groups(&chars);
+    let mut parser = Parser {
+        chars,
+        pos: 0,
+        unicode,
+        group_count: 0,
+        group_names: vec![],
+        total_group_count,
+        all_group_names,
+    };
+    let node = parser.parse_disjunction()?;
+    if parser.pos < parser.chars.len() {
+        return Err("unmatched ')'".to_string());
+    }
+    Ok((node, parser.group_count, parser.group_names))
+}
+
+/// Count the capturing groups and collect their names before parsing.
+fn scan_groups(chars: &[char]) -> (usize, Vec<(String, usize)>) {
+    let mut count = 0;
+    let mut names = vec![];
+    let mut in_class = false;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '(' if !in_class => match (chars.get(i + 1), chars.get(i + 2), chars.get(i + 3)) {
+                (Some('?'), Some('<'), Some('=')) | (Some('?'), Some('<'), Some('!')) => {}
+                (Some('?'), Some('<'), _) => {
+                    count += 1;
+                    let name = chars[i + 3..].iter().take_while(|&&c| c != '>').collect();
+                    names.push((name, count));
+                }
+                (Some('?'), _, _) => {}
+                _ => count += 1,
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+    (count, names)
+}
+
+impl Parser {
+    fn parse_disjunction(&mut self) -> Result<Node, String> {
+        let mut alternatives = vec![self.parse_alternative()?];
+        while self.eat('|') {
+            alternatives.push(self.parse_alternative()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Node::Disjunction(alternatives)
+        })
+    }
+
+    fn parse_alternative(&mut self) -> Result<Node, String> {
+        let mut terms = vec![];
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            terms.push(self.parse_term()?);
+        }
+        Ok(Node::Sequence(terms))
+    }
+
+    fn parse_term(&mut self) -> Result<Node, String> {
+        let group_start = self.group_count;
+        let atom = match self.next().unwrap() {
+            '^' => return Ok(Node::LineStart),
+            '$' => return Ok(Node::LineEnd),
+            '\\' if self.eat('b') => return Ok(Node::WordBoundary(true)),
+            '\\' if self.eat('B') => return Ok(Node::WordBoundary(false)),
+            '\\' => self.parse_atom_escape()?,
+            '(' if self.eat_str("?=") || self.eat_str("?!") => {
+                let negated = self.chars[self.pos - 1] == '!';
+                let node = self.parse_group_body()?;
+                let lookahead = Node::Lookaround(Box::new(node), true, negated);
+                // Lookaheads can be quantified only in Annex B.
+                if self.unicode {
+                    return Ok(lookahead);
+                }
+                lookahead
+            }
+            '(' if self.eat_str("?<=") || self.eat_str("?<!") => {
+                let negated = self.chars[self.pos - 1] == '!';
+                let node = self.parse_group_body()?;
+                return Ok(Node::Lookaround(Box::new(node), false, negated));
+            }
+            '(' if self.eat_str("?:") => Node::Group(Box::new(self.parse_group_body()?), None),
+            '(' => {
+                let name = if self.eat_str("?<") {
+                    Some(self.parse_group_name()?)
+                } else if self.peek() == Some('?') {
+                    return Err("invalid group".to_string());
+                } else {
+                    None
+                };
+                self.group_count += 1;
+                let index = self.group_count;
+                if let Some(name) = name {
+                    if self.group_names.iter().any(|(n, _)| n == &name) {
+                        return Err("duplicate capture group name".to_string());
+                    }
+                    self.group_names.push((name, index));
+                }
+                Node::Group(Box::new(self.parse_group_body()?), Some(index))
+            }
+            '.' => Node::Any,
+            '[' => self.parse_class()?,
+            '*' | '+' | '?' => return Err("nothing to repeat".to_string()),
+            '{' => {
+                self.pos -= 1;
+                if self.unicode || self.parse_braced_quantifier()?.is_some() {
+                    return Err("nothing to repeat".to_string());
+                }
+                self.pos += 1;
+                Node::Char('{' as u32)
+            }
+            '}' | ']' if self.unicode => return Err("lone quantifier brackets".to_string()),
+            c if !self.unicode && c > '\u{ffff}' => {
+                // Without the u flag, a quantifier applies only to the trail surrogate.
+                let (lead, trail) = surrogate_pair(c);
+                let trail = self.parse_quantifier(Node::Char(trail), group_start)?;
+                return Ok(Node::Sequence(vec![Node::Char(lead), trail]));
+            }
+            c => Node::Char(c as u32),
+        };
+        self.parse_quantifier(atom, group_start)
+    }
+
+    /// Read the rest of a group after its opening.
+    fn parse_group_body(&mut self) -> Result<Node, String> {
+        let node = self.parse_disjunction()?;
+        if !self.eat(')') {
+            return Err("unterminated group".to_string());
+        }
+        Ok(node)
+    }
+
+    fn parse_group_name(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            let valid = c.is_alphabetic()
+                || c == '_'
+                || c == '$'
+                || (c.is_alphanumeric() && self.pos > start);
+            if !valid {
+                break;
+            }
+            self.pos += 1;
+        }
+        let name = self.chars[start..self.pos].iter().collect::<String>();
+        if name.is_empty() || !self.eat('>') {
+            return Err("invalid capture group name".to_string());
+        }
+        Ok(name)
+    }
+
+    fn parse_quantifier(&mut self, atom: Node, group_start: usize) -> Result<Node, String> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => match self.parse_braced_quantifier()? {
+                Some(bounds) => bounds,
+                None => return Ok(atom),
+            },
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        let greedy = !self.eat('?');
+        Ok(Node::Repeat(
+            Box::new(atom),
+            Quantifier {
+                min,
+                max,
+                greedy,
+                groups: (group_start + 1, self.group_count + 1),
+            },
+        ))
+    }
+
+    /// Read a quantifier like '{n}', '{n,}' or '{n,m}'. The position is not moved if there is no
+    /// such quantifier, and is left at the closing brace otherwise.
+    fn parse_braced_quantifier(&mut self) -> Result<Option<(usize, Option<usize>)>, String> {
+        let start = self.pos;
+        self.pos += 1;
+        let min = match self.parse_decimal() {
+            Some(min) => min,
+            None => {
+                self.pos = start;
+                return Ok(None);
+            }
+        };
+        let max = if self.eat(',') {
+            self.parse_decimal()
+        } else {
+            Some(min)
+        };
+        if self.peek() != Some('}') {
+            self.pos = start;
+            return Ok(None);
+        }
+        if max.map_or(false, |max| max < min) {
+            return Err("numbers out of order in {} quantifier".to_string());
+        }
+        Ok(Some((min, max)))
+    }
+
+    fn parse_atom_escape(&mut self) -> Result<Node, String> {
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Err("\\ at end of pattern".to_string()),
+        };
+        match c {
+            '1'...'9' => {
+                let start = self.pos;
+                let n = self.parse_decimal().unwrap();
+                if n <= self.total_group_count {
+                    return Ok(Node::BackReference(n));
+                }
+                if self.unicode {
+                    return Err("invalid escape".to_string());
+                }
+                // Annex B: Legacy octal escapes and identity escapes
+                self.pos = start;
+                Ok(Node::Char(self.parse_character_escape(false)?))
+            }
+            'k' if self.unicode || !self.all_group_names.is_empty() => {
+                self.pos += 1;
+                if !self.eat('<') {
+                    return Err("invalid named reference".to_string());
+                }
+                let name = self.parse_group_name()?;
+                match self.all_group_names.iter().find(|(n, _)| n == &name) {
+                    Some(&(_, index)) => Ok(Node::BackReference(index)),
+                    None => Err("invalid named capture referenced".to_string()),
+                }
+            }
+            'd' | 'D' | 's' | 'S' | 'w' | 'W' => {
+                self.pos += 1;
+                Ok(Node::Class(vec![class_escape(c)], false))
+            }
+            _ => Ok(Node::Char(self.parse_character_escape(false)?)),
+        }
+    }
+
+    /// Read an escape sequence after '\' which represents a character.
+    fn parse_character_escape(&mut self, in_class: bool) -> Result<u32, String> {
+        let c = match self.next() {
+            Some(c) => c,
+            None => return Err("\\ at end of pattern".to_string()),
+        };
+        Ok(match c {
+            't' => '\t' as u32,
+            'n' => '\n' as u32,
+            'v' => 0x0b,
+            'f' => 0x0c,
+            'r' => '\r' as u32,
+            'c' => match self.peek() {
+                Some(l) if l.is_ascii_alphabetic() => {
+                    self.pos += 1;
+                    l as u32 % 32
+                }
+                _ if self.unicode => return Err("invalid unicode escape".to_string()),
+                _ => {
+                    // Annex B: '\' is an ordinary character if '\c' is not followed by a letter.
+                    self.pos -= 1;
+                    '\\' as u32
+                }
+            },
+            '0' if !self.peek().map_or(false, |c| c.is_digit(10)) => 0,
+            '0'...'7' if !self.unicode => {
+                // Annex B: Legacy octal escape
+                let mut value = c.to_digit(8).unwrap();
+                let max_len = if c <= '3' { 3 } else { 2 };
+                for _ in 1..max_len {
+                    match self.peek().and_then(|c| c.to_digit(8)) {
+                        Some(d) => {
+                            value = value * 8 + d;
+                            self.pos += 1;
+                        }
+                        None => break,
+                    }
+                }
+                value
+            }
+            'x' => match self.parse_hex(2) {
+                Some(n) => n,
+                None if self.unicode => return Err("invalid escape".to_string()),
+                None => 'x' as u32,
+            },
+            'u' => self.parse_unicode_escape()?,
+            'b' if in_class => 0x08,
+            '-' if in_class => '-' as u32,
+            c if self.unicode => {
+                if "^$\\.*+?()[]{}|/".contains(c) {
+                    c as u32
+                } else {
+                    return Err("invalid escape".to_string());
+                }
+            }
+            // Annex B: Identity escape
+            c => c as u32,
+        })
+    }
+
+    /// Read the rest of an escape sequence after '\u'.
+    fn parse_unicode_escape(&mut self) -> Result<u32, String> {
+        let invalid = || Err("invalid unicode escape".to_string());
+        if self.unicode && self.eat('{') {
+            let start = self.pos;
+            while self.peek().map_or(false, |c| c.is_digit(16)) {
+                self.pos += 1;
+            }
+            let hex = self.chars[start..self.pos].iter().collect::<String>();
+            return match u32::from_str_radix(&hex, 16) {
+                Ok(c) if c <= 0x10ffff && self.eat('}') => Ok(c),
+                _ => invalid(),
+            };
+        }
+        let lead = match self.parse_hex(4) {
+            Some(n) => n,
+            None if self.unicode => return invalid(),
+            None => return Ok('u' as u32),
+        };
+        if self.unicode && 0xd800 <= lead && lead < 0xdc00 {
+            // With the u flag, an escaped surrogate pair is a code point.
+            let start = self.pos;
+            if self.eat_str("\\u") {
+                if let Some(trail) = self.parse_hex(4) {
+                    if 0xdc00 <= trail && trail < 0xe000 {
+                        return Ok(0x10000 + ((lead - 0xd800) << 10) + (trail - 0xdc00));
+                    }
+                }
+            }
+            self.pos = start;
+        }
+        Ok(lead)
+    }
+
+    fn parse_class(&mut self) -> Result<Node, String> {
+        let negated = self.eat('^');
+        let mut items = vec![];
+        loop {
+            let start = match self.next() {
+                Some(']') => break,
+                Some(c) => match self.parse_class_atom(c)? {
+                    (lead, Some(trail)) => {
+                        items.push(lead);
+                        trail
+                    }
+                    (atom, None) => atom,
+                },
+                None => return Err("unterminated character class".to_string()),
+            };
+            let is_range = self.peek() == Some('-')
+                && match self.chars.get(self.pos + 1) {
+                    Some(']') | None => false,
+                    _ => true,
+                };
+            if !is_range {
+                items.push(start);
+                continue;
+            }
+            self.pos += 1;
+            let c = self.next().unwrap();
+            let (end, trail) = self.parse_class_atom(c)?;
+            match (start, end) {
+                (ClassItem::Range(from, _), ClassItem::Range(to, _)) => {
+                    if from > to {
+                        return Err("range out of order in character class".to_string());
+                    }
+                    items.push(ClassItem::Range(from, to))
+                }
+                _ if self.unicode => return Err("invalid character class".to_string()),
+                _ => {
+                    // Annex B: '-' is an ordinary character next to a class escape.
+                    items.push(start);
+                    items.push(ClassItem::Range('-' as u32, '-' as u32));
+                    items.push(end);
+                }
+            }
+            items.extend(trail);
+        }
+        Ok(Node::Class(items, negated))
+    }
+
+    /// Read a class atom starting with `c`. Without the u flag, a character outside the BMP is
+    /// read as two atoms of its surrogate pair.
+    fn parse_class_atom(&mut self, c: char) -> Result<(ClassItem, Option<ClassItem>), String> {
+        if c != '\\' {
+            if !self.unicode && c > '\u{ffff}' {
+                let (lead, trail) = surrogate_pair(c);
+                return Ok((
+                    ClassItem::Range(lead, lead),
+                    Some(ClassItem::Range(trail, trail)),
+                ));
+            }
+            return Ok((ClassItem::Range(c as u32, c as u32), None));
+        }
+        match self.peek() {
+            Some(e @ 'd') | Some(e @ 'D') | Some(e @ 's') | Some(e @ 'S') | Some(e @ 'w')
+            | Some(e @ 'W') => {
+                self.pos += 1;
+                Ok((class_escape(e), None))
+            }
+            _ => {
+                let c = self.parse_character_escape(true)?;
+                Ok((ClassItem::Range(c, c), None))
+            }
+        }
+    }
+
+    fn parse_decimal(&mut self) -> Option<usize> {
+        let start = self.pos;
+        let mut value: usize = 0;
+        while let Some(d) = self.peek().and_then(|c| c.to_digit(10)) {
+            value = value.saturating_mul(10).saturating_add(d as usize);
+            self.pos += 1;
+        }
+        if self.pos == start {
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    /// Read exactly `len` hex digits. The position is not moved if they are not found.
+    fn parse_hex(&mut self, len: usize) -> Option<u32> {
+        if self.pos + len > self.chars.len() {
+            return None;
+        }
+        let hex = self.chars[self.pos..self.pos + len]
+            .iter()
+            .collect::<String>();
+        if !hex.chars().all(|c| c.is_digit(16)) {
+            return None;
+        }
+        self.pos += len;
+        u32::from_str_radix(&hex, 16).ok()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.pos += 1;
+        }
+        c
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let len = s.chars().count();
+        if self.pos + len <= self.chars.len()
+            && s.chars()
+                .eq(self.chars[self.pos..self.pos + len].iter().cloned())
+        {
+            self.pos += len;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+fn surrogate_pair(c: char) -> (u32, u32) {
+    let mut units = [0; 2];
+    c.encode_utf16(&mut units);
+    (units[0] as u32, units[1] as u32)
+}
+
+fn class_escape(c: char) -> ClassItem {
+    match c {
+        'd' => ClassItem::Digit(false),
+        'D' => ClassItem::Digit(true),
+        's' => ClassItem::Space(false),
+        'S' => ClassItem::Space(true),
+        'w' => ClassItem::Word(false),
+        'W' => ClassItem::Word(true),
+        _ => unreachable!(),
+    }
+}
@@ -14,12 +14,30 @@ pub enum Kind {
     Keyword(Keyword),
     Identifier(String),
     Number(f64),
-    String(String),
+    /// String literal in UTF-16 code units, which may contain lone surrogates
+    String(Vec<u16>),
+    Template(TemplatePart),
+    /// Regular expression literal (pattern, flags)
+    RegExp(String, String),
     Symbol(Symbol),
     LineTerminator,
     EOF,
 }
 
+/// A string part of template literals, which is delimited by '`', '${' and '}'.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplatePart {
+    /// The string value with escape sequences processed, in UTF-16 code units. None if the part
+    /// has an invalid escape sequence.
+    pub cooked: Option<Vec<u16>>,
+    /// The source text
+    pub raw: String,
+    /// Represent if the part begins with '`'
+    pub head: bool,
+    /// Represent if the part ends with '`'
+    pub tail: bool,
+}
+
 #[derive(Clone, Debug, PartialEq, Copy)]
 pub enum Keyword {
     Abstract,
@@ -27,6 +45,7 @@ pub enum Keyword {
     Break,
     Case,
     Catch,
+    Class,
     Const,
     Continue,
     Debugger,
@@ -34,6 +53,7 @@ pub enum Keyword {
     Delete,
     Do,
     Else,
+    Extends,
     Finally,
     For,
     Function,
@@ -43,6 +63,7 @@ pub enum Keyword {
     Let,
     New,
     Return,
+    Super,
     Switch,
     This,
     Throw,
@@ -94,6 +115,8 @@ pub enum Symbol {
     LAnd,
     LOr,
     Question,
+    OptionalChaining,
+    Nullish,
     Assign,
     AssignAdd,
     AssignSub,
@@ -107,6 +130,9 @@ pub enum Symbol {
     AssignXor,
     AssignLAnd,
     AssignLOr,
+    AssignExp,
+    AssignZFShr,
+    AssignNullish,
     Hash,
     Rest,
     FatArrow,
@@ -137,7 +163,7 @@ impl Token {
         }
     }
 
-    pub fn new_string(s: String, pos: usize) -> Token {
+    pub fn new_string(s: Vec<u16>, pos: usize) -> Token {
         Token {
             kind: Kind::String(s),
             pos: pos,
@@ -145,6 +171,22 @@ impl Token {
         }
     }
 
+    pub fn new_template(part: TemplatePart, pos: usize) -> Token {
+        Token {
+            kind: Kind::Template(part),
+            pos: pos,
+            prev_pos: 0,
+        }
+    }
+
+    pub fn new_regexp(pattern: String, flags: String, pos: usize) -> Token {
+        Token {
+            kind: Kind::RegExp(pattern, flags),
+            pos: pos,
+            prev_pos: 0,
+        }
+    }
+
     pub fn new_symbol(symbol: Symbol, pos: usize) -> Token {
         Token {
             kind: Kind::Symbol(symbol),
@@ -192,6 +234,7 @@ impl Symbol {
             Symbol::Xor => Some(BinOp::Xor),
             Symbol::LAnd => Some(BinOp::LAnd),
             Symbol::LOr => Some(BinOp::LOr),
+            Symbol::Nullish => Some(BinOp::Nullish),
             Symbol::Eq => Some(BinOp::Eq),
             Symbol::Ne => Some(BinOp::Ne),
             Symbol::SEq => Some(BinOp::SEq),
@@ -218,6 +261,7 @@ impl Keyword {
             Keyword::Break => "break",
             Keyword::Case => "case",
             Keyword::Catch => "catch",
+            Keyword::Class => "class",
             Keyword::Continue => "continue",
             Keyword::Const => "const",
             Keyword::Debugger => "debugger",
@@ -225,6 +269,7 @@ impl Keyword {
             Keyword::Delete => "delete",
             Keyword::Do => "do",
             Keyword::Else => "else",
+            Keyword::Extends => "extends",
             Keyword::Finally => "finally",
             Keyword::For => "for",
             Keyword::Function => "function",
@@ -234,6 +279,7 @@ impl Keyword {
             Keyword::Let => "let",
             Keyword::New => "new",
             Keyword::Return => "return",
+            Keyword::Super => "super",
             Keyword::Switch => "switch",
             Keyword::This => "this",
             Keyword::Throw => "throw",
@@ -254,6 +300,7 @@ pub fn convert_reserved_keyword(keyword: &str) -> Option<Keyword> {
         "break" => Some(Keyword::Break),
         "case" => Some(Keyword::Case),
         "catch" => Some(Keyword::Catch),
+        "class" => Some(Keyword::Class),
         "continue" => Some(Keyword::Continue),
         "const" => Some(Keyword::Const),
         "debugger" => Some(Keyword::Debugger),
@@ -261,6 +308,7 @@ pub fn convert_reserved_keyword(keyword: &str) -> Option<Keyword> {
         "delete" => Some(Keyword::Delete),
         "do" => Some(Keyword::Do),
         "else" => Some(Keyword::Else),
+        "extends" => Some(Keyword::Extends),
         "finally" => Some(Keyword::Finally),
         "for" => Some(Keyword::For),
         "function" => Some(Keyword::Function),
@@ -270,6 +318,7 @@ pub fn convert_reserved_keyword(keyword: &str) -> Option<Keyword> {
         "let" => Some(Keyword::Let),
         "new" => Some(Keyword::New),
         "return" => Some(Keyword::Return),
+        "super" => Some(Keyword::Super),
         "switch" => Some(Keyword::Switch),
         "this" => Some(Keyword::This),
         "throw" => Some(Keyword::Throw),
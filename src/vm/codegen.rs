@@ -2,17 +2,30 @@ use crate::id::get_unique_id;
 use bytecode_gen::{ByteCode, ByteCodeGenerator, VMInst};
 use gc::MemoryAllocator;
 use node::{
-    BinOp, FormalParameter, FormalParameters, MethodDefinitionKind, Node, NodeBase,
-    PropertyDefinition, UnaryOp, VarKind,
+    BinOp, CaseClause, ClassElement, FormalParameter, FormalParameters, FunctionKind,
+    MethodDefinitionKind, Node, NodeBase, PropertyDefinition, PropertyName, PropertyPattern,
+    UnaryOp, VarKind,
 };
+use regexp::RegExp;
 use rustc_hash::FxHashMap;
-use vm::constant::{ConstantTable, SpecialProperties, SpecialPropertyKind};
+use std::mem;
+use vm::constant::{ClassMethodInfo, ConstantTable, SpecialProperties, SpecialPropertyKind};
 use vm::jsvalue::function::{DestinationKind, Exception, ThisMode, UserFunctionInfo};
+use vm::jsvalue::generator::ResumeKind;
+use vm::jsvalue::string::JSString;
 use vm::jsvalue::value::Value;
 use vm::jsvalue::{prototype, value};
 
 pub type CodeGenResult = Result<(), Error>;
 
+/// Name of the hidden binding in the class scope, which holds the class. 'super' is looked up
+/// through it.
+const CLASS_BINDING: &str = "%class";
+
+/// Name of the hidden binding in the scope of an object literal with methods, which holds the
+/// object. 'super' in the methods is looked up through it.
+const HOME_OBJECT_BINDING: &str = "%home";
+
 #[derive(Clone, Debug)]
 pub struct Error {
     pub msg: String,
@@ -43,8 +56,39 @@ pub struct FunctionInfo {
     pub lex_names: Vec<String>,
     pub func_decls: Vec<Value>,
     pub level: Vec<Level>,
+    pub label_set: Vec<String>,
     pub exception_table: Vec<Exception>,
     pub to_source_pos: ToSourcePos,
+    /// None if 'super' is not available in the function.
+    pub super_kind: Option<SuperKind>,
+    /// Represent if 'arguments' is referred to in the function or arrow functions in it.
+    pub uses_arguments: bool,
+    /// Optional chains being compiled. The last one is the innermost.
+    pub optional_chains: Vec<OptionalChain>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OptionalChain {
+    /// Positions of the jumps to the end of the chain, which are taken when the expression
+    /// before '?.' is null or undefined.
+    jmp_instr_pos: Vec<usize>,
+    /// Number of the values pushed on the stack in the chain, which are popped before the jumps.
+    stack_depth: usize,
+}
+
+/// Kind of class methods, which decides what 'super' refers to. Arrow functions in the methods
+/// inherit it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SuperKind {
+    /// 'super' refers to the prototype of the parent class.
+    Method,
+    /// 'super' refers to the parent class.
+    StaticMethod,
+    BaseConstructor,
+    /// 'super()' is available only in this kind.
+    DerivedConstructor,
+    /// 'super' refers to the prototype of the object literal.
+    ObjectMethod,
 }
 
 #[derive(Debug, Clone)]
@@ -63,11 +107,29 @@ pub enum Level {
     },
     Finally,
     Loop {
+        kind: LoopKind,
+        labels: Vec<String>,
         break_jmp_instr_pos: Vec<usize>,
         continue_jmp_instr_pos: Vec<usize>,
     },
 }
 
+/// Statements that 'break' or 'continue' can jump out of.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoopKind {
+    /// while, do-while and for statements.
+    Iteration,
+    /// for-in statement. The list of keys is on the stack while iterating.
+    ForIn,
+    /// for-of statement. The iterator is on the stack while iterating, and it is closed when the
+    /// loop is left by 'break', 'continue label', 'return' or an exception.
+    ForOf,
+    /// Only 'break' can target switch statements.
+    Switch,
+    /// Labelled statements other than iteration statements. Only 'break label' can target them.
+    Labelled,
+}
+
 impl<'a> CodeGenerator<'a> {
     pub fn new(
         constant_table: &'a mut ConstantTable,
@@ -115,26 +177,43 @@ impl<'a> CodeGenerator<'a> {
                 self.visit_if(&*cond, &*then, &*else_, iseq)?
             }
             NodeBase::While(ref cond, ref body) => self.visit_while(&*cond, &*body, iseq)?,
+            NodeBase::DoWhile(ref body, ref cond) => self.visit_do_while(&*body, &*cond, iseq)?,
             NodeBase::For(ref init, ref cond, ref step, ref body) => {
                 self.visit_for(&*init, &*cond, &*step, &*body, iseq)?
             }
-            NodeBase::Break(ref name) => self.visit_break(name, iseq)?,
-            NodeBase::Continue(ref name) => self.visit_continue(name, iseq)?,
+            NodeBase::ForIn(ref binding, ref obj, ref body) => {
+                self.visit_for_in(&*binding, &*obj, &*body, iseq)?
+            }
+            NodeBase::ForOf(ref binding, ref iterable, ref body) => {
+                self.visit_for_of(&*binding, &*iterable, &*body, iseq)?
+            }
+            NodeBase::Switch(ref discriminant, ref clauses) => {
+                self.visit_switch(&*discriminant, clauses, iseq)?
+            }
+            NodeBase::Label(_, _) => self.visit_label(node, iseq)?,
+            NodeBase::Break(ref name) => self.visit_break(node, name, iseq)?,
+            NodeBase::Continue(ref name) => self.visit_continue(node, name, iseq)?,
             NodeBase::Try(ref try, ref catch, ref param, ref finally) => {
                 self.visit_try(&*try, &*catch, &*param, &*finally, iseq)?
             }
-            NodeBase::FunctionDecl(ref name, ref params, ref body) => {
-                self.visit_function_decl(name, params, &*body)?
+            NodeBase::FunctionDecl(ref name, ref params, ref body, kind) => {
+                self.visit_function_decl(name, params, &*body, kind)?
+            }
+            NodeBase::FunctionExpr(ref name, ref params, ref body, kind) => {
+                self.visit_function_expr(name, params, &*body, kind, true, iseq, use_value)?
             }
-            NodeBase::FunctionExpr(ref name, ref params, ref body) => {
-                self.visit_function_expr(name, params, &*body, true, iseq, use_value)?
+            NodeBase::ArrowFunction(ref params, ref body, kind) => {
+                self.visit_function_expr(&None, params, &*body, kind, false, iseq, use_value)?
             }
-            NodeBase::ArrowFunction(ref params, ref body) => {
-                self.visit_function_expr(&None, params, &*body, false, iseq, use_value)?
+            NodeBase::Class(ref name, ref heritage, ref constructor, ref methods) => {
+                self.visit_class(name, &*heritage, &*constructor, methods, iseq, use_value)?
             }
             NodeBase::VarDecl(ref name, ref init, ref kind) => {
                 self.visit_var_decl(node, name, init, kind, iseq)?
             }
+            NodeBase::PatternDecl(ref pattern, ref init, ref kind) => {
+                self.visit_pattern_decl(node, &*pattern, init, kind, iseq)?
+            }
             NodeBase::Member(ref parent, ref property) => {
                 self.visit_member(&*parent, property, iseq, use_value)?
             }
@@ -153,9 +232,24 @@ impl<'a> CodeGenerator<'a> {
                     .append(iseq.len(), node.pos);
                 self.visit_assign(&*dst, &*src, iseq, use_value)?
             }
+            NodeBase::CompoundAssign(ref dst, ref src, ref op) => {
+                self.current_function()
+                    .to_source_pos
+                    .append(iseq.len(), node.pos);
+                self.visit_compound_assign(&*dst, &*src, op, iseq, use_value)?
+            }
             NodeBase::Call(ref callee, ref args) => {
                 self.visit_call(&*callee, args, iseq, use_value)?
             }
+            NodeBase::OptionalChain(ref expr) => {
+                self.visit_optional_chain(&*expr, iseq, use_value)?
+            }
+            NodeBase::Optional(ref expr) => {
+                self.visit(&*expr, iseq, true)?;
+                self.bytecode_generator.append_double(iseq);
+                self.bytecode_generator.append_is_nullish(iseq);
+                self.append_jmp_out_of_optional_chain(1, iseq);
+            }
             NodeBase::Throw(ref val) => {
                 self.current_function()
                     .to_source_pos
@@ -163,9 +257,27 @@ impl<'a> CodeGenerator<'a> {
                 self.visit_throw(val, iseq)?
             }
             NodeBase::Return(ref val) => self.visit_return(val, iseq)?,
+            NodeBase::Yield(ref val, delegate) => {
+                self.current_function()
+                    .to_source_pos
+                    .append(iseq.len(), node.pos);
+                self.visit_yield(val, delegate, iseq, use_value)?
+            }
+            NodeBase::Await(ref val) => {
+                self.current_function()
+                    .to_source_pos
+                    .append(iseq.len(), node.pos);
+                self.visit_await(&*val, iseq, use_value)?
+            }
             NodeBase::New(ref expr) => self.visit_new(&*expr, iseq, use_value)?,
             NodeBase::Object(ref properties) => self.visit_object_literal(properties, iseq)?,
             NodeBase::Array(ref elems) => self.visit_array_literal(elems, iseq)?,
+            NodeBase::Template(ref cooked_strings, _, ref substitutions) => {
+                self.visit_template_literal(cooked_strings, substitutions, iseq, use_value)?
+            }
+            NodeBase::TaggedTemplate(ref tag, ref template) => {
+                self.visit_tagged_template(&*tag, &*template, iseq, use_value)?
+            }
             NodeBase::Identifier(ref name) => {
                 if use_value {
                     self.current_function()
@@ -189,10 +301,32 @@ impl<'a> CodeGenerator<'a> {
                     self.bytecode_generator.append_push_this(iseq);
                 }
             }
+            NodeBase::Arguments => {
+                self.current_function().uses_arguments = true;
+                if use_value {
+                    self.bytecode_generator.append_push_arguments(iseq);
+                }
+            }
+            NodeBase::Super => self.visit_super(node, iseq)?,
+            NodeBase::SuperCall(ref args) => {
+                self.current_function()
+                    .to_source_pos
+                    .append(iseq.len(), node.pos);
+                self.visit_super_call(node, &*args, iseq, use_value)?
+            }
             NodeBase::String(ref s) => {
                 if use_value {
+                    let s = JSString::new(s.clone());
                     self.bytecode_generator
-                        .append_push_const(Value::string(self.memory_allocator, s.clone()), iseq)
+                        .append_push_const(Value::js_string(self.memory_allocator, s), iseq)
+                }
+            }
+            NodeBase::RegExp(ref pattern, ref flags) => {
+                if use_value {
+                    let regexp = RegExp::new(pattern, flags)
+                        .map_err(|msg| Error::new_general_error(msg, node.pos))?;
+                    let id = self.bytecode_generator.constant_table.add_regexp(regexp);
+                    self.bytecode_generator.append_create_regexp(id, iseq)
                 }
             }
             NodeBase::Number(n) => {
@@ -211,7 +345,12 @@ impl<'a> CodeGenerator<'a> {
                         .append_push_const(Value::empty(), iseq)
                 }
             }
-            ref e => unimplemented!("{:?}", e),
+            _ => {
+                return Err(Error::new_unimplemented_error(
+                    "Unimplemented syntax".to_string(),
+                    node.pos,
+                ));
+            }
         }
 
         Ok(())
@@ -223,8 +362,11 @@ impl<'a> CodeGenerator<'a> {
         iseq: &mut ByteCode,
         use_value: bool,
     ) -> CodeGenResult {
-        for node in node_list {
-            self.visit(node, iseq, use_value)?;
+        // Only the value of the last statement is left, so that the values kept on the stack
+        // by the enclosing statements are known when an exception is caught.
+        let len = node_list.len();
+        for (i, node) in node_list.iter().enumerate() {
+            self.visit(node, iseq, use_value && i + 1 == len)?;
         }
 
         Ok(())
@@ -244,8 +386,9 @@ impl<'a> CodeGenerator<'a> {
 
         self.current_function().level.push(Level::new_block_level());
 
-        for node in node_list {
-            self.visit(node, iseq, use_value)?;
+        let len = node_list.len();
+        for (i, node) in node_list.iter().enumerate() {
+            self.visit(node, iseq, use_value && i + 1 == len)?;
         }
 
         match self.current_function().level.pop().unwrap() {
@@ -307,13 +450,7 @@ impl<'a> CodeGenerator<'a> {
     }
 
     pub fn visit_while(&mut self, cond: &Node, body: &Node, iseq: &mut ByteCode) -> CodeGenResult {
-        // name:
-        //   while(...) {} // <- this while is named 'name'
-        // let name = self.state.loop_names.pop();
-        self.current_function().level.push(Level::Loop {
-            break_jmp_instr_pos: vec![],
-            continue_jmp_instr_pos: vec![],
-        });
+        self.push_loop_level_with_label_set(LoopKind::Iteration);
 
         let start = iseq.len() as isize;
 
@@ -345,6 +482,44 @@ impl<'a> CodeGenerator<'a> {
         Ok(())
     }
 
+    pub fn visit_do_while(
+        &mut self,
+        body: &Node,
+        cond: &Node,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        self.push_loop_level_with_label_set(LoopKind::Iteration);
+
+        let start = iseq.len() as isize;
+
+        self.visit(body, iseq, false)?;
+
+        let continue_pos = iseq.len() as isize;
+
+        self.visit(cond, iseq, true)?;
+
+        let cond_pos = iseq.len() as isize;
+        self.bytecode_generator.append_jmp_if_false(0, iseq);
+
+        let loop_pos = iseq.len() as isize;
+        self.bytecode_generator
+            .append_jmp((start - loop_pos) as i32 - 5, iseq);
+
+        let end = iseq.len() as isize;
+        self.bytecode_generator.replace_int32(
+            (end - cond_pos) as i32 - 5,
+            &mut iseq[cond_pos as usize + 1..cond_pos as usize + 5],
+        );
+
+        self.current_function()
+            .level
+            .pop()
+            .unwrap()
+            .replace_break_and_continue(&mut self.bytecode_generator, iseq, end, continue_pos);
+
+        Ok(())
+    }
+
     pub fn visit_for(
         &mut self,
         init: &Node,
@@ -353,10 +528,15 @@ impl<'a> CodeGenerator<'a> {
         body: &Node,
         iseq: &mut ByteCode,
     ) -> CodeGenResult {
-        self.current_function().level.push(Level::Loop {
-            break_jmp_instr_pos: vec![],
-            continue_jmp_instr_pos: vec![],
-        });
+        // The let and const declarations in init are scoped to the for statement.
+        let id = self
+            .bytecode_generator
+            .constant_table
+            .add_lex_env_info(vec![]);
+        self.bytecode_generator.append_push_env(id as u32, iseq);
+        self.current_function().level.push(Level::new_block_level());
+
+        self.push_loop_level_with_label_set(LoopKind::Iteration);
 
         self.visit(init, iseq, false)?;
 
@@ -389,164 +569,462 @@ impl<'a> CodeGenerator<'a> {
             .unwrap()
             .replace_break_and_continue(&mut self.bytecode_generator, iseq, end, continue_pos);
 
-        Ok(())
-    }
-
-    pub fn visit_break(&mut self, _name: &Option<String>, iseq: &mut ByteCode) -> CodeGenResult {
-        self.unwind_loop(iseq);
-
-        let break_instr_pos = iseq.len();
-        self.bytecode_generator.append_jmp(0, iseq);
+        match self.current_function().level.pop().unwrap() {
+            Level::Block { names } => {
+                *self
+                    .bytecode_generator
+                    .constant_table
+                    .get_mut(id)
+                    .as_lex_env_info_mut() = names;
+            }
+            _ => unreachable!(),
+        };
 
-        self.current_function()
-            .get_last_loop()
-            .as_loop_mut()
-            .0
-            .push(break_instr_pos);
+        self.bytecode_generator.append_pop_env(iseq);
 
         Ok(())
     }
 
-    pub fn visit_continue(&mut self, _name: &Option<String>, iseq: &mut ByteCode) -> CodeGenResult {
-        let continue_instr_pos = iseq.len();
-        self.bytecode_generator.append_jmp(0, iseq);
-
-        self.current_function()
-            .get_last_loop()
-            .as_loop_mut()
-            .1
-            .push(continue_instr_pos);
-
-        Ok(())
+    pub fn visit_for_in(
+        &mut self,
+        binding: &Node,
+        obj: &Node,
+        body: &Node,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        self.visit(obj, iseq, true)?;
+        self.bytecode_generator.append_for_in_init(iseq);
+        self.visit_for_in_of_loop(LoopKind::ForIn, binding, body, iseq)
     }
 
-    pub fn visit_try(
+    pub fn visit_for_of(
         &mut self,
-        try: &Node,
-        catch: &Node,
-        param: &Node,
-        finally: &Node,
+        binding: &Node,
+        iterable: &Node,
+        body: &Node,
         iseq: &mut ByteCode,
     ) -> CodeGenResult {
-        // TODO: Refine code
-
-        let has_catch = catch.base != NodeBase::Nope;
-
-        // Try block
-        let (try_, try_to_finally, leave_try) = {
-            let try_start = iseq.len() as usize;
-
-            self.current_function()
-                .level
-                .push(Level::new_try_or_catch_level());
-
-            self.visit(try, iseq, false)?;
-
-            let try_ = self.current_function().level.pop().unwrap();
-
-            let try_to_finally = iseq.len() as usize;
-            self.bytecode_generator.append_jmp_sub(0, iseq);
+        self.visit(iterable, iseq, true)?;
+        self.bytecode_generator.append_get_iterator(iseq);
+        self.visit_for_in_of_loop(LoopKind::ForOf, binding, body, iseq)
+    }
 
-            let leave_try = iseq.len() as usize;
-            self.bytecode_generator.append_jmp_sub(0, iseq);
+    /// Emit the loop of for-in or for-of statement. The list of keys or the iterator must be on
+    /// the stack top.
+    fn visit_for_in_of_loop(
+        &mut self,
+        kind: LoopKind,
+        binding: &Node,
+        body: &Node,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        self.push_loop_level_with_label_set(kind.clone());
+        let stack_depth = self.current_function().stack_depth();
 
-            let try_end = iseq.len() as usize;
-            self.current_function().exception_table.push(Exception {
-                start: try_start,
-                end: try_end,
-                dst_kind: if has_catch {
-                    DestinationKind::Catch
-                } else {
-                    DestinationKind::Finally
-                },
-            });
+        let start = iseq.len() as isize;
+        if kind == LoopKind::ForOf {
+            self.bytecode_generator.append_iter_next(0, iseq);
+        } else {
+            self.bytecode_generator.append_for_in_next(0, iseq);
+        }
+        let body_start = iseq.len();
 
-            (try_, try_to_finally, leave_try)
+        // let and const declarations create a new binding for each iteration.
+        let env_id = match binding.base {
+            NodeBase::VarDecl(ref name, _, VarKind::Var) => {
+                self.current_function().var_names.push(name.clone());
+                self.bytecode_generator.append_set_value(name, iseq);
+                None
+            }
+            NodeBase::VarDecl(ref name, _, _) => {
+                let id = self
+                    .bytecode_generator
+                    .constant_table
+                    .add_lex_env_info(vec![]);
+                self.bytecode_generator.append_push_env(id as u32, iseq);
+                self.current_function().level.push(Level::Block {
+                    names: vec![name.clone()],
+                });
+                self.bytecode_generator.append_set_value(name, iseq);
+                Some(id)
+            }
+            NodeBase::PatternDecl(ref pattern, _, VarKind::Var) => {
+                let names = pattern.bound_names();
+                self.current_function().var_names.extend(names);
+                self.assign_stack_top_to(pattern, iseq)?;
+                None
+            }
+            NodeBase::PatternDecl(ref pattern, _, _) => {
+                let id = self
+                    .bytecode_generator
+                    .constant_table
+                    .add_lex_env_info(vec![]);
+                self.bytecode_generator.append_push_env(id as u32, iseq);
+                self.current_function().level.push(Level::Block {
+                    names: pattern.bound_names(),
+                });
+                self.assign_stack_top_to(pattern, iseq)?;
+                Some(id)
+            }
+            _ => {
+                self.assign_stack_top_to(binding, iseq)?;
+                None
+            }
         };
 
-        // Catch block
-        let (catch_, catch_to_finally, leave_catch) = if has_catch {
-            let catch_start = iseq.len() as usize;
-            let param_name = match param.base {
-                NodeBase::Identifier(ref name) => name.clone(),
-                _ => unimplemented!(),
-            };
-
-            self.current_function()
-                .level
-                .push(Level::new_try_or_catch_level());
-            let env_id = self
-                .bytecode_generator
-                .constant_table
-                .add_lex_env_info(vec![]);
-            self.bytecode_generator.append_push_env(env_id as u32, iseq);
-            self.current_function().level.push(Level::Block {
-                names: vec![param_name.clone()],
-            });
-            self.bytecode_generator.append_set_value(&param_name, iseq);
-
-            self.visit(catch, iseq, false)?;
-
-            self.bytecode_generator.append_pop_env(iseq);
+        self.visit(body, iseq, false)?;
 
+        if let Some(id) = env_id {
             let names = self.current_function().level.pop().unwrap().as_block();
-            let catch_ = self.current_function().level.pop().unwrap();
             *self
                 .bytecode_generator
                 .constant_table
-                .get_mut(env_id)
+                .get_mut(id)
                 .as_lex_env_info_mut() = names;
+            self.bytecode_generator.append_pop_env(iseq);
+        }
 
-            let catch_to_finally = iseq.len() as usize;
-            self.bytecode_generator.append_jmp_sub(0, iseq);
-
-            let leave_catch = iseq.len() as usize;
-            self.bytecode_generator.append_jmp_sub(0, iseq);
+        let loop_pos = iseq.len() as isize;
+        self.bytecode_generator
+            .append_jmp((start - loop_pos) as i32 - 5, iseq);
 
-            let catch_end = iseq.len() as usize;
+        // Close the iterator when the binding or the body throws, and rethrow the exception.
+        // The range starts after the first byte of the body since the pc has passed the
+        // instruction that throws, so that errors from ITER_NEXT are not caught.
+        if kind == LoopKind::ForOf {
+            let handler = iseq.len();
             self.current_function().exception_table.push(Exception {
-                start: catch_start,
-                end: catch_end,
-                dst_kind: DestinationKind::Finally,
+                start: body_start + 1,
+                end: handler,
+                dst_kind: DestinationKind::Catch,
+                stack_depth,
             });
+            self.bytecode_generator.append_rotate(1, iseq);
+            self.bytecode_generator.append_iter_close_throw(iseq);
+            self.bytecode_generator.append_throw(iseq);
+        }
 
-            (catch_, catch_to_finally, leave_catch)
+        // 'break' jumps here to pop the list of keys, or to close the iterator.
+        let break_pos = iseq.len() as isize;
+        if kind == LoopKind::ForOf {
+            self.bytecode_generator.append_iter_close(iseq);
         } else {
-            (Level::new_try_or_catch_level(), 0, 0)
-        };
-
-        // Finally block
-        let finally_start = iseq.len() as usize;
+            self.bytecode_generator.append_pop(iseq);
+        }
 
-        let has_return_try =
-            try_.set_jmp_to_finally(finally_start, &mut self.bytecode_generator, iseq)
-                || catch_.set_jmp_to_finally(finally_start, &mut self.bytecode_generator, iseq);
+        let end = iseq.len() as isize;
+        self.bytecode_generator.replace_int32(
+            (end - start) as i32 - 5,
+            &mut iseq[start as usize + 1..start as usize + 5],
+        );
 
-        self.current_function().level.push(Level::Finally);
-        self.visit(finally, iseq, false)?;
+        self.current_function()
+            .level
+            .pop()
+            .unwrap()
+            .replace_break_and_continue(&mut self.bytecode_generator, iseq, break_pos, start);
 
-        let finally_to_outer_finally_jmp_instr_pos = iseq.len();
-        if has_return_try
-            && self
-                .current_function()
-                .find_last_try_or_catch()
-                .map(|try_or_catch| {
-                    try_or_catch
-                        .as_try_or_catch_mut()
-                        .push(finally_to_outer_finally_jmp_instr_pos)
-                })
-                .is_some()
-        {
-            self.bytecode_generator.append_jmp(0, iseq);
-        }
+        Ok(())
+    }
 
-        assert_eq!(self.current_function().level.pop().unwrap(), Level::Finally);
-        self.bytecode_generator.append_return_sub(iseq);
+    pub fn visit_switch(
+        &mut self,
+        discriminant: &Node,
+        clauses: &Vec<CaseClause>,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        // 'break' in a switch statement is handled in the same way as in loops.
+        self.current_function()
+            .level
+            .push(Level::new_loop_level(LoopKind::Switch, vec![]));
 
-        let finally_end = iseq.len() as usize;
+        self.visit(discriminant, iseq, true)?;
 
-        self.bytecode_generator.replace_int32(
-            (finally_start - try_to_finally) as i32 - 5,
+        let id = self
+            .bytecode_generator
+            .constant_table
+            .add_lex_env_info(vec![]);
+        self.bytecode_generator.append_push_env(id as u32, iseq);
+
+        self.current_function().level.push(Level::new_block_level());
+
+        // Compare the discriminant with each case in order.
+        // Jump to the matched clause with the discriminant popped.
+        let mut jmp_to_clause_instr_pos = vec![];
+        for clause in clauses {
+            if let Some(ref test) = clause.test {
+                self.bytecode_generator.append_double(iseq);
+                self.visit(test, iseq, true)?;
+                self.bytecode_generator.append_seq(iseq);
+
+                let cond_pos = iseq.len() as isize;
+                self.bytecode_generator.append_jmp_if_false(0, iseq);
+
+                self.bytecode_generator.append_pop(iseq);
+                jmp_to_clause_instr_pos.push(Some(iseq.len()));
+                self.bytecode_generator.append_jmp(0, iseq);
+
+                let pos = iseq.len() as isize;
+                self.bytecode_generator.replace_int32(
+                    (pos - cond_pos) as i32 - 5,
+                    &mut iseq[cond_pos as usize + 1..cond_pos as usize + 5],
+                );
+            } else {
+                jmp_to_clause_instr_pos.push(None);
+            }
+        }
+
+        // No case matched. Go to the default clause if any.
+        self.bytecode_generator.append_pop(iseq);
+        let default_jmp_instr_pos = iseq.len();
+        self.bytecode_generator.append_jmp(0, iseq);
+
+        let mut default_pos = None;
+        for (clause, jmp_instr_pos) in clauses.iter().zip(jmp_to_clause_instr_pos) {
+            let clause_pos = iseq.len();
+            match jmp_instr_pos {
+                Some(instr_pos) => self.bytecode_generator.replace_int32(
+                    (clause_pos - instr_pos) as i32 - 5,
+                    &mut iseq[instr_pos + 1..instr_pos + 5],
+                ),
+                None => default_pos = Some(clause_pos),
+            }
+            for node in &clause.body {
+                self.visit(node, iseq, false)?;
+            }
+        }
+
+        let body_end = iseq.len();
+        self.bytecode_generator.replace_int32(
+            (default_pos.unwrap_or(body_end) - default_jmp_instr_pos) as i32 - 5,
+            &mut iseq[default_jmp_instr_pos + 1..default_jmp_instr_pos + 5],
+        );
+
+        match self.current_function().level.pop().unwrap() {
+            Level::Block { names } => {
+                *self
+                    .bytecode_generator
+                    .constant_table
+                    .get_mut(id)
+                    .as_lex_env_info_mut() = names;
+            }
+            _ => unreachable!(),
+        };
+
+        self.bytecode_generator.append_pop_env(iseq);
+
+        let end = iseq.len() as isize;
+        self.current_function()
+            .level
+            .pop()
+            .unwrap()
+            .replace_break_and_continue(&mut self.bytecode_generator, iseq, end, end);
+
+        Ok(())
+    }
+
+    fn visit_label(&mut self, node: &Node, iseq: &mut ByteCode) -> CodeGenResult {
+        let mut labels: Vec<String> = vec![];
+        let mut item = node;
+
+        while let NodeBase::Label(ref name, ref labelled_item) = item.base {
+            if labels.contains(name) || self.current_function().has_label(name) {
+                return Err(Error::new_general_error(
+                    format!("Label '{}' has already been declared", name),
+                    item.pos,
+                ));
+            }
+            labels.push(name.clone());
+            item = &*labelled_item;
+        }
+
+        // The labels of an iteration statement are given to its Level::Loop, so that 'continue'
+        // can target it.
+        if item.is_iteration_statement() {
+            self.current_function().label_set = labels;
+            return self.visit(item, iseq, false);
+        }
+
+        self.current_function()
+            .level
+            .push(Level::new_loop_level(LoopKind::Labelled, labels));
+
+        self.visit(item, iseq, false)?;
+
+        let end = iseq.len() as isize;
+        self.current_function()
+            .level
+            .pop()
+            .unwrap()
+            .replace_break_and_continue(&mut self.bytecode_generator, iseq, end, end);
+
+        Ok(())
+    }
+
+    pub fn visit_break(
+        &mut self,
+        node: &Node,
+        name: &Option<String>,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        let target = self
+            .current_function()
+            .find_jump_target(name, false)
+            .map_err(|msg| Error::new_general_error(msg, node.pos))?;
+
+        self.unwind_to(target, iseq);
+
+        let break_instr_pos = iseq.len();
+        self.bytecode_generator.append_jmp(0, iseq);
+
+        self.current_function().level[target]
+            .as_loop_mut()
+            .0
+            .push(break_instr_pos);
+
+        Ok(())
+    }
+
+    pub fn visit_continue(
+        &mut self,
+        node: &Node,
+        name: &Option<String>,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        let target = self
+            .current_function()
+            .find_jump_target(name, true)
+            .map_err(|msg| Error::new_general_error(msg, node.pos))?;
+
+        self.unwind_to(target, iseq);
+
+        let continue_instr_pos = iseq.len();
+        self.bytecode_generator.append_jmp(0, iseq);
+
+        self.current_function().level[target]
+            .as_loop_mut()
+            .1
+            .push(continue_instr_pos);
+
+        Ok(())
+    }
+
+    pub fn visit_try(
+        &mut self,
+        try: &Node,
+        catch: &Node,
+        param: &Node,
+        finally: &Node,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        // TODO: Refine code
+
+        let has_catch = catch.base != NodeBase::Nope;
+        let stack_depth = self.current_function().stack_depth();
+
+        // Try block
+        let (try_, try_to_finally, leave_try) = {
+            let try_start = iseq.len() as usize;
+
+            self.current_function()
+                .level
+                .push(Level::new_try_or_catch_level());
+
+            self.visit(try, iseq, false)?;
+
+            let try_ = self.current_function().level.pop().unwrap();
+
+            // The finally block always has a value on the stack, which is the exception or
+            // the return value if it is executed on the way.
+            self.bytecode_generator.append_push_undefined(iseq);
+            let try_to_finally = iseq.len() as usize;
+            self.bytecode_generator.append_jmp_sub(0, iseq);
+            self.bytecode_generator.append_pop(iseq);
+
+            let leave_try = iseq.len() as usize;
+            self.bytecode_generator.append_jmp_sub(0, iseq);
+
+            let try_end = iseq.len() as usize;
+            self.current_function().exception_table.push(Exception {
+                start: try_start,
+                end: try_end,
+                dst_kind: if has_catch {
+                    DestinationKind::Catch
+                } else {
+                    DestinationKind::Finally
+                },
+                stack_depth,
+            });
+
+            (try_, try_to_finally, leave_try)
+        };
+
+        // Catch block
+        let (catch_, catch_to_finally, leave_catch) = if has_catch {
+            let catch_start = iseq.len() as usize;
+
+            self.current_function()
+                .level
+                .push(Level::new_try_or_catch_level());
+            let env_id = self
+                .bytecode_generator
+                .constant_table
+                .add_lex_env_info(vec![]);
+            self.bytecode_generator.append_push_env(env_id as u32, iseq);
+            self.current_function().level.push(Level::Block {
+                names: param.bound_names(),
+            });
+            self.assign_stack_top_to(param, iseq)?;
+
+            self.visit(catch, iseq, false)?;
+
+            self.bytecode_generator.append_pop_env(iseq);
+
+            let names = self.current_function().level.pop().unwrap().as_block();
+            let catch_ = self.current_function().level.pop().unwrap();
+            *self
+                .bytecode_generator
+                .constant_table
+                .get_mut(env_id)
+                .as_lex_env_info_mut() = names;
+
+            self.bytecode_generator.append_push_undefined(iseq);
+            let catch_to_finally = iseq.len() as usize;
+            self.bytecode_generator.append_jmp_sub(0, iseq);
+            self.bytecode_generator.append_pop(iseq);
+
+            let leave_catch = iseq.len() as usize;
+            self.bytecode_generator.append_jmp_sub(0, iseq);
+
+            let catch_end = iseq.len() as usize;
+            self.current_function().exception_table.push(Exception {
+                start: catch_start,
+                end: catch_end,
+                dst_kind: DestinationKind::Finally,
+                stack_depth,
+            });
+
+            (catch_, catch_to_finally, leave_catch)
+        } else {
+            (Level::new_try_or_catch_level(), 0, 0)
+        };
+
+        // Finally block
+        let finally_start = iseq.len() as usize;
+
+        try_.set_jmp_to_finally(finally_start, &mut self.bytecode_generator, iseq);
+        catch_.set_jmp_to_finally(finally_start, &mut self.bytecode_generator, iseq);
+
+        self.current_function().level.push(Level::Finally);
+        self.visit(finally, iseq, false)?;
+
+        assert_eq!(self.current_function().level.pop().unwrap(), Level::Finally);
+        self.bytecode_generator.append_return_sub(iseq);
+
+        let finally_end = iseq.len() as usize;
+
+        self.bytecode_generator.replace_int32(
+            (finally_start - try_to_finally) as i32 - 5,
             &mut iseq[try_to_finally + 1..try_to_finally + 5],
         );
         self.bytecode_generator.replace_int32(
@@ -573,8 +1051,9 @@ impl<'a> CodeGenerator<'a> {
         name: &String,
         params: &FormalParameters,
         body: &Node,
+        kind: FunctionKind,
     ) -> CodeGenResult {
-        let func = self.visit_function(Some(name.clone()), params, body, true)?;
+        let func = self.visit_function(Some(name.clone()), params, body, kind, true, None)?;
         self.current_function().var_names.push(name.clone());
         self.current_function().func_decls.push(func);
         Ok(())
@@ -585,6 +1064,7 @@ impl<'a> CodeGenerator<'a> {
         name: &Option<String>,
         params: &FormalParameters,
         body: &Node,
+        kind: FunctionKind,
         arrow_function: bool,
         iseq: &mut ByteCode,
         use_value: bool,
@@ -593,7 +1073,7 @@ impl<'a> CodeGenerator<'a> {
             return Ok(());
         }
 
-        let func = self.visit_function(name.clone(), params, body, arrow_function)?;
+        let func = self.visit_function(name.clone(), params, body, kind, arrow_function, None)?;
         self.bytecode_generator.append_push_const(func, iseq);
         self.bytecode_generator.append_set_outer_env(iseq);
 
@@ -605,12 +1085,61 @@ impl<'a> CodeGenerator<'a> {
         name: Option<String>,
         params: &FormalParameters,
         body: &Node,
+        kind: FunctionKind,
         arrow_function: bool,
+        super_kind: Option<SuperKind>,
     ) -> Result<Value, Error> {
+        let class_constructor = match super_kind {
+            Some(SuperKind::BaseConstructor) | Some(SuperKind::DerivedConstructor) => true,
+            _ => false,
+        };
+        let derived_constructor = super_kind == Some(SuperKind::DerivedConstructor);
+        let super_kind = if arrow_function {
+            super_kind
+        } else {
+            self.current_function().super_kind
+        };
+
         self.function_stack.push(FunctionInfo::new(name));
+        self.current_function().super_kind = super_kind;
 
         let mut func_iseq = vec![];
 
+        // Arguments for binding patterns are passed through hidden parameters, and destructured
+        // before the body. Default values are also evaluated here, from left to right.
+        // TODO: Generators should evaluate them before they start.
+        let param_name = |i: usize, param: &FormalParameter| match param.pattern {
+            Some(_) => format!("%param{}", i),
+            None => param.name.clone(),
+        };
+        for (i, param) in params.iter().enumerate() {
+            if param.init.is_none() && param.pattern.is_none() {
+                continue;
+            }
+            let name = param_name(i, param);
+            self.bytecode_generator
+                .append_get_value(&name, &mut func_iseq);
+            if let Some(ref init) = param.init {
+                self.append_default_value(init, &mut func_iseq)?;
+            }
+            match param.pattern {
+                Some(ref pattern) => self.assign_stack_top_to(pattern, &mut func_iseq)?,
+                None => self
+                    .bytecode_generator
+                    .append_set_value(&name, &mut func_iseq),
+            }
+        }
+
+        // Closures in default values can't see the variables declared in the body.
+        // https://tc39.github.io/ecma262/#sec-functiondeclarationinstantiation
+        let simple_parameter_list = params
+            .iter()
+            .all(|param| !param.is_rest_param && param.init.is_none() && param.pattern.is_none());
+        if !simple_parameter_list {
+            self.bytecode_generator
+                .append_enter_function_body(&mut func_iseq);
+        }
+
         self.visit(body, &mut func_iseq, false)?;
 
         self.bytecode_generator
@@ -618,22 +1147,26 @@ impl<'a> CodeGenerator<'a> {
         self.bytecode_generator.append_return(&mut func_iseq);
 
         let params = params
-            .clone()
             .iter()
-            .map(
-                |FormalParameter {
-                     name,
-                     is_rest_param,
-                     ..
-                 }| value::FunctionParameter {
-                    name: name.clone(),
-                    rest_param: *is_rest_param,
+            .enumerate()
+            .map(|(i, param)| value::FunctionParameter {
+                name: param_name(i, param),
+                rest_param: param.is_rest_param,
+                has_initializer: param.init.is_some(),
+                bound_names: match param.pattern {
+                    Some(ref pattern) => pattern.bound_names(),
+                    None => vec![],
                 },
-            )
+            })
             .collect();
 
         let function_info = self.function_stack.pop().unwrap();
 
+        // Arrow functions refer to 'arguments' of the enclosing function.
+        if !arrow_function && function_info.uses_arguments {
+            self.current_function().uses_arguments = true;
+        }
+
         let id = get_unique_id();
 
         self.to_source_map.insert(id, function_info.to_source_pos);
@@ -648,7 +1181,14 @@ impl<'a> CodeGenerator<'a> {
                 var_names: function_info.var_names,
                 lex_names: function_info.lex_names,
                 func_decls: function_info.func_decls,
-                constructible: arrow_function,
+                constructible: arrow_function
+                    && kind == FunctionKind::Ordinary
+                    && (super_kind.is_none() || class_constructor),
+                class_constructor,
+                derived_constructor,
+                uses_arguments: arrow_function && function_info.uses_arguments,
+                simple_parameter_list,
+                kind,
                 this_mode: if arrow_function {
                     ThisMode::Global
                 } else {
@@ -661,36 +1201,186 @@ impl<'a> CodeGenerator<'a> {
         ))
     }
 
-    pub fn visit_var_decl(
+    fn visit_class(
         &mut self,
-        node: &Node,
-        name: &String,
-        init: &Option<Box<Node>>,
-        kind: &VarKind,
+        name: &Option<String>,
+        heritage: &Node,
+        constructor: &Node,
+        methods: &Vec<ClassElement>,
         iseq: &mut ByteCode,
+        use_value: bool,
     ) -> CodeGenResult {
-        fn let_decl(codegen: &mut CodeGenerator, node: &Node, name: String) -> CodeGenResult {
-            fn check_duplicate(
-                names: &mut Vec<String>,
-                name: String,
-                node: &Node,
-            ) -> CodeGenResult {
-                if names.iter().find(|declared| *declared == &name).is_some() {
-                    return Err(Error::new_general_error(
-                        format!("Identifier '{}' has already been declared", name),
-                        node.pos,
-                    ));
-                }
-                names.push(name);
-                Ok(())
-            }
-            let cur_func = codegen.current_function();
-            if let Some(ref mut block) = cur_func.get_last_block() {
-                return check_duplicate(block.as_block_mut(), name, node);
-            }
-            check_duplicate(&mut cur_func.lex_names, name, node)
+        // The class scope holds the class name binding, which is visible only in the class.
+        let mut names = vec![CLASS_BINDING.to_string()];
+        if let Some(name) = name {
+            names.push(name.clone());
         }
+        let env_id = self
+            .bytecode_generator
+            .constant_table
+            .add_lex_env_info(names.clone());
+        self.bytecode_generator.append_push_env(env_id as u32, iseq);
+        self.current_function().level.push(Level::Block { names });
+
+        let has_heritage = heritage.base != NodeBase::Nope;
+        if has_heritage {
+            self.visit(heritage, iseq, true)?;
+        }
+
+        self.visit_method(
+            constructor,
+            if has_heritage {
+                SuperKind::DerivedConstructor
+            } else {
+                SuperKind::BaseConstructor
+            },
+            iseq,
+        )?;
+
+        let mut method_infos = vec![];
+        for ClassElement {
+            is_static,
+            kind,
+            name,
+            func,
+        } in methods
+        {
+            self.visit_method(
+                func,
+                if *is_static {
+                    SuperKind::StaticMethod
+                } else {
+                    SuperKind::Method
+                },
+                iseq,
+            )?;
+            self.visit_property_name(name, iseq)?;
+            method_infos.push(ClassMethodInfo {
+                is_static: *is_static,
+                special: match kind {
+                    MethodDefinitionKind::Ordinary => None,
+                    MethodDefinitionKind::Get => Some(SpecialPropertyKind::Getter),
+                    MethodDefinitionKind::Set => Some(SpecialPropertyKind::Setter),
+                },
+            });
+        }
+
+        let id = self
+            .bytecode_generator
+            .constant_table
+            .add_class_info(has_heritage, method_infos);
+        self.bytecode_generator.append_create_class(id, iseq);
+
+        self.bytecode_generator.append_double(iseq);
+        self.bytecode_generator
+            .append_set_value(&CLASS_BINDING.to_string(), iseq);
+        if let Some(name) = name {
+            self.bytecode_generator.append_double(iseq);
+            self.bytecode_generator.append_set_value(name, iseq);
+        }
+
+        self.current_function().level.pop();
+        self.bytecode_generator.append_pop_env(iseq);
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    fn visit_method(
+        &mut self,
+        func: &Node,
+        super_kind: SuperKind,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        let (name, params, body, kind) = match func.base {
+            NodeBase::FunctionExpr(ref name, ref params, ref body, kind) => {
+                (name, params, body, kind)
+            }
+            _ => unreachable!(),
+        };
+        let func =
+            self.visit_function(name.clone(), params, &*body, kind, true, Some(super_kind))?;
+        self.bytecode_generator.append_push_const(func, iseq);
+        self.bytecode_generator.append_set_outer_env(iseq);
+        Ok(())
+    }
+
+    /// Push the object 'super' refers to, which is the \[\[Prototype\]\] of the home object.
+    /// https://tc39.github.io/ecma262/#sec-getsuperbase
+    fn visit_super(&mut self, node: &Node, iseq: &mut ByteCode) -> CodeGenResult {
+        let super_kind = match self.current_function().super_kind {
+            Some(super_kind) => super_kind,
+            None => {
+                return Err(Error::new_general_error(
+                    "'super' keyword unexpected here".to_string(),
+                    node.pos,
+                ))
+            }
+        };
+
+        if super_kind == SuperKind::ObjectMethod {
+            self.bytecode_generator
+                .append_get_value(&HOME_OBJECT_BINDING.to_string(), iseq);
+            self.bytecode_generator.append_get_super(iseq);
+            return Ok(());
+        }
+
+        self.bytecode_generator
+            .append_get_value(&CLASS_BINDING.to_string(), iseq);
+        if super_kind != SuperKind::StaticMethod {
+            self.bytecode_generator.append_push_const(
+                Value::string(self.memory_allocator, "prototype".to_string()),
+                iseq,
+            );
+            self.bytecode_generator.append_get_member(iseq);
+        }
+        self.bytecode_generator.append_get_super(iseq);
+
+        Ok(())
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-super-keyword-runtime-semantics-evaluation
+    fn visit_super_call(
+        &mut self,
+        node: &Node,
+        args: &Node,
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        if self.current_function().super_kind != Some(SuperKind::DerivedConstructor) {
+            return Err(Error::new_general_error(
+                "'super' keyword unexpected here".to_string(),
+                node.pos,
+            ));
+        }
+
+        self.visit(args, iseq, true)?;
+
+        // The parent class is the \[\[Prototype\]\] of the class.
+        self.bytecode_generator
+            .append_get_value(&CLASS_BINDING.to_string(), iseq);
+        self.bytecode_generator.append_get_super(iseq);
+        self.bytecode_generator.append_call_super(iseq);
+        self.bytecode_generator.append_bind_this(iseq);
 
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    pub fn visit_var_decl(
+        &mut self,
+        node: &Node,
+        name: &String,
+        init: &Option<Box<Node>>,
+        kind: &VarKind,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
         // let mut is_initialized = false;
 
         if let &Some(ref init) = init {
@@ -699,6 +1389,52 @@ impl<'a> CodeGenerator<'a> {
             // is_initialized = true;
         }
 
+        self.declare(node, name, kind)
+    }
+
+    pub fn visit_pattern_decl(
+        &mut self,
+        node: &Node,
+        pattern: &Node,
+        init: &Option<Box<Node>>,
+        kind: &VarKind,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        if let &Some(ref init) = init {
+            self.visit(&*init, iseq, true)?;
+            self.assign_stack_top_to(pattern, iseq)?;
+        }
+
+        for name in pattern.bound_names() {
+            self.declare(node, &name, kind)?;
+        }
+
+        Ok(())
+    }
+
+    fn declare(&mut self, node: &Node, name: &String, kind: &VarKind) -> CodeGenResult {
+        fn let_decl(codegen: &mut CodeGenerator, node: &Node, name: String) -> CodeGenResult {
+            fn check_duplicate(
+                names: &mut Vec<String>,
+                name: String,
+                node: &Node,
+            ) -> CodeGenResult {
+                if names.iter().find(|declared| *declared == &name).is_some() {
+                    return Err(Error::new_general_error(
+                        format!("Identifier '{}' has already been declared", name),
+                        node.pos,
+                    ));
+                }
+                names.push(name);
+                Ok(())
+            }
+            let cur_func = codegen.current_function();
+            if let Some(ref mut block) = cur_func.get_last_block() {
+                return check_duplicate(block.as_block_mut(), name, node);
+            }
+            check_duplicate(&mut cur_func.lex_names, name, node)
+        }
+
         match kind {
             VarKind::Var => {
                 self.current_function().var_names.push(name.clone());
@@ -725,7 +1461,7 @@ impl<'a> CodeGenerator<'a> {
         self.visit(parent, iseq, true)?;
         let property = Value::string(self.memory_allocator, member.clone());
         self.bytecode_generator.append_push_const(property, iseq);
-        self.bytecode_generator.append_get_member(iseq);
+        self.append_get_member_of(parent, iseq);
 
         Ok(())
     }
@@ -743,11 +1479,20 @@ impl<'a> CodeGenerator<'a> {
 
         self.visit(parent, iseq, true)?;
         self.visit(index, iseq, true)?;
-        self.bytecode_generator.append_get_member(iseq);
+        self.append_get_member_of(parent, iseq);
 
         Ok(())
     }
 
+    /// Properties of 'super' are looked up with the current 'this' as the receiver.
+    fn append_get_member_of(&mut self, parent: &Node, iseq: &mut ByteCode) {
+        if parent.base == NodeBase::Super {
+            self.bytecode_generator.append_get_super_member(iseq);
+        } else {
+            self.bytecode_generator.append_get_member(iseq);
+        }
+    }
+
     fn visit_unary_op(
         &mut self,
         expr: &Node,
@@ -755,6 +1500,10 @@ impl<'a> CodeGenerator<'a> {
         iseq: &mut ByteCode,
         use_value: bool,
     ) -> CodeGenResult {
+        if let &UnaryOp::Delete = op {
+            return self.visit_delete(expr, iseq, use_value);
+        }
+
         self.visit(expr, iseq, true)?;
 
         match op {
@@ -763,6 +1512,11 @@ impl<'a> CodeGenerator<'a> {
             &UnaryOp::Minus => self.bytecode_generator.append_neg(iseq),
             &UnaryOp::Not => self.bytecode_generator.append_lnot(iseq),
             &UnaryOp::BitwiseNot => self.bytecode_generator.append_not(iseq),
+            // https://tc39.github.io/ecma262/#sec-void-operator
+            &UnaryOp::Void => {
+                self.bytecode_generator.append_pop(iseq);
+                self.bytecode_generator.append_push_undefined(iseq);
+            }
             &UnaryOp::PrInc => {
                 self.bytecode_generator.append_push_int8(1, iseq);
                 self.bytecode_generator.append_add(iseq);
@@ -791,7 +1545,59 @@ impl<'a> CodeGenerator<'a> {
                 self.bytecode_generator.append_sub(iseq);
                 self.assign_stack_top_to(expr, iseq)?;
             }
-            _ => unimplemented!(),
+            &UnaryOp::Delete => unreachable!(),
+        }
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-delete-operator
+    fn visit_delete(&mut self, expr: &Node, iseq: &mut ByteCode, use_value: bool) -> CodeGenResult {
+        match expr.base {
+            NodeBase::Member(ref parent, ref property) => {
+                self.visit(&*parent, iseq, true)?;
+                let property = Value::string(self.memory_allocator, property.clone());
+                self.bytecode_generator.append_push_const(property, iseq);
+                self.bytecode_generator.append_delete_member(iseq);
+            }
+            NodeBase::Index(ref parent, ref index) => {
+                self.visit(&*parent, iseq, true)?;
+                self.visit(&*index, iseq, true)?;
+                self.bytecode_generator.append_delete_member(iseq);
+            }
+            NodeBase::Identifier(ref name) => {
+                self.bytecode_generator.append_delete_binding(name, iseq);
+            }
+            // The arguments object is bound in the function environment, so it isn't deletable
+            NodeBase::Arguments => {
+                self.current_function().uses_arguments = true;
+                self.bytecode_generator
+                    .append_delete_binding(&"arguments".to_string(), iseq);
+            }
+            // a?.b is deleted unless the chain is short-circuited, in which case it's true
+            NodeBase::OptionalChain(ref chain)
+                if match chain.base {
+                    NodeBase::Member(_, _) | NodeBase::Index(_, _) => true,
+                    _ => false,
+                } =>
+            {
+                self.current_function().optional_chains.push(OptionalChain {
+                    jmp_instr_pos: vec![],
+                    stack_depth: 0,
+                });
+                self.visit_delete(&*chain, iseq, true)?;
+                let chain = self.current_function().optional_chains.pop().unwrap();
+                self.append_optional_chain_end(chain, true, iseq);
+            }
+            // Not a reference
+            _ => {
+                self.visit(expr, iseq, false)?;
+                self.bytecode_generator.append_push_bool(true, iseq);
+            }
         }
 
         if !use_value {
@@ -809,20 +1615,11 @@ impl<'a> CodeGenerator<'a> {
         iseq: &mut ByteCode,
         use_value: bool,
     ) -> CodeGenResult {
-        if !use_value {
-            return Ok(());
-        }
-
         match op {
-            &BinOp::LAnd => {
+            &BinOp::LAnd | &BinOp::LOr | &BinOp::Nullish => {
                 self.visit(lhs, iseq, true)?;
 
-                self.bytecode_generator.append_double(iseq);
-
-                let lhs_cond_pos = iseq.len() as isize;
-                self.bytecode_generator.append_jmp_if_false(0, iseq);
-
-                self.bytecode_generator.append_pop(iseq);
+                let lhs_cond_pos = self.append_short_circuit(op, iseq);
 
                 self.visit(rhs, iseq, true)?;
 
@@ -831,90 +1628,563 @@ impl<'a> CodeGenerator<'a> {
                     (pos - lhs_cond_pos) as i32 - 5,
                     &mut iseq[lhs_cond_pos as usize + 1..lhs_cond_pos as usize + 5],
                 );
-
-                return Ok(());
             }
             // http://www.ecma-international.org/ecma-262/9.0/index.html#sec-comma-operator
             &BinOp::Comma => {
                 self.visit(lhs, iseq, false)?;
-                self.visit(rhs, iseq, true)?;
+                self.visit(rhs, iseq, use_value)?;
                 return Ok(());
             }
-            _ => {}
+            _ => {
+                self.visit(lhs, iseq, true)?;
+                self.visit(rhs, iseq, true)?;
+                self.append_binary_operator(op, iseq);
+            }
+        }
+
+        // Operators may have side effects such as calling functions or throwing exceptions even
+        // if their result is not used.
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    fn append_binary_operator(&mut self, op: &BinOp, iseq: &mut ByteCode) {
+        match op {
+            &BinOp::Add => self.bytecode_generator.append_add(iseq),
+            &BinOp::Sub => self.bytecode_generator.append_sub(iseq),
+            &BinOp::Mul => self.bytecode_generator.append_mul(iseq),
+            &BinOp::Div => self.bytecode_generator.append_div(iseq),
+            &BinOp::Rem => self.bytecode_generator.append_rem(iseq),
+            &BinOp::Exp => self.bytecode_generator.append_exp(iseq),
+            &BinOp::Eq => self.bytecode_generator.append_eq(iseq),
+            &BinOp::Ne => self.bytecode_generator.append_ne(iseq),
+            &BinOp::SEq => self.bytecode_generator.append_seq(iseq),
+            &BinOp::SNe => self.bytecode_generator.append_sne(iseq),
+            &BinOp::And => self.bytecode_generator.append_and(iseq),
+            &BinOp::Or => self.bytecode_generator.append_or(iseq),
+            &BinOp::Xor => self.bytecode_generator.append_xor(iseq),
+            &BinOp::Lt => self.bytecode_generator.append_lt(iseq),
+            &BinOp::Gt => self.bytecode_generator.append_gt(iseq),
+            &BinOp::Le => self.bytecode_generator.append_le(iseq),
+            &BinOp::Ge => self.bytecode_generator.append_ge(iseq),
+            &BinOp::InstanceOf => self.bytecode_generator.append_instance_of(iseq),
+            &BinOp::In => self.bytecode_generator.append_in(iseq),
+            &BinOp::Shl => self.bytecode_generator.append_shl(iseq),
+            &BinOp::Shr => self.bytecode_generator.append_shr(iseq),
+            &BinOp::ZFShr => self.bytecode_generator.append_zfshr(iseq),
+            // Logical and comma operators are not simple operations on two values
+            _ => unreachable!(),
+        }
+    }
+
+    /// Append the jump taken when the value on the stack top alone decides the result of the
+    /// logical operator, leaving the value. Otherwise the value is popped. Return the position of
+    /// the jump to be replaced.
+    fn append_short_circuit(&mut self, op: &BinOp, iseq: &mut ByteCode) -> isize {
+        self.bytecode_generator.append_double(iseq);
+        match op {
+            &BinOp::LAnd => {}
+            &BinOp::LOr => self.bytecode_generator.append_lnot(iseq),
+            &BinOp::Nullish => self.bytecode_generator.append_is_nullish(iseq),
+            _ => unreachable!(),
+        }
+        let jmp_pos = iseq.len() as isize;
+        self.bytecode_generator.append_jmp_if_false(0, iseq);
+        self.bytecode_generator.append_pop(iseq);
+        jmp_pos
+    }
+
+    fn visit_assign(
+        &mut self,
+        dst: &Node,
+        src: &Node,
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        self.visit(src, iseq, true)?;
+
+        if use_value {
+            self.bytecode_generator.append_double(iseq);
+        }
+
+        self.assign_stack_top_to(dst, iseq)?;
+
+        Ok(())
+    }
+
+    /// The reference of `dst` is evaluated only once. The logical operators don't evaluate `src`
+    /// nor assign if the current value decides the result.
+    /// https://tc39.github.io/ecma262/#sec-assignment-operators-runtime-semantics-evaluation
+    fn visit_compound_assign(
+        &mut self,
+        dst: &Node,
+        src: &Node,
+        op: &BinOp,
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        // Push the parent and the property if any, followed by the current value
+        let is_property = match dst.base {
+            NodeBase::Identifier(ref name) => {
+                self.bytecode_generator.append_get_value(name, iseq);
+                false
+            }
+            NodeBase::Member(ref parent, ref property) => {
+                self.visit(&*parent, iseq, true)?;
+                let property = Value::string(self.memory_allocator, property.clone());
+                self.bytecode_generator.append_push_const(property, iseq);
+                self.bytecode_generator.append_double2(iseq);
+                self.append_get_member_of(&*parent, iseq);
+                true
+            }
+            NodeBase::Index(ref parent, ref index) => {
+                self.visit(&*parent, iseq, true)?;
+                self.visit(&*index, iseq, true)?;
+                self.bytecode_generator.append_double2(iseq);
+                self.append_get_member_of(&*parent, iseq);
+                true
+            }
+            _ => {
+                return Err(Error::new_general_error(
+                    "Invalid left-hand side in assignment".to_string(),
+                    dst.pos,
+                ))
+            }
+        };
+
+        let short_circuit = match op {
+            &BinOp::LAnd | &BinOp::LOr | &BinOp::Nullish => {
+                Some(self.append_short_circuit(op, iseq))
+            }
+            _ => None,
+        };
+
+        self.visit(src, iseq, true)?;
+        if short_circuit.is_none() {
+            self.append_binary_operator(op, iseq);
+        }
+
+        self.bytecode_generator.append_double(iseq);
+        if is_property {
+            // [parent, property, val, val] -> [val, val, parent, property]
+            self.bytecode_generator.append_rotate(3, iseq);
+            self.bytecode_generator.append_rotate(2, iseq);
+            self.bytecode_generator.append_set_member(iseq);
+        } else if let NodeBase::Identifier(ref name) = dst.base {
+            self.bytecode_generator.append_set_value(name, iseq);
+        }
+
+        if let Some(jmp_pos) = short_circuit {
+            let end_jmp_pos = iseq.len() as isize;
+            if is_property {
+                self.bytecode_generator.append_jmp(0, iseq);
+            }
+
+            let pos = iseq.len() as isize;
+            self.bytecode_generator.replace_int32(
+                (pos - jmp_pos) as i32 - 5,
+                &mut iseq[jmp_pos as usize + 1..jmp_pos as usize + 5],
+            );
+
+            if is_property {
+                // [parent, property, val] -> [val]
+                self.bytecode_generator.append_rotate(2, iseq);
+                self.bytecode_generator.append_pop(iseq);
+                self.bytecode_generator.append_pop(iseq);
+                let pos = iseq.len() as isize;
+                self.bytecode_generator.replace_int32(
+                    (pos - end_jmp_pos) as i32 - 5,
+                    &mut iseq[end_jmp_pos as usize + 1..end_jmp_pos as usize + 5],
+                );
+            }
+        }
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    fn visit_call(
+        &mut self,
+        callee: &Node,
+        args: &Vec<Node>,
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        if has_optional(callee) {
+            return self.visit_call_in_optional_chain(callee, args, iseq, use_value);
+        }
+
+        // Arguments with spread elements are passed as an array.
+        let spread = has_spread(args);
+        if spread {
+            self.visit_array_literal(args, iseq)?;
+        } else {
+            for arg in args.iter().rev() {
+                self.visit(arg, iseq, true)?
+            }
+        }
+
+        let argc = if spread { 1 } else { args.len() };
+        self.add_optional_chain_stack_depth(argc as isize);
+        self.visit_callee_and_call(callee, args.len(), spread, iseq)?;
+        self.add_optional_chain_stack_depth(-(argc as isize));
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate the callee, and call it with the arguments on the stack. Methods are called with
+    /// their parent objects as 'this'.
+    fn visit_callee_and_call(
+        &mut self,
+        callee: &Node,
+        argc: usize,
+        spread: bool,
+        iseq: &mut ByteCode,
+    ) -> CodeGenResult {
+        match callee.base {
+            NodeBase::Member(ref parent, _) | NodeBase::Index(ref parent, _)
+                if parent.base == NodeBase::Super =>
+            {
+                // Methods of the parent class are called with the current 'this'.
+                self.visit(callee, iseq, true)?;
+                self.append_call(argc, spread, iseq);
+            }
+            NodeBase::Member(ref parent, ref property_name) => {
+                self.bytecode_generator.append_push_const(
+                    Value::string(self.memory_allocator, property_name.clone()),
+                    iseq,
+                );
+                self.add_optional_chain_stack_depth(1);
+                self.visit(&*parent, iseq, true)?;
+                self.add_optional_chain_stack_depth(-1);
+                self.append_call_method(argc, spread, iseq);
+            }
+            NodeBase::Index(ref parent, ref property) => {
+                self.visit(&*property, iseq, true)?;
+                self.add_optional_chain_stack_depth(1);
+                self.visit(&*parent, iseq, true)?;
+                self.add_optional_chain_stack_depth(-1);
+                self.append_call_method(argc, spread, iseq);
+            }
+            _ => {
+                self.visit(callee, iseq, true)?;
+                self.append_call(argc, spread, iseq);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Unlike other calls, the callee is evaluated before the arguments, which are moved under it,
+    /// so that the arguments are not evaluated if the chain is short-circuited. An optional callee
+    /// is called if it's neither null nor undefined.
+    fn visit_call_in_optional_chain(
+        &mut self,
+        callee: &Node,
+        args: &Vec<Node>,
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        let (callee, optional) = match callee.base {
+            NodeBase::Optional(ref callee) => (&**callee, true),
+            _ => (callee, false),
+        };
+        let is_method = match callee.base {
+            NodeBase::Member(ref parent, _) | NodeBase::Index(ref parent, _) => {
+                parent.base != NodeBase::Super
+            }
+            _ => false,
+        };
+
+        if is_method {
+            // a.b?.(), which is called with 'a' as 'this'
+            let parent = match callee.base {
+                NodeBase::Member(ref parent, ref property_name) => {
+                    self.bytecode_generator.append_push_const(
+                        Value::string(self.memory_allocator, property_name.clone()),
+                        iseq,
+                    );
+                    parent
+                }
+                NodeBase::Index(ref parent, ref property) => {
+                    self.visit(&*property, iseq, true)?;
+                    parent
+                }
+                _ => unreachable!(),
+            };
+            self.add_optional_chain_stack_depth(1);
+            self.visit(&*parent, iseq, true)?;
+            self.add_optional_chain_stack_depth(-1);
+            if optional {
+                // [property, parent] -> [property, parent, method]
+                self.bytecode_generator.append_double2(iseq);
+                self.bytecode_generator.append_rotate(1, iseq);
+                self.bytecode_generator.append_get_member(iseq);
+                self.bytecode_generator.append_is_nullish(iseq);
+                self.append_jmp_out_of_optional_chain(2, iseq);
+            }
+        } else {
+            self.visit(callee, iseq, true)?;
+            if optional {
+                self.bytecode_generator.append_double(iseq);
+                self.bytecode_generator.append_is_nullish(iseq);
+                self.append_jmp_out_of_optional_chain(1, iseq);
+            }
+        }
+
+        let callee_len = if is_method { 2 } else { 1 };
+        let spread = has_spread(args);
+        if spread {
+            self.visit_array_literal(args, iseq)?;
+            self.bytecode_generator.append_rotate(callee_len, iseq);
+        } else {
+            for arg in args.iter().rev() {
+                self.visit(arg, iseq, true)?;
+                self.bytecode_generator.append_rotate(callee_len, iseq);
+            }
+        }
+
+        if is_method {
+            self.append_call_method(args.len(), spread, iseq);
+        } else {
+            self.append_call(args.len(), spread, iseq);
+        }
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// Expressions before '?.' jump to the end of the chain if they are null or undefined, and the
+    /// chain evaluates to undefined.
+    /// https://tc39.github.io/ecma262/#sec-optional-chaining-evaluation
+    fn visit_optional_chain(
+        &mut self,
+        expr: &Node,
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        self.current_function().optional_chains.push(OptionalChain {
+            jmp_instr_pos: vec![],
+            stack_depth: 0,
+        });
+        self.visit(expr, iseq, true)?;
+        let chain = self.current_function().optional_chains.pop().unwrap();
+        self.append_optional_chain_end(chain, false, iseq);
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// Append the end of `chain`, where its jumps land and push undefined, or true if the chain
+    /// is the operand of delete.
+    fn append_optional_chain_end(
+        &mut self,
+        chain: OptionalChain,
+        delete: bool,
+        iseq: &mut ByteCode,
+    ) {
+        let end_jmp_pos = iseq.len() as isize;
+        self.bytecode_generator.append_jmp(0, iseq);
+
+        let pos = iseq.len() as isize;
+        for jmp_pos in chain.jmp_instr_pos {
+            let jmp_pos = jmp_pos as isize;
+            self.bytecode_generator.replace_int32(
+                (pos - jmp_pos) as i32 - 5,
+                &mut iseq[jmp_pos as usize + 1..jmp_pos as usize + 5],
+            );
+        }
+        if delete {
+            self.bytecode_generator.append_push_bool(true, iseq);
+        } else {
+            self.bytecode_generator.append_push_undefined(iseq);
+        }
+
+        let pos = iseq.len() as isize;
+        self.bytecode_generator.replace_int32(
+            (pos - end_jmp_pos) as i32 - 5,
+            &mut iseq[end_jmp_pos as usize + 1..end_jmp_pos as usize + 5],
+        );
+    }
+
+    /// Append the jump to the end of the innermost optional chain, which is taken if the boolean
+    /// on the stack top is true. `pop` values on the stack besides those counted in the chain are
+    /// popped before the jump.
+    fn append_jmp_out_of_optional_chain(&mut self, pop: usize, iseq: &mut ByteCode) {
+        let skip_pos = iseq.len() as isize;
+        self.bytecode_generator.append_jmp_if_false(0, iseq);
+
+        let stack_depth = self
+            .current_function()
+            .optional_chains
+            .last()
+            .unwrap()
+            .stack_depth;
+        for _ in 0..stack_depth + pop {
+            self.bytecode_generator.append_pop(iseq);
+        }
+        let jmp_pos = iseq.len();
+        self.bytecode_generator.append_jmp(0, iseq);
+        self.current_function()
+            .optional_chains
+            .last_mut()
+            .unwrap()
+            .jmp_instr_pos
+            .push(jmp_pos);
+
+        let pos = iseq.len() as isize;
+        self.bytecode_generator.replace_int32(
+            (pos - skip_pos) as i32 - 5,
+            &mut iseq[skip_pos as usize + 1..skip_pos as usize + 5],
+        );
+    }
+
+    /// Count the values pushed on the stack in the innermost optional chain if any.
+    fn add_optional_chain_stack_depth(&mut self, n: isize) {
+        if let Some(chain) = self.current_function().optional_chains.last_mut() {
+            chain.stack_depth = (chain.stack_depth as isize + n) as usize;
+        }
+    }
+
+    /// Template literals are lowered to string concatenation.
+    fn visit_template_literal(
+        &mut self,
+        cooked_strings: &Vec<Option<Vec<u16>>>,
+        substitutions: &Vec<Node>,
+        iseq: &mut ByteCode,
+        use_value: bool,
+    ) -> CodeGenResult {
+        // Only tagged templates may have invalid escape sequences, which the parser checks.
+        let cooked_strings = cooked_strings
+            .iter()
+            .map(|string| string.as_ref().unwrap())
+            .collect::<Vec<&Vec<u16>>>();
+
+        let head = JSString::new(cooked_strings[0].clone());
+        self.bytecode_generator
+            .append_push_const(Value::js_string(self.memory_allocator, head), iseq);
+        for (substitution, string) in substitutions.iter().zip(&cooked_strings[1..]) {
+            self.visit(substitution, iseq, true)?;
+            self.bytecode_generator.append_add(iseq);
+            if string.len() > 0 {
+                let string = JSString::new(string.to_vec());
+                self.bytecode_generator
+                    .append_push_const(Value::js_string(self.memory_allocator, string), iseq);
+                self.bytecode_generator.append_add(iseq);
+            }
         }
 
-        self.visit(lhs, iseq, true)?;
-        self.visit(rhs, iseq, true)?;
-
-        match op {
-            &BinOp::Add => self.bytecode_generator.append_add(iseq),
-            &BinOp::Sub => self.bytecode_generator.append_sub(iseq),
-            &BinOp::Mul => self.bytecode_generator.append_mul(iseq),
-            &BinOp::Div => self.bytecode_generator.append_div(iseq),
-            &BinOp::Rem => self.bytecode_generator.append_rem(iseq),
-            &BinOp::Eq => self.bytecode_generator.append_eq(iseq),
-            &BinOp::Ne => self.bytecode_generator.append_ne(iseq),
-            &BinOp::SEq => self.bytecode_generator.append_seq(iseq),
-            &BinOp::SNe => self.bytecode_generator.append_sne(iseq),
-            &BinOp::And => self.bytecode_generator.append_and(iseq),
-            &BinOp::Or => self.bytecode_generator.append_or(iseq),
-            &BinOp::Xor => self.bytecode_generator.append_xor(iseq),
-            &BinOp::Lt => self.bytecode_generator.append_lt(iseq),
-            &BinOp::Gt => self.bytecode_generator.append_gt(iseq),
-            &BinOp::Le => self.bytecode_generator.append_le(iseq),
-            &BinOp::Ge => self.bytecode_generator.append_ge(iseq),
-            &BinOp::Shl => self.bytecode_generator.append_shl(iseq),
-            &BinOp::Shr => self.bytecode_generator.append_shr(iseq),
-            &BinOp::ZFShr => self.bytecode_generator.append_zfshr(iseq),
-            _ => unimplemented!(),
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
         }
 
         Ok(())
     }
 
-    fn visit_assign(
+    /// The tag is called with the template object followed by the substitutions.
+    fn visit_tagged_template(
         &mut self,
-        dst: &Node,
-        src: &Node,
+        tag: &Node,
+        template: &Node,
         iseq: &mut ByteCode,
         use_value: bool,
     ) -> CodeGenResult {
-        self.visit(src, iseq, true)?;
+        let (cooked_strings, raw_strings, substitutions) = match template.base {
+            NodeBase::Template(ref cooked_strings, ref raw_strings, ref substitutions) => {
+                (cooked_strings, raw_strings, substitutions)
+            }
+            _ => unreachable!(),
+        };
 
-        if use_value {
-            self.bytecode_generator.append_double(iseq);
+        for substitution in substitutions.iter().rev() {
+            self.visit(substitution, iseq, true)?
         }
+        let template_object = self.create_template_object(cooked_strings, raw_strings);
+        self.bytecode_generator
+            .append_push_const(template_object, iseq);
 
-        self.assign_stack_top_to(dst, iseq)?;
+        self.visit_callee_and_call(tag, substitutions.len() + 1, false, iseq)?;
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
 
         Ok(())
     }
 
-    fn visit_call(
+    fn visit_throw(&mut self, val: &Node, iseq: &mut ByteCode) -> CodeGenResult {
+        self.visit(val, iseq, true)?;
+        self.throw_stack_top(iseq);
+        Ok(())
+    }
+
+    fn visit_return(&mut self, val: &Option<Box<Node>>, iseq: &mut ByteCode) -> CodeGenResult {
+        if let Some(val) = val {
+            self.visit(val, iseq, true)?
+        } else {
+            self.bytecode_generator.append_push_undefined(iseq);
+        }
+        self.return_stack_top(iseq);
+        Ok(())
+    }
+
+    fn visit_yield(
         &mut self,
-        callee: &Node,
-        args: &Vec<Node>,
+        val: &Option<Box<Node>>,
+        delegate: bool,
         iseq: &mut ByteCode,
         use_value: bool,
     ) -> CodeGenResult {
-        for arg in args.iter().rev() {
-            self.visit(arg, iseq, true)?
+        if let Some(val) = val {
+            self.visit(val, iseq, true)?
+        } else {
+            self.bytecode_generator.append_push_undefined(iseq);
         }
 
-        match callee.base {
-            NodeBase::Member(ref parent, ref property_name) => {
-                self.bytecode_generator.append_push_const(
-                    Value::string(self.memory_allocator, property_name.clone()),
-                    iseq,
-                );
-                self.visit(&*parent, iseq, true)?;
-                self.bytecode_generator
-                    .append_call_method(args.len() as u32, iseq);
-            }
-            _ => {
-                self.visit(callee, iseq, true)?;
-                self.bytecode_generator.append_call(args.len() as u32, iseq);
-            }
+        if delegate {
+            // 'yield*' passes all the resumptions to the inner iterator until it is done.
+            self.bytecode_generator.append_get_iterator(iseq);
+            self.bytecode_generator.append_push_undefined(iseq);
+            self.bytecode_generator
+                .append_push_int8(ResumeKind::Next as i8, iseq);
+            let loop_start = iseq.len();
+            self.bytecode_generator.append_yield_delegate(0, iseq);
+            self.bytecode_generator.append_yield(iseq);
+            let loop_end = iseq.len();
+            self.bytecode_generator
+                .append_jmp(loop_start as i32 - loop_end as i32 - 5, iseq);
+            let done = iseq.len();
+            self.bytecode_generator.replace_int32(
+                (done - loop_start) as i32 - 5,
+                &mut iseq[loop_start + 1..loop_start + 5],
+            );
+            // The inner iterator was done by 'return' resumption.
+            let jmp_instr_pos = iseq.len();
+            self.bytecode_generator.append_jmp_if_false(0, iseq);
+            self.return_stack_top(iseq);
+            let normal = iseq.len();
+            self.bytecode_generator.replace_int32(
+                (normal - jmp_instr_pos) as i32 - 5,
+                &mut iseq[jmp_instr_pos + 1..jmp_instr_pos + 5],
+            );
+        } else {
+            self.suspend(iseq);
         }
 
         if !use_value {
@@ -924,9 +2194,53 @@ impl<'a> CodeGenerator<'a> {
         Ok(())
     }
 
-    fn visit_throw(&mut self, val: &Node, iseq: &mut ByteCode) -> CodeGenResult {
+    /// The async function is suspended by 'await' in the same way as generators by 'yield', and
+    /// resumed when the awaited promise is settled.
+    fn visit_await(&mut self, val: &Node, iseq: &mut ByteCode, use_value: bool) -> CodeGenResult {
         self.visit(val, iseq, true)?;
+        self.suspend(iseq);
+
+        if !use_value {
+            self.bytecode_generator.append_pop(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// Suspend the function with the value on the stack top. The function is resumed with the
+    /// sent value and the ResumeKind on the stack. Resumptions by 'throw' and 'return' are
+    /// performed at the point of suspension so that catch and finally blocks are executed.
+    fn suspend(&mut self, iseq: &mut ByteCode) {
+        self.bytecode_generator.append_yield(iseq);
+
+        self.bytecode_generator.append_double(iseq);
+        let next_jmp_instr_pos = iseq.len();
+        self.bytecode_generator.append_jmp_if_false(0, iseq);
+
+        self.bytecode_generator
+            .append_push_int8(ResumeKind::Throw as i8, iseq);
+        self.bytecode_generator.append_seq(iseq);
+        let return_jmp_instr_pos = iseq.len();
+        self.bytecode_generator.append_jmp_if_false(0, iseq);
+        self.throw_stack_top(iseq);
+
+        let return_pos = iseq.len();
+        self.return_stack_top(iseq);
 
+        let next_pos = iseq.len();
+        self.bytecode_generator.append_pop(iseq);
+
+        self.bytecode_generator.replace_int32(
+            (next_pos - next_jmp_instr_pos) as i32 - 5,
+            &mut iseq[next_jmp_instr_pos + 1..next_jmp_instr_pos + 5],
+        );
+        self.bytecode_generator.replace_int32(
+            (return_pos - return_jmp_instr_pos) as i32 - 5,
+            &mut iseq[return_jmp_instr_pos + 1..return_jmp_instr_pos + 5],
+        );
+    }
+
+    fn throw_stack_top(&mut self, iseq: &mut ByteCode) {
         if self.current_function().in_try_or_catch() {
             self.unwind_try_or_catch(iseq);
         } else if self.current_function().in_finally() {
@@ -934,37 +2248,72 @@ impl<'a> CodeGenerator<'a> {
         }
 
         self.bytecode_generator.append_throw(iseq);
-        Ok(())
     }
 
-    fn visit_return(&mut self, val: &Option<Box<Node>>, iseq: &mut ByteCode) -> CodeGenResult {
-        if let Some(val) = val {
-            self.visit(val, iseq, true)?
-        } else {
-            self.bytecode_generator.append_push_undefined(iseq);
-        }
+    /// Leave the function with the value on the stack top. On the way, iterators of for-of
+    /// statements are closed and finally blocks are executed from the innermost one.
+    fn return_stack_top(&mut self, iseq: &mut ByteCode) {
+        let levels = self.current_function().level.clone();
+        let outermost = levels.iter().position(|level| match level {
+            &Level::Loop {
+                kind: LoopKind::ForOf,
+                ..
+            }
+            | &Level::TryOrCatch { .. } => true,
+            _ => false,
+        });
 
-        if self.current_function().in_try_or_catch() {
-            self.current_function()
-                .get_last_try_or_catch()
-                .as_try_or_catch_mut()
-                .push(iseq.len() as usize);
-            self.bytecode_generator.append_return_try(iseq);
-        } else {
-            self.bytecode_generator.append_return(iseq);
+        if let Some(outermost) = outermost {
+            for (i, level) in levels.iter().enumerate().skip(outermost).rev() {
+                match level {
+                    // Finally blocks see the environments of the try statements.
+                    &Level::Block { .. } => self.bytecode_generator.append_pop_env(iseq),
+                    // The values kept on the stack are under the return value, so swap each of
+                    // them with the return value before discarding it.
+                    &Level::Loop {
+                        kind: LoopKind::ForIn,
+                        ..
+                    }
+                    | &Level::Finally => {
+                        self.bytecode_generator.append_rotate(1, iseq);
+                        self.bytecode_generator.append_pop(iseq);
+                    }
+                    &Level::Loop {
+                        kind: LoopKind::ForOf,
+                        ..
+                    } => {
+                        self.bytecode_generator.append_rotate(1, iseq);
+                        self.bytecode_generator.append_iter_close(iseq);
+                    }
+                    // The return value is the value of the finally block on the stack.
+                    &Level::TryOrCatch { .. } => {
+                        let instr_pos = iseq.len();
+                        self.bytecode_generator.append_jmp_sub(0, iseq);
+                        self.current_function().level[i]
+                            .as_try_or_catch_mut()
+                            .push(instr_pos);
+                    }
+                    _ => {}
+                }
+            }
         }
 
-        Ok(())
+        self.bytecode_generator.append_return(iseq);
     }
 
     fn visit_new(&mut self, expr: &Node, iseq: &mut ByteCode, use_value: bool) -> CodeGenResult {
         let (callee, args) = match expr.base {
             NodeBase::Call(ref callee, ref args) => (&*callee, args),
-            _ => unimplemented!(),
+            _ => unreachable!(),
         };
 
-        for arg in args.iter().rev() {
-            self.visit(arg, iseq, true)?
+        let spread = has_spread(args);
+        if spread {
+            self.visit_array_literal(args, iseq)?;
+        } else {
+            for arg in args.iter().rev() {
+                self.visit(arg, iseq, true)?
+            }
         }
 
         match callee.base {
@@ -979,7 +2328,11 @@ impl<'a> CodeGenerator<'a> {
             }
         }
 
-        self.bytecode_generator.append_construct(args.len(), iseq);
+        if spread {
+            self.bytecode_generator.append_construct_spread(iseq);
+        } else {
+            self.bytecode_generator.append_construct(args.len(), iseq);
+        }
 
         if !use_value {
             self.bytecode_generator.append_pop(iseq);
@@ -988,29 +2341,52 @@ impl<'a> CodeGenerator<'a> {
         Ok(())
     }
 
+    /// Push the key and the value of each property, and then create the object from them.
     fn visit_object_literal(
         &mut self,
         properties: &Vec<PropertyDefinition>,
         iseq: &mut ByteCode,
     ) -> CodeGenResult {
+        // Methods are closures over the scope which holds their home object.
+        let has_method = properties.iter().any(|property| match property {
+            PropertyDefinition::MethodDefinition(_, _, _) => true,
+            _ => false,
+        });
+        if has_method {
+            let names = vec![HOME_OBJECT_BINDING.to_string()];
+            let env_id = self
+                .bytecode_generator
+                .constant_table
+                .add_lex_env_info(names.clone());
+            self.bytecode_generator.append_push_env(env_id as u32, iseq);
+            self.current_function().level.push(Level::Block { names });
+        }
+
         let mut special_properties = SpecialProperties::default();
         let len = properties.len();
 
         for (i, property) in properties.iter().enumerate() {
             match property {
                 PropertyDefinition::IdentifierReference(name) => {
-                    self.bytecode_generator.append_get_value(name, iseq);
                     self.bytecode_generator.append_push_const(
                         Value::string(self.memory_allocator, name.clone()),
                         iseq,
                     );
+                    self.bytecode_generator.append_get_value(name, iseq);
                 }
                 PropertyDefinition::Property(name, node) => {
+                    self.visit_property_name(name, iseq)?;
+                    self.visit(&node, iseq, true)?;
+                }
+                PropertyDefinition::Spread(node) => {
+                    special_properties.insert(len - i - 1, SpecialPropertyKind::Spread);
+                    self.bytecode_generator.append_push_undefined(iseq);
+                    self.visit(&node, iseq, true)?;
+                }
+                PropertyDefinition::Proto(node) => {
+                    special_properties.insert(len - i - 1, SpecialPropertyKind::Proto);
+                    self.bytecode_generator.append_push_undefined(iseq);
                     self.visit(&node, iseq, true)?;
-                    self.bytecode_generator.append_push_const(
-                        Value::string(self.memory_allocator, name.clone()),
-                        iseq,
-                    );
                 }
                 PropertyDefinition::MethodDefinition(kind, name, node) => {
                     match kind {
@@ -1022,11 +2398,8 @@ impl<'a> CodeGenerator<'a> {
                             special_properties.insert(len - i - 1, SpecialPropertyKind::Getter);
                         }
                     };
-                    self.visit(&node, iseq, true)?;
-                    self.bytecode_generator.append_push_const(
-                        Value::string(self.memory_allocator, name.clone()),
-                        iseq,
-                    );
+                    self.visit_property_name(name, iseq)?;
+                    self.visit_method(&node, SuperKind::ObjectMethod, iseq)?;
                 }
             }
         }
@@ -1038,10 +2411,50 @@ impl<'a> CodeGenerator<'a> {
 
         self.bytecode_generator.append_create_object(id, iseq);
 
+        if has_method {
+            self.bytecode_generator.append_double(iseq);
+            self.bytecode_generator
+                .append_set_value(&HOME_OBJECT_BINDING.to_string(), iseq);
+            self.current_function().level.pop();
+            self.bytecode_generator.append_pop_env(iseq);
+        }
+
+        Ok(())
+    }
+
+    /// Push the property key. Computed keys are converted to property keys when the object is
+    /// created.
+    fn visit_property_name(&mut self, name: &PropertyName, iseq: &mut ByteCode) -> CodeGenResult {
+        match name {
+            PropertyName::Literal(name) => {
+                self.bytecode_generator
+                    .append_push_const(Value::string_from_key(self.memory_allocator, name), iseq);
+            }
+            PropertyName::Computed(key) => self.visit(key, iseq, true)?,
+        }
         Ok(())
     }
 
     fn visit_array_literal(&mut self, elems: &Vec<Node>, iseq: &mut ByteCode) -> CodeGenResult {
+        if has_spread(elems) {
+            // The length is unknown until spread elements are iterated.
+            self.bytecode_generator.append_create_array(0, iseq);
+            for elem in elems {
+                match elem.base {
+                    NodeBase::Spread(ref iterable) => {
+                        self.visit(&*iterable, iseq, true)?;
+                        self.bytecode_generator.append_get_iterator(iseq);
+                        self.bytecode_generator.append_array_spread(iseq);
+                    }
+                    _ => {
+                        self.visit(elem, iseq, true)?;
+                        self.bytecode_generator.append_array_push(iseq);
+                    }
+                }
+            }
+            return Ok(());
+        }
+
         for elem in elems.iter().rev() {
             self.visit(elem, iseq, true)?;
         }
@@ -1070,12 +2483,158 @@ impl<'a> CodeGenerator<'a> {
                 self.visit(&*index, iseq, true)?;
                 self.bytecode_generator.append_set_member(iseq);
             }
-            _ => unimplemented!(),
+            // Element of patterns with the default value
+            NodeBase::Assign(ref target, ref default) => {
+                self.append_default_value(&*default, iseq)?;
+                self.assign_stack_top_to(&*target, iseq)?;
+            }
+            NodeBase::ObjectPattern(ref properties, ref rest) => {
+                // https://tc39.github.io/ecma262/#sec-runtime-semantics-bindinginitialization
+                self.bytecode_generator
+                    .append_require_object_coercible(iseq);
+                for PropertyPattern { key, value } in properties {
+                    self.bytecode_generator.append_double(iseq);
+                    self.visit_property_name(key, iseq)?;
+                    if rest.is_some() {
+                        // Keys are kept under the object to be excluded from the rest, since
+                        // computed keys can't be evaluated again.
+                        self.bytecode_generator.append_double(iseq);
+                        self.bytecode_generator.append_rotate(3, iseq);
+                    }
+                    self.bytecode_generator.append_get_member(iseq);
+                    self.assign_stack_top_to(value, iseq)?;
+                }
+                match rest {
+                    Some(rest) => {
+                        self.bytecode_generator
+                            .append_object_rest(properties.len(), iseq);
+                        self.assign_stack_top_to(&*rest, iseq)?;
+                    }
+                    None => self.bytecode_generator.append_pop(iseq),
+                }
+            }
+            NodeBase::ArrayPattern(ref elems, ref rest) => {
+                self.bytecode_generator.append_get_iterator(iseq);
+                for elem in elems {
+                    self.bytecode_generator.append_iter_step(iseq);
+                    if elem.base == NodeBase::Nope {
+                        self.bytecode_generator.append_pop(iseq);
+                    } else {
+                        self.assign_stack_top_to(elem, iseq)?;
+                    }
+                }
+                if let Some(rest) = rest {
+                    self.bytecode_generator.append_iter_rest(iseq);
+                    self.assign_stack_top_to(&*rest, iseq)?;
+                }
+                self.bytecode_generator.append_iter_close(iseq);
+            }
+            _ => {
+                return Err(Error::new_unimplemented_error(
+                    "Unimplemented assignment target".to_string(),
+                    dst.pos,
+                ));
+            }
         }
 
         Ok(())
     }
 
+    /// Replace the value on the stack top with the default value if it is undefined.
+    fn append_default_value(&mut self, default: &Node, iseq: &mut ByteCode) -> CodeGenResult {
+        self.bytecode_generator.append_double(iseq);
+        self.bytecode_generator.append_push_undefined(iseq);
+        self.bytecode_generator.append_seq(iseq);
+        let jmp_pos = iseq.len() as isize;
+        self.bytecode_generator.append_jmp_if_false(0, iseq);
+        self.bytecode_generator.append_pop(iseq);
+        self.visit(default, iseq, true)?;
+        let end = iseq.len() as isize;
+        self.bytecode_generator.replace_int32(
+            (end - jmp_pos) as i32 - 5,
+            &mut iseq[jmp_pos as usize + 1..jmp_pos as usize + 5],
+        );
+        Ok(())
+    }
+
+    /// Create the frozen array of the cooked strings, which has the frozen array of the raw
+    /// strings as `raw`. Each site of tagged templates has its own object, so it is created here.
+    /// Cooked strings with invalid escape sequences are undefined.
+    /// https://tc39.github.io/ecma262/#sec-gettemplateobject
+    fn create_template_object(
+        &mut self,
+        cooked_strings: &Vec<Option<Vec<u16>>>,
+        raw_strings: &Vec<String>,
+    ) -> Value {
+        let cooked_strings = cooked_strings
+            .iter()
+            .map(|string| match string {
+                Some(string) => {
+                    Value::js_string(self.memory_allocator, JSString::new(string.clone()))
+                }
+                None => Value::undefined(),
+            })
+            .collect::<Vec<Value>>();
+        let raw_strings = raw_strings
+            .iter()
+            .map(|string| Value::string(self.memory_allocator, string.clone()))
+            .collect::<Vec<Value>>();
+
+        let mut arrays = vec![];
+        for strings in &[cooked_strings, raw_strings] {
+            let elems = strings
+                .iter()
+                .map(|string| {
+                    value::Property::Data(value::DataProperty {
+                        val: *string,
+                        writable: false,
+                        enumerable: true,
+                        configurable: false,
+                    })
+                })
+                .collect();
+            arrays.push(Value::array(
+                self.memory_allocator,
+                self.object_prototypes,
+                elems,
+            ));
+        }
+
+        let template_object = arrays[0];
+        template_object.get_object_info().property.insert(
+            "raw".to_string(),
+            value::Property::Data(value::DataProperty {
+                val: arrays[1],
+                writable: false,
+                enumerable: false,
+                configurable: false,
+            }),
+        );
+
+        for array in arrays {
+            array.as_array_mut().length_writable = false;
+            array.get_object_info().prevent_extensions();
+        }
+        template_object
+    }
+
+    fn append_call(&mut self, argc: usize, spread: bool, iseq: &mut ByteCode) {
+        if spread {
+            self.bytecode_generator.append_call_spread(iseq);
+        } else {
+            self.bytecode_generator.append_call(argc as u32, iseq);
+        }
+    }
+
+    fn append_call_method(&mut self, argc: usize, spread: bool, iseq: &mut ByteCode) {
+        if spread {
+            self.bytecode_generator.append_call_method_spread(iseq);
+        } else {
+            self.bytecode_generator
+                .append_call_method(argc as u32, iseq);
+        }
+    }
+
     fn current_function(&mut self) -> &mut FunctionInfo {
         self.function_stack.last_mut().unwrap()
     }
@@ -1100,21 +2659,61 @@ impl<'a> CodeGenerator<'a> {
         }
     }
 
-    fn unwind_loop(&mut self, iseq: &mut ByteCode) {
-        let mut count = 0;
-        for level in self.current_function().level.iter().rev() {
+    /// Leave all the levels above the level specified by `target`.
+    /// Lexical environments are popped, iterators of for-of statements are closed, and finally
+    /// blocks are executed on the way.
+    fn unwind_to(&mut self, target: usize, iseq: &mut ByteCode) {
+        let levels = self.current_function().level.clone();
+        for (i, level) in levels.iter().enumerate().skip(target + 1).rev() {
             match level {
-                &Level::Loop { .. } => break,
-                &Level::Block { .. } => count += 1,
+                &Level::Block { .. } => self.bytecode_generator.append_pop_env(iseq),
+                &Level::Loop {
+                    kind: LoopKind::ForIn,
+                    ..
+                } => self.bytecode_generator.append_pop(iseq),
+                &Level::Loop {
+                    kind: LoopKind::ForOf,
+                    ..
+                } => self.bytecode_generator.append_iter_close(iseq),
+                &Level::TryOrCatch { .. } => {
+                    self.bytecode_generator.append_push_undefined(iseq);
+                    let instr_pos = iseq.len();
+                    self.bytecode_generator.append_jmp_sub(0, iseq);
+                    self.current_function().level[i]
+                        .as_try_or_catch_mut()
+                        .push(instr_pos);
+                    self.bytecode_generator.append_pop(iseq);
+                }
+                &Level::Finally => self.bytecode_generator.append_pop(iseq),
                 _ => {}
             }
         }
-        for _ in 0..count {
-            self.bytecode_generator.append_pop_env(iseq);
-        }
+    }
+
+    fn push_loop_level_with_label_set(&mut self, kind: LoopKind) {
+        let cur_func = self.current_function();
+        let labels = mem::replace(&mut cur_func.label_set, vec![]);
+        cur_func.level.push(Level::new_loop_level(kind, labels));
     }
 }
 
+/// Return true if `expr` is a part of an optional chain which has '?.' in it.
+fn has_optional(expr: &Node) -> bool {
+    match expr.base {
+        NodeBase::Optional(_) => true,
+        NodeBase::Member(ref parent, _) | NodeBase::Index(ref parent, _) => has_optional(&*parent),
+        NodeBase::Call(ref callee, _) => has_optional(&*callee),
+        _ => false,
+    }
+}
+
+fn has_spread(elems: &Vec<Node>) -> bool {
+    elems.iter().any(|elem| match elem.base {
+        NodeBase::Spread(_) => true,
+        _ => false,
+    })
+}
+
 // Methods for Error handling
 
 impl Error {
@@ -1146,8 +2745,12 @@ impl FunctionInfo {
             func_decls: vec![],
             param_names: vec![],
             level: vec![Level::Function],
+            label_set: vec![],
             exception_table: vec![],
             to_source_pos: ToSourcePos::new(),
+            super_kind: None,
+            uses_arguments: false,
+            optional_chains: vec![],
         }
     }
 
@@ -1162,6 +2765,25 @@ impl FunctionInfo {
             .is_some()
     }
 
+    /// Count the values kept on the stack by the enclosing statements.
+    pub fn stack_depth(&self) -> usize {
+        self.level
+            .iter()
+            .filter(|level| match level {
+                &Level::Loop {
+                    kind: LoopKind::ForIn,
+                    ..
+                }
+                | &Level::Loop {
+                    kind: LoopKind::ForOf,
+                    ..
+                }
+                | &Level::Finally => true,
+                _ => false,
+            })
+            .count()
+    }
+
     pub fn in_finally(&self) -> bool {
         self.level
             .iter()
@@ -1170,33 +2792,66 @@ impl FunctionInfo {
             .is_some()
     }
 
-    pub fn get_last_try_or_catch(&mut self) -> &mut Level {
+    pub fn get_last_loop(&mut self) -> &mut Level {
         self.level
             .iter_mut()
             .rev()
             .find(|level| match level {
-                &Level::TryOrCatch { .. } => true,
+                &Level::Loop { .. } => true,
                 _ => false,
             })
             .unwrap()
     }
 
-    pub fn find_last_try_or_catch(&mut self) -> Option<&mut Level> {
-        self.level.iter_mut().rev().find(|level| match level {
-            &Level::TryOrCatch { .. } => true,
+    pub fn has_label(&self, name: &String) -> bool {
+        self.level.iter().any(|level| match level {
+            &Level::Loop { ref labels, .. } => labels.contains(name),
             _ => false,
         })
     }
 
-    pub fn get_last_loop(&mut self) -> &mut Level {
-        self.level
-            .iter_mut()
-            .rev()
-            .find(|level| match level {
-                &Level::Loop { .. } => true,
-                _ => false,
-            })
-            .unwrap()
+    /// Find the level that 'break' (or 'continue' if `is_continue`) with `label` jumps to.
+    /// Return the index of the level in `self.level`, or the message of an early error.
+    pub fn find_jump_target(
+        &self,
+        label: &Option<String>,
+        is_continue: bool,
+    ) -> Result<usize, String> {
+        for (i, level) in self.level.iter().enumerate().rev() {
+            let (kind, labels) = match level {
+                &Level::Loop {
+                    ref kind,
+                    ref labels,
+                    ..
+                } => (kind, labels),
+                _ => continue,
+            };
+            match label {
+                None => {
+                    if kind.is_iteration() || (!is_continue && *kind == LoopKind::Switch) {
+                        return Ok(i);
+                    }
+                }
+                Some(label) if labels.contains(label) => {
+                    if is_continue && !kind.is_iteration() {
+                        return Err(format!(
+                            "Illegal continue statement: '{}' does not denote an iteration statement",
+                            label
+                        ));
+                    }
+                    return Ok(i);
+                }
+                Some(_) => {}
+            }
+        }
+
+        Err(match label {
+            Some(label) => format!("Undefined label '{}'", label),
+            None if is_continue => {
+                "Illegal continue statement: no surrounding iteration statement".to_string()
+            }
+            None => "Illegal break statement".to_string(),
+        })
     }
 
     pub fn get_last_block(&mut self) -> Option<&mut Level> {
@@ -1207,6 +2862,17 @@ impl FunctionInfo {
     }
 }
 
+// LoopKind
+
+impl LoopKind {
+    pub fn is_iteration(&self) -> bool {
+        match self {
+            LoopKind::Iteration | LoopKind::ForIn | LoopKind::ForOf => true,
+            LoopKind::Switch | LoopKind::Labelled => false,
+        }
+    }
+}
+
 // Level
 
 impl Level {
@@ -1218,6 +2884,15 @@ impl Level {
         Level::Block { names: vec![] }
     }
 
+    pub fn new_loop_level(kind: LoopKind, labels: Vec<String>) -> Self {
+        Level::Loop {
+            kind,
+            labels,
+            break_jmp_instr_pos: vec![],
+            continue_jmp_instr_pos: vec![],
+        }
+    }
+
     pub fn new_try_or_catch_level() -> Self {
         Level::TryOrCatch {
             finally_jmp_instr_pos: vec![],
@@ -1243,6 +2918,7 @@ impl Level {
             Level::Loop {
                 break_jmp_instr_pos,
                 continue_jmp_instr_pos,
+                ..
             } => (break_jmp_instr_pos, continue_jmp_instr_pos),
             _ => panic!(),
         }
@@ -1253,6 +2929,7 @@ impl Level {
             Level::Loop {
                 ref mut break_jmp_instr_pos,
                 ref mut continue_jmp_instr_pos,
+                ..
             } => (break_jmp_instr_pos, continue_jmp_instr_pos),
             _ => panic!(),
         }
@@ -1303,21 +2980,15 @@ impl Level {
         dst: usize,
         bytecode_generator: &mut ByteCodeGenerator,
         iseq: &mut ByteCode,
-    ) -> bool {
+    ) {
         let finally_jmp_instr_pos = self.as_try_or_catch();
-        let mut has_return_from_try_or_catch = false;
         for instr_pos in finally_jmp_instr_pos {
-            has_return_from_try_or_catch |= iseq[instr_pos] == VMInst::RETURN_TRY;
-            assert!(match iseq[instr_pos] {
-                VMInst::RETURN_TRY | VMInst::JMP => true,
-                _ => false,
-            });
+            assert_eq!(iseq[instr_pos], VMInst::JMP_SUB);
             bytecode_generator.replace_int32(
                 (dst - instr_pos) as i32 - 5,
                 &mut iseq[instr_pos as usize + 1..instr_pos as usize + 5],
             );
         }
-        has_return_from_try_or_catch
     }
 }
 
@@ -1,3 +1,4 @@
+use regexp::RegExp;
 use rustc_hash::FxHashMap;
 use vm::jsvalue::value::Value;
 
@@ -12,6 +13,12 @@ pub enum Constant {
         len: usize,
         special_properties: SpecialProperties,
     },
+    ClassInfo {
+        has_heritage: bool,
+        methods: Vec<ClassMethodInfo>,
+    },
+    /// Compiled pattern of a regular expression literal
+    RegExp(Box<RegExp>),
 }
 
 pub type SpecialProperties = FxHashMap<usize, SpecialPropertyKind>;
@@ -20,6 +27,17 @@ pub type SpecialProperties = FxHashMap<usize, SpecialPropertyKind>;
 pub enum SpecialPropertyKind {
     Getter,
     Setter,
+    /// Properties of the value are copied.
+    Spread,
+    /// The value becomes \[\[Prototype\]\] of the object.
+    Proto,
+}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub struct ClassMethodInfo {
+    pub is_static: bool,
+    /// None for ordinary methods
+    pub special: Option<SpecialPropertyKind>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -81,6 +99,21 @@ impl ConstantTable {
         id
     }
 
+    pub fn add_class_info(&mut self, has_heritage: bool, methods: Vec<ClassMethodInfo>) -> usize {
+        let id = self.table.len();
+        self.table.push(Constant::ClassInfo {
+            has_heritage,
+            methods,
+        });
+        id
+    }
+
+    pub fn add_regexp(&mut self, regexp: RegExp) -> usize {
+        let id = self.table.len();
+        self.table.push(Constant::RegExp(Box::new(regexp)));
+        id
+    }
+
     pub fn add_lex_env_info(&mut self, names: Vec<String>) -> usize {
         let id = self.table.len();
         self.table.push(Constant::LexicalEnvironmentInfo { names });
@@ -134,4 +167,21 @@ impl Constant {
             _ => panic!(),
         }
     }
+
+    pub fn as_class_info(&self) -> (bool, Vec<ClassMethodInfo>) {
+        match self {
+            Constant::ClassInfo {
+                has_heritage,
+                methods,
+            } => (*has_heritage, methods.clone()),
+            _ => panic!(),
+        }
+    }
+
+    pub fn as_regexp(&self) -> &RegExp {
+        match self {
+            Constant::RegExp(regexp) => regexp,
+            _ => panic!(),
+        }
+    }
 }
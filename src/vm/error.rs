@@ -9,6 +9,7 @@ pub enum RuntimeError {
     Unknown,
     Type(String),
     Reference(String),
+    Range(String),
     General(String),
     Exception2(Value, Option<usize>),
     Unimplemented,
@@ -20,6 +21,7 @@ impl RuntimeError {
         match self {
             RuntimeError::Exception2(v, _) => v,
             RuntimeError::Type(s) => Value::string(memory_allocator, s),
+            RuntimeError::Range(s) => Value::string(memory_allocator, s),
             RuntimeError::General(s) => Value::string(memory_allocator, s),
             RuntimeError::Reference(s) => {
                 Value::string(memory_allocator, format!("Reference error: {}", s))
@@ -35,9 +37,10 @@ impl RuntimeError {
         match self {
             RuntimeError::Unknown => runtime_error("unknown error occurred"),
             RuntimeError::Unimplemented => runtime_error("unimplemented feature"),
-            RuntimeError::Reference(msg) | RuntimeError::Type(msg) | RuntimeError::General(msg) => {
-                runtime_error(msg.as_str())
-            }
+            RuntimeError::Reference(msg)
+            | RuntimeError::Type(msg)
+            | RuntimeError::Range(msg)
+            | RuntimeError::General(msg) => runtime_error(msg.as_str()),
             RuntimeError::Exception2(val, node_pos) => {
                 runtime_error("Uncaught Exception");
                 if let (Some(pos), Some(lexer)) = (node_pos, lexer) {
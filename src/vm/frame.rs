@@ -7,8 +7,9 @@ use std::ops::{Deref, DerefMut};
 use vm::codegen::FunctionInfo;
 use vm::error::RuntimeError;
 use vm::jsvalue::function::Exception;
-use vm::jsvalue::object::{DataProperty, ObjectInfo, ObjectKind2, Property};
+use vm::jsvalue::object::{DataProperty, ObjectInfo, ObjectKind2, Property, PropertyMap};
 use vm::jsvalue::prototype::ObjectPrototypes;
+use vm::jsvalue::symbol::WellKnownSymbols;
 use vm::jsvalue::value::Value;
 use vm::vm::VMResult;
 
@@ -25,7 +26,20 @@ pub struct Frame {
     pub exception_table: Vec<Exception>,
     pub this: Value,
     pub constructor_call: bool,
+    /// Represent if the frame executes the constructor of a derived class, which returns the
+    /// 'this' bound by super().
+    pub derived_constructor: bool,
     pub escape: bool,
+    pub subroutine_stack: Vec<SubroutineKind>,
+    /// The generator object if the frame executes the body of a generator function.
+    pub generator: Option<Value>,
+}
+
+/// Where to go after a finally block, which is executed as a subroutine.
+#[derive(Debug, Clone)]
+pub enum SubroutineKind {
+    Ordinary(usize), // Return address
+    Throw,
 }
 
 #[derive(Debug, Clone)]
@@ -47,7 +61,9 @@ pub enum EnvironmentRecord {
     Object(Value),
     Global(Value),
     Function {
+        /// Uninitialized until super() returns in derived constructors.
         this: Value,
+        new_target: Value,
         record: FxHashMap<String, Value>,
         // TODO: https://www.ecma-international.org/ecma-262/6.0/#sec-function-environment-records
     },
@@ -70,7 +86,10 @@ impl Frame {
             exception_table,
             this,
             constructor_call,
+            derived_constructor: false,
             escape: false,
+            subroutine_stack: vec![],
+            generator: None,
         }
     }
 
@@ -84,7 +103,10 @@ impl Frame {
             exception_table: vec![],
             this,
             constructor_call,
+            derived_constructor: false,
             escape: false,
+            subroutine_stack: vec![],
+            generator: None,
         }
     }
 
@@ -111,6 +133,58 @@ impl Frame {
         self
     }
 
+    pub fn derived_constructor(mut self) -> Self {
+        self.derived_constructor = true;
+        self
+    }
+
+    pub fn generator(mut self, generator: Value) -> Self {
+        self.generator = Some(generator);
+        self
+    }
+
+    /// Derived constructors and arrow functions in them find the binding made by super().
+    /// https://tc39.github.io/ecma262/#sec-resolvethisbinding
+    pub fn resolve_this_binding(&self) -> Result<Value, RuntimeError> {
+        let this = if self.this == Value::uninitialized() {
+            self.lex_env().get_this_binding()
+        } else {
+            self.this
+        };
+        if this == Value::uninitialized() {
+            return Err(RuntimeError::Reference(
+                "Must call super constructor in derived class before accessing 'this'".to_string(),
+            ));
+        }
+        Ok(this)
+    }
+
+    /// Leave the environment of the parameters for the body. The variables with the same names
+    /// as the parameters start with the values of them.
+    /// https://tc39.github.io/ecma262/#sec-functiondeclarationinstantiation (step 28)
+    pub fn enter_function_body(&mut self) {
+        let params_env = self.execution_context.lexical_environment;
+        let mut var_env = self.execution_context.variable_environment;
+        let params = match params_env.record {
+            EnvironmentRecord::Function { ref record, .. }
+            | EnvironmentRecord::Declarative(ref record) => record,
+            _ => unreachable!(),
+        };
+        if let EnvironmentRecord::Declarative(ref mut vars) = var_env.record {
+            for (name, val) in vars.iter_mut() {
+                if let Some(param) = params.get(name) {
+                    *val = *param;
+                }
+            }
+        }
+
+        self.execution_context.lexical_environment = self
+            .execution_context
+            .saved_lexical_environment
+            .pop()
+            .unwrap();
+    }
+
     pub fn append_function(&mut self, memory_allocator: &mut gc::MemoryAllocator, f: Value) {
         let mut val = f.copy_object(memory_allocator);
         let name = val.as_function().name.clone().unwrap();
@@ -195,6 +269,7 @@ impl LexicalEnvironment {
     pub fn new_global_initialized(
         memory_allocator: &mut gc::MemoryAllocator,
         object_prototypes: &ObjectPrototypes,
+        well_known_symbols: &WellKnownSymbols,
     ) -> Self {
         use builtin::parse_float;
         use builtins;
@@ -218,8 +293,12 @@ impl LexicalEnvironment {
         let function_constructor =
             builtins::function::function(memory_allocator, object_prototypes);
         let array_constructor = builtins::array::array(memory_allocator, object_prototypes);
-        let symbol_constructor = builtins::symbol::symbol(memory_allocator, object_prototypes);
+        let string_constructor = builtins::string::string(memory_allocator, object_prototypes);
+        let symbol_constructor =
+            builtins::symbol::symbol(memory_allocator, object_prototypes, well_known_symbols);
         let math_object = builtins::math::math(memory_allocator, object_prototypes);
+        let promise_constructor = builtins::promise::promise(memory_allocator, object_prototypes);
+        let regexp_constructor = builtins::regexp::regexp(memory_allocator, object_prototypes);
         LexicalEnvironment {
             record: EnvironmentRecord::Global(make_normal_object!(
                 memory_allocator,
@@ -232,8 +311,11 @@ impl LexicalEnvironment {
                 Object     => true, false, true: object_constructor,
                 Function   => true, false, true: function_constructor,
                 Array      => true, false, true: array_constructor,
+                String     => true, false, true: string_constructor,
                 Symbol     => true, false, true: symbol_constructor,
-                Math       => true, false, true: math_object
+                Math       => true, false, true: math_object,
+                Promise    => true, false, true: promise_constructor,
+                RegExp     => true, false, true: regexp_constructor
             )),
             outer: None,
         }
@@ -315,6 +397,36 @@ impl LexicalEnvironment {
         return Ok(());
     }
 
+    /// Declarative bindings can't be deleted. Bindings of object records, including the global
+    /// object, are deleted like properties, and unresolvable names are treated as deleted.
+    /// https://tc39.github.io/ecma262/#sec-delete-operator-runtime-semantics-evaluation
+    pub fn delete_binding(
+        &self,
+        memory_allocator: &mut gc::MemoryAllocator,
+        name: &String,
+    ) -> bool {
+        match self.record {
+            EnvironmentRecord::Function { ref record, .. }
+            | EnvironmentRecord::Declarative(ref record) => {
+                if record.contains_key(name) {
+                    return false;
+                }
+            }
+            EnvironmentRecord::Global(obj) | EnvironmentRecord::Object(obj) => {
+                if obj.has_own_property(name.as_str()) {
+                    let key = Value::string(memory_allocator, name.clone());
+                    return obj.delete_property(memory_allocator, key);
+                }
+            }
+        };
+
+        if let Some(outer) = self.get_outer() {
+            outer.delete_binding(memory_allocator, name)
+        } else {
+            true
+        }
+    }
+
     pub fn get_outer(&self) -> Option<&mut LexicalEnvironment> {
         self.outer
             .and_then(|outer| Some(unsafe { &mut *outer.as_ptr() }))
@@ -340,6 +452,37 @@ impl LexicalEnvironment {
             }
         }
     }
+
+    /// https://tc39.github.io/ecma262/#sec-bindthisvalue
+    pub fn bind_this_value(&mut self, val: Value) -> VMResult {
+        match self.record {
+            EnvironmentRecord::Function { ref mut this, .. } => {
+                if *this != Value::uninitialized() {
+                    return Err(RuntimeError::Reference(
+                        "Super constructor may only be called once".to_string(),
+                    ));
+                }
+                *this = val;
+                Ok(())
+            }
+            _ => match self.get_outer() {
+                Some(outer) => outer.bind_this_value(val),
+                None => Err(RuntimeError::Reference(
+                    "'super' keyword unexpected here".to_string(),
+                )),
+            },
+        }
+    }
+
+    pub fn get_new_target(&self) -> Value {
+        match self.record {
+            EnvironmentRecord::Function { new_target, .. } => new_target,
+            _ => match self.outer {
+                Some(outer) => outer.get_new_target(),
+                None => Value::undefined(),
+            },
+        }
+    }
 }
 
 impl LexicalEnvironmentRef {
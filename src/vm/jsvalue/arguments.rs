@@ -0,0 +1,46 @@
+use super::super::frame::LexicalEnvironmentRef;
+use super::value::*;
+
+/// https://tc39.github.io/ecma262/#sec-arguments-exotic-objects
+#[derive(Clone, Debug)]
+pub struct ArgumentsObjectInfo {
+    /// Function environment which holds the parameters
+    pub env: LexicalEnvironmentRef,
+    /// Internal slot \[\[ParameterMap\]\]. The i-th element is the name of the parameter which the
+    /// i-th argument is mapped to. Empty if the parameter list is not simple.
+    pub mapped_names: Vec<Option<String>>,
+}
+
+impl ArgumentsObjectInfo {
+    /// Return the name of the parameter mapped to the property `key`, if any.
+    pub fn mapped_name(&self, key: &str) -> Option<&String> {
+        match key.parse::<usize>() {
+            Ok(idx) if idx.to_string() == key => match self.mapped_names.get(idx) {
+                Some(Some(name)) => Some(name),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Remove the mapping of the property `key` to the parameter, if any.
+    pub fn unmap(&mut self, key: &str) {
+        if let Ok(idx) = key.parse::<usize>() {
+            if let Some(name) = self.mapped_names.get_mut(idx) {
+                *name = None;
+            }
+        }
+    }
+
+    /// Return `prop` whose value is replaced with the current value of the parameter mapped to
+    /// the property `key`, if any.
+    pub fn reflect_parameter(&self, key: &str, prop: Property) -> Property {
+        match (self.mapped_name(key), prop) {
+            (Some(name), Property::Data(data)) => match self.env.get_value(name) {
+                Ok(val) => Property::Data(DataProperty { val, ..data }),
+                Err(_) => prop,
+            },
+            _ => prop,
+        }
+    }
+}
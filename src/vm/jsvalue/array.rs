@@ -1,11 +1,18 @@
 // use super::super::frame::LexicalEnvironmentRef;
+use super::super::super::gc::MemoryAllocator;
+use super::super::error;
 use super::value::*;
 // use builtin::BuiltinFuncTy2;
 // use bytecode_gen::ByteCode;
 
+/// Elements are stored densely including holes, so longer arrays can't be created.
+pub const MAX_DENSE_LENGTH: usize = 1 << 24;
+
 #[derive(Clone, Debug)]
 pub struct ArrayObjectInfo {
     pub elems: Vec<Property>,
+    /// The writable attribute of the length property
+    pub length_writable: bool,
 }
 
 impl ArrayObjectInfo {
@@ -39,8 +46,14 @@ impl ArrayObjectInfo {
         }
 
         match self.elems[idx] {
-            Property::Data(DataProperty { ref mut val, .. }) => {
-                *val = val_;
+            Property::Data(DataProperty {
+                ref mut val,
+                writable,
+                ..
+            }) => {
+                if writable {
+                    *val = val_;
+                }
                 None
             }
             Property::Accessor(AccessorProperty { set, .. }) => {
@@ -53,6 +66,18 @@ impl ArrayObjectInfo {
         }
     }
 
+    /// Replace the element with a hole. Return false if the element is not configurable.
+    pub fn delete_element(&mut self, idx: usize) -> bool {
+        match self.elems.get_mut(idx) {
+            Some(prop) if !prop.is_configurable() => false,
+            Some(prop) => {
+                *prop = Property::new_data_simple(Value::empty());
+                true
+            }
+            None => true,
+        }
+    }
+
     pub fn set_length(&mut self, len: usize) {
         // Extend
         if self.elems.len() < len {
@@ -69,28 +94,107 @@ impl ArrayObjectInfo {
         }
     }
 
+    /// Define the length property as described by desc. Return false if it is not allowed. The
+    /// array is shortened only down to the last non-configurable element.
+    /// https://tc39.github.io/ecma262/#sec-arraysetlength
+    pub fn define_length(
+        &mut self,
+        allocator: &mut MemoryAllocator,
+        desc: PropertyDescriptor,
+        current: Property,
+    ) -> Result<bool, error::RuntimeError> {
+        let new_len = match desc.value {
+            Some(val) => {
+                let number = val.to_number(allocator);
+                let new_len = number as u32;
+                if new_len as f64 != number {
+                    return Err(error::RuntimeError::Range(
+                        "Invalid array length".to_string(),
+                    ));
+                }
+                if new_len as usize > MAX_DENSE_LENGTH {
+                    return Err(error::RuntimeError::Range(format!(
+                        "Array length {} is too large",
+                        new_len
+                    )));
+                }
+                Some(new_len as usize)
+            }
+            None => None,
+        };
+        let desc = PropertyDescriptor {
+            value: new_len.map(|len| Value::Number(len as f64)),
+            ..desc
+        };
+
+        let writable = match validate_and_apply_property_descriptor(false, desc, Some(current)) {
+            Some(property) => property.as_data().writable,
+            None => return Ok(false),
+        };
+
+        let mut succeeded = true;
+        if let Some(mut new_len) = new_len {
+            if let Some(idx) = (new_len..self.elems.len())
+                .rev()
+                .find(|&idx| !self.elems[idx].is_configurable())
+            {
+                new_len = idx + 1;
+                succeeded = false;
+            }
+            self.set_length(new_len);
+        }
+        self.length_writable = writable;
+        Ok(succeeded)
+    }
+
     #[inline]
     pub fn get_length(&self) -> usize {
         self.elems.len()
     }
+
+    /// Return the values of the elements. Holes are undefined.
+    pub fn to_vec(&self) -> Vec<Value> {
+        (0..self.elems.len())
+            .map(|i| self.get_element(i).as_data().val)
+            .collect()
+    }
 }
 
 impl ArrayObjectInfo {
     /// https://tc39.github.io/ecma262/#sec-array.prototype.join
-    pub fn join(&self, separator: Option<String>) -> String {
-        let separator = separator.unwrap_or(",".to_string());
-        let separator_str = separator.as_str();
-        let mut res = "".to_string();
+    /// `joining` holds the arrays being joined, including this one.
+    pub fn join(&self, separator: Option<&JSString>, joining: &mut Vec<Value>) -> JSString {
+        let comma = JSString::from(",");
+        let separator = separator.unwrap_or(&comma);
+        let mut res = vec![];
         for (i, elem) in self.elems.iter().enumerate() {
+            if i > 0 {
+                res.extend_from_slice(&separator.units());
+            }
             if let Some(data) = elem.get_data() {
-                res += &(data.val.to_string()
-                    + if self.elems.len() - 1 != i {
-                        separator_str
-                    } else {
-                        ""
-                    });
+                res.extend_from_slice(&join_element_to_string(data.val, joining).units());
+            }
+        }
+        JSString::new(res)
+    }
+}
+
+/// Convert an element of an array being joined to a string. Holes, undefined and null are empty,
+/// and so is an array which is already being joined, as engines do to avoid infinite recursion.
+pub fn join_element_to_string(val: Value, joining: &mut Vec<Value>) -> JSString {
+    if val.is_undefined() || val.is_null() || val == Value::empty() {
+        return JSString::from("");
+    }
+    if let Value::Object(info) = val {
+        if let ObjectKind2::Array(ref ary) = unsafe { &*info }.kind {
+            if joining.contains(&val) {
+                return JSString::from("");
             }
+            joining.push(val);
+            let joined = ary.join(None, joining);
+            joining.pop();
+            return joined;
         }
-        res
     }
+    val.to_js_string()
 }
@@ -2,6 +2,7 @@ use super::super::frame::LexicalEnvironmentRef;
 use super::value::*;
 use builtin::BuiltinFuncTy2;
 use bytecode_gen::ByteCode;
+use node::FunctionKind;
 
 #[derive(Clone, Debug)]
 pub struct FunctionObjectInfo {
@@ -13,6 +14,7 @@ pub struct FunctionObjectInfo {
 pub enum FunctionObjectKind {
     User(UserFunctionInfo),
     Builtin(BuiltinFuncTy2),
+    PromiseResolving(PromiseResolvingFunctionInfo),
 }
 
 #[derive(Clone, Debug)]
@@ -41,6 +43,23 @@ pub struct UserFunctionInfo {
     /// Represent if constructible or not
     pub constructible: bool,
 
+    /// Represent if the function is a class constructor, which cannot be called without 'new'
+    pub class_constructor: bool,
+
+    /// Represent if the function is the constructor of a derived class, whose 'this' is bound
+    /// by super()
+    pub derived_constructor: bool,
+
+    /// Represent if the arguments object needs to be created on each call
+    pub uses_arguments: bool,
+
+    /// Represent if the parameters have no default values, rest parameters and binding
+    /// patterns. Only then the arguments object is mapped to the parameters.
+    pub simple_parameter_list: bool,
+
+    /// Internal slot \[\[FunctionKind\]\]
+    pub kind: FunctionKind,
+
     /// Internal slot \[\[ThisMode\]\]
     pub this_mode: ThisMode,
 
@@ -60,6 +79,10 @@ pub enum ThisMode {
 pub struct FunctionParameter {
     pub name: String,
     pub rest_param: bool,
+    /// The default value is assigned by the code of the function.
+    pub has_initializer: bool,
+    /// Names bound by the binding pattern, which is destructured by the code of the function.
+    pub bound_names: Vec<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -70,6 +93,10 @@ pub struct Exception {
 
     /// Kind of throw's destination
     pub dst_kind: DestinationKind,
+
+    /// Number of values kept on the stack by the enclosing statements. The values pushed in
+    /// the middle of expressions are discarded when the exception is caught.
+    pub stack_depth: usize,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -97,6 +124,7 @@ impl ::std::fmt::Debug for FunctionObjectKind {
             match self {
                 FunctionObjectKind::User(user_func) => format!("{:?}", user_func),
                 FunctionObjectKind::Builtin(_) => "[BuiltinFunction]".to_string(),
+                FunctionObjectKind::PromiseResolving(info) => format!("{:?}", info),
             }
         )
     }
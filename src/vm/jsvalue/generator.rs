@@ -0,0 +1,41 @@
+use super::super::frame::Frame;
+use super::value::*;
+
+/// https://tc39.github.io/ecma262/#sec-properties-of-generator-instances
+#[derive(Clone, Debug)]
+pub struct GeneratorInfo {
+    /// Internal slot \[\[GeneratorState\]\]
+    pub state: GeneratorState,
+    /// Frame of the generator body. Taken out while executing and discarded when completed.
+    pub frame: Option<Frame>,
+    /// Operand stack of the suspended frame.
+    pub stack: Vec<BoxedValue>,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum GeneratorState {
+    SuspendedStart,
+    SuspendedYield,
+    Executing,
+    Completed,
+}
+
+/// How a suspended generator is resumed. The discriminant is pushed onto the stack of the
+/// generator frame following the sent value.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum ResumeKind {
+    Next = 0,
+    Throw = 1,
+    Return = 2,
+}
+
+impl ResumeKind {
+    pub fn from_value(val: Value) -> Self {
+        match val {
+            Value::Number(n) if n == 0.0 => ResumeKind::Next,
+            Value::Number(n) if n == 1.0 => ResumeKind::Throw,
+            Value::Number(n) if n == 2.0 => ResumeKind::Return,
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::value::*;
+
+/// https://tc39.github.io/ecma262/#sec-properties-of-array-iterator-instances
+#[derive(Clone, Debug)]
+pub struct ArrayIteratorInfo {
+    /// Internal slot \[\[IteratedObject\]\]. Undefined after the iteration has finished.
+    pub iterated: Value,
+    /// Internal slot \[\[ArrayIteratorNextIndex\]\]
+    pub next_index: usize,
+    /// Internal slot \[\[ArrayIterationKind\]\]
+    pub kind: ArrayIterationKind,
+}
+
+/// What an Array Iterator yields: the indices, the elements, or \[index, element\] pairs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArrayIterationKind {
+    Key,
+    Value,
+    KeyAndValue,
+}
+
+/// https://tc39.github.io/ecma262/#sec-properties-of-string-iterator-instances
+#[derive(Clone, Debug)]
+pub struct StringIteratorInfo {
+    /// Internal slot \[\[IteratedString\]\]. Undefined after the iteration has finished.
+    pub iterated: Value,
+    /// Internal slot \[\[StringIteratorNextIndex\]\]. This is an index of a UTF-16 code unit.
+    pub next_index: usize,
+}
@@ -1,7 +1,12 @@
 #[macro_use]
 pub mod value;
+pub mod arguments;
 pub mod array;
 pub mod function;
+pub mod generator;
+pub mod iterator;
 pub mod object;
+pub mod promise;
 pub mod prototype;
+pub mod string;
 pub mod symbol;
@@ -2,7 +2,14 @@ use super::super::super::gc::MemoryAllocator;
 use super::super::error;
 use super::prototype::ObjectPrototypes;
 use super::value::*;
+use indexmap::IndexMap;
+use regexp::RegExp;
 pub use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashSet, FxHasher};
+use std::hash::BuildHasherDefault;
+
+/// String-keyed properties, kept in the order they were created.
+pub type PropertyMap = IndexMap<String, Property, BuildHasherDefault<FxHasher>>;
 
 #[derive(Clone, Debug)]
 pub struct ObjectInfo {
@@ -11,9 +18,11 @@ pub struct ObjectInfo {
     /// Internal slot \[\[Prototype\]\]
     pub prototype: Value,
     /// Properties
-    pub property: FxHashMap<String, Property>,
+    pub property: PropertyMap,
     /// Symbol properties
-    pub sym_property: FxHashMap<usize, Property>,
+    pub sym_property: FxHashMap<SymbolKey, Property>,
+    /// Internal slot \[\[Extensible\]\]
+    pub extensible: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -21,6 +30,12 @@ pub enum ObjectKind2 {
     Function(FunctionObjectInfo),
     Array(ArrayObjectInfo),
     Symbol(SymbolInfo),
+    ArrayIterator(ArrayIteratorInfo),
+    StringIterator(StringIteratorInfo),
+    Generator(GeneratorInfo),
+    Promise(PromiseInfo),
+    Arguments(ArgumentsObjectInfo),
+    RegExp(Box<RegExp>),
     Ordinary,
 }
 
@@ -46,11 +61,74 @@ pub struct AccessorProperty {
     pub configurable: bool,
 }
 
+/// The result of \[\[Set\]\]. Setters are called by the VM, and the assignment succeeds then.
+#[derive(Clone, PartialEq, Debug, Copy)]
+pub enum SetResult {
+    /// Whether the value was stored.
+    Stored(bool),
+    /// The setter to be called with the receiver as this.
+    Setter(Value),
+}
+
+/// A property descriptor whose fields may be absent, which is given to Object.defineProperty.
+/// https://tc39.github.io/ecma262/#sec-property-descriptor-specification-type
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct PropertyDescriptor {
+    pub value: Option<Value>,
+    pub writable: Option<bool>,
+    pub get: Option<Value>,
+    pub set: Option<Value>,
+    pub enumerable: Option<bool>,
+    pub configurable: Option<bool>,
+}
+
 impl ObjectInfo {
     pub fn has_own_property(&self, key: &str) -> bool {
         self.property.contains_key(key)
     }
 
+    /// Return true if the object or its prototype chain has the property.
+    /// https://tc39.github.io/ecma262/#sec-hasproperty
+    pub fn has_property(&self, allocator: &mut MemoryAllocator, key: Value) -> bool {
+        let mut object = self;
+
+        loop {
+            if object.has_own_property_key(allocator, key) {
+                return true;
+            }
+
+            match object.prototype {
+                Value::Object(proto) => object = unsafe { &*proto },
+                _ => return false,
+            }
+        }
+    }
+
+    fn has_own_property_key(&self, allocator: &mut MemoryAllocator, key: Value) -> bool {
+        if key.is_symbol() {
+            return self.sym_property.contains_key(&SymbolKey(key));
+        }
+
+        if let ObjectKind2::Array(ref info) = self.kind {
+            let idx = key
+                .is_array_index()
+                .or_else(|| key.is_canonical_numeric_index_string(allocator));
+            if let Some(idx) = idx {
+                return match info.elems.get(idx) {
+                    Some(Property::Data(DataProperty { val, .. })) => *val != Value::empty(),
+                    Some(_) => true,
+                    None => false,
+                };
+            }
+
+            if key.is_string() && key.as_string() == "length" {
+                return true;
+            }
+        }
+
+        self.property.contains_key(key.to_property_key().as_str())
+    }
+
     #[inline]
     pub fn get_prototype(&self) -> Value {
         self.prototype
@@ -62,14 +140,8 @@ impl ObjectInfo {
         object_prototypes: &ObjectPrototypes,
         key: Value,
     ) -> Result<Property, error::RuntimeError> {
-        // Annoying
-        if key.is_string() && key.into_str() == "__proto__" {
-            return Ok(Property::new_data_simple(self.get_prototype()));
-        }
-
         if key.is_symbol() {
-            let id = key.get_symbol_info().id;
-            return match self.sym_property.get(&id) {
+            return match self.sym_property.get(&SymbolKey(key)) {
                 Some(prop) => Ok(*prop),
                 None => self
                     .prototype
@@ -87,16 +159,22 @@ impl ObjectInfo {
                     return Ok(info.get_element(idx));
                 }
 
-                if key.is_string() && key.into_str() == "length" {
+                if key.is_string() && key.as_string() == "length" {
                     return Ok(Property::new_data_simple(Value::Number(
                         info.elems.len() as f64
                     )));
                 }
             }
+            ObjectKind2::Arguments(ref info) => {
+                let key = key.to_property_key();
+                if let Some(prop) = self.property.get(key.as_str()) {
+                    return Ok(info.reflect_parameter(key.as_str(), *prop));
+                }
+            }
             _ => {}
         }
 
-        match self.property.get(key.to_string().as_str()) {
+        match self.property.get(key.to_property_key().as_str()) {
             Some(prop) => Ok(*prop),
             None => self
                 .prototype
@@ -104,6 +182,278 @@ impl ObjectInfo {
         }
     }
 
+    /// Remove the own property. Return false if the property is not configurable.
+    /// https://tc39.github.io/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-delete-p
+    pub fn delete_property(&mut self, allocator: &mut MemoryAllocator, key: Value) -> bool {
+        if key.is_symbol() {
+            let key = SymbolKey(key);
+            return match self.sym_property.get(&key) {
+                Some(prop) if !prop.is_configurable() => false,
+                Some(_) => {
+                    self.sym_property.remove(&key);
+                    true
+                }
+                None => true,
+            };
+        }
+
+        if let ObjectKind2::Array(ref mut info) = self.kind {
+            let idx = key
+                .is_array_index()
+                .or_else(|| key.is_canonical_numeric_index_string(allocator));
+            if let Some(idx) = idx {
+                return info.delete_element(idx);
+            }
+
+            if key.is_string() && key.as_string() == "length" {
+                return false;
+            }
+        }
+
+        let key = key.to_property_key();
+        match self.property.get(key.as_str()) {
+            Some(prop) if !prop.is_configurable() => return false,
+            Some(_) => {}
+            None => return true,
+        }
+        self.property.shift_remove(key.as_str());
+
+        if let ObjectKind2::Arguments(ref mut info) = self.kind {
+            info.unmap(key.as_str());
+        }
+
+        true
+    }
+
+    /// Return the keys of the enumerable string-keyed properties of the object and its prototype
+    /// chain, which for-in statement iterates over. Shadowed properties are visited only once.
+    /// https://tc39.github.io/ecma262/#sec-enumerate-object-properties
+    pub fn enumerate_object_properties(&self) -> Vec<String> {
+        let mut keys = vec![];
+        let mut visited = FxHashSet::default();
+        let mut object = self;
+
+        loop {
+            for (key, enumerable) in object.own_property_keys() {
+                if visited.insert(key.clone()) && enumerable {
+                    keys.push(key);
+                }
+            }
+
+            match object.prototype {
+                Value::Object(proto) => object = unsafe { &*proto },
+                _ => break,
+            }
+        }
+
+        keys
+    }
+
+    /// Return own string keys with their enumerability. Array indices come first in ascending
+    /// order, and the other keys follow in the order they were created. Symbol keys are not
+    /// included.
+    pub fn own_property_keys(&self) -> Vec<(String, bool)> {
+        let mut keys = vec![];
+
+        if let ObjectKind2::Array(ref info) = self.kind {
+            for (i, prop) in info.elems.iter().enumerate() {
+                // Skip holes
+                if let Property::Data(DataProperty { val, .. }) = prop {
+                    if *val == Value::empty() {
+                        continue;
+                    }
+                }
+                keys.push((i.to_string(), prop.is_enumerable()));
+            }
+            keys.push(("length".to_string(), false));
+        }
+
+        let mut index_keys = vec![];
+        let mut string_keys = vec![];
+        for (key, prop) in &self.property {
+            match key.parse::<u32>() {
+                Ok(idx) if idx.to_string() == *key => index_keys.push((idx, prop.is_enumerable())),
+                _ => string_keys.push((key.clone(), prop.is_enumerable())),
+            }
+        }
+        index_keys.sort_by_key(|(idx, _)| *idx);
+
+        keys.extend(
+            index_keys
+                .into_iter()
+                .map(|(idx, enumerable)| (idx.to_string(), enumerable)),
+        );
+        keys.extend(string_keys);
+        keys
+    }
+
+    /// Return own symbol keys with their enumerability, in the order the symbols were created.
+    pub fn own_symbol_keys(&self) -> Vec<(Value, bool)> {
+        let mut keys = self
+            .sym_property
+            .iter()
+            .map(|(key, prop)| (*key, prop.is_enumerable()))
+            .collect::<Vec<(SymbolKey, bool)>>();
+        keys.sort_by_key(|(key, _)| key.id());
+        keys.into_iter()
+            .map(|(key, enumerable)| (key.0, enumerable))
+            .collect()
+    }
+
+    /// Return the own property, or None if the object doesn't have it.
+    /// https://tc39.github.io/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-getownproperty-p
+    pub fn get_own_property(
+        &self,
+        allocator: &mut MemoryAllocator,
+        key: Value,
+    ) -> Option<Property> {
+        if key.is_symbol() {
+            return self.sym_property.get(&SymbolKey(key)).cloned();
+        }
+
+        match self.kind {
+            ObjectKind2::Array(ref info) => {
+                let idx = key
+                    .is_array_index()
+                    .or_else(|| key.is_canonical_numeric_index_string(allocator));
+                if let Some(idx) = idx {
+                    return match info.elems.get(idx) {
+                        Some(Property::Data(DataProperty { val, .. }))
+                            if *val == Value::empty() =>
+                        {
+                            None
+                        }
+                        prop => prop.cloned(),
+                    };
+                }
+
+                if key.is_string() && key.as_string() == "length" {
+                    return Some(Property::Data(DataProperty {
+                        val: Value::Number(info.elems.len() as f64),
+                        writable: info.length_writable,
+                        enumerable: false,
+                        configurable: false,
+                    }));
+                }
+            }
+            ObjectKind2::Arguments(ref info) => {
+                let key = key.to_property_key();
+                return self
+                    .property
+                    .get(key.as_str())
+                    .map(|prop| info.reflect_parameter(key.as_str(), *prop));
+            }
+            _ => {}
+        }
+
+        self.property.get(key.to_property_key().as_str()).cloned()
+    }
+
+    /// Return the own or inherited property, or None if neither the object nor its prototype
+    /// chain has it.
+    pub fn lookup_property(&self, allocator: &mut MemoryAllocator, key: Value) -> Option<Property> {
+        let mut object = self;
+
+        loop {
+            if let Some(prop) = object.get_own_property(allocator, key) {
+                return Some(prop);
+            }
+
+            match object.prototype {
+                Value::Object(proto) => object = unsafe { &*proto },
+                _ => return None,
+            }
+        }
+    }
+
+    /// Define or update the own property as described by desc. Return false if it is not allowed,
+    /// e.g. the property is not configurable or the object is not extensible.
+    /// https://tc39.github.io/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-defineownproperty-p-desc
+    pub fn define_own_property(
+        &mut self,
+        allocator: &mut MemoryAllocator,
+        key: Value,
+        desc: PropertyDescriptor,
+    ) -> Result<bool, error::RuntimeError> {
+        let current = self.get_own_property(allocator, key);
+
+        if let ObjectKind2::Array(ref mut info) = self.kind {
+            if key.is_string() && key.as_string() == "length" {
+                return info.define_length(allocator, desc, current.unwrap());
+            }
+
+            let idx = key
+                .is_array_index()
+                .or_else(|| key.is_canonical_numeric_index_string(allocator));
+            if let Some(idx) = idx {
+                if idx >= info.elems.len() && !info.length_writable {
+                    return Ok(false);
+                }
+            }
+        }
+
+        let property = match validate_and_apply_property_descriptor(self.extensible, desc, current)
+        {
+            Some(property) => property,
+            None => return Ok(false),
+        };
+        self.put_own_property(allocator, key, property);
+
+        // https://tc39.github.io/ecma262/#sec-arguments-exotic-objects-defineownproperty-p-desc
+        if let ObjectKind2::Arguments(ref mut info) = self.kind {
+            let key = key.to_property_key();
+            if let Some(name) = info.mapped_name(key.as_str()).cloned() {
+                if desc.is_accessor_descriptor() {
+                    info.unmap(key.as_str());
+                } else {
+                    if let Some(val) = desc.value {
+                        let mut env = info.env;
+                        env.set_value(name, val)?;
+                    }
+                    if desc.writable == Some(false) {
+                        info.unmap(key.as_str());
+                    }
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Store the property without any checks.
+    fn put_own_property(
+        &mut self,
+        allocator: &mut MemoryAllocator,
+        key: Value,
+        property: Property,
+    ) {
+        if key.is_symbol() {
+            self.sym_property.insert(SymbolKey(key), property);
+            return;
+        }
+
+        if let ObjectKind2::Array(ref mut info) = self.kind {
+            let idx = key
+                .is_array_index()
+                .or_else(|| key.is_canonical_numeric_index_string(allocator));
+            if let Some(idx) = idx {
+                if idx >= info.elems.len() {
+                    info.set_length(idx + 1);
+                }
+                info.elems[idx] = property;
+                return;
+            }
+        }
+
+        self.property.insert(key.to_property_key(), property);
+    }
+
+    /// Make the object non-extensible.
+    /// https://tc39.github.io/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-preventextensions
+    pub fn prevent_extensions(&mut self) {
+        self.extensible = false;
+    }
+
     pub fn get_property_by_str_key(&self, key: &str) -> Value {
         match self.property.get(key) {
             Some(prop) => prop.as_data().val,
@@ -122,68 +472,74 @@ impl ObjectInfo {
         }
     }
 
+    /// Assign val to the property as the ordinary \[\[Set\]\] does, with the object as the
+    /// receiver. Setters, which may be inherited, are returned to be called by the caller.
+    /// https://tc39.github.io/ecma262/#sec-ordinaryset
     pub fn set_property(
         &mut self,
         allocator: &mut MemoryAllocator,
         key: Value,
         val_: Value,
-    ) -> Result<Option<Value>, error::RuntimeError> {
-        // Annoying
-        if key.is_string() && key.into_str() == "__proto__" {
-            self.prototype = val_;
-            return Ok(None);
-        }
-
-        match self.kind {
-            ObjectKind2::Array(ref mut info) => {
-                if let Some(idx) = key.is_array_index() {
-                    return Ok(info.set_element(idx, val_));
-                }
-
-                if let Some(idx) = key.is_canonical_numeric_index_string(allocator) {
-                    return Ok(info.set_element(idx, val_));
-                }
+    ) -> Result<SetResult, error::RuntimeError> {
+        let own = self.get_own_property(allocator, key);
+        let property = match (own, self.prototype) {
+            (None, Value::Object(proto)) => unsafe { &*proto }.lookup_property(allocator, key),
+            _ => own,
+        };
 
-                if key.is_string() && key.into_str() == "length" {
-                    if let Some(new_length) = val_.is_array_index() {
-                        info.set_length(new_length);
-                        return Ok(None);
-                    }
+        match property {
+            Some(Property::Data(DataProperty {
+                writable: false, ..
+            })) => return Ok(SetResult::Stored(false)),
+            Some(Property::Accessor(AccessorProperty { set, .. })) => {
+                if set.is_undefined() {
+                    return Ok(SetResult::Stored(false));
                 }
+                return Ok(SetResult::Setter(set));
             }
             _ => {}
         }
 
-        let property = if key.is_symbol() {
-            let id = key.get_symbol_info().id;
-            self.sym_property
-                .entry(id)
-                .or_insert_with(|| Property::new_data_simple(Value::undefined()))
-        } else {
-            self.property
-                .entry(key.to_string())
-                .or_insert_with(|| Property::new_data_simple(Value::undefined()))
+        let desc = match own {
+            Some(_) => PropertyDescriptor {
+                value: Some(val_),
+                ..PropertyDescriptor::default()
+            },
+            None => PropertyDescriptor {
+                value: Some(val_),
+                writable: Some(true),
+                enumerable: Some(true),
+                configurable: Some(true),
+                ..PropertyDescriptor::default()
+            },
         };
+        Ok(SetResult::Stored(
+            self.define_own_property(allocator, key, desc)?,
+        ))
+    }
 
-        match property {
-            Property::Data(DataProperty {
-                ref mut val,
-                writable,
-                ..
-            }) => {
-                if *writable {
-                    *val = val_;
-                }
-                Ok(None)
-            }
-            Property::Accessor(AccessorProperty { set, .. }) => {
-                if set.is_undefined() {
-                    Ok(None)
-                } else {
-                    Ok(Some(*set))
-                }
+    /// Change the prototype. Return false if the object is not extensible or the prototype chain
+    /// would be circular.
+    /// https://tc39.github.io/ecma262/#sec-ordinarysetprototypeof
+    pub fn set_prototype_of(&mut self, proto: Value) -> bool {
+        if self.prototype.strict_eq(proto).into_bool() {
+            return true;
+        }
+
+        if !self.extensible {
+            return false;
+        }
+
+        let mut p = proto;
+        while let Value::Object(info) = p {
+            if info as *const ObjectInfo == self as *const ObjectInfo {
+                return false;
             }
+            p = unsafe { &*info }.prototype;
         }
+
+        self.prototype = proto;
+        true
     }
 }
 
@@ -229,6 +585,20 @@ impl Property {
         }
     }
 
+    pub fn is_enumerable(&self) -> bool {
+        match self {
+            Property::Data(DataProperty { enumerable, .. })
+            | Property::Accessor(AccessorProperty { enumerable, .. }) => *enumerable,
+        }
+    }
+
+    pub fn is_configurable(&self) -> bool {
+        match self {
+            Property::Data(DataProperty { configurable, .. })
+            | Property::Accessor(AccessorProperty { configurable, .. }) => *configurable,
+        }
+    }
+
     pub fn get_data(&self) -> Option<&DataProperty> {
         match self {
             Property::Data(data) => Some(data),
@@ -236,3 +606,144 @@ impl Property {
         }
     }
 }
+
+impl PropertyDescriptor {
+    pub fn is_accessor_descriptor(&self) -> bool {
+        self.get.is_some() || self.set.is_some()
+    }
+
+    pub fn is_data_descriptor(&self) -> bool {
+        self.value.is_some() || self.writable.is_some()
+    }
+
+    pub fn is_generic_descriptor(&self) -> bool {
+        !self.is_accessor_descriptor() && !self.is_data_descriptor()
+    }
+}
+
+impl From<Property> for PropertyDescriptor {
+    fn from(property: Property) -> Self {
+        match property {
+            Property::Data(DataProperty {
+                val,
+                writable,
+                enumerable,
+                configurable,
+            }) => PropertyDescriptor {
+                value: Some(val),
+                writable: Some(writable),
+                enumerable: Some(enumerable),
+                configurable: Some(configurable),
+                ..PropertyDescriptor::default()
+            },
+            Property::Accessor(AccessorProperty {
+                get,
+                set,
+                enumerable,
+                configurable,
+            }) => PropertyDescriptor {
+                get: Some(get),
+                set: Some(set),
+                enumerable: Some(enumerable),
+                configurable: Some(configurable),
+                ..PropertyDescriptor::default()
+            },
+        }
+    }
+}
+
+/// Return the property updated by desc, or None if the update is not allowed. Absent fields of
+/// desc are taken from current, or default to false and undefined for a new property.
+/// https://tc39.github.io/ecma262/#sec-validateandapplypropertydescriptor
+pub fn validate_and_apply_property_descriptor(
+    extensible: bool,
+    desc: PropertyDescriptor,
+    current: Option<Property>,
+) -> Option<Property> {
+    let current = match current {
+        Some(current) => current,
+        None if !extensible => return None,
+        None if desc.is_accessor_descriptor() => {
+            return Some(Property::Accessor(AccessorProperty {
+                get: desc.get.unwrap_or(Value::undefined()),
+                set: desc.set.unwrap_or(Value::undefined()),
+                enumerable: desc.enumerable.unwrap_or(false),
+                configurable: desc.configurable.unwrap_or(false),
+            }))
+        }
+        None => {
+            return Some(Property::Data(DataProperty {
+                val: desc.value.unwrap_or(Value::undefined()),
+                writable: desc.writable.unwrap_or(false),
+                enumerable: desc.enumerable.unwrap_or(false),
+                configurable: desc.configurable.unwrap_or(false),
+            }))
+        }
+    };
+
+    let configurable = current.is_configurable();
+    let enumerable = current.is_enumerable();
+    if !configurable {
+        if desc.configurable == Some(true) {
+            return None;
+        }
+        if desc.enumerable.map_or(false, |e| e != enumerable) {
+            return None;
+        }
+    }
+
+    let changed = |new: Option<Value>, old: Value| new.map_or(false, |new| !new.same_value(old));
+    let property = match current {
+        _ if desc.is_generic_descriptor() => current,
+        Property::Data(data) if !desc.is_accessor_descriptor() => {
+            if !configurable && !data.writable {
+                if desc.writable == Some(true) || changed(desc.value, data.val) {
+                    return None;
+                }
+            }
+            Property::Data(DataProperty {
+                val: desc.value.unwrap_or(data.val),
+                writable: desc.writable.unwrap_or(data.writable),
+                ..data
+            })
+        }
+        Property::Accessor(accessor) if !desc.is_data_descriptor() => {
+            if !configurable && (changed(desc.get, accessor.get) || changed(desc.set, accessor.set))
+            {
+                return None;
+            }
+            Property::Accessor(AccessorProperty {
+                get: desc.get.unwrap_or(accessor.get),
+                set: desc.set.unwrap_or(accessor.set),
+                ..accessor
+            })
+        }
+        // Converting between a data property and an accessor property
+        _ if !configurable => return None,
+        Property::Data(_) => Property::Accessor(AccessorProperty {
+            get: desc.get.unwrap_or(Value::undefined()),
+            set: desc.set.unwrap_or(Value::undefined()),
+            enumerable,
+            configurable,
+        }),
+        Property::Accessor(_) => Property::Data(DataProperty {
+            val: desc.value.unwrap_or(Value::undefined()),
+            writable: desc.writable.unwrap_or(false),
+            enumerable,
+            configurable,
+        }),
+    };
+
+    Some(match property {
+        Property::Data(data) => Property::Data(DataProperty {
+            enumerable: desc.enumerable.unwrap_or(enumerable),
+            configurable: desc.configurable.unwrap_or(configurable),
+            ..data
+        }),
+        Property::Accessor(accessor) => Property::Accessor(AccessorProperty {
+            enumerable: desc.enumerable.unwrap_or(enumerable),
+            configurable: desc.configurable.unwrap_or(configurable),
+            ..accessor
+        }),
+    })
+}
@@ -0,0 +1,94 @@
+use super::value::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// https://tc39.github.io/ecma262/#sec-properties-of-promise-instances
+#[derive(Clone, Debug)]
+pub struct PromiseInfo {
+    /// Internal slot \[\[PromiseState\]\]
+    pub state: PromiseState,
+    /// Internal slot \[\[PromiseResult\]\]
+    pub result: Value,
+    /// Internal slot \[\[PromiseFulfillReactions\]\]
+    pub fulfill_reactions: Vec<PromiseReaction>,
+    /// Internal slot \[\[PromiseRejectReactions\]\]
+    pub reject_reactions: Vec<PromiseReaction>,
+    /// Internal slot \[\[PromiseIsHandled\]\]
+    pub is_handled: bool,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum PromiseState {
+    Pending,
+    Fulfilled,
+    Rejected,
+}
+
+/// https://tc39.github.io/ecma262/#sec-promisereaction-records
+#[derive(Clone, Debug)]
+pub struct PromiseReaction {
+    /// The promise settled with the result of the handler. None if the handler settles promises
+    /// by itself.
+    pub capability: Option<Value>,
+    pub kind: PromiseReactionKind,
+    pub handler: PromiseReactionHandler,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum PromiseReactionKind {
+    Fulfill,
+    Reject,
+}
+
+/// Handlers implemented by the VM are represented as variants instead of builtin functions,
+/// because builtin functions cannot hold any state.
+#[derive(Clone, Debug)]
+pub enum PromiseReactionHandler {
+    /// Function passed to then(). Undefined behaves as the identity function for fulfillment
+    /// and as the thrower function for rejection.
+    Function(Value),
+    /// Resume the async function suspended by 'await'.
+    Await { generator: Value, promise: Value },
+    /// https://tc39.github.io/ecma262/#sec-promise.all-resolve-element-functions
+    AllResolveElement {
+        index: usize,
+        values: Value,
+        remaining: Rc<Cell<usize>>,
+        promise: Value,
+    },
+    /// https://tc39.github.io/ecma262/#sec-thenfinallyfunctions
+    /// https://tc39.github.io/ecma262/#sec-catchfinallyfunctions
+    Finally(Value),
+    /// Fulfill with the value ignoring the argument. Used by Finally.
+    ValueThunk(Value),
+    /// Reject with the value ignoring the argument. Used by Finally.
+    Thrower(Value),
+}
+
+/// https://tc39.github.io/ecma262/#sec-promise-resolve-functions
+/// https://tc39.github.io/ecma262/#sec-promise-reject-functions
+#[derive(Clone, Debug)]
+pub struct PromiseResolvingFunctionInfo {
+    /// Internal slot \[\[Promise\]\]
+    pub promise: Value,
+    /// Reject function if PromiseReactionKind::Reject, otherwise resolve function.
+    pub kind: PromiseReactionKind,
+    /// Internal slot \[\[AlreadyResolved\]\] shared by the pair of resolving functions
+    pub already_resolved: Rc<Cell<bool>>,
+}
+
+/// https://tc39.github.io/ecma262/#sec-jobs-and-job-queues
+#[derive(Clone, Debug)]
+pub enum Job {
+    /// https://tc39.github.io/ecma262/#sec-promisereactionjob
+    PromiseReaction {
+        reaction: PromiseReaction,
+        argument: Value,
+    },
+    /// https://tc39.github.io/ecma262/#sec-promiseresolvethenablejob
+    PromiseResolveThenable {
+        promise: Value,
+        thenable: Value,
+        then: Value,
+    },
+}
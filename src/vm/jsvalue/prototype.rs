@@ -1,8 +1,12 @@
 #![macro_use]
 use super::super::super::builtins;
-use super::super::super::builtins::{array, function};
+use super::super::super::builtins::{
+    array, function, generator, iterator, object, promise, regexp, string,
+};
 use super::super::super::id::get_unique_id;
+use super::super::super::node::FunctionKind;
 use super::{function::ThisMode, value::*};
+use builtin::BuiltinFuncTy2;
 use gc::MemoryAllocator;
 use rustc_hash::FxHashMap;
 
@@ -13,6 +17,12 @@ pub struct ObjectPrototypes {
     pub string: Value,
     pub array: Value,
     pub symbol: Value,
+    pub iterator: Value,
+    pub array_iterator: Value,
+    pub string_iterator: Value,
+    pub generator: Value,
+    pub promise: Value,
+    pub regexp: Value,
 }
 
 impl ObjectPrototypes {
@@ -22,6 +32,7 @@ impl ObjectPrototypes {
             prototype: Value::null(),
             property: make_property_map!(),
             sym_property: FxHashMap::default(),
+            extensible: true,
         }));
 
         let function_prototype = {
@@ -35,6 +46,11 @@ impl ObjectPrototypes {
                         lex_names: vec![],
                         func_decls: vec![],
                         constructible: false,
+                        class_constructor: false,
+                        derived_constructor: false,
+                        uses_arguments: false,
+                        simple_parameter_list: true,
+                        kind: FunctionKind::Ordinary,
                         this_mode: ThisMode::Global,
                         code: vec![],
                         exception_table: vec![],
@@ -44,6 +60,7 @@ impl ObjectPrototypes {
                 prototype: object_prototype,
                 property: make_property_map!(),
                 sym_property: FxHashMap::default(),
+                extensible: true,
             }));
 
             let function_prototype_call = Value::builtin_function_with_proto(
@@ -60,63 +77,364 @@ impl ObjectPrototypes {
             function_prototype
         };
 
-        let string_prototype = {
-            let index_of = Value::builtin_function_with_proto(
+        {
+            let methods: [(&str, BuiltinFuncTy2); 4] = [
+                ("hasOwnProperty", object::object_prototype_has_own_property),
+                ("isPrototypeOf", object::object_prototype_is_prototype_of),
+                (
+                    "propertyIsEnumerable",
+                    object::object_prototype_property_is_enumerable,
+                ),
+                ("toString", object::object_prototype_to_string),
+            ];
+            for (name, func) in &methods {
+                let func = Value::builtin_function_with_proto(
+                    memory_allocator,
+                    function_prototype,
+                    name.to_string(),
+                    *func,
+                );
+                object_prototype.get_object_info().property.insert(
+                    name.to_string(),
+                    Property::Data(DataProperty {
+                        val: func,
+                        writable: true,
+                        enumerable: false,
+                        configurable: true,
+                    }),
+                );
+            }
+
+            let get = Value::builtin_function_with_proto(
                 memory_allocator,
                 function_prototype,
-                "indexOf".to_string(),
-                builtins::string::string_prototype_index_of,
+                "get __proto__".to_string(),
+                object::object_prototype_get_proto,
             );
-
-            let split = Value::builtin_function_with_proto(
+            let set = Value::builtin_function_with_proto(
                 memory_allocator,
                 function_prototype,
-                "split".to_string(),
-                builtins::string::string_prototype_split,
+                "set __proto__".to_string(),
+                object::object_prototype_set_proto,
+            );
+            object_prototype.get_object_info().property.insert(
+                "__proto__".to_string(),
+                Property::Accessor(AccessorProperty {
+                    get,
+                    set,
+                    enumerable: false,
+                    configurable: true,
+                }),
             );
+        }
+
+        let string_prototype = {
+            let mut property = make_property_map!();
+
+            let methods: [(&str, BuiltinFuncTy2); 26] = [
+                ("charAt", string::string_prototype_char_at),
+                ("charCodeAt", string::string_prototype_char_code_at),
+                ("codePointAt", string::string_prototype_code_point_at),
+                ("indexOf", string::string_prototype_index_of),
+                ("lastIndexOf", string::string_prototype_last_index_of),
+                ("includes", string::string_prototype_includes),
+                ("startsWith", string::string_prototype_starts_with),
+                ("endsWith", string::string_prototype_ends_with),
+                ("slice", string::string_prototype_slice),
+                ("substring", string::string_prototype_substring),
+                ("substr", string::string_prototype_substr),
+                ("toUpperCase", string::string_prototype_to_upper_case),
+                ("toLowerCase", string::string_prototype_to_lower_case),
+                ("trim", string::string_prototype_trim),
+                ("trimStart", string::string_prototype_trim_start),
+                ("trimEnd", string::string_prototype_trim_end),
+                ("padStart", string::string_prototype_pad_start),
+                ("padEnd", string::string_prototype_pad_end),
+                ("repeat", string::string_prototype_repeat),
+                ("concat", string::string_prototype_concat),
+                ("localeCompare", string::string_prototype_locale_compare),
+                ("normalize", string::string_prototype_normalize),
+                ("split", string::string_prototype_split),
+                ("match", string::string_prototype_match),
+                ("replace", string::string_prototype_replace),
+                ("search", string::string_prototype_search),
+            ];
+            for (name, func) in &methods {
+                let func = Value::builtin_function_with_proto(
+                    memory_allocator,
+                    function_prototype,
+                    name.to_string(),
+                    *func,
+                );
+                property.insert(
+                    name.to_string(),
+                    Property::Data(DataProperty {
+                        val: func,
+                        writable: true,
+                        enumerable: false,
+                        configurable: true,
+                    }),
+                );
+            }
 
             Value::Object(memory_allocator.alloc(ObjectInfo {
                 kind: ObjectKind2::Ordinary,
                 prototype: object_prototype,
-                property: make_property_map!(indexOf: index_of, split: split),
+                property,
                 sym_property: FxHashMap::default(),
+                extensible: true,
             }))
         };
 
         let array_prototype = {
-            let push = Value::builtin_function_with_proto(
+            let mut property = make_property_map!(
+                length => false, false, true : Value::Number(0.0)
+            );
+
+            let methods: [(&str, BuiltinFuncTy2); 27] = [
+                ("push", array::array_prototype_push),
+                ("pop", array::array_prototype_pop),
+                ("shift", array::array_prototype_shift),
+                ("unshift", array::array_prototype_unshift),
+                ("slice", array::array_prototype_slice),
+                ("splice", array::array_prototype_splice),
+                ("concat", array::array_prototype_concat),
+                ("join", array::array_prototype_join),
+                ("reverse", array::array_prototype_reverse),
+                ("indexOf", array::array_prototype_index_of),
+                ("lastIndexOf", array::array_prototype_last_index_of),
+                ("includes", array::array_prototype_includes),
+                ("map", array::array_prototype_map),
+                ("filter", array::array_prototype_filter),
+                ("reduce", array::array_prototype_reduce),
+                ("reduceRight", array::array_prototype_reduce_right),
+                ("forEach", array::array_prototype_for_each),
+                ("some", array::array_prototype_some),
+                ("every", array::array_prototype_every),
+                ("find", array::array_prototype_find),
+                ("findIndex", array::array_prototype_find_index),
+                ("fill", array::array_prototype_fill),
+                ("sort", array::array_prototype_sort),
+                ("keys", array::array_prototype_keys),
+                ("values", array::array_prototype_values),
+                ("entries", array::array_prototype_entries),
+                ("toString", array::array_prototype_to_string),
+            ];
+            for (name, func) in &methods {
+                let func = Value::builtin_function_with_proto(
+                    memory_allocator,
+                    function_prototype,
+                    name.to_string(),
+                    *func,
+                );
+                property.insert(
+                    name.to_string(),
+                    Property::Data(DataProperty {
+                        val: func,
+                        writable: true,
+                        enumerable: false,
+                        configurable: true,
+                    }),
+                );
+            }
+
+            Value::Object(memory_allocator.alloc(ObjectInfo {
+                kind: ObjectKind2::Array(ArrayObjectInfo {
+                    elems: vec![],
+                    length_writable: true,
+                }),
+                prototype: object_prototype,
+                property,
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        };
+
+        let symbol_prototype = {
+            Value::Object(memory_allocator.alloc(ObjectInfo {
+                kind: ObjectKind2::Ordinary,
+                prototype: object_prototype,
+                // TODO: https://tc39.github.io/ecma262/#sec-properties-of-the-symbol-prototype-object
+                property: make_property_map!(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        };
+
+        let iterator_prototype = Value::Object(memory_allocator.alloc(ObjectInfo {
+            kind: ObjectKind2::Ordinary,
+            prototype: object_prototype,
+            property: make_property_map!(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }));
+
+        let array_iterator_prototype = {
+            let next = Value::builtin_function_with_proto(
                 memory_allocator,
                 function_prototype,
-                "push".to_string(),
-                array::array_prototype_push,
+                "next".to_string(),
+                array::array_iterator_prototype_next,
             );
 
-            let map = Value::builtin_function_with_proto(
+            Value::Object(memory_allocator.alloc(ObjectInfo {
+                kind: ObjectKind2::Ordinary,
+                prototype: iterator_prototype,
+                property: make_property_map!(next => true, false, true : next),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        };
+
+        let string_iterator_prototype = {
+            let next = Value::builtin_function_with_proto(
                 memory_allocator,
                 function_prototype,
-                "map".to_string(),
-                array::array_prototype_map,
+                "next".to_string(),
+                builtins::string::string_iterator_prototype_next,
             );
 
             Value::Object(memory_allocator.alloc(ObjectInfo {
-                kind: ObjectKind2::Array(ArrayObjectInfo { elems: vec![] }),
+                kind: ObjectKind2::Ordinary,
+                prototype: iterator_prototype,
+                property: make_property_map!(next => true, false, true : next),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        };
+
+        let generator_prototype = {
+            let next = Value::builtin_function_with_proto(
+                memory_allocator,
+                function_prototype,
+                "next".to_string(),
+                generator::generator_prototype_next,
+            );
+
+            let return_ = Value::builtin_function_with_proto(
+                memory_allocator,
+                function_prototype,
+                "return".to_string(),
+                generator::generator_prototype_return,
+            );
+
+            let throw = Value::builtin_function_with_proto(
+                memory_allocator,
+                function_prototype,
+                "throw".to_string(),
+                generator::generator_prototype_throw,
+            );
+
+            Value::Object(memory_allocator.alloc(ObjectInfo {
+                kind: ObjectKind2::Ordinary,
+                prototype: iterator_prototype,
+                property: make_property_map!(
+                    next   => true, false, true : next,
+                    return => true, false, true : return_,
+                    throw  => true, false, true : throw
+                ),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            }))
+        };
+
+        let promise_prototype = {
+            let then = Value::builtin_function_with_proto(
+                memory_allocator,
+                function_prototype,
+                "then".to_string(),
+                promise::promise_prototype_then,
+            );
+
+            let catch = Value::builtin_function_with_proto(
+                memory_allocator,
+                function_prototype,
+                "catch".to_string(),
+                promise::promise_prototype_catch,
+            );
+
+            let finally = Value::builtin_function_with_proto(
+                memory_allocator,
+                function_prototype,
+                "finally".to_string(),
+                promise::promise_prototype_finally,
+            );
+
+            Value::Object(memory_allocator.alloc(ObjectInfo {
+                kind: ObjectKind2::Ordinary,
                 prototype: object_prototype,
                 property: make_property_map!(
-                    length => false, false, true : Value::Number(0.0),
-                    push   => true,  false, true : push,
-                    map    => true,  false, true : map
+                    then    => true, false, true : then,
+                    catch   => true, false, true : catch,
+                    finally => true, false, true : finally
                 ),
                 sym_property: FxHashMap::default(),
+                extensible: true,
             }))
         };
 
-        let symbol_prototype = {
+        let regexp_prototype = {
+            let exec = Value::builtin_function_with_proto(
+                memory_allocator,
+                function_prototype,
+                "exec".to_string(),
+                regexp::regexp_prototype_exec,
+            );
+
+            let test = Value::builtin_function_with_proto(
+                memory_allocator,
+                function_prototype,
+                "test".to_string(),
+                regexp::regexp_prototype_test,
+            );
+
+            let to_string = Value::builtin_function_with_proto(
+                memory_allocator,
+                function_prototype,
+                "toString".to_string(),
+                regexp::regexp_prototype_to_string,
+            );
+
+            let mut property = make_property_map!(
+                exec     => true, false, true : exec,
+                test     => true, false, true : test,
+                toString => true, false, true : to_string
+            );
+
+            let getters: [(&str, BuiltinFuncTy2); 8] = [
+                ("source", regexp::regexp_prototype_source),
+                ("flags", regexp::regexp_prototype_flags),
+                ("global", regexp::regexp_prototype_global),
+                ("ignoreCase", regexp::regexp_prototype_ignore_case),
+                ("multiline", regexp::regexp_prototype_multiline),
+                ("dotAll", regexp::regexp_prototype_dot_all),
+                ("unicode", regexp::regexp_prototype_unicode),
+                ("sticky", regexp::regexp_prototype_sticky),
+            ];
+            for (name, getter) in &getters {
+                let get = Value::builtin_function_with_proto(
+                    memory_allocator,
+                    function_prototype,
+                    format!("get {}", name),
+                    *getter,
+                );
+                property.insert(
+                    name.to_string(),
+                    Property::Accessor(AccessorProperty {
+                        get,
+                        set: Value::undefined(),
+                        enumerable: false,
+                        configurable: true,
+                    }),
+                );
+            }
+
             Value::Object(memory_allocator.alloc(ObjectInfo {
                 kind: ObjectKind2::Ordinary,
                 prototype: object_prototype,
-                // TODO: https://tc39.github.io/ecma262/#sec-properties-of-the-symbol-prototype-object
-                property: make_property_map!(),
+                property,
                 sym_property: FxHashMap::default(),
+                extensible: true,
             }))
         };
 
@@ -126,6 +444,59 @@ impl ObjectPrototypes {
             string: string_prototype,
             array: array_prototype,
             symbol: symbol_prototype,
+            iterator: iterator_prototype,
+            array_iterator: array_iterator_prototype,
+            string_iterator: string_iterator_prototype,
+            generator: generator_prototype,
+            promise: promise_prototype,
+            regexp: regexp_prototype,
         }
     }
+
+    /// Define the methods keyed by well-known symbols. This is separated from new() because the
+    /// symbols are created after the prototypes.
+    pub fn define_symbol_methods(
+        &self,
+        memory_allocator: &mut MemoryAllocator,
+        symbols: &WellKnownSymbols,
+    ) {
+        let iterator_prototype_iterator = Value::builtin_function_with_proto(
+            memory_allocator,
+            self.function,
+            "[Symbol.iterator]".to_string(),
+            iterator::iterator_prototype_iterator,
+        );
+        self.iterator
+            .set_symbol_method(symbols.iterator, iterator_prototype_iterator);
+
+        // Array.prototype[@@iterator] is the same function object as Array.prototype.values
+        let array_prototype_values = self.array.get_property_by_str_key("values");
+        self.array
+            .set_symbol_method(symbols.iterator, array_prototype_values);
+
+        let string_prototype_iterator = Value::builtin_function_with_proto(
+            memory_allocator,
+            self.function,
+            "[Symbol.iterator]".to_string(),
+            builtins::string::string_prototype_iterator,
+        );
+        self.string
+            .set_symbol_method(symbols.iterator, string_prototype_iterator);
+
+        let function_prototype_has_instance = Value::builtin_function_with_proto(
+            memory_allocator,
+            self.function,
+            "[Symbol.hasInstance]".to_string(),
+            function::function_prototype_has_instance,
+        );
+        self.function.get_object_info().sym_property.insert(
+            SymbolKey(symbols.has_instance),
+            Property::Data(DataProperty {
+                val: function_prototype_has_instance,
+                writable: false,
+                enumerable: false,
+                configurable: false,
+            }),
+        );
+    }
 }
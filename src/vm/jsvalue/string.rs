@@ -0,0 +1,285 @@
+use std::cell::{Ref, RefCell};
+use std::char;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// The maximum length of a string in code units. Builtins creating a longer string throw.
+pub const MAX_STRING_LENGTH: usize = (1 << 28) - 16;
+
+/// Noncharacter which introduces an escaped code unit in property keys. A lone surrogate is
+/// written as KEY_ESCAPE followed by a char in the supplementary private use area, and
+/// KEY_ESCAPE itself is doubled.
+const KEY_ESCAPE: char = '\u{FDD0}';
+const KEY_SURROGATE_BASE: u32 = 0xF0000;
+
+/// A JavaScript string, which is an immutable sequence of UTF-16 code units. Any code units
+/// including lone surrogates and NUL can be contained.
+///
+/// The code units are stored in a buffer shared between strings. A substring is a view into the
+/// buffer of the original string, and concatenation appends to the buffer of the left string in
+/// place if it ends at the end of the buffer, so that building a string by repeated `+=` does
+/// not copy the string built so far. Code units once written to a buffer are never changed.
+#[derive(Clone)]
+pub struct JSString {
+    buf: Rc<RefCell<Vec<u16>>>,
+    start: usize,
+    end: usize,
+}
+
+impl JSString {
+    pub fn new(units: Vec<u16>) -> Self {
+        let end = units.len();
+        JSString {
+            buf: Rc::new(RefCell::new(units)),
+            start: 0,
+            end,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn units(&self) -> Ref<'_, [u16]> {
+        Ref::map(self.buf.borrow(), |buf| &buf[self.start..self.end])
+    }
+
+    pub fn code_unit_at(&self, idx: usize) -> Option<u16> {
+        if idx < self.len() {
+            Some(self.buf.borrow()[self.start + idx])
+        } else {
+            None
+        }
+    }
+
+    /// Return the code point at idx, which is made of two code units if they are a surrogate
+    /// pair. Lone surrogates are returned as they are.
+    /// https://tc39.github.io/ecma262/#sec-codepointat
+    pub fn code_point_at(&self, idx: usize) -> Option<(u32, usize)> {
+        let first = self.code_unit_at(idx)? as u32;
+        if 0xD800 <= first && first <= 0xDBFF {
+            if let Some(second) = self.code_unit_at(idx + 1) {
+                let second = second as u32;
+                if 0xDC00 <= second && second <= 0xDFFF {
+                    return Some(((first - 0xD800) * 0x400 + (second - 0xDC00) + 0x10000, 2));
+                }
+            }
+        }
+        Some((first, 1))
+    }
+
+    /// Return the string of the code units in start..end, sharing the buffer with self.
+    pub fn substring(&self, start: usize, end: usize) -> JSString {
+        assert!(start <= end && end <= self.len());
+        JSString {
+            buf: self.buf.clone(),
+            start: self.start + start,
+            end: self.start + end,
+        }
+    }
+
+    pub fn concat(&self, other: &JSString) -> JSString {
+        if other.is_empty() {
+            return self.clone();
+        }
+        if self.is_empty() {
+            return other.clone();
+        }
+
+        // Copied first since other may share the buffer with self
+        let other_units = other.units().to_vec();
+
+        let mut buf = self.buf.borrow_mut();
+        if buf.len() == self.end {
+            buf.extend(other_units);
+            return JSString {
+                buf: self.buf.clone(),
+                start: self.start,
+                end: buf.len(),
+            };
+        }
+
+        let mut units = Vec::with_capacity(self.len() + other_units.len());
+        units.extend_from_slice(&buf[self.start..self.end]);
+        units.extend(other_units);
+        JSString::new(units)
+    }
+
+    /// Return the index of the first occurrence of search at or after from.
+    pub fn index_of(&self, search: &JSString, from: usize) -> Option<usize> {
+        let units = self.units();
+        let search = search.units();
+        (from..units.len() + 1).find(|&k| units[k..].starts_with(&search))
+    }
+
+    /// Return the index of the last occurrence of search at or before from.
+    pub fn last_index_of(&self, search: &JSString, from: usize) -> Option<usize> {
+        let units = self.units();
+        let search = search.units();
+        (0..from.min(units.len()) + 1)
+            .rev()
+            .find(|&k| units[k..].starts_with(&search))
+    }
+
+    /// Return the key of the string in property maps, which are keyed by Rust strings. Lone
+    /// surrogates are escaped so that distinct strings are always distinct keys.
+    pub fn to_property_key(&self) -> String {
+        let mut key = String::with_capacity(self.len());
+        for c in char::decode_utf16(self.units().iter().cloned()) {
+            match c {
+                Ok(KEY_ESCAPE) => {
+                    key.push(KEY_ESCAPE);
+                    key.push(KEY_ESCAPE);
+                }
+                Ok(c) => key.push(c),
+                Err(err) => {
+                    let offset = err.unpaired_surrogate() as u32 - 0xD800;
+                    key.push(KEY_ESCAPE);
+                    key.push(char::from_u32(KEY_SURROGATE_BASE + offset).unwrap());
+                }
+            }
+        }
+        key
+    }
+
+    /// Return the string of the key made by to_property_key.
+    pub fn from_property_key(key: &str) -> JSString {
+        let mut units = Vec::with_capacity(key.len());
+        let mut chars = key.chars();
+        while let Some(c) = chars.next() {
+            let c = match c {
+                KEY_ESCAPE => match chars.next() {
+                    Some(KEY_ESCAPE) | None => KEY_ESCAPE,
+                    Some(c) => {
+                        units.push((c as u32 - KEY_SURROGATE_BASE + 0xD800) as u16);
+                        continue;
+                    }
+                },
+                c => c,
+            };
+            let mut buf = [0; 2];
+            units.extend_from_slice(c.encode_utf16(&mut buf));
+        }
+        JSString::new(units)
+    }
+}
+
+impl<'a> From<&'a str> for JSString {
+    fn from(s: &'a str) -> Self {
+        JSString::new(s.encode_utf16().collect())
+    }
+}
+
+impl From<String> for JSString {
+    fn from(s: String) -> Self {
+        JSString::from(s.as_str())
+    }
+}
+
+/// Lone surrogates are written as U+FFFD.
+impl fmt::Display for JSString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in char::decode_utf16(self.units().iter().cloned()) {
+            write!(f, "{}", c.unwrap_or(char::REPLACEMENT_CHARACTER))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for JSString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+impl PartialEq for JSString {
+    fn eq(&self, other: &JSString) -> bool {
+        *self.units() == *other.units()
+    }
+}
+
+impl Eq for JSString {}
+
+impl PartialEq<str> for JSString {
+    fn eq(&self, other: &str) -> bool {
+        self.units().iter().cloned().eq(other.encode_utf16())
+    }
+}
+
+/// Strings are ordered by code units.
+impl PartialOrd for JSString {
+    fn partial_cmp(&self, other: &JSString) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JSString {
+    fn cmp(&self, other: &JSString) -> Ordering {
+        self.units().cmp(&other.units())
+    }
+}
+
+impl Hash for JSString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.units().hash(state)
+    }
+}
+
+#[test]
+fn js_string_substring_and_concat() {
+    let hello = JSString::from("hello");
+    let hell = hello.substring(0, 4);
+    assert_eq!(hell, *"hell");
+
+    // Appended in place since hello ends at the end of the buffer
+    let hello_world = hello.concat(&JSString::from(" world"));
+    assert!(Rc::ptr_eq(&hello.buf, &hello_world.buf));
+    assert_eq!(hello_world, *"hello world");
+
+    // Copied since hell doesn't end at the end of the buffer
+    let hello2 = hell.concat(&JSString::from("o!"));
+    assert!(!Rc::ptr_eq(&hello.buf, &hello2.buf));
+    assert_eq!(hello2, *"hello!");
+    assert_eq!(hello, *"hello");
+    assert_eq!(hello_world, *"hello world");
+
+    let twice = hello_world.concat(&hello_world);
+    assert_eq!(twice, *"hello worldhello world");
+}
+
+#[test]
+fn js_string_utf16() {
+    let s = JSString::from("a😀");
+    assert_eq!(s.len(), 3);
+    assert_eq!(s.code_point_at(1), Some((0x1F600, 2)));
+    assert_eq!(s.code_point_at(2), Some((0xDE00, 1)));
+    assert_eq!(s.substring(0, 2).to_string(), "a\u{FFFD}");
+    assert!(JSString::new(vec![0xD800]) < JSString::from("\u{E000}"));
+    assert_eq!(JSString::from("a\0b").len(), 3);
+}
+
+#[test]
+fn js_string_property_key() {
+    let lead = JSString::new(vec![0xD800]);
+    let trail = JSString::new(vec![0xDC00]);
+    assert_ne!(lead.to_property_key(), trail.to_property_key());
+    assert_eq!(JSString::from("abc").to_property_key(), "abc");
+
+    for s in &[
+        lead.clone(),
+        lead.concat(&trail),
+        trail.concat(&lead),
+        JSString::new(vec![0x61, 0xDBFF, 0xFDD0, 0xFDD0]),
+        JSString::from("\u{FDD0}\u{F0000}"),
+    ] {
+        assert_eq!(JSString::from_property_key(&s.to_property_key()), *s);
+    }
+}
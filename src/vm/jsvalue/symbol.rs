@@ -1,4 +1,5 @@
 use super::{super::super::gc::MemoryAllocator, prototype::ObjectPrototypes, value::Value};
+use std::hash::{Hash, Hasher};
 
 #[derive(Debug, Clone)]
 pub struct SymbolInfo {
@@ -15,14 +16,77 @@ impl SymbolInfo {
     }
 }
 
+/// The key of a symbol-keyed property. The symbol is kept so that the keys can be enumerated and
+/// traced, but keys are compared by the id of the symbol.
+#[derive(Debug, Clone, Copy)]
+pub struct SymbolKey(pub Value);
+
+impl SymbolKey {
+    pub fn id(&self) -> usize {
+        self.0.get_symbol_info().id
+    }
+}
+
+impl PartialEq for SymbolKey {
+    fn eq(&self, other: &SymbolKey) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for SymbolKey {}
+
+impl Hash for SymbolKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GlobalSymbolRegistry {
     list: Vec<(String, Value)>,
+    pub well_known: WellKnownSymbols,
+}
+
+/// https://tc39.github.io/ecma262/#sec-well-known-symbols
+#[derive(Debug, Clone)]
+pub struct WellKnownSymbols {
+    /// Symbol.hasInstance
+    pub has_instance: Value,
+    /// Symbol.isConcatSpreadable
+    pub is_concat_spreadable: Value,
+    /// Symbol.iterator
+    pub iterator: Value,
+    /// Symbol.toStringTag
+    pub to_string_tag: Value,
 }
 
 impl GlobalSymbolRegistry {
-    pub fn new() -> Self {
-        Self { list: vec![] }
+    pub fn new(allocator: &mut MemoryAllocator, object_prototypes: &ObjectPrototypes) -> Self {
+        Self {
+            list: vec![],
+            well_known: WellKnownSymbols {
+                has_instance: Value::symbol(
+                    allocator,
+                    object_prototypes,
+                    Some("Symbol.hasInstance".to_string()),
+                ),
+                is_concat_spreadable: Value::symbol(
+                    allocator,
+                    object_prototypes,
+                    Some("Symbol.isConcatSpreadable".to_string()),
+                ),
+                iterator: Value::symbol(
+                    allocator,
+                    object_prototypes,
+                    Some("Symbol.iterator".to_string()),
+                ),
+                to_string_tag: Value::symbol(
+                    allocator,
+                    object_prototypes,
+                    Some("Symbol.toStringTag".to_string()),
+                ),
+            },
+        }
     }
 
     pub fn for_(
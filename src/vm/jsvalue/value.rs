@@ -1,15 +1,21 @@
 use super::super::error;
 use super::super::frame::LexicalEnvironmentRef;
+pub use super::arguments::*;
 pub use super::array::*;
 pub use super::function::*;
+pub use super::generator::*;
+pub use super::iterator::*;
 pub use super::object::*;
+pub use super::promise::*;
 pub use super::prototype::*;
+pub use super::string::*;
 pub use super::symbol::*;
 use builtin::BuiltinFuncTy2;
 use gc;
 use id::get_unique_id;
+use node::FunctionKind;
+use regexp::RegExp;
 pub use rustc_hash::FxHashMap;
-use std::ffi::CString;
 
 pub const UNINITIALIZED: i32 = 0;
 pub const EMPTY: i32 = 1;
@@ -28,7 +34,7 @@ make_nanbox! {
     pub unsafe enum BoxedValue, Value {
         Number(f64),
         Bool(u8), // 0 | 1 = false | true
-        String(*mut CString), // TODO: Using CString is good for JIT. However, we need better one instead.
+        String(*mut JSString),
         Object(*mut ObjectInfo),
         // Symbol(*mut SymbolInfo),
         Other(i32) // UNINITIALIZED | EMPTY | NULL | UNDEFINED
@@ -44,7 +50,7 @@ macro_rules! make_property_map_sub {
          $configurable:ident
     ),*) => { {
         #[allow(unused_mut)]
-        let mut record = FxHashMap::default();
+        let mut record = PropertyMap::default();
         $( record.insert(
             (stringify!($property_name)).to_string(),
             Property::Data(DataProperty {
@@ -76,8 +82,9 @@ macro_rules! make_normal_object {
             ObjectInfo {
                 kind: ObjectKind2::Ordinary,
                 prototype: $object_prototypes.object,
-                property: FxHashMap::default(),
-                sym_property: FxHashMap::default()
+                property: PropertyMap::default(),
+                sym_property: FxHashMap::default(),
+                extensible: true
             }
         ))
     } };
@@ -87,7 +94,8 @@ macro_rules! make_normal_object {
                 kind: ObjectKind2::Ordinary,
                 prototype: $object_prototypes.object,
                 property: make_property_map_sub!($($property_name, $val, $x, $y, $z),* ),
-                sym_property: FxHashMap::default()
+                sym_property: FxHashMap::default(),
+                extensible: true
             }
             ))
     } };
@@ -116,19 +124,29 @@ impl Value {
     }
 
     pub fn string(memory_allocator: &mut gc::MemoryAllocator, body: String) -> Self {
-        Value::String(memory_allocator.alloc(CString::new(body).unwrap()))
+        Value::String(memory_allocator.alloc(JSString::from(body)))
+    }
+
+    pub fn js_string(memory_allocator: &mut gc::MemoryAllocator, body: JSString) -> Self {
+        Value::String(memory_allocator.alloc(body))
+    }
+
+    /// Make the string of a key in property maps. See JSString::from_property_key.
+    pub fn string_from_key(memory_allocator: &mut gc::MemoryAllocator, key: &str) -> Self {
+        Value::String(memory_allocator.alloc(JSString::from_property_key(key)))
     }
 
     pub fn object(
         memory_allocator: &mut gc::MemoryAllocator,
         object_prototypes: &ObjectPrototypes,
-        property: FxHashMap<String, Property>,
+        property: PropertyMap,
     ) -> Self {
         Value::Object(memory_allocator.alloc(ObjectInfo {
             kind: ObjectKind2::Ordinary,
             prototype: object_prototypes.object,
             property,
             sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 
@@ -150,6 +168,7 @@ impl Value {
                 name   => false, false, true : name_prop
             ),
             sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 
@@ -171,6 +190,7 @@ impl Value {
                 name   => false, false, true : name_prop
             ),
             sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 
@@ -189,40 +209,199 @@ impl Value {
         // exception_table: Vec<Exception>,
     ) -> Self {
         let name_prop = Value::string(memory_allocator, name.clone().unwrap_or("".to_string()));
-        let prototype = Value::object(memory_allocator, object_prototypes, FxHashMap::default());
+        let kind = info.kind;
+        let prototype = match kind {
+            FunctionKind::Ordinary if info.constructible => {
+                Value::object(memory_allocator, object_prototypes, PropertyMap::default())
+            }
+            // Methods and arrow functions have no 'prototype' property.
+            FunctionKind::Ordinary => Value::undefined(),
+            // Prototype of the generator objects. It has no 'constructor' property.
+            FunctionKind::Generator => Value::Object(memory_allocator.alloc(ObjectInfo {
+                kind: ObjectKind2::Ordinary,
+                prototype: object_prototypes.generator,
+                property: PropertyMap::default(),
+                sym_property: FxHashMap::default(),
+                extensible: true,
+            })),
+            // Async functions have no 'prototype' property.
+            FunctionKind::Async => Value::undefined(),
+        };
+
+        // Parameters after the first one with a default value or the rest parameter are not
+        // counted.
+        let length = info
+            .params
+            .iter()
+            .take_while(|param| !param.rest_param && !param.has_initializer)
+            .count();
+        let mut property = make_property_map!(
+            length    => false, false, true : Value::Number(length as f64),
+            name      => false, false, true : name_prop
+        );
+        if !prototype.is_undefined() {
+            property.insert(
+                "prototype".to_string(),
+                Property::Data(DataProperty {
+                    val: prototype,
+                    writable: true,
+                    enumerable: false,
+                    configurable: false,
+                }),
+            );
+        }
 
         let f = Value::Object(memory_allocator.alloc(ObjectInfo {
             prototype: object_prototypes.function,
-            property: make_property_map!(
-                length    => false, false, true : Value::Number(info.params.len() as f64), /* TODO: rest param */
-                name      => false, false, true : name_prop,
-                prototype => true , false, false: prototype
-            ),
+            property,
             kind: ObjectKind2::Function(FunctionObjectInfo {
                 name: name,
                 kind: FunctionObjectKind::User(info)
             }),
             sym_property: FxHashMap::default(),
+            extensible: true,
         }));
 
-        f.get_property_by_str_key("prototype")
-            .get_object_info()
-            .property
-            .insert("constructor".to_string(), Property::new_data_simple(f));
+        if kind == FunctionKind::Ordinary && !prototype.is_undefined() {
+            prototype.set_constructor(f);
+        }
 
         f
     }
 
+    pub fn promise_resolving_function(
+        memory_allocator: &mut gc::MemoryAllocator,
+        object_prototypes: &ObjectPrototypes,
+        info: PromiseResolvingFunctionInfo,
+    ) -> Self {
+        let name_prop = Value::string(memory_allocator, "".to_string());
+        Value::Object(memory_allocator.alloc(ObjectInfo {
+            kind: ObjectKind2::Function(FunctionObjectInfo {
+                name: None,
+                kind: FunctionObjectKind::PromiseResolving(info),
+            }),
+            prototype: object_prototypes.function,
+            property: make_property_map!(
+                length => false, false, true : Value::Number(1.0),
+                name   => false, false, true : name_prop
+            ),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
     pub fn array(
         memory_allocator: &mut gc::MemoryAllocator,
         object_prototypes: &ObjectPrototypes,
         elems: Vec<Property>,
     ) -> Self {
         Value::Object(memory_allocator.alloc(ObjectInfo {
-            kind: ObjectKind2::Array(ArrayObjectInfo { elems }),
+            kind: ObjectKind2::Array(ArrayObjectInfo {
+                elems,
+                length_writable: true,
+            }),
             prototype: object_prototypes.array,
             property: make_property_map!(),
             sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    pub fn arguments_object(
+        memory_allocator: &mut gc::MemoryAllocator,
+        object_prototypes: &ObjectPrototypes,
+        info: ArgumentsObjectInfo,
+        property: PropertyMap,
+    ) -> Self {
+        Value::Object(memory_allocator.alloc(ObjectInfo {
+            kind: ObjectKind2::Arguments(info),
+            prototype: object_prototypes.object,
+            property,
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    pub fn array_iterator(
+        memory_allocator: &mut gc::MemoryAllocator,
+        object_prototypes: &ObjectPrototypes,
+        iterated: Value,
+        kind: ArrayIterationKind,
+    ) -> Self {
+        Value::Object(memory_allocator.alloc(ObjectInfo {
+            kind: ObjectKind2::ArrayIterator(ArrayIteratorInfo {
+                iterated,
+                next_index: 0,
+                kind,
+            }),
+            prototype: object_prototypes.array_iterator,
+            property: PropertyMap::default(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    pub fn string_iterator(
+        memory_allocator: &mut gc::MemoryAllocator,
+        object_prototypes: &ObjectPrototypes,
+        iterated: Value,
+    ) -> Self {
+        Value::Object(memory_allocator.alloc(ObjectInfo {
+            kind: ObjectKind2::StringIterator(StringIteratorInfo {
+                iterated,
+                next_index: 0,
+            }),
+            prototype: object_prototypes.string_iterator,
+            property: PropertyMap::default(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    pub fn generator(memory_allocator: &mut gc::MemoryAllocator, prototype: Value) -> Self {
+        Value::Object(memory_allocator.alloc(ObjectInfo {
+            kind: ObjectKind2::Generator(GeneratorInfo {
+                state: GeneratorState::SuspendedStart,
+                frame: None,
+                stack: vec![],
+            }),
+            prototype,
+            property: PropertyMap::default(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    pub fn promise(
+        memory_allocator: &mut gc::MemoryAllocator,
+        object_prototypes: &ObjectPrototypes,
+    ) -> Self {
+        Value::Object(memory_allocator.alloc(ObjectInfo {
+            kind: ObjectKind2::Promise(PromiseInfo {
+                state: PromiseState::Pending,
+                result: Value::undefined(),
+                fulfill_reactions: vec![],
+                reject_reactions: vec![],
+                is_handled: false,
+            }),
+            prototype: object_prototypes.promise,
+            property: PropertyMap::default(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
+    pub fn regexp(
+        memory_allocator: &mut gc::MemoryAllocator,
+        object_prototypes: &ObjectPrototypes,
+        regexp: RegExp,
+    ) -> Self {
+        Value::Object(memory_allocator.alloc(ObjectInfo {
+            kind: ObjectKind2::RegExp(Box::new(regexp)),
+            prototype: object_prototypes.regexp,
+            property: make_property_map!(lastIndex => true, false, false: Value::Number(0.0)),
+            sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 
@@ -239,6 +418,7 @@ impl Value {
             prototype: object_prototypes.symbol,
             property: make_property_map!(),
             sym_property: FxHashMap::default(),
+            extensible: true,
         }))
     }
 }
@@ -251,6 +431,13 @@ impl Value {
         }
     }
 
+    pub fn is_null(&self) -> bool {
+        match self {
+            Value::Other(NULL) => true,
+            _ => false,
+        }
+    }
+
     pub fn is_object(&self) -> bool {
         match self {
             Value::Object(_) => true,
@@ -278,6 +465,26 @@ impl Value {
         }
     }
 
+    pub fn is_promise(&self) -> bool {
+        match self {
+            Value::Object(info) => match unsafe { &**info }.kind {
+                ObjectKind2::Promise(_) => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    pub fn is_regexp(&self) -> bool {
+        match self {
+            Value::Object(info) => match unsafe { &**info }.kind {
+                ObjectKind2::RegExp(_) => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
     pub fn is_string(&self) -> bool {
         match self {
             Value::String(_) => true,
@@ -310,9 +517,8 @@ impl Value {
         if !self.is_string() {
             return None;
         }
-        let s = self.into_str();
         let num = self.to_number(allocator);
-        if s == Value::Number(num).to_string() && is_integer(num) && num >= 0.0 {
+        if *self.as_string() == *Value::Number(num).to_string() && is_integer(num) && num >= 0.0 {
             Some(num as usize)
         } else {
             None
@@ -340,6 +546,32 @@ impl Value {
         }
     }
 
+    pub fn has_property(&self, allocator: &mut gc::MemoryAllocator, key: Value) -> bool {
+        match self {
+            Value::Object(obj_info) => unsafe { &**obj_info }.has_property(allocator, key),
+            _ => false,
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-delete-operator-runtime-semantics-evaluation
+    pub fn delete_property(&self, allocator: &mut gc::MemoryAllocator, key: Value) -> bool {
+        match self {
+            Value::String(s) => {
+                let len = unsafe { &**s }.len();
+                let idx = key
+                    .is_array_index()
+                    .or_else(|| key.is_canonical_numeric_index_string(allocator));
+                let is_own = match idx {
+                    Some(idx) => idx < len,
+                    None => key.is_string() && key.as_string() == "length",
+                };
+                !is_own
+            }
+            Value::Object(obj_info) => unsafe { &mut **obj_info }.delete_property(allocator, key),
+            _ => true,
+        }
+    }
+
     pub fn get_prototype(&self) -> Value {
         match self {
             Value::Object(info) => unsafe { &**info }.get_prototype(),
@@ -347,7 +579,7 @@ impl Value {
         }
     }
 
-    pub fn get_object_properties(&self) -> Option<&FxHashMap<String, Property>> {
+    pub fn get_object_properties(&self) -> Option<&PropertyMap> {
         match self {
             Value::Object(obj_info) => Some(&unsafe { &**obj_info }.property),
             _ => None,
@@ -370,34 +602,35 @@ impl Value {
         fn string_get_property(
             allocator: &mut gc::MemoryAllocator,
             object_prototypes: &ObjectPrototypes,
-            s: &str,
+            s: &JSString,
             key: Value,
         ) -> Result<Property, error::RuntimeError> {
-            match key {
-                Value::Number(idx) if is_integer(idx) => Ok(Property::new_data_simple(
-                    Value::string(allocator, s.chars().nth(idx as usize).unwrap().to_string()),
-                )),
-                Value::String(x) if unsafe { &*x }.to_str().unwrap() == "length" => {
-                    Ok(Property::new_data_simple(Value::Number(
-                        s.chars().fold(0, |x, c| x + c.len_utf16()) as f64,
+            let idx = key
+                .is_array_index()
+                .or_else(|| key.is_canonical_numeric_index_string(allocator));
+            match idx {
+                Some(idx) if idx < s.len() => {
+                    return Ok(Property::new_data_simple(Value::js_string(
+                        allocator,
+                        s.substring(idx, idx + 1),
                     )))
                 }
-                key => object_prototypes.string.get_object_info().get_property(
-                    allocator,
-                    object_prototypes,
-                    key,
-                ),
+                _ => {}
             }
+
+            if key.is_string() && key.as_string() == "length" {
+                return Ok(Property::new_data_simple(Value::Number(s.len() as f64)));
+            }
+
+            object_prototypes
+                .string
+                .get_object_info()
+                .get_property(allocator, object_prototypes, key)
         }
 
         match self {
             Value::String(s) => {
-                return string_get_property(
-                    allocator,
-                    object_prototypes,
-                    unsafe { &**s }.to_str().unwrap(),
-                    key,
-                );
+                return string_get_property(allocator, object_prototypes, unsafe { &**s }, key);
             }
             // TODO: Number
             _ => {}
@@ -411,6 +644,26 @@ impl Value {
         }
     }
 
+    /// Return the property keys that for-in statement iterates over.
+    pub fn enumerate_object_properties(&self, object_prototypes: &ObjectPrototypes) -> Vec<String> {
+        match self {
+            Value::String(s) => {
+                let len = unsafe { &**s }.len();
+                let mut keys: Vec<String> = (0..len).map(|i| i.to_string()).collect();
+                keys.extend(
+                    object_prototypes
+                        .string
+                        .get_object_info()
+                        .enumerate_object_properties(),
+                );
+                keys
+            }
+            Value::Object(obj_info) => unsafe { &**obj_info }.enumerate_object_properties(),
+            // TODO: Number and Boolean
+            _ => vec![],
+        }
+    }
+
     pub fn set_property_by_string_key(&self, key: String, val: Value) {
         match self {
             Value::Object(obj_info) => {
@@ -420,15 +673,49 @@ impl Value {
         }
     }
 
+    /// Assign val to the property with self as the receiver, as \[\[Set\]\] does. Primitives
+    /// have no properties to assign to, but can have inherited setters.
+    /// https://tc39.github.io/ecma262/#sec-putvalue
     pub fn set_property(
         &self,
         allocator: &mut gc::MemoryAllocator,
+        object_prototypes: &ObjectPrototypes,
         key: Value,
         val: Value,
-    ) -> Result<Option<Value>, error::RuntimeError> {
-        match self {
-            Value::Object(obj_info) => unsafe { &mut **obj_info }.set_property(allocator, key, val),
-            _ => Ok(None),
+    ) -> Result<SetResult, error::RuntimeError> {
+        let proto = match self {
+            Value::Object(obj_info) if !self.is_symbol() => {
+                return unsafe { &mut **obj_info }.set_property(allocator, key, val)
+            }
+            Value::Other(UNDEFINED) | Value::Other(NULL) => {
+                return Err(error::RuntimeError::Type(format!(
+                    "Cannot set property {} of {}",
+                    key.debug_string(false),
+                    self.debug_string(false)
+                )))
+            }
+            Value::String(_) => object_prototypes.string,
+            Value::Object(_) => object_prototypes.symbol,
+            // TODO: Number and Boolean
+            _ => object_prototypes.object,
+        };
+
+        if let Value::String(s) = self {
+            let len = unsafe { &**s }.len();
+            let idx = key
+                .is_array_index()
+                .or_else(|| key.is_canonical_numeric_index_string(allocator));
+            if idx.map_or(false, |idx| idx < len) || key.is_string() && key.as_string() == "length"
+            {
+                return Ok(SetResult::Stored(false));
+            }
+        }
+
+        match proto.get_object_info().lookup_property(allocator, key) {
+            Some(Property::Accessor(AccessorProperty { set, .. })) if !set.is_undefined() => {
+                Ok(SetResult::Setter(set))
+            }
+            _ => Ok(SetResult::Stored(false)),
         }
     }
 
@@ -444,6 +731,18 @@ impl Value {
         );
     }
 
+    pub fn set_symbol_method(&self, sym: Value, method: Value) {
+        self.get_object_info().sym_property.insert(
+            SymbolKey(sym),
+            Property::Data(DataProperty {
+                val: method,
+                writable: true,
+                enumerable: false,
+                configurable: true,
+            }),
+        );
+    }
+
     pub fn set_function_outer_environment(&mut self, env: LexicalEnvironmentRef) {
         match self {
             Value::Object(obj) => {
@@ -490,6 +789,42 @@ impl Value {
         }
     }
 
+    pub fn as_generator_mut(&self) -> &mut GeneratorInfo {
+        match self {
+            Value::Object(obj) => {
+                let obj = unsafe { &mut **obj };
+                match obj.kind {
+                    ObjectKind2::Generator(ref mut info) => return info,
+                    _ => panic!(),
+                }
+            }
+            e => panic!("{:?}", e),
+        }
+    }
+
+    pub fn as_promise_mut(&self) -> &mut PromiseInfo {
+        match self {
+            Value::Object(obj) => {
+                let obj = unsafe { &mut **obj };
+                match obj.kind {
+                    ObjectKind2::Promise(ref mut info) => return info,
+                    _ => panic!(),
+                }
+            }
+            e => panic!("{:?}", e),
+        }
+    }
+
+    pub fn as_regexp(&self) -> &RegExp {
+        match self {
+            Value::Object(obj) => match unsafe { &**obj }.kind {
+                ObjectKind2::RegExp(ref regexp) => regexp,
+                _ => panic!(),
+            },
+            e => panic!("{:?}", e),
+        }
+    }
+
     pub fn get_object_info(&self) -> &mut ObjectInfo {
         match self {
             Value::Object(obj) => unsafe { &mut **obj },
@@ -514,9 +849,9 @@ impl Value {
         }
     }
 
-    pub fn into_str(self) -> &'static str {
+    pub fn as_string(&self) -> &JSString {
         match self {
-            Value::String(s) => unsafe { &*s }.to_str().unwrap(),
+            Value::String(s) => unsafe { &**s },
             _ => panic!(),
         }
     }
@@ -552,7 +887,7 @@ impl Value {
             Value::Bool(1) => 1.0,
             Value::Number(n) => *n,
             Value::String(s) => {
-                let s = unsafe { &**s }.to_str().unwrap();
+                let s = unsafe { &**s }.to_string();
                 if s == "Infinity" || s == "-Infinity" {
                     ::std::f64::INFINITY
                 } else if s.len() == 0 {
@@ -571,13 +906,37 @@ impl Value {
         }
     }
 
+    /// Same as to_string, but keeps the code units of a string including lone surrogates.
+    pub fn to_js_string(&self) -> JSString {
+        match self {
+            Value::String(s) => unsafe { &**s }.clone(),
+            Value::Object(info) => match unsafe { &**info }.kind {
+                ObjectKind2::Array(ref info) => info.join(None, &mut vec![*self]),
+                ObjectKind2::RegExp(ref regexp) => JSString::new(regexp.to_units()),
+                _ => JSString::from(self.to_string()),
+            },
+            _ => JSString::from(self.to_string()),
+        }
+    }
+
+    /// Return the key of the string-keyed property self in property maps.
+    /// See JSString::to_property_key.
+    pub fn to_property_key(&self) -> String {
+        match self {
+            Value::String(s) => unsafe { &**s }.to_property_key(),
+            Value::Object(_) => self.to_js_string().to_property_key(),
+            _ => self.to_string(),
+        }
+    }
+
     // TODO: https://www.ecma-international.org/ecma-262/6.0/#sec-tostring
     pub fn to_string(&self) -> String {
         match self {
             Value::Bool(0) => "false".to_string(),
             Value::Bool(1) => "true".to_string(),
-            Value::String(s) => unsafe { &**s }.to_str().unwrap().to_string(),
+            Value::String(s) => unsafe { &**s }.to_string(),
             Value::Other(UNDEFINED) => "undefined".to_string(),
+            Value::Other(NULL) => "null".to_string(),
             Value::Number(n) => {
                 if n.is_nan() {
                     "NaN".to_string()
@@ -591,7 +950,8 @@ impl Value {
                 let info = unsafe { &**info };
                 match info.kind {
                     ObjectKind2::Ordinary => "[object Object]".to_string(),
-                    ObjectKind2::Array(ref info) => info.join(None),
+                    ObjectKind2::Array(ref info) => info.join(None, &mut vec![*self]).to_string(),
+                    ObjectKind2::RegExp(ref regexp) => regexp.to_string(),
                     _ => "[unimplemented]".to_string(), // TODO
                 }
             }
@@ -603,6 +963,7 @@ impl Value {
         match self {
             Value::Bool(0) => false,
             Value::Bool(1) => true,
+            Value::Other(NULL) | Value::Other(UNDEFINED) => false,
             Value::Number(num) => {
                 if *num == 0f64 || num.is_nan() {
                     false
@@ -610,11 +971,21 @@ impl Value {
                     true
                 }
             }
-            Value::String(s) => unsafe { &**s }.to_str().unwrap().len() != 0,
+            Value::String(s) => !unsafe { &**s }.is_empty(),
             _ => true,
         }
     }
 
+    /// https://tc39.github.io/ecma262/#sec-tointeger
+    pub fn to_integer(&self, allocator: &mut gc::MemoryAllocator) -> f64 {
+        let number = self.to_number(allocator);
+        if number.is_nan() {
+            0.0
+        } else {
+            number.trunc()
+        }
+    }
+
     /// https://tc39.github.io/ecma262/#sec-toint32
     pub fn to_int32(&self, allocator: &mut gc::MemoryAllocator) -> i32 {
         let number = self.to_number(allocator);
@@ -668,9 +1039,9 @@ impl Value {
                     }
                 }
 
-                Value::string(allocator, self.to_string())
+                Value::js_string(allocator, self.to_js_string())
             }
-            PreferredType::String => Value::string(allocator, self.to_string()),
+            PreferredType::String => Value::js_string(allocator, self.to_js_string()),
             PreferredType::Default => unreachable!(),
         }
     }
@@ -684,6 +1055,12 @@ impl Value {
                     ObjectKind2::Function(_) => None,
                     ObjectKind2::Array(_) => None,
                     ObjectKind2::Symbol(_) => Some(self), // TODO
+                    ObjectKind2::ArrayIterator(_)
+                    | ObjectKind2::StringIterator(_)
+                    | ObjectKind2::Generator(_)
+                    | ObjectKind2::Promise(_)
+                    | ObjectKind2::Arguments(_)
+                    | ObjectKind2::RegExp(_) => Some(self),
                 }
             }
             Value::String(_) => Some(self), // TODO
@@ -700,18 +1077,16 @@ impl Value {
         match (lprim, rprim) {
             (Value::Number(x), Value::Number(y)) => Value::Number(x + y),
             (Value::String(x), Value::String(y)) => {
-                let x = unsafe { &*x }.to_str().unwrap();
-                let y = unsafe { &*y }.to_str().unwrap();
-                let cat = format!("{}{}", x, y);
-                Value::string(allocator, cat)
+                let cat = unsafe { &*x }.concat(unsafe { &*y });
+                Value::js_string(allocator, cat)
             }
             (Value::String(x), _) => {
-                let x = unsafe { &*x }.to_str().unwrap();
-                Value::string(allocator, format!("{}{}", x, rprim.to_string()))
+                let cat = unsafe { &*x }.concat(&JSString::from(rprim.to_string()));
+                Value::js_string(allocator, cat)
             }
             (_, Value::String(y)) => {
-                let y = unsafe { &*y }.to_str().unwrap();
-                Value::string(allocator, format!("{}{}", lprim.to_string(), y))
+                let cat = JSString::from(lprim.to_string()).concat(unsafe { &*y });
+                Value::js_string(allocator, cat)
             }
             (x, y) => Value::Number(x.to_number(allocator) + y.to_number(allocator)),
         }
@@ -746,6 +1121,16 @@ impl Value {
         }
     }
 
+    /// https://tc39.github.io/ecma262/#sec-exp-operator
+    pub fn exp(self, allocator: &mut gc::MemoryAllocator, val: Value) -> Self {
+        let base = self.to_number(allocator);
+        let exponent = val.to_number(allocator);
+        if exponent.is_nan() || base.abs() == 1.0 && exponent.is_infinite() {
+            return Value::Number(::std::f64::NAN);
+        }
+        Value::Number(base.powf(exponent))
+    }
+
     pub fn and(self, allocator: &mut gc::MemoryAllocator, val: Value) -> Self {
         Value::Number((self.to_int32(allocator) & val.to_int32(allocator)) as f64)
     }
@@ -821,13 +1206,24 @@ impl Value {
 
         match self {
             Value::Number(_) => Value::bool(self.into_number() == val.into_number()),
-            Value::String(_) => Value::bool(self.into_str() == val.into_str()),
+            Value::String(_) => Value::bool(self.as_string() == val.as_string()),
             Value::Bool(_) => Value::bool(self.into_bool() == val.into_bool()),
             Value::Object(_) => Value::bool(get_obj_ptr(self) == get_obj_ptr(val)),
             _ => Value::bool(false),
         }
     }
 
+    /// https://tc39.github.io/ecma262/#sec-samevalue
+    pub fn same_value(self, val: Value) -> bool {
+        match (self, val) {
+            (Value::Number(x), Value::Number(y)) if x.is_nan() && y.is_nan() => true,
+            (Value::Number(x), Value::Number(y)) => {
+                x == y && x.is_sign_negative() == y.is_sign_negative()
+            }
+            (x, y) => x.strict_eq(y).into_bool(),
+        }
+    }
+
     pub fn ne(self, allocator: &mut gc::MemoryAllocator, val: Value) -> Self {
         Value::bool(!self.eq(allocator, val).into_bool())
     }
@@ -842,7 +1238,7 @@ impl Value {
         let py = val.to_primitive(allocator, None);
 
         if let (Value::String(x), Value::String(y)) = (px, py) {
-            return Value::bool(cstrp_to_str(x) < cstrp_to_str(y));
+            return Value::bool(unsafe { &*x } < unsafe { &*y });
         }
 
         let nx = px.to_number(allocator);
@@ -914,7 +1310,13 @@ impl Value {
                     ObjectKind2::Function(_) => "function",
                     ObjectKind2::Array(_) => "object",
                     ObjectKind2::Symbol(_) => "symbol",
-                    ObjectKind2::Ordinary => "object",
+                    ObjectKind2::ArrayIterator(_)
+                    | ObjectKind2::StringIterator(_)
+                    | ObjectKind2::Generator(_)
+                    | ObjectKind2::Promise(_)
+                    | ObjectKind2::Arguments(_)
+                    | ObjectKind2::RegExp(_)
+                    | ObjectKind2::Ordinary => "object",
                 }
             }
             _ => panic!(),
@@ -980,9 +1382,9 @@ impl Value {
             Value::String(s) => {
                 let s = unsafe { &**s };
                 if nest {
-                    format!("'{}'", s.to_str().unwrap())
+                    format!("'{}'", s)
                 } else {
-                    s.to_str().unwrap().to_string()
+                    s.to_string()
                 }
             }
             Value::Object(obj_info) => {
@@ -1002,6 +1404,35 @@ impl Value {
                         "Symbol({})",
                         info.description.as_ref().unwrap_or(&"".to_string())
                     ),
+                    ObjectKind2::ArrayIterator(_) => "Object [Array Iterator] {}".to_string(),
+                    ObjectKind2::StringIterator(_) => "Object [String Iterator] {}".to_string(),
+                    ObjectKind2::Generator(_) => "Object [Generator] {}".to_string(),
+                    ObjectKind2::Arguments(ref info) => {
+                        let key_val = (&obj_info.property)
+                            .iter()
+                            .filter(|(_, prop)| prop.is_enumerable())
+                            .map(|(key, prop)| (key, info.reflect_parameter(key, *prop)))
+                            .collect::<Vec<(&String, Property)>>();
+                        let mut sorted_key_val = key_val
+                            .iter()
+                            .map(|(key, prop)| (*key, prop))
+                            .collect::<Vec<(&String, &Property)>>();
+                        sorted_key_val
+                            .sort_by(|(key1, _), (key2, _)| key1.as_str().cmp(key2.as_str()));
+
+                        format!("[Arguments] {{ {} }}", property_string(sorted_key_val))
+                    }
+                    ObjectKind2::RegExp(ref regexp) => regexp.to_string(),
+                    ObjectKind2::Promise(ref info) => match info.state {
+                        PromiseState::Pending => "Promise { <pending> }".to_string(),
+                        PromiseState::Fulfilled => {
+                            format!("Promise {{ {} }}", info.result.debug_string(true))
+                        }
+                        PromiseState::Rejected => format!(
+                            "Promise {{ <rejected> {} }}",
+                            info.result.debug_string(true)
+                        ),
+                    },
                     ObjectKind2::Function(ref func_info) => {
                         if let Some(ref name) = func_info.name {
                             format!("[Function: {}]", name)
@@ -1075,8 +1506,3 @@ impl Value {
 pub fn is_integer(n: f64) -> bool {
     n - n.floor() == 0.0
 }
-
-#[inline]
-pub fn cstrp_to_str(p: *mut CString) -> &'static str {
-    unsafe { &*p }.to_str().unwrap()
-}
@@ -5,16 +5,24 @@ use super::{
     constant,
     error::*,
     frame,
+    frame::SubroutineKind,
     jsvalue::function::{DestinationKind, ThisMode},
     jsvalue::prototype::ObjectPrototypes,
     jsvalue::symbol::GlobalSymbolRegistry,
     jsvalue::value::*,
 };
+use builtin::BuiltinFuncTy2;
+use builtins::iterator::create_iter_result_object;
+use builtins::promise;
 use bytecode_gen::show_inst2;
 use bytecode_gen::ByteCode;
 use bytecode_gen::VMInst;
 use gc;
+use gc::GcTarget;
+use node::FunctionKind;
 use rustc_hash::FxHashMap;
+use std::collections::VecDeque;
+use std::mem;
 
 // New VM
 
@@ -29,6 +37,9 @@ pub struct VM2 {
     pub stack: Vec<BoxedValue>,
     pub saved_frame: Vec<frame::Frame>,
     pub to_source_map: FxHashMap<usize, codegen::ToSourcePos>,
+    pub job_queue: VecDeque<Job>,
+    /// Promises rejected without any handlers. Reported after the job queue becomes empty.
+    pub rejected_promises: Vec<Value>,
 }
 
 macro_rules! gc_lock {
@@ -44,9 +55,14 @@ impl VM2 {
     pub fn new() -> Self {
         let mut memory_allocator = gc::MemoryAllocator::new();
         let object_prototypes = ObjectPrototypes::new(&mut memory_allocator);
+        let global_symbol_registry =
+            GlobalSymbolRegistry::new(&mut memory_allocator, &object_prototypes);
+        object_prototypes
+            .define_symbol_methods(&mut memory_allocator, &global_symbol_registry.well_known);
         let global_env = frame::LexicalEnvironment::new_global_initialized(
             &mut memory_allocator,
             &object_prototypes,
+            &global_symbol_registry.well_known,
         );
         let global_environment = frame::LexicalEnvironmentRef(memory_allocator.alloc(global_env));
         VM2 {
@@ -54,10 +70,12 @@ impl VM2 {
             memory_allocator,
             object_prototypes,
             constant_table: constant::ConstantTable::new(),
-            global_symbol_registry: GlobalSymbolRegistry::new(),
+            global_symbol_registry,
             stack: vec![],
             saved_frame: vec![],
             to_source_map: FxHashMap::default(),
+            job_queue: VecDeque::new(),
+            rejected_promises: vec![],
         }
     }
 
@@ -182,6 +200,36 @@ impl VM2 {
         );
 
         self.run(frame)?;
+        self.run_jobs()?;
+
+        Ok(())
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-enqueuejob
+    pub fn enqueue_job(&mut self, job: Job) {
+        // The job queue is traced only at the beginning of marking, so the values moved into
+        // the queue during marking must be marked here.
+        job.initial_trace(&mut self.memory_allocator.roots);
+        self.job_queue.push_back(job);
+    }
+
+    /// Run the jobs in the job queue until it becomes empty. Then the reason of the first
+    /// promise rejected without any handlers is thrown as an uncaught exception.
+    /// https://tc39.github.io/ecma262/#sec-runjobs
+    pub fn run_jobs(&mut self) -> VMResult {
+        let cur_frame = frame::Frame::new_empty_with_this(Value::undefined(), false);
+        // The running job is left in the queue so that the values it holds are not collected.
+        while let Some(job) = self.job_queue.front().cloned() {
+            let result = promise::run_job(self, job, &cur_frame);
+            self.job_queue.pop_front();
+            result?;
+        }
+
+        if self.rejected_promises.len() > 0 {
+            let reason = self.rejected_promises[0].as_promise_mut().result;
+            self.rejected_promises.clear();
+            return Err(RuntimeError::Exception2(reason, None));
+        }
 
         Ok(())
     }
@@ -200,13 +248,36 @@ impl VM2 {
         let info = callee.as_function();
 
         match info.kind {
-            FunctionObjectKind::Builtin(func) => gc_lock!(
-                self,
-                args,
-                func(self, args, &frame::Frame::new_empty_with_this(this, false))
-            ),
+            FunctionObjectKind::Builtin(func) => {
+                // As in enter_builtin_function(), the caller's frame is saved so that it's
+                // traced while a nested run(), such as a resumed generator, runs GC.
+                self.saved_frame
+                    .push(cur_frame.clone().saved_stack_len(self.stack.len()));
+                let result = gc_lock!(
+                    self,
+                    args,
+                    func(self, args, &frame::Frame::new_empty_with_this(this, false))
+                );
+                self.saved_frame.pop();
+                result
+            }
+            FunctionObjectKind::User(ref user_func)
+                if user_func.kind == FunctionKind::Generator =>
+            {
+                let generator = self.create_generator(callee, user_func, args, this)?;
+                self.stack.push(generator.into());
+                Ok(())
+            }
+            FunctionObjectKind::User(ref user_func) if user_func.kind == FunctionKind::Async => {
+                let promise = self.call_async_function(user_func, args, this)?;
+                self.stack.push(promise.into());
+                Ok(())
+            }
             FunctionObjectKind::User(ref user_func) => {
-                self.call_user_function(user_func, args, this, cur_frame, false)
+                self.call_user_function(user_func, args, this, cur_frame)
+            }
+            FunctionObjectKind::PromiseResolving(ref info) => {
+                promise::call_promise_resolving_function(self, info, args, cur_frame)
             }
         }
     }
@@ -217,11 +288,24 @@ impl VM2 {
         args: &[Value],
         this: Value,
         cur_frame: &frame::Frame,
-        constructor_call: bool,
     ) -> VMResult {
+        let frame = self.create_function_frame(user_func, args, this, None)?;
+
         self.saved_frame
             .push(cur_frame.clone().saved_stack_len(self.stack.len()));
 
+        self.run(frame.escape())
+    }
+
+    /// Create a frame to execute the function. Parameters, variables and function declarations
+    /// are instantiated in the new environment.
+    fn create_function_frame(
+        &mut self,
+        user_func: &UserFunctionInfo,
+        args: &[Value],
+        this: Value,
+        new_target: Option<Value>,
+    ) -> Result<frame::Frame, RuntimeError> {
         let this = if user_func.this_mode == ThisMode::Lexical {
             // Arrow function
             user_func.outer.unwrap().get_this_binding()
@@ -229,37 +313,74 @@ impl VM2 {
             this
         };
 
-        let var_env_ref = self.create_function_environment(
-            |vm, record| {
+        // With parameter expressions, the variables are declared in another environment, which
+        // is entered by ENTER_FUNCTION_BODY after the default values are evaluated.
+        let separate_var_env = !user_func.simple_parameter_list;
+
+        let init_record = |vm: &mut VM2, record: &mut FxHashMap<String, Value>| {
+            if !separate_var_env {
                 for name in &user_func.var_names {
                     record.insert(name.clone(), Value::undefined());
                 }
+            }
 
-                for (i, FunctionParameter { name, rest_param }) in
-                    user_func.params.iter().enumerate()
-                {
-                    record.insert(
-                        name.clone(),
-                        if *rest_param {
-                            Value::array(
-                                &mut vm.memory_allocator,
-                                &vm.object_prototypes,
-                                (*args)
-                                    .get(i..)
-                                    .unwrap_or(&vec![])
-                                    .iter()
-                                    .map(|elem| Property::new_data_simple(*elem))
-                                    .collect::<Vec<Property>>(),
-                            )
-                        } else {
-                            *args.get(i).unwrap_or(&Value::undefined())
-                        },
-                    );
+            for param in &user_func.params {
+                for name in &param.bound_names {
+                    record.insert(name.clone(), Value::undefined());
                 }
-            },
-            this,
-            user_func.outer,
-        );
+            }
+
+            for (i, param) in user_func.params.iter().enumerate() {
+                record.insert(
+                    param.name.clone(),
+                    if param.rest_param {
+                        Value::array(
+                            &mut vm.memory_allocator,
+                            &vm.object_prototypes,
+                            (*args)
+                                .get(i..)
+                                .unwrap_or(&vec![])
+                                .iter()
+                                .map(|elem| Property::new_data_simple(*elem))
+                                .collect::<Vec<Property>>(),
+                        )
+                    } else {
+                        *args.get(i).unwrap_or(&Value::undefined())
+                    },
+                );
+            }
+        };
+
+        // Arrow functions have no own 'this', so super() and 'this' refer to the enclosing
+        // function environment.
+        let mut func_env_ref = if user_func.this_mode == ThisMode::Lexical {
+            self.create_declarative_environment(&init_record, user_func.outer)
+        } else {
+            self.create_function_environment(
+                &init_record,
+                this,
+                new_target.unwrap_or(Value::undefined()),
+                user_func.outer,
+            )
+        };
+
+        if user_func.uses_arguments {
+            let arguments = self.create_arguments_object(user_func, args, func_env_ref);
+            func_env_ref.set_own_value("arguments".to_string(), arguments)?;
+        }
+
+        let var_env_ref = if separate_var_env {
+            self.create_declarative_environment(
+                |_, record| {
+                    for name in &user_func.var_names {
+                        record.insert(name.clone(), Value::undefined());
+                    }
+                },
+                Some(func_env_ref),
+            )
+        } else {
+            func_env_ref
+        };
 
         let mut lex_env_ref = self.create_declarative_environment(
             |_, record| {
@@ -277,10 +398,20 @@ impl VM2 {
             lex_env_ref.set_value(name, func)?;
         }
 
-        let exec_ctx = frame::ExecutionContext {
-            variable_environment: var_env_ref,
-            lexical_environment: lex_env_ref,
-            saved_lexical_environment: vec![],
+        // Default values are evaluated in the environment of the parameters, and the
+        // environment of the body is restored after that.
+        let exec_ctx = if separate_var_env {
+            frame::ExecutionContext {
+                variable_environment: var_env_ref,
+                lexical_environment: func_env_ref,
+                saved_lexical_environment: vec![lex_env_ref],
+            }
+        } else {
+            frame::ExecutionContext {
+                variable_environment: var_env_ref,
+                lexical_environment: lex_env_ref,
+                saved_lexical_environment: vec![],
+            }
         };
 
         let frame = frame::Frame::new(
@@ -288,18 +419,272 @@ impl VM2 {
             user_func.code.clone(),
             user_func.exception_table.clone(),
             this,
-            constructor_call,
+            new_target.is_some(),
         )
-        .escape()
         .id(user_func.id);
 
-        self.run(frame)
+        Ok(if user_func.derived_constructor {
+            frame.derived_constructor()
+        } else {
+            frame
+        })
+    }
+
+    /// Create the arguments object of a call. If the parameter list is simple, the indices
+    /// are mapped to the parameters in `env`, where the last one wins for duplicated names.
+    /// https://tc39.github.io/ecma262/#sec-createmappedargumentsobject
+    fn create_arguments_object(
+        &mut self,
+        user_func: &UserFunctionInfo,
+        args: &[Value],
+        env: frame::LexicalEnvironmentRef,
+    ) -> Value {
+        let mut property = make_property_map!(
+            length => true, false, true: Value::Number(args.len() as f64)
+        );
+        for (i, arg) in args.iter().enumerate() {
+            property.insert(i.to_string(), Property::new_data_simple(*arg));
+        }
+
+        let mut mapped_names = vec![];
+        if user_func.simple_parameter_list {
+            let len = user_func.params.len().min(args.len());
+            mapped_names = vec![None; len];
+            for i in (0..len).rev() {
+                let name = &user_func.params[i].name;
+                if !mapped_names.contains(&Some(name.clone())) {
+                    mapped_names[i] = Some(name.clone());
+                }
+            }
+        }
+
+        let arguments = Value::arguments_object(
+            &mut self.memory_allocator,
+            &self.object_prototypes,
+            ArgumentsObjectInfo { env, mapped_names },
+            property,
+        );
+
+        let sym_iterator = self.global_symbol_registry.well_known.iterator;
+        let array_prototype_values = self
+            .object_prototypes
+            .array
+            .get_object_info()
+            .sym_property
+            .get(&SymbolKey(sym_iterator))
+            .unwrap()
+            .as_data()
+            .val;
+        arguments.set_symbol_method(sym_iterator, array_prototype_values);
+
+        arguments
+    }
+
+    /// Create a generator object in the suspended start state instead of executing the body of
+    /// the generator function.
+    fn create_generator(
+        &mut self,
+        callee: Value,
+        user_func: &UserFunctionInfo,
+        args: &[Value],
+        this: Value,
+    ) -> Result<Value, RuntimeError> {
+        let frame = self.create_function_frame(user_func, args, this, None)?;
+        let prototype = callee.get_property_by_str_key("prototype");
+        let prototype = if prototype.is_object() {
+            prototype
+        } else {
+            self.object_prototypes.generator
+        };
+        let generator = Value::generator(&mut self.memory_allocator, prototype);
+        generator.as_generator_mut().frame = Some(frame.escape().generator(generator));
+        Ok(generator)
+    }
+
+    /// Resume the generator and push the iterator result object onto the stack.
+    /// https://tc39.github.io/ecma262/#sec-generatorresume
+    pub fn resume_generator(&mut self, generator: Value, val: Value, kind: ResumeKind) -> VMResult {
+        let info = match generator {
+            Value::Object(obj) => match unsafe { &mut *obj }.kind {
+                ObjectKind2::Generator(ref mut info) => info,
+                _ => return Err(RuntimeError::Type("not a Generator".to_string())),
+            },
+            _ => return Err(RuntimeError::Type("not a Generator".to_string())),
+        };
+
+        match info.state {
+            GeneratorState::Executing => {
+                return Err(RuntimeError::Type(
+                    "Generator is already running".to_string(),
+                ))
+            }
+            GeneratorState::SuspendedStart if kind != ResumeKind::Next => {
+                info.state = GeneratorState::Completed;
+                info.frame = None;
+            }
+            _ => {}
+        }
+
+        if info.state == GeneratorState::Completed {
+            let result = match kind {
+                ResumeKind::Next => create_iter_result_object(self, Value::undefined(), true),
+                ResumeKind::Return => create_iter_result_object(self, val, true),
+                ResumeKind::Throw => return Err(RuntimeError::Exception2(val, None)),
+            };
+            self.stack.push(result.into());
+            return Ok(());
+        }
+
+        let (val, done) = self.run_generator(generator, val, kind)?;
+        let result = create_iter_result_object(self, val, done);
+        self.stack.push(result.into());
+        Ok(())
+    }
+
+    /// Execute the suspended generator until it yields or completes. Return the yielded or
+    /// returned value and whether the generator has completed.
+    fn run_generator(
+        &mut self,
+        generator: Value,
+        val: Value,
+        kind: ResumeKind,
+    ) -> Result<(Value, bool), RuntimeError> {
+        // The generator is traced only while it is suspended, so the values held by it must be
+        // marked here before they are moved out of it.
+        generator
+            .get_object_info()
+            .kind
+            .initial_trace(&mut self.memory_allocator.roots);
+
+        let info = generator.as_generator_mut();
+        let frame = info.frame.take().unwrap();
+
+        // The frame of the generator returns to this empty frame.
+        self.saved_frame.push(
+            frame::Frame::new_empty_with_this(generator, false).saved_stack_len(self.stack.len()),
+        );
+        self.stack.append(&mut info.stack);
+        if info.state == GeneratorState::SuspendedYield {
+            self.stack.push(val.into());
+            self.stack.push(Value::Number(kind as i32 as f64).into());
+        }
+        info.state = GeneratorState::Executing;
+
+        let result = self.run(frame);
+
+        // The generator may have been suspended by 'yield' during run().
+        let info = generator.as_generator_mut();
+        if let Err(err) = result {
+            info.state = GeneratorState::Completed;
+            return Err(err);
+        }
+
+        let val: Value = self.stack.pop().unwrap().into();
+        let done = info.state != GeneratorState::SuspendedYield;
+        if done {
+            info.state = GeneratorState::Completed;
+        }
+        Ok((val, done))
+    }
+
+    /// Start executing the body of the async function, and return the promise for its result.
+    /// The body is executed by an internal generator object, which is suspended by 'await'.
+    /// https://tc39.github.io/ecma262/#sec-async-functions-abstract-operations-async-function-start
+    fn call_async_function(
+        &mut self,
+        user_func: &UserFunctionInfo,
+        args: &[Value],
+        this: Value,
+    ) -> Result<Value, RuntimeError> {
+        let frame = self.create_function_frame(user_func, args, this, None)?;
+        let generator = Value::generator(&mut self.memory_allocator, Value::null());
+        generator.as_generator_mut().frame = Some(frame.escape().generator(generator));
+        let promise = Value::promise(&mut self.memory_allocator, &self.object_prototypes);
+        gc_lock!(
+            self,
+            &[promise],
+            self.resume_async_function(generator, promise, Value::undefined(), ResumeKind::Next)
+        )?;
+        Ok(promise)
+    }
+
+    /// Resume the async function until it awaits or completes. When it awaits, it is resumed
+    /// again by a job after the awaited value is settled. When it completes, the promise for its
+    /// result is settled.
+    /// https://tc39.github.io/ecma262/#await
+    pub fn resume_async_function(
+        &mut self,
+        generator: Value,
+        promise: Value,
+        val: Value,
+        kind: ResumeKind,
+    ) -> VMResult {
+        let cur_frame = frame::Frame::new_empty_with_this(Value::undefined(), false);
+        match self.run_generator(generator, val, kind) {
+            Ok((awaited, false)) => {
+                let awaited = promise::to_promise(self, awaited, &cur_frame)?;
+                let handler = PromiseReactionHandler::Await { generator, promise };
+                promise::perform_promise_then(self, awaited, handler.clone(), handler, None);
+            }
+            Ok((val, true)) => promise::resolve_promise(self, promise, val, &cur_frame)?,
+            Err(err) => {
+                let reason = err.to_value2(&mut self.memory_allocator);
+                promise::reject_promise(self, promise, reason);
+            }
+        }
+        Ok(())
+    }
+
+    /// Pass the resumption of the generator executing 'yield*' to the inner iterator.
+    /// Return the iterator result object.
+    /// https://tc39.github.io/ecma262/#sec-generator-function-definitions-runtime-semantics-evaluation
+    fn resume_inner_iterator(
+        &mut self,
+        iterator: Value,
+        received: Value,
+        kind: ResumeKind,
+        cur_frame: &frame::Frame,
+    ) -> Result<Value, RuntimeError> {
+        let method_name = match kind {
+            ResumeKind::Next => "next",
+            ResumeKind::Throw => "throw",
+            ResumeKind::Return => "return",
+        };
+        let method_key = Value::string(&mut self.memory_allocator, method_name.to_string());
+        let method = self.get_property(iterator, method_key, cur_frame)?;
+
+        if method.is_undefined() || method.is_null() {
+            match kind {
+                ResumeKind::Next => {}
+                ResumeKind::Throw => {
+                    self.iterator_close(iterator, cur_frame)?;
+                    return Err(RuntimeError::Type(
+                        "The iterator does not provide a 'throw' method".to_string(),
+                    ));
+                }
+                ResumeKind::Return => {
+                    return Ok(create_iter_result_object(self, received, true));
+                }
+            }
+        }
+
+        self.call_function(method, &[received], iterator, cur_frame)?;
+        let result: Value = self.stack.pop().unwrap().into();
+        if !result.is_object() {
+            return Err(RuntimeError::Type(format!(
+                "Iterator result {} is not an object",
+                result.debug_string(true)
+            )));
+        }
+
+        Ok(result)
     }
 
     fn get_property_to_stack_top(
         &mut self,
         parent: Value,
         key: Value,
+        receiver: Value,
         cur_frame: &mut frame::Frame,
     ) -> VMResult {
         let val = parent.get_property(&mut self.memory_allocator, &self.object_prototypes, key)?;
@@ -313,7 +698,7 @@ impl VM2 {
                     self.stack.push(Value::undefined().into());
                     return Ok(());
                 }
-                self.enter_function(get, &[], parent, cur_frame, false)
+                self.enter_function(get, &[], receiver, cur_frame, false)
             }
         }
     }
@@ -337,6 +722,8 @@ impl VM2 {
         }
     }
 
+    /// Assign val to the property as PutValue does. A failed assignment is ignored since strict
+    /// mode code, where it is a TypeError, is not supported.
     pub fn set_property(
         &mut self,
         parent: Value,
@@ -344,13 +731,250 @@ impl VM2 {
         val: Value,
         cur_frame: &frame::Frame,
     ) -> VMResult {
-        let maybe_setter = parent.set_property(&mut self.memory_allocator, key, val)?;
-        if let Some(setter) = maybe_setter {
-            self.call_function(setter, &[val], parent, cur_frame)?;
-            self.stack.pop().unwrap(); // Pop undefined (setter's return value)
+        self.set_property_by_receiver(parent, key, val, cur_frame)?;
+        Ok(())
+    }
+
+    /// Assign val to the property and throw a TypeError if it fails, as the builtin functions do.
+    /// https://tc39.github.io/ecma262/#sec-set-o-p-v-throw
+    pub fn set_property_or_throw(
+        &mut self,
+        parent: Value,
+        key: Value,
+        val: Value,
+        cur_frame: &frame::Frame,
+    ) -> VMResult {
+        if !self.set_property_by_receiver(parent, key, val, cur_frame)? {
+            return Err(RuntimeError::Type(format!(
+                "Cannot assign to property {} of {}",
+                key.debug_string(false),
+                parent.debug_string(true)
+            )));
         }
         Ok(())
     }
+
+    /// Return false if the assignment failed.
+    fn set_property_by_receiver(
+        &mut self,
+        parent: Value,
+        key: Value,
+        val: Value,
+        cur_frame: &frame::Frame,
+    ) -> Result<bool, RuntimeError> {
+        match parent.set_property(
+            &mut self.memory_allocator,
+            &self.object_prototypes,
+            key,
+            val,
+        )? {
+            SetResult::Stored(stored) => Ok(stored),
+            SetResult::Setter(setter) => {
+                self.call_function(setter, &[val], parent, cur_frame)?;
+                self.stack.pop().unwrap(); // Pop undefined (setter's return value)
+                Ok(true)
+            }
+        }
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-instanceofoperator
+    pub fn instance_of(
+        &mut self,
+        val: Value,
+        target: Value,
+        cur_frame: &frame::Frame,
+    ) -> Result<bool, RuntimeError> {
+        if !target.is_object() || target.is_symbol() {
+            return Err(RuntimeError::Type(format!(
+                "Right-hand side of 'instanceof' is not an object: {}",
+                target.debug_string(true)
+            )));
+        }
+
+        let sym_has_instance = self.global_symbol_registry.well_known.has_instance;
+        let method = self.get_property(target, sym_has_instance, cur_frame)?;
+        if !method.is_undefined() && !method.is_null() {
+            if !method.is_function_object() {
+                return Err(RuntimeError::Type(
+                    "Symbol.hasInstance method is not callable".to_string(),
+                ));
+            }
+            self.call_function(method, &[val], target, cur_frame)?;
+            let result: Value = self.stack.pop().unwrap().into();
+            return Ok(result.to_boolean());
+        }
+
+        if !target.is_function_object() {
+            return Err(RuntimeError::Type(format!(
+                "Right-hand side of 'instanceof' is not callable: {}",
+                target.debug_string(true)
+            )));
+        }
+
+        self.ordinary_has_instance(target, val)
+    }
+
+    /// Walk the prototype chain of `val` looking for the `prototype` property of `constructor`.
+    /// https://tc39.github.io/ecma262/#sec-ordinaryhasinstance
+    pub fn ordinary_has_instance(
+        &mut self,
+        constructor: Value,
+        val: Value,
+    ) -> Result<bool, RuntimeError> {
+        if !constructor.is_function_object() || !val.is_object() || val.is_symbol() {
+            return Ok(false);
+        }
+
+        let prototype = constructor.get_property_by_str_key("prototype");
+        if !prototype.is_object() {
+            return Err(RuntimeError::Type(
+                "Function has non-object prototype in instanceof check".to_string(),
+            ));
+        }
+
+        let mut object = val.get_prototype();
+        while object.is_object() {
+            if object == prototype {
+                return Ok(true);
+            }
+            object = object.get_prototype();
+        }
+
+        Ok(false)
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-getiterator
+    pub fn get_iterator(
+        &mut self,
+        iterable: Value,
+        cur_frame: &frame::Frame,
+    ) -> Result<Value, RuntimeError> {
+        let sym_iterator = self.global_symbol_registry.well_known.iterator;
+        let method = self.get_property(iterable, sym_iterator, cur_frame)?;
+        if !method.is_function_object() {
+            return Err(RuntimeError::Type(format!(
+                "{} is not iterable",
+                iterable.debug_string(true)
+            )));
+        }
+
+        self.call_function(method, &[], iterable, cur_frame)?;
+        let iterator: Value = self.stack.pop().unwrap().into();
+        if !iterator.is_object() {
+            return Err(RuntimeError::Type(
+                "Result of the Symbol.iterator method is not an object".to_string(),
+            ));
+        }
+
+        Ok(iterator)
+    }
+
+    /// Call the next method of the iterator. Return None if the iteration has finished.
+    /// https://tc39.github.io/ecma262/#sec-iteratorstep
+    pub fn iterator_step(
+        &mut self,
+        iterator: Value,
+        cur_frame: &frame::Frame,
+    ) -> Result<Option<Value>, RuntimeError> {
+        let next_key = Value::string(&mut self.memory_allocator, "next".to_string());
+        let next = self.get_property(iterator, next_key, cur_frame)?;
+        self.call_function(next, &[], iterator, cur_frame)?;
+
+        let result: Value = self.stack.pop().unwrap().into();
+        if !result.is_object() {
+            return Err(RuntimeError::Type(format!(
+                "Iterator result {} is not an object",
+                result.debug_string(true)
+            )));
+        }
+
+        let done_key = Value::string(&mut self.memory_allocator, "done".to_string());
+        if self.get_property(result, done_key, cur_frame)?.to_boolean() {
+            return Ok(None);
+        }
+
+        let value_key = Value::string(&mut self.memory_allocator, "value".to_string());
+        Ok(Some(self.get_property(result, value_key, cur_frame)?))
+    }
+
+    /// https://tc39.github.io/ecma262/#sec-iteratorclose
+    pub fn iterator_close(&mut self, iterator: Value, cur_frame: &frame::Frame) -> VMResult {
+        let return_key = Value::string(&mut self.memory_allocator, "return".to_string());
+        let return_ = self.get_property(iterator, return_key, cur_frame)?;
+        if return_.is_undefined() || return_.is_null() {
+            return Ok(());
+        }
+
+        self.call_function(return_, &[], iterator, cur_frame)?;
+        let result: Value = self.stack.pop().unwrap().into();
+        if !result.is_object() {
+            return Err(RuntimeError::Type(format!(
+                "Iterator result {} is not an object",
+                result.debug_string(true)
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Append the remaining values of the iterator to the array.
+    pub fn append_iterator_values(
+        &mut self,
+        ary: Value,
+        iterator: Value,
+        cur_frame: &frame::Frame,
+    ) -> VMResult {
+        while let Some(val) = self.iterator_step(iterator, cur_frame)? {
+            ary.as_array_mut()
+                .elems
+                .push(Property::new_data_simple(val));
+            self.memory_allocator.gray2(val);
+        }
+        Ok(())
+    }
+
+    /// Copy the own enumerable string-keyed properties of the source to the target, except those
+    /// with the excluded keys.
+    /// https://tc39.github.io/ecma262/#sec-copydataproperties
+    pub fn copy_data_properties(
+        &mut self,
+        target: Value,
+        source: Value,
+        excluded: &[String],
+        cur_frame: &frame::Frame,
+    ) -> VMResult {
+        // Strings are copied as if converted to String objects, whose own enumerable properties
+        // are their code units.
+        if source.is_string() {
+            let string = source.as_string();
+            for i in 0..string.len() {
+                let key = i.to_string();
+                if excluded.contains(&key) {
+                    continue;
+                }
+                let val = Value::js_string(&mut self.memory_allocator, string.substring(i, i + 1));
+                target.set_property_by_string_key(key, val);
+                self.memory_allocator.gray2(val);
+            }
+            return Ok(());
+        }
+
+        if !source.is_object() {
+            return Ok(());
+        }
+
+        for (key, enumerable) in source.get_object_info().own_property_keys() {
+            if !enumerable || excluded.contains(&key) {
+                continue;
+            }
+            let key_val = Value::string_from_key(&mut self.memory_allocator, &key);
+            let val = self.get_property(source, key_val, cur_frame)?;
+            target.set_property_by_string_key(key, val);
+            self.memory_allocator.gray2(val);
+        }
+
+        Ok(())
+    }
 }
 
 macro_rules! read_int8 {
@@ -372,15 +996,6 @@ macro_rules! read_int32 {
 
 impl VM2 {
     pub fn run(&mut self, mut cur_frame: frame::Frame) -> VMResult {
-        #[derive(Debug, Clone)]
-        enum SubroutineKind {
-            Ordinary(usize),
-            Throw,
-            Return,
-        }
-
-        let mut subroutine_stack: Vec<SubroutineKind> = vec![];
-
         macro_rules! exception {
             () => {{
                 let mut exception_found = false;
@@ -399,10 +1014,17 @@ impl VM2 {
                         if !in_range {
                             continue;
                         }
+                        let base = self
+                            .saved_frame
+                            .last()
+                            .map_or(0, |frame| frame.saved_stack_len);
+                        let val = self.stack.pop().unwrap();
+                        self.stack.truncate(base + exception.stack_depth);
+                        self.stack.push(val);
                         match exception.dst_kind {
                             DestinationKind::Catch => cur_frame.pc = exception.end,
                             DestinationKind::Finally => {
-                                subroutine_stack.push(SubroutineKind::Throw);
+                                cur_frame.subroutine_stack.push(SubroutineKind::Throw);
                                 cur_frame.pc = exception.end
                             }
                         }
@@ -415,6 +1037,14 @@ impl VM2 {
                         break;
                     }
 
+                    // The frame was entered from native code such as call_function().
+                    // Leave the frame and let the caller handle the exception.
+                    if cur_frame.escape {
+                        let val: Value = self.stack.pop().unwrap().into();
+                        self.unwind_frame(&mut cur_frame);
+                        return Err(RuntimeError::Exception2(val, node_pos));
+                    }
+
                     if self.saved_frame.len() == 0 {
                         break;
                     }
@@ -489,6 +1119,13 @@ impl VM2 {
                     let lhs: Value = self.stack.pop().unwrap().into();
                     self.stack.push(lhs.rem(rhs).into());
                 }
+                VMInst::EXP => {
+                    cur_frame.pc += 1;
+                    let rhs: Value = self.stack.pop().unwrap().into();
+                    let lhs: Value = self.stack.pop().unwrap().into();
+                    self.stack
+                        .push(lhs.exp(&mut self.memory_allocator, rhs).into());
+                }
                 VMInst::EQ => {
                     cur_frame.pc += 1;
                     let rhs: Value = self.stack.pop().unwrap().into();
@@ -633,7 +1270,14 @@ impl VM2 {
                 }
                 VMInst::PUSH_THIS => {
                     cur_frame.pc += 1;
-                    self.stack.push(cur_frame.this.into());
+                    let this = etry!(cur_frame.resolve_this_binding());
+                    self.stack.push(this.into());
+                }
+                VMInst::PUSH_ARGUMENTS => {
+                    cur_frame.pc += 1;
+                    // Arrow functions find the binding of the enclosing function.
+                    let arguments = etry!(cur_frame.lex_env().get_value(&"arguments".to_string()));
+                    self.stack.push(arguments.into());
                 }
                 VMInst::PUSH_FALSE => {
                     cur_frame.pc += 1;
@@ -647,7 +1291,19 @@ impl VM2 {
                     cur_frame.pc += 1;
                     let property: Value = self.stack.pop().unwrap().into();
                     let parent: Value = self.stack.pop().unwrap().into();
-                    etry!(self.get_property_to_stack_top(parent, property, &mut cur_frame))
+                    etry!(self.get_property_to_stack_top(parent, property, parent, &mut cur_frame))
+                }
+                VMInst::GET_SUPER_MEMBER => {
+                    cur_frame.pc += 1;
+                    let property: Value = self.stack.pop().unwrap().into();
+                    let super_base: Value = self.stack.pop().unwrap().into();
+                    let this = cur_frame.this;
+                    etry!(self.get_property_to_stack_top(
+                        super_base,
+                        property,
+                        this,
+                        &mut cur_frame
+                    ))
                 }
                 VMInst::SET_MEMBER => {
                     cur_frame.pc += 1;
@@ -679,7 +1335,7 @@ impl VM2 {
                     for _ in 0..argc {
                         args.push(self.stack.pop().unwrap().into());
                     }
-                    self.enter_constructor(callee, &args, &mut cur_frame)?;
+                    etry!(self.enter_constructor(callee, &args, callee, &mut cur_frame))
                 }
                 VMInst::CALL => {
                     cur_frame.pc += 1;
@@ -710,6 +1366,36 @@ impl VM2 {
                     };
                     etry!(self.enter_function(callee, &args, parent, &mut cur_frame, false))
                 }
+                VMInst::CONSTRUCT_SPREAD => {
+                    cur_frame.pc += 1;
+                    let callee: Value = self.stack.pop().unwrap().into();
+                    let args: Value = self.stack.pop().unwrap().into();
+                    let args = args.as_array_mut().to_vec();
+                    etry!(self.enter_constructor(callee, &args, callee, &mut cur_frame))
+                }
+                VMInst::CALL_SPREAD => {
+                    cur_frame.pc += 1;
+                    let callee: Value = self.stack.pop().unwrap().into();
+                    let args: Value = self.stack.pop().unwrap().into();
+                    let args = args.as_array_mut().to_vec();
+                    etry!(self.enter_function(callee, &args, cur_frame.this, &mut cur_frame, false))
+                }
+                VMInst::CALL_METHOD_SPREAD => {
+                    cur_frame.pc += 1;
+                    let parent: Value = self.stack.pop().unwrap().into();
+                    let method: Value = self.stack.pop().unwrap().into();
+                    let args: Value = self.stack.pop().unwrap().into();
+                    let args = args.as_array_mut().to_vec();
+                    let callee = match etry!(parent.get_property(
+                        &mut self.memory_allocator,
+                        &self.object_prototypes,
+                        method
+                    )) {
+                        Property::Data(DataProperty { val, .. }) => val,
+                        _ => type_error!("Not a function"),
+                    };
+                    etry!(self.enter_function(callee, &args, parent, &mut cur_frame, false))
+                }
                 VMInst::SET_OUTER_ENV => {
                     cur_frame.pc += 1;
                     let func_template: Value = self.stack.pop().unwrap().into();
@@ -722,7 +1408,7 @@ impl VM2 {
                 VMInst::CREATE_OBJECT => {
                     cur_frame.pc += 1;
                     read_int32!(cur_frame.bytecode, cur_frame.pc, id, usize);
-                    self.create_object(id)?;
+                    etry!(self.create_object(id, &cur_frame));
                     self.memory_allocator.mark(
                         self.global_environment,
                         &self.object_prototypes,
@@ -730,6 +1416,8 @@ impl VM2 {
                         &self.stack,
                         &cur_frame,
                         &self.saved_frame,
+                        &self.job_queue,
+                        &self.rejected_promises,
                     );
                 }
                 VMInst::CREATE_ARRAY => {
@@ -743,18 +1431,129 @@ impl VM2 {
                         &self.stack,
                         &cur_frame,
                         &self.saved_frame,
+                        &self.job_queue,
+                        &self.rejected_promises,
                     );
                 }
+                VMInst::CREATE_CLASS => {
+                    cur_frame.pc += 1;
+                    read_int32!(cur_frame.bytecode, cur_frame.pc, id, usize);
+                    etry!(self.create_class(id));
+                }
+                VMInst::CREATE_REGEXP => {
+                    cur_frame.pc += 1;
+                    read_int32!(cur_frame.bytecode, cur_frame.pc, id, usize);
+                    let regexp = self.constant_table.get(id).as_regexp().clone();
+                    let regexp =
+                        Value::regexp(&mut self.memory_allocator, &self.object_prototypes, regexp);
+                    self.stack.push(regexp.into());
+                }
+                VMInst::GET_SUPER => {
+                    cur_frame.pc += 1;
+                    let home_object: Value = self.stack.pop().unwrap().into();
+                    let super_base = home_object.get_object_info().prototype;
+                    self.stack.push(super_base.into());
+                }
+                VMInst::CALL_SUPER => {
+                    cur_frame.pc += 1;
+                    let callee: Value = self.stack.pop().unwrap().into();
+                    let args: Value = self.stack.pop().unwrap().into();
+                    let args = args.as_array_mut().to_vec();
+                    if !callee.is_function_object() {
+                        type_error!("Super constructor is not a constructor")
+                    }
+                    let new_target = cur_frame.lex_env().get_new_target();
+                    etry!(self.enter_constructor(callee, &args, new_target, &mut cur_frame))
+                }
+                VMInst::BIND_THIS => {
+                    cur_frame.pc += 1;
+                    let this: Value = (*self.stack.last().unwrap()).into();
+                    etry!(cur_frame.lex_env_mut().bind_this_value(this));
+                    cur_frame.this = this;
+                }
                 VMInst::DOUBLE => {
                     cur_frame.pc += 1;
                     let val = *self.stack.last().unwrap();
                     self.stack.push(val);
                 }
+                VMInst::DOUBLE2 => {
+                    cur_frame.pc += 1;
+                    let len = self.stack.len();
+                    let (x, y) = (self.stack[len - 2], self.stack[len - 1]);
+                    self.stack.push(x);
+                    self.stack.push(y);
+                }
+                VMInst::ROTATE => {
+                    cur_frame.pc += 1;
+                    read_int8!(cur_frame.bytecode, cur_frame.pc, depth, usize);
+                    let val = self.stack.pop().unwrap();
+                    let len = self.stack.len();
+                    self.stack.insert(len - depth, val);
+                }
+                VMInst::IS_NULLISH => {
+                    cur_frame.pc += 1;
+                    let val: Value = self.stack.pop().unwrap().into();
+                    self.stack.push(Value::bool(val.is_null() || val.is_undefined()).into());
+                }
+                VMInst::INSTANCE_OF => {
+                    cur_frame.pc += 1;
+                    let target: Value = self.stack.pop().unwrap().into();
+                    let val: Value = self.stack.pop().unwrap().into();
+                    let result = etry!(self.instance_of(val, target, &cur_frame));
+                    self.stack.push(Value::bool(result).into());
+                }
+                VMInst::IN => {
+                    cur_frame.pc += 1;
+                    let obj: Value = self.stack.pop().unwrap().into();
+                    let key: Value = self.stack.pop().unwrap().into();
+                    if !obj.is_object() || obj.is_symbol() {
+                        type_error!(format!(
+                            "Cannot use 'in' operator to search for '{}' in {}",
+                            key.to_string(),
+                            obj.debug_string(true)
+                        ))
+                    }
+                    let result = obj.has_property(&mut self.memory_allocator, key);
+                    self.stack.push(Value::bool(result).into());
+                }
+                VMInst::REQUIRE_OBJECT_COERCIBLE => {
+                    cur_frame.pc += 1;
+                    let val: Value = (*self.stack.last().unwrap()).into();
+                    if val.is_null() || val.is_undefined() {
+                        type_error!(format!("Cannot destructure {}", val.debug_string(true)))
+                    }
+                }
+                VMInst::DELETE_MEMBER => {
+                    cur_frame.pc += 1;
+                    let property: Value = self.stack.pop().unwrap().into();
+                    let parent: Value = self.stack.pop().unwrap().into();
+                    if parent.is_null() || parent.is_undefined() {
+                        type_error!(format!(
+                            "Cannot convert {} to object",
+                            parent.debug_string(true)
+                        ))
+                    }
+                    let result = parent.delete_property(&mut self.memory_allocator, property);
+                    self.stack.push(Value::bool(result).into());
+                }
+                VMInst::DELETE_BINDING => {
+                    cur_frame.pc += 1;
+                    read_int32!(cur_frame.bytecode, cur_frame.pc, name_id, usize);
+                    let name = self.constant_table.get(name_id).as_string().clone();
+                    let result = cur_frame
+                        .lex_env()
+                        .delete_binding(&mut self.memory_allocator, &name);
+                    self.stack.push(Value::bool(result).into());
+                }
                 VMInst::PUSH_ENV => {
                     cur_frame.pc += 1;
                     read_int32!(cur_frame.bytecode, cur_frame.pc, id, usize);
                     self.push_env(id, &mut cur_frame)?;
                 }
+                VMInst::ENTER_FUNCTION_BODY => {
+                    cur_frame.pc += 1;
+                    cur_frame.enter_function_body();
+                }
                 VMInst::POP_ENV => {
                     cur_frame.pc += 1;
                     let lex_env = cur_frame
@@ -785,23 +1584,16 @@ impl VM2 {
                 VMInst::JMP_SUB => {
                     cur_frame.pc += 1;
                     read_int32!(cur_frame.bytecode, cur_frame.pc, dst, i32);
-                    subroutine_stack.push(SubroutineKind::Ordinary(cur_frame.pc));
+                    cur_frame
+                        .subroutine_stack
+                        .push(SubroutineKind::Ordinary(cur_frame.pc));
                     cur_frame.pc = (cur_frame.pc as isize + dst as isize) as usize;
                 }
-                VMInst::RETURN_TRY => {
-                    cur_frame.pc += 1;
-                    read_int32!(cur_frame.bytecode, cur_frame.pc, dst, i32);
-                    cur_frame.pc = (cur_frame.pc as isize + dst as isize) as usize;
-                    subroutine_stack.push(SubroutineKind::Return);
-                }
                 VMInst::RETURN_SUB => {
                     cur_frame.pc += 1;
-                    match subroutine_stack.pop().unwrap() {
+                    match cur_frame.subroutine_stack.pop().unwrap() {
                         SubroutineKind::Ordinary(pos) => cur_frame.pc = pos,
                         SubroutineKind::Throw => exception!(),
-                        SubroutineKind::Return => {
-                            self.unwind_frame_saving_stack_top(&mut cur_frame);
-                        }
                     }
                 }
                 VMInst::THROW => {
@@ -811,7 +1603,8 @@ impl VM2 {
                 VMInst::RETURN => {
                     cur_frame.pc += 1;
                     let escape = cur_frame.escape;
-                    self.unwind_frame_saving_stack_top(&mut cur_frame);
+                    // Constructors are never entered from native code.
+                    etry!(self.return_from_function(&mut cur_frame));
                     // TODO: GC schedule
                     self.memory_allocator.mark(
                         self.global_environment,
@@ -820,6 +1613,8 @@ impl VM2 {
                         &self.stack,
                         &cur_frame,
                         &self.saved_frame,
+                        &self.job_queue,
+                        &self.rejected_promises,
                     );
                     if escape {
                         break;
@@ -833,6 +1628,187 @@ impl VM2 {
                         Value::string(&mut self.memory_allocator, type_str.to_string());
                     self.stack.push(type_str_val.into());
                 }
+                VMInst::FOR_IN_INIT => {
+                    cur_frame.pc += 1;
+                    let obj: Value = self.stack.pop().unwrap().into();
+                    // Keys are stored in reverse order to take them out from the last. The object
+                    // is stored first, to skip the keys deleted during the iteration.
+                    let mut keys = vec![Property::new_data_simple(obj)];
+                    keys.extend(
+                        obj.enumerate_object_properties(&self.object_prototypes)
+                            .into_iter()
+                            .rev()
+                            .map(|key| {
+                                Property::new_data_simple(Value::string_from_key(
+                                    &mut self.memory_allocator,
+                                    &key,
+                                ))
+                            }),
+                    );
+                    let keys =
+                        Value::array(&mut self.memory_allocator, &self.object_prototypes, keys);
+                    self.stack.push(keys.into());
+                }
+                VMInst::FOR_IN_NEXT => {
+                    cur_frame.pc += 1;
+                    read_int32!(cur_frame.bytecode, cur_frame.pc, dst, i32);
+                    let keys: Value = (*self.stack.last().unwrap()).into();
+                    let elems = &mut keys.as_array_mut().elems;
+                    let obj = elems[0].as_data().val;
+                    let mut next = None;
+                    // https://tc39.github.io/ecma262/#sec-enumerate-object-properties
+                    while elems.len() > 1 {
+                        let key = elems.pop().unwrap().as_data().val;
+                        if !obj.is_object() || obj.has_property(&mut self.memory_allocator, key) {
+                            next = Some(key);
+                            break;
+                        }
+                    }
+                    match next {
+                        Some(key) => self.stack.push(key.into()),
+                        None => {
+                            self.stack.pop();
+                            cur_frame.pc = (cur_frame.pc as isize + dst as isize) as usize;
+                        }
+                    }
+                }
+                VMInst::GET_ITERATOR => {
+                    cur_frame.pc += 1;
+                    let iterable: Value = self.stack.pop().unwrap().into();
+                    let iterator = etry!(self.get_iterator(iterable, &cur_frame));
+                    self.stack.push(iterator.into());
+                }
+                VMInst::ITER_NEXT => {
+                    cur_frame.pc += 1;
+                    read_int32!(cur_frame.bytecode, cur_frame.pc, dst, i32);
+                    let iterator: Value = (*self.stack.last().unwrap()).into();
+                    match etry!(self.iterator_step(iterator, &cur_frame)) {
+                        Some(val) => self.stack.push(val.into()),
+                        None => {
+                            self.stack.pop();
+                            cur_frame.pc = (cur_frame.pc as isize + dst as isize) as usize;
+                        }
+                    }
+                }
+                VMInst::ITER_CLOSE => {
+                    cur_frame.pc += 1;
+                    let iterator: Value = self.stack.pop().unwrap().into();
+                    // Iterators already done by ITER_STEP or ITER_REST are undefined.
+                    if !iterator.is_undefined() {
+                        etry!(self.iterator_close(iterator, &cur_frame));
+                    }
+                }
+                VMInst::ITER_CLOSE_THROW => {
+                    cur_frame.pc += 1;
+                    let iterator: Value = self.stack.pop().unwrap().into();
+                    // The exception is rethrown instead of the errors from return().
+                    if !iterator.is_undefined() {
+                        let _ = self.iterator_close(iterator, &cur_frame);
+                    }
+                }
+                VMInst::ITER_STEP => {
+                    cur_frame.pc += 1;
+                    let iterator: Value = (*self.stack.last().unwrap()).into();
+                    if iterator.is_undefined() {
+                        self.stack.push(Value::undefined().into());
+                    } else {
+                        match etry!(self.iterator_step(iterator, &cur_frame)) {
+                            Some(val) => self.stack.push(val.into()),
+                            None => {
+                                *self.stack.last_mut().unwrap() = Value::undefined().into();
+                                self.stack.push(Value::undefined().into());
+                            }
+                        }
+                    }
+                }
+                VMInst::ITER_REST => {
+                    cur_frame.pc += 1;
+                    let iterator: Value = (*self.stack.last().unwrap()).into();
+                    let ary =
+                        Value::array(&mut self.memory_allocator, &self.object_prototypes, vec![]);
+                    self.stack.push(ary.into());
+                    if !iterator.is_undefined() {
+                        etry!(self.append_iterator_values(ary, iterator, &cur_frame));
+                    }
+                    let len = self.stack.len();
+                    self.stack[len - 2] = Value::undefined().into();
+                }
+                VMInst::ARRAY_PUSH => {
+                    cur_frame.pc += 1;
+                    let val: Value = self.stack.pop().unwrap().into();
+                    let ary: Value = (*self.stack.last().unwrap()).into();
+                    ary.as_array_mut()
+                        .elems
+                        .push(Property::new_data_simple(val));
+                    self.memory_allocator.gray2(val);
+                }
+                VMInst::ARRAY_SPREAD => {
+                    cur_frame.pc += 1;
+                    // The iterator is left on the stack while iterating.
+                    let iterator: Value = (*self.stack.last().unwrap()).into();
+                    let ary: Value = self.stack[self.stack.len() - 2].into();
+                    etry!(self.append_iterator_values(ary, iterator, &cur_frame));
+                    self.stack.pop();
+                }
+                VMInst::OBJECT_REST => {
+                    cur_frame.pc += 1;
+                    read_int32!(cur_frame.bytecode, cur_frame.pc, len, usize);
+                    // The keys to exclude are under the source object.
+                    let source: Value = (*self.stack.last().unwrap()).into();
+                    let keys_start = self.stack.len() - 1 - len;
+                    let excluded = self.stack[keys_start..keys_start + len]
+                        .iter()
+                        .map(|key| {
+                            let key: Value = (*key).into();
+                            key.to_property_key()
+                        })
+                        .collect::<Vec<_>>();
+                    let rest = Value::object(
+                        &mut self.memory_allocator,
+                        &self.object_prototypes,
+                        PropertyMap::default(),
+                    );
+                    self.stack.push(rest.into());
+                    etry!(self.copy_data_properties(rest, source, &excluded, &cur_frame));
+                    self.stack.truncate(keys_start);
+                    self.stack.push(rest.into());
+                }
+                VMInst::YIELD => {
+                    cur_frame.pc += 1;
+                    let val = self.stack.pop().unwrap();
+                    let generator = cur_frame.generator.unwrap();
+                    let caller_frame = self.saved_frame.pop().unwrap();
+                    let info = generator.as_generator_mut();
+                    info.stack = self.stack.split_off(caller_frame.saved_stack_len);
+                    info.state = GeneratorState::SuspendedYield;
+                    info.frame = Some(mem::replace(&mut cur_frame, caller_frame));
+                    self.memory_allocator.gray2(generator);
+                    self.stack.push(val);
+                    // The frame of a generator is always entered from run_generator().
+                    break;
+                }
+                VMInst::YIELD_DELEGATE => {
+                    cur_frame.pc += 1;
+                    read_int32!(cur_frame.bytecode, cur_frame.pc, dst, i32);
+                    let kind = ResumeKind::from_value(self.stack.pop().unwrap().into());
+                    let received: Value = self.stack.pop().unwrap().into();
+                    let iterator: Value = (*self.stack.last().unwrap()).into();
+                    let result =
+                        etry!(self.resume_inner_iterator(iterator, received, kind, &cur_frame));
+                    let value_key = Value::string(&mut self.memory_allocator, "value".to_string());
+                    let done_key = Value::string(&mut self.memory_allocator, "done".to_string());
+                    let done = etry!(self.get_property(result, done_key, &cur_frame)).to_boolean();
+                    let value = etry!(self.get_property(result, value_key, &cur_frame));
+                    if done {
+                        self.stack.pop(); // iterator
+                        self.stack.push(value.into());
+                        self.stack
+                            .push(Value::bool(kind == ResumeKind::Return).into());
+                        cur_frame.pc = (cur_frame.pc as isize + dst as isize) as usize;
+                    } else {
+                        self.stack.push(value.into());
+                    }
+                }
                 VMInst::END => break,
                 _ => {
                     print!("Not yet implemented VMInst: ");
@@ -848,17 +1824,36 @@ impl VM2 {
 
     pub fn unwind_frame_saving_stack_top(&mut self, cur_frame: &mut frame::Frame) {
         let ret_val_boxed = self.stack.pop().unwrap();
-        let ret_val: Value = ret_val_boxed.into();
         let frame = self.saved_frame.pop().unwrap();
         self.stack.truncate(frame.saved_stack_len);
-        if cur_frame.constructor_call && !ret_val.is_object() {
-            self.stack.push(cur_frame.this.into());
-        } else {
-            self.stack.push(ret_val_boxed);
-        }
+        self.stack.push(ret_val_boxed);
         *cur_frame = frame;
     }
 
+    /// Leave the function with the value on the stack top. Constructors return 'this' unless
+    /// an object is returned. The error for a derived constructor is thrown in the caller.
+    /// https://tc39.github.io/ecma262/#sec-ecmascript-function-objects-construct-argumentslist-newtarget
+    pub fn return_from_function(&mut self, cur_frame: &mut frame::Frame) -> VMResult {
+        if !cur_frame.constructor_call {
+            self.unwind_frame_saving_stack_top(cur_frame);
+            return Ok(());
+        }
+
+        let ret_val: Value = self.stack.pop().unwrap().into();
+        let result = if ret_val.is_object() {
+            Ok(ret_val)
+        } else if cur_frame.derived_constructor && !ret_val.is_undefined() {
+            Err(RuntimeError::Type(
+                "Derived constructors may only return object or undefined".to_string(),
+            ))
+        } else {
+            cur_frame.resolve_this_binding()
+        };
+        self.unwind_frame(cur_frame);
+        self.stack.push(result?.into());
+        Ok(())
+    }
+
     pub fn unwind_frame(&mut self, cur_frame: &mut frame::Frame) {
         let frame = self.saved_frame.pop().unwrap();
         self.stack.truncate(frame.saved_stack_len);
@@ -887,48 +1882,47 @@ impl VM2 {
         Ok(())
     }
 
-    fn create_object(&mut self, id: usize) -> VMResult {
+    fn create_object(&mut self, id: usize, cur_frame: &frame::Frame) -> VMResult {
         let (len, special_properties) = self.constant_table.get(id).as_object_literal_info();
-        let mut properties = FxHashMap::default();
+        let obj = Value::object(
+            &mut self.memory_allocator,
+            &self.object_prototypes,
+            PropertyMap::default(),
+        );
+
+        // Properties are defined in the source order. They are left on the stack until the end
+        // because getters of spread objects may run GC.
+        let base = self.stack.len() - len * 2;
+        self.stack.push(obj.into());
 
         for i in 0..len {
-            let prop: Value = self.stack.pop().unwrap().into();
-            let name = prop.to_string();
-            let val: Value = self.stack.pop().unwrap().into();
-            if let Some(kind) = special_properties.get(&i) {
-                let AccessorProperty { get, set, .. } = properties
-                    .entry(name)
-                    .or_insert(Property::Accessor(AccessorProperty {
-                        get: Value::undefined(),
-                        set: Value::undefined(),
-                        // TODO
-                        enumerable: true,
-                        configurable: true,
-                    }))
-                    .as_accessor_mut();
-                match kind {
-                    constant::SpecialPropertyKind::Getter => *get = val,
-                    constant::SpecialPropertyKind::Setter => *set = val,
+            let key: Value = self.stack[base + i * 2].into();
+            let val: Value = self.stack[base + i * 2 + 1].into();
+            match special_properties.get(&(len - i - 1)) {
+                Some(constant::SpecialPropertyKind::Spread) => {
+                    self.copy_data_properties(obj, val, &[], cur_frame)?
+                }
+                Some(constant::SpecialPropertyKind::Proto) => {
+                    if (val.is_object() && !val.is_symbol()) || val.is_null() {
+                        obj.get_object_info().prototype = val;
+                    }
+                }
+                special => {
+                    let info = obj.get_object_info();
+                    let undefined = Property::new_data_simple(Value::undefined());
+                    let property = if key.is_symbol() {
+                        info.sym_property.entry(SymbolKey(key)).or_insert(undefined)
+                    } else {
+                        info.property
+                            .entry(key.to_property_key())
+                            .or_insert(undefined)
+                    };
+                    define_literal_property(property, val, special);
                 }
-            } else {
-                properties.insert(
-                    name,
-                    Property::Data(DataProperty {
-                        val,
-                        // TODO
-                        writable: true,
-                        enumerable: true,
-                        configurable: true,
-                    }),
-                );
             }
         }
 
-        let obj = Value::object(
-            &mut self.memory_allocator,
-            &self.object_prototypes,
-            properties,
-        );
+        self.stack.truncate(base);
         self.stack.push(obj.into());
 
         Ok(())
@@ -952,35 +1946,171 @@ impl VM2 {
         Ok(())
     }
 
+    /// https://tc39.github.io/ecma262/#sec-runtime-semantics-classdefinitionevaluation
+    fn create_class(&mut self, id: usize) -> VMResult {
+        let (has_heritage, method_infos) = self.constant_table.get(id).as_class_info();
+
+        let mut methods = vec![];
+        for _ in 0..method_infos.len() {
+            let key: Value = self.stack.pop().unwrap().into();
+            let func: Value = self.stack.pop().unwrap().into();
+            methods.push((key, func));
+        }
+        methods.reverse();
+
+        let constructor: Value = self.stack.pop().unwrap().into();
+
+        let (proto_parent, constructor_parent) = if has_heritage {
+            let heritage: Value = self.stack.pop().unwrap().into();
+            if heritage.is_null() {
+                (Value::null(), self.object_prototypes.function)
+            } else if heritage.is_function_object() {
+                let proto_parent = heritage.get_property_by_str_key("prototype");
+                if !proto_parent.is_object() && !proto_parent.is_null() {
+                    return Err(RuntimeError::Type(
+                        "Class extends value does not have valid prototype property".to_string(),
+                    ));
+                }
+                (proto_parent, heritage)
+            } else {
+                return Err(RuntimeError::Type(format!(
+                    "Class extends value {} is not a constructor or null",
+                    heritage.to_string()
+                )));
+            }
+        } else {
+            (
+                self.object_prototypes.object,
+                self.object_prototypes.function,
+            )
+        };
+
+        let proto = Value::Object(self.memory_allocator.alloc(ObjectInfo {
+            kind: ObjectKind2::Ordinary,
+            prototype: proto_parent,
+            property: PropertyMap::default(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }));
+        proto.set_constructor(constructor);
+
+        constructor.get_object_info().prototype = constructor_parent;
+        constructor.get_object_info().property.insert(
+            "prototype".to_string(),
+            Property::Data(DataProperty {
+                val: proto,
+                writable: false,
+                enumerable: false,
+                configurable: false,
+            }),
+        );
+
+        for (method_info, (key, func)) in method_infos.iter().zip(methods) {
+            let home_object = if method_info.is_static {
+                constructor
+            } else {
+                proto
+            };
+            let info = home_object.get_object_info();
+            let undefined = Property::new_data_simple(Value::undefined());
+            let property = if key.is_symbol() {
+                info.sym_property.entry(SymbolKey(key)).or_insert(undefined)
+            } else {
+                info.property
+                    .entry(key.to_property_key())
+                    .or_insert(undefined)
+            };
+            match method_info.special {
+                Some(kind) => {
+                    // A getter and a setter with the same name are merged into one property.
+                    if property.get_data().is_some() {
+                        *property = Property::Accessor(AccessorProperty {
+                            get: Value::undefined(),
+                            set: Value::undefined(),
+                            enumerable: false,
+                            configurable: true,
+                        });
+                    }
+                    let AccessorProperty { get, set, .. } = property.as_accessor_mut();
+                    match kind {
+                        constant::SpecialPropertyKind::Getter => *get = func,
+                        constant::SpecialPropertyKind::Setter => *set = func,
+                        constant::SpecialPropertyKind::Spread
+                        | constant::SpecialPropertyKind::Proto => unreachable!(),
+                    }
+                }
+                None => {
+                    *property = Property::Data(DataProperty {
+                        val: func,
+                        writable: true,
+                        enumerable: false,
+                        configurable: true,
+                    });
+                }
+            }
+        }
+
+        self.stack.push(constructor.into());
+
+        Ok(())
+    }
+
+    /// Construct an object with the constructor. The prototype of the object is taken from
+    /// `new_target`, which is the constructor given to 'new' even if super() calls the parent
+    /// constructor.
+    /// https://tc39.github.io/ecma262/#sec-ecmascript-function-objects-construct-argumentslist-newtarget
     fn enter_constructor(
         &mut self,
         callee: Value,
         args: &[Value],
+        new_target: Value,
         cur_frame: &mut frame::Frame,
     ) -> VMResult {
-        let this = Value::Object(self.memory_allocator.alloc(ObjectInfo {
-            kind: ObjectKind2::Ordinary,
-            prototype: callee.get_property_by_str_key("prototype"),
-            property: FxHashMap::default(),
-            sym_property: FxHashMap::default(),
-        }));
-
         if !callee.is_function_object() {
             return Err(RuntimeError::Type("Not a function".to_string()));
         }
 
+        let prototype = new_target.get_property_by_str_key("prototype");
         let info = callee.as_function();
 
         match info.kind {
             FunctionObjectKind::Builtin(func) => {
-                func(self, args, &frame::Frame::new_empty_with_this(this, true))
+                let this = self.create_ordinary_object(prototype);
+                self.enter_builtin_function(func, args, this, cur_frame, true)?;
+                // Builtin constructors create exotic objects such as arrays by themselves.
+                // Instances of derived classes inherit from the class instead.
+                if new_target != callee {
+                    let obj: Value = (*self.stack.last().unwrap()).into();
+                    if obj.is_object() {
+                        obj.get_object_info().prototype = prototype;
+                    }
+                }
+                Ok(())
             }
             FunctionObjectKind::User(ref user_func) => {
-                self.enter_user_function(user_func.clone(), args, this, cur_frame, true)
+                let this = if user_func.derived_constructor {
+                    Value::uninitialized()
+                } else {
+                    self.create_ordinary_object(prototype)
+                };
+                self.enter_user_function(user_func, args, this, cur_frame, Some(new_target))
+            }
+            FunctionObjectKind::PromiseResolving(_) => {
+                Err(RuntimeError::Type("Not a constructor".to_string()))
             }
         }
     }
 
+    fn create_ordinary_object(&mut self, prototype: Value) -> Value {
+        Value::Object(self.memory_allocator.alloc(ObjectInfo {
+            kind: ObjectKind2::Ordinary,
+            prototype,
+            property: PropertyMap::default(),
+            sym_property: FxHashMap::default(),
+            extensible: true,
+        }))
+    }
+
     fn enter_function(
         &mut self,
         callee: Value,
@@ -996,17 +2126,52 @@ impl VM2 {
         let info = callee.as_function();
 
         match info.kind {
-            FunctionObjectKind::Builtin(func) => gc_lock!(
-                self,
-                args,
-                func(self, args, &frame::Frame::new_empty_with_this(this, false))
-            ),
+            FunctionObjectKind::Builtin(func) => {
+                self.enter_builtin_function(func, args, this, cur_frame, false)
+            }
+            FunctionObjectKind::User(ref user_func)
+                if user_func.kind == FunctionKind::Generator =>
+            {
+                let generator = self.create_generator(callee, user_func, args, this)?;
+                self.stack.push(generator.into());
+                Ok(())
+            }
+            FunctionObjectKind::User(ref user_func) if user_func.kind == FunctionKind::Async => {
+                let promise = self.call_async_function(user_func, args, this)?;
+                self.stack.push(promise.into());
+                Ok(())
+            }
             FunctionObjectKind::User(ref user_func) => {
-                self.enter_user_function(user_func.clone(), args, this, cur_frame, constructor_call)
+                let new_target = if constructor_call { Some(callee) } else { None };
+                self.enter_user_function(user_func, args, this, cur_frame, new_target)
+            }
+            FunctionObjectKind::PromiseResolving(ref info) => {
+                promise::call_promise_resolving_function(self, info, args, cur_frame)
             }
         }
     }
 
+    /// Builtin functions may execute code such as callbacks and generator bodies in a nested
+    /// run(), so the current frame is saved in the meantime to keep the values in it traced.
+    fn enter_builtin_function(
+        &mut self,
+        func: BuiltinFuncTy2,
+        args: &[Value],
+        this: Value,
+        cur_frame: &mut frame::Frame,
+        constructor_call: bool,
+    ) -> VMResult {
+        let caller = mem::replace(
+            cur_frame,
+            frame::Frame::new_empty_with_this(this, constructor_call),
+        );
+        self.saved_frame
+            .push(caller.saved_stack_len(self.stack.len()));
+        let result = gc_lock!(self, args, func(self, args, cur_frame));
+        *cur_frame = self.saved_frame.pop().unwrap();
+        result
+    }
+
     fn create_declarative_environment<F>(
         &mut self,
         f: F,
@@ -1031,6 +2196,7 @@ impl VM2 {
         &mut self,
         f: F,
         this: Value,
+        new_target: Value,
         outer: Option<frame::LexicalEnvironmentRef>,
     ) -> frame::LexicalEnvironmentRef
     where
@@ -1044,6 +2210,7 @@ impl VM2 {
                     record
                 },
                 this,
+                new_target,
             },
             outer,
         };
@@ -1053,91 +2220,64 @@ impl VM2 {
 
     fn enter_user_function(
         &mut self,
-        user_func: UserFunctionInfo,
+        user_func: &UserFunctionInfo,
         args: &[Value],
         this: Value,
         cur_frame: &mut frame::Frame,
-        constructor_call: bool,
+        new_target: Option<Value>,
     ) -> VMResult {
+        let constructor_call = new_target.is_some();
         if !user_func.constructible && constructor_call {
             return Err(RuntimeError::Type("Not a constructor".to_string()));
         }
 
-        self.saved_frame
-            .push(cur_frame.clone().saved_stack_len(self.stack.len()));
+        if user_func.class_constructor && !constructor_call {
+            return Err(RuntimeError::Type(
+                "Class constructor cannot be invoked without 'new'".to_string(),
+            ));
+        }
 
-        let this = if user_func.this_mode == ThisMode::Lexical {
-            // Arrow function
-            user_func.outer.unwrap().get_this_binding()
-        } else {
-            this
-        };
+        let frame = self.create_function_frame(user_func, args, this, new_target)?;
 
-        let var_env_ref = self.create_function_environment(
-            |vm, record| {
-                for name in &user_func.var_names {
-                    record.insert(name.clone(), Value::undefined());
-                }
+        self.saved_frame
+            .push(cur_frame.clone().saved_stack_len(self.stack.len()));
 
-                for (i, FunctionParameter { name, rest_param }) in
-                    user_func.params.iter().enumerate()
-                {
-                    record.insert(
-                        name.clone(),
-                        if *rest_param {
-                            Value::array(
-                                &mut vm.memory_allocator,
-                                &vm.object_prototypes,
-                                (*args)
-                                    .get(i..)
-                                    .unwrap_or(&vec![])
-                                    .iter()
-                                    .map(|elem| Property::new_data_simple(*elem))
-                                    .collect::<Vec<Property>>(),
-                            )
-                        } else {
-                            *args.get(i).unwrap_or(&Value::undefined())
-                        },
-                    );
-                }
-            },
-            this,
-            user_func.outer,
-        );
+        *cur_frame = frame;
 
-        let mut lex_env_ref = self.create_declarative_environment(
-            |_, record| {
-                for name in &user_func.lex_names {
-                    record.insert(name.clone(), Value::uninitialized());
-                }
-            },
-            Some(var_env_ref),
-        );
+        Ok(())
+    }
+}
 
-        for func in user_func.func_decls {
-            let mut func = func.copy_object(&mut self.memory_allocator);
-            let name = func.as_function().name.clone().unwrap();
-            func.set_function_outer_environment(lex_env_ref);
-            lex_env_ref.set_value(name, func)?;
+/// Define a property of an object literal. A getter and a setter with the same key are merged
+/// into one accessor property.
+fn define_literal_property(
+    property: &mut Property,
+    val: Value,
+    special: Option<&constant::SpecialPropertyKind>,
+) {
+    let kind = match special {
+        Some(kind) => kind,
+        None => {
+            *property = Property::new_data_simple(val);
+            return;
         }
+    };
 
-        let exec_ctx = frame::ExecutionContext {
-            variable_environment: var_env_ref,
-            lexical_environment: lex_env_ref,
-            saved_lexical_environment: vec![],
-        };
-
-        let frame = frame::Frame::new(
-            exec_ctx,
-            user_func.code,
-            user_func.exception_table,
-            this,
-            constructor_call,
-        )
-        .id(user_func.id);
-
-        *cur_frame = frame;
+    if property.get_data().is_some() {
+        *property = Property::Accessor(AccessorProperty {
+            get: Value::undefined(),
+            set: Value::undefined(),
+            enumerable: true,
+            configurable: true,
+        });
+    }
 
-        Ok(())
+    let AccessorProperty { get, set, .. } = property.as_accessor_mut();
+    match kind {
+        constant::SpecialPropertyKind::Getter => *get = val,
+        constant::SpecialPropertyKind::Setter => *set = val,
+        constant::SpecialPropertyKind::Spread | constant::SpecialPropertyKind::Proto => {
+            unreachable!()
+        }
     }
 }
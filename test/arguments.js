@@ -0,0 +1,84 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+function count() {
+  return arguments.length
+}
+assert(count(), 0)
+assert(count(1, 2, 3), 3)
+assert(count(undefined), 1)
+
+// Indices are mapped to the parameters.
+function mapped(a, b) {
+  a = 'a'
+  arguments[1] = 'b'
+  return a + b + arguments[0] + arguments[1]
+}
+assert(mapped(1, 2), 'abab')
+
+// Only the passed arguments are mapped.
+function partial(a, b) {
+  b = 'b'
+  arguments[2] = 'c'
+  return '' + arguments[1] + arguments.length + arguments[2]
+}
+assert(partial(1), 'undefined1c')
+
+// The last parameter wins for duplicated names.
+function duplicated(a, a) {
+  a = 'x'
+  return arguments[0] + arguments[1]
+}
+assert(duplicated(1, 2), '1x')
+
+// Not mapped with non-simple parameter lists.
+function withDefault(a, b = 0) {
+  a = 'a'
+  arguments[1] = 'b'
+  return a + b + arguments[0] + arguments[1]
+}
+assert(withDefault(1, 2), 'a21b')
+
+function withRest(a, ...rest) {
+  a = 'a'
+  return arguments[0] + arguments.length
+}
+assert(withRest(1, 2, 3), 4)
+
+// Arrow functions refer to the arguments of the enclosing function.
+function outer() {
+  let inner = () => arguments[0]
+  return inner('ignored')
+}
+assert(outer('outer'), 'outer')
+
+// Each call gets its own arguments object.
+function keep() {
+  return arguments
+}
+let args1 = keep(1)
+let args2 = keep(2)
+assert(args1 !== args2, true)
+assert(args1[0] + args2[0], 3)
+
+// Array-like and iterable
+let sum = 0
+for (let x of keep(1, 2, 3)) sum += x
+assert(sum, 6)
+let copy = [...keep('a', 'b')]
+assert(copy.length, 2)
+assert(copy[1], 'b')
+let keys = 0
+for (let key in keep(1, 2)) keys++
+assert(keys, 2)
+
+function forward() {
+  return count(...arguments)
+}
+assert(forward(1, 2), 2)
+
+let obj = {
+  method: function() {
+    return arguments.length
+  },
+}
+assert(obj.method(1, 2), 2)
@@ -0,0 +1,212 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+// Constructor and statics
+assert(Array().length, 0)
+assert(Array(3).length, 3)
+assert(0 in Array(3), false)
+assert(new Array(2).length, 2)
+assert(Array(1, 2).join(), "1,2")
+assert(Array("3").length, 1)
+assert(Array("3")[0], "3")
+let thrown = false
+try { Array(-1) } catch (e) { thrown = true }
+assert(thrown, true)
+thrown = false
+try { Array(1.5) } catch (e) { thrown = true }
+assert(thrown, true)
+thrown = false
+try { Array(4294967294) } catch (e) { thrown = true }
+assert(thrown, true)
+thrown = false
+try { [].length = 4294967294 } catch (e) { thrown = true }
+assert(thrown, true)
+assert(Array.isArray([]), true)
+assert(Array.isArray({ length: 0 }), false)
+assert(Array.isArray(), false)
+assert(Array.of(7).length, 1)
+assert(Array.of(1, 2, 3).join("-"), "1-2-3")
+assert(Array.from([1, 2, 3], (x) => x * 2).join(), "2,4,6")
+assert(Array.from({ length: 2, 0: "a", 1: "b" }).join(), "a,b")
+assert(Array.from("abc").join(), "a,b,c")
+assert(Array.from([1, , 3])[1], undefined)
+assert(1 in Array.from([1, , 3]), true)
+function* gen() { yield 1; yield 2 }
+assert(Array.from(gen(), (x, i) => x + i).join(), "1,3")
+
+// push, pop, shift and unshift
+let a = [1, 2, 3]
+assert(a.push(4, 5), 5)
+assert(a.pop(), 5)
+assert(a.length, 4)
+assert(a.shift(), 1)
+assert(a.join(), "2,3,4")
+assert(a.unshift(0, 1), 5)
+assert(a.join(), "0,1,2,3,4")
+assert([].pop(), undefined)
+assert([].shift(), undefined)
+let holey = [1, , 3]
+holey.shift()
+assert(holey.length, 2)
+assert(0 in holey, false)
+assert(holey[1], 3)
+
+// slice and splice
+a = [0, 1, 2, 3, 4]
+assert(a.slice(1, 3).join(), "1,2")
+assert(a.slice(-2).join(), "3,4")
+assert(a.slice().length, 5)
+assert(a.slice(3, 1).length, 0)
+assert([1, , 3].slice(0, 2).length, 2)
+let removed = a.splice(1, 2)
+assert(removed.join(), "1,2")
+assert(a.join(), "0,3,4")
+removed = a.splice(1, 0, "a", "b")
+assert(removed.length, 0)
+assert(a.join(), "0,a,b,3,4")
+removed = a.splice(-2)
+assert(removed.join(), "3,4")
+assert(a.join(), "0,a,b")
+a.splice(1, 2, "c")
+assert(a.join(), "0,c")
+
+// concat, join, toString and reverse
+assert([1, 2].concat([3], 4, [[5]]).length, 5)
+assert([1, 2].concat([3], 4).join(), "1,2,3,4")
+assert(1 in [0].concat([, 1]), false)
+assert([1, null, undefined, 2].join("-"), "1---2")
+assert([1, [2, 3]].toString(), "1,2,3")
+assert([].join(), "")
+assert([1, 2, 3].reverse().join(), "3,2,1")
+let r = [1, , 3, 4].reverse()
+assert(r.join(), "4,3,,1")
+assert(2 in r, false)
+let cyclic = [1]
+cyclic.push(cyclic)
+assert(cyclic.join(), "1,")
+assert(String([cyclic, 2]), "1,,2")
+let outer = [1]
+let inner = [outer, 2]
+outer.push(inner)
+assert(outer.join("-"), "1-,2")
+assert([[1, undefined], [null, 2]].join(), "1,,,2")
+let concatThrown = false
+try { Array.prototype.concat.call(undefined) } catch (e) { concatThrown = true }
+assert(concatThrown, true)
+let arrayLike = { length: 2, 0: "a", 1: "b" }
+assert([0].concat(arrayLike).length, 2)
+arrayLike[Symbol.isConcatSpreadable] = true
+assert([0].concat(arrayLike).join(), "0,a,b")
+let unspread = [1, 2]
+unspread[Symbol.isConcatSpreadable] = false
+assert([0].concat(unspread).length, 2)
+assert([0].concat(unspread)[1], unspread)
+assert(Array.prototype.concat.call(1, 2).length, 2)
+assert(Array.prototype.concat.call({ length: 1, 0: "x" }, [1]).length, 2)
+
+// indexOf, lastIndexOf and includes
+a = [1, 2, NaN, 2, undefined]
+assert(a.indexOf(2), 1)
+assert(a.indexOf(2, 2), 3)
+assert(a.indexOf(2, -2), 3)
+assert(a.indexOf(NaN), -1)
+assert(a.lastIndexOf(2), 3)
+assert(a.lastIndexOf(2, 2), 1)
+assert(a.lastIndexOf(2, -3), 1)
+assert(a.lastIndexOf(7), -1)
+assert(a.includes(NaN), true)
+assert(a.includes(undefined), true)
+assert(a.includes(1, 1), false)
+assert([, 1].indexOf(undefined), -1)
+assert([, 1].includes(undefined), true)
+
+// Callbacks
+a = [1, 2, 3, 4]
+let sum = 0
+a.forEach((x, i, ary) => { sum += x * i; assert(ary, a) })
+assert(sum, 20)
+let ctx = { factor: 10 }
+assert(a.map(function (x) { return x * this.factor }, ctx).join(), "10,20,30,40")
+let mapped = [1, , 3].map((x) => x)
+assert(mapped.length, 3)
+assert(1 in mapped, false)
+assert(a.filter((x) => x % 2 == 0).join(), "2,4")
+assert(a.reduce((acc, x) => acc + x), 10)
+assert(a.reduce((acc, x) => acc + x, ""), "1234")
+assert(a.reduceRight((acc, x) => acc + x, ""), "4321")
+assert([, 5].reduce((acc, x) => acc + x), 5)
+thrown = false
+try { [].reduce((acc, x) => acc + x) } catch (e) { thrown = true }
+assert(thrown, true)
+assert(a.some((x) => x > 3), true)
+assert(a.some((x) => x > 4), false)
+assert(a.every((x) => x > 0), true)
+assert(a.every((x) => x > 1), false)
+assert(a.find((x) => x > 2), 3)
+assert(a.find((x) => x > 4), undefined)
+assert(a.findIndex((x) => x > 2), 2)
+assert(a.findIndex((x) => x > 4), -1)
+let visited = 0
+let sparse = [1, , 3]
+sparse.forEach(() => visited++)
+assert(visited, 2)
+visited = 0
+sparse.find(() => { visited++; return false })
+assert(visited, 3)
+thrown = false
+try { [1].forEach(1) } catch (e) { thrown = true }
+assert(thrown, true)
+
+// fill and sort
+assert([1, 2, 3].fill(0).join(), "0,0,0")
+assert([1, 2, 3, 4].fill(9, 1, -1).join(), "1,9,9,4")
+assert([3, 1, 10, 2].sort().join(), "1,10,2,3")
+assert([3, 1, 10, 2].sort((x, y) => x - y).join(), "1,2,3,10")
+let privateUse = String.fromCharCode(0xe000)
+let emoji = String.fromCharCode(0xd83d, 0xde00)
+let units = [privateUse, emoji, "a"].sort()
+assert(units[0], "a")
+assert(units[1], emoji)
+assert(units[2], privateUse)
+let s = [3, undefined, , 1].sort()
+assert(s[0], 1)
+assert(s[1], 3)
+assert(s[2], undefined)
+assert(2 in s, true)
+assert(3 in s, false)
+assert(s.length, 4)
+let people = [{ n: "a", age: 2 }, { n: "b", age: 1 }, { n: "c", age: 2 }, { n: "d", age: 1 }]
+people.sort((x, y) => x.age - y.age)
+assert(people.map((p) => p.n).join(""), "bdac")
+
+// keys, values and entries
+let keys = []
+for (let k of ["a", "b"].keys()) keys.push(k)
+assert(keys.join(), "0,1")
+let values = []
+for (let v of ["a", "b"].values()) values.push(v)
+assert(values.join(), "a,b")
+let entries = []
+for (let [k, v] of ["a", "b"].entries()) entries.push(k + v)
+assert(entries.join(), "0a,1b")
+assert([][Symbol.iterator], [].values)
+
+// Accessor elements
+let log = []
+let obj = { get 0() { log.push("get"); return "g" }, length: 1 }
+assert(Array.prototype.join.call(obj), "g")
+assert(log.length, 1)
+let setterLog = []
+let withSetter = { set 0(v) { setterLog.push(v) }, length: 0 }
+Array.prototype.push.call(withSetter, "s")
+assert(setterLog.join(), "s")
+assert(withSetter.length, 1)
+
+// Generic array-likes
+let like = { length: 2, 0: "x", 1: "y" }
+assert(Array.prototype.map.call(like, (x) => x + x).join(), "xx,yy")
+assert(Array.prototype.push.call(like, "z"), 3)
+assert(like[2], "z")
+assert(like.length, 3)
+assert(Array.prototype.pop.call(like), "z")
+assert(like.length, 2)
+assert(2 in like, false)
@@ -0,0 +1,111 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+async function add(x, y) {
+  return x + y
+}
+
+let p = add(1, 2)
+assert(typeof p, 'object')
+p.then(x => assert(x, 3))
+
+// The body runs synchronously until the first 'await'.
+let log = ''
+async function f() {
+  log += 'a'
+  let x = await 1
+  log += 'c'
+  let y = await Promise.resolve(2)
+  return x + y
+}
+f().then(x => {
+  assert(x, 3)
+  assert(log, 'abc')
+})
+log += 'b'
+assert(log, 'ab')
+
+// Awaiting a rejected promise throws.
+async function catcher() {
+  try {
+    await Promise.reject('rejected')
+  } catch (e) {
+    return e
+  }
+}
+catcher().then(x => assert(x, 'rejected'))
+
+// An exception rejects the promise.
+async function thrower() {
+  await null
+  throw 'thrown'
+}
+thrower().catch(e => assert(e, 'thrown'))
+
+// finally
+let finallyLog = ''
+async function withFinally() {
+  try {
+    return await 'value'
+  } finally {
+    await null
+    finallyLog += 'f'
+  }
+}
+withFinally().then(x => {
+  assert(x, 'value')
+  assert(finallyLog, 'f')
+})
+
+// Async function expressions and async arrow functions
+let double = async function (x) {
+  return (await x) * 2
+}
+let triple = async x => (await x) * 3
+let sum = async (x, y) => {
+  return await double(x) + await triple(y)
+}
+sum(1, Promise.resolve(2)).then(x => assert(x, 8))
+
+// 'this' and 'arguments' of async methods
+let obj = {
+  x: 5,
+  get: async function () {
+    return this.x
+  },
+  arrow: function () {
+    return (async () => this.x)()
+  },
+}
+obj.get().then(x => assert(x, 5))
+obj.arrow().then(x => assert(x, 5))
+
+// Awaiting in loops
+async function loop(n) {
+  let total = 0
+  for (let i = 0; i < n; i++) {
+    total += await i
+  }
+  return total
+}
+loop(5).then(x => assert(x, 10))
+
+// Promise.all with async functions
+let delayed = async x => {
+  await null
+  await null
+  return x
+}
+Promise.all([delayed(1), delayed(2), 3]).then(values => {
+  assert(values[0], 1)
+  assert(values[1], 2)
+  assert(values[2], 3)
+})
+
+// 'async' and 'await' are identifiers elsewhere.
+let async = 1
+let await = 2
+assert(async + await, 3)
+function g(async) {
+  return async
+}
+assert(g(4), 4)
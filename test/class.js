@@ -0,0 +1,276 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+class Point {
+  constructor(x, y) {
+    this.x = x
+    this.y = y
+  }
+  add(other) {
+    return new Point(this.x + other.x, this.y + other.y)
+  }
+  get norm2() {
+    return this.x * this.x + this.y * this.y
+  }
+  static origin() {
+    return new Point(0, 0)
+  }
+  static get unit() {
+    return Point.unitLength
+  }
+  static set unit(v) {
+    this.unitLength = v
+  }
+}
+
+let p = new Point(1, 2).add(new Point(3, 4))
+assert(p.x, 4)
+assert(p.y, 6)
+assert(p.norm2, 52)
+Point.unit = 5
+assert(Point.unit, 5)
+assert(Point.origin().norm2, 0)
+assert(typeof Point, 'function')
+assert(Point.prototype.constructor, Point)
+assert(p.constructor, Point)
+
+// Methods are not enumerable.
+let keys = ''
+for (let key in p) keys += key
+assert(keys, 'xy')
+
+// Class constructors cannot be called without 'new'.
+let thrown = false
+try {
+  Point(1, 2)
+} catch (e) {
+  thrown = true
+}
+assert(thrown, true)
+
+// Methods are not constructors.
+thrown = false
+try {
+  new p.add(p)
+} catch (e) {
+  thrown = true
+}
+assert(thrown, true)
+
+// Inheritance
+class Point3D extends Point {
+  constructor(x, y, z) {
+    super(x, y)
+    this.z = z
+  }
+  get norm2() {
+    return super.norm2 + this.z * this.z
+  }
+  toString() {
+    return this.x + ',' + this.y + ',' + this.z
+  }
+  static origin() {
+    let o = super.origin()
+    return new Point3D(o.x, o.y, 0)
+  }
+}
+
+let q = new Point3D(1, 2, 3)
+assert(q.norm2, 14)
+assert(q.toString(), '1,2,3')
+assert(q.add(q).x, 2)
+assert(q.constructor, Point3D)
+assert(Point3D.origin().z, 0)
+assert(Point3D.origin().norm2, 0)
+
+// The default constructor of a derived class passes the arguments to the parent.
+class Animal {
+  constructor(name) {
+    this.name = name
+  }
+  speak() {
+    return this.name + ' makes a sound'
+  }
+}
+class Dog extends Animal {
+  speak() {
+    // Arrow functions refer to the 'super' of the enclosing method.
+    let f = () => super.speak() + ' and barks'
+    return f()
+  }
+}
+let d = new Dog('Rex')
+assert(d.name, 'Rex')
+assert(d.speak(), 'Rex makes a sound and barks')
+
+// Class expressions
+let Counter = class C {
+  constructor() {
+    this.count = 0
+  }
+  increment() {
+    this.count++
+    return this
+  }
+  self() {
+    return C
+  }
+}
+let c = new Counter()
+assert(c.increment().increment().count, 2)
+assert(c.self(), Counter)
+
+// Classes without constructors
+class Empty {}
+let e = new Empty()
+assert(typeof e, 'object')
+assert(e.constructor, Empty)
+
+// Extending constructor functions
+function Base(v) {
+  this.v = v
+}
+Base.prototype.get = function () {
+  return this.v
+}
+class Derived extends Base {
+  constructor() {
+    super(42)
+  }
+}
+assert(new Derived().get(), 42)
+
+// Extending null
+class Null extends null {}
+assert(typeof Null.prototype, 'object')
+
+// Invalid heritage
+thrown = false
+try {
+  class Bad extends 1 {}
+} catch (e) {
+  thrown = true
+}
+assert(thrown, true)
+
+// Computed method names
+let prefix = 'get'
+let tag = Symbol('tag')
+class Computed {
+  constructor() {
+    this.items = [1, 2]
+  }
+  [prefix + 'First']() {
+    return this.items[0]
+  }
+  *[Symbol.iterator]() {
+    yield* this.items
+  }
+  get [tag]() {
+    return 'computed'
+  }
+  static [1 + 1]() {
+    return 'two'
+  }
+  ['constructor']() {
+    return 'method'
+  }
+}
+let computed = new Computed()
+assert(computed.getFirst(), 1)
+assert([...computed].join(), '1,2')
+assert(computed[tag], 'computed')
+assert(Computed['2'](), 'two')
+assert(Computed.prototype.hasOwnProperty('constructor'), true)
+assert(computed.constructor(), 'method')
+assert(Object.getOwnPropertySymbols(Computed.prototype).length, 2)
+assert(Computed.prototype.propertyIsEnumerable(Symbol.iterator), false)
+
+// 'this' of a derived constructor is bound by super().
+let isReferenceError = e => typeof e === 'string' && e.startsWith('Reference error')
+class Early extends Point {
+  constructor() {
+    this.x = 1
+    super(0, 0)
+  }
+}
+thrown = false
+try {
+  new Early()
+} catch (e) {
+  thrown = isReferenceError(e)
+}
+assert(thrown, true)
+
+class Twice extends Point {
+  constructor() {
+    super(1, 2)
+    super(3, 4)
+  }
+}
+thrown = false
+try {
+  new Twice()
+} catch (e) {
+  thrown = isReferenceError(e)
+}
+assert(thrown, true)
+
+class NoSuper extends Point {
+  constructor() {}
+}
+thrown = false
+try {
+  new NoSuper()
+} catch (e) {
+  thrown = isReferenceError(e)
+}
+assert(thrown, true)
+
+// The object returned by the parent constructor becomes 'this'.
+let returned = { tag: 'returned' }
+function ReturnsObject() {
+  this.tag = 'ignored'
+  return returned
+}
+class FromReturned extends ReturnsObject {
+  constructor() {
+    let result = super()
+    assert(result, this)
+    this.extra = true
+  }
+}
+let fromReturned = new FromReturned()
+assert(fromReturned, returned)
+assert(returned.extra, true)
+
+// Base constructors create instances of the class given to 'new'.
+let r = new Point3D(1, 2, 3)
+assert(Object.getPrototypeOf(r), Point3D.prototype)
+assert(r instanceof Point, true)
+
+// Arrow functions share 'this' with the constructor.
+class ArrowSuper extends Point {
+  constructor() {
+    let init = () => super(5, 6)
+    let self = () => this
+    init()
+    assert(self(), this)
+  }
+}
+assert(new ArrowSuper().y, 6)
+
+// Builtin constructors
+class List extends Array {
+  first() {
+    return this[0]
+  }
+}
+let list = new List(3)
+assert(list.length, 3)
+list[0] = 'a'
+list.push('b')
+assert(list.length, 4)
+assert(list.first(), 'a')
+assert(list instanceof List, true)
+assert(list instanceof Array, true)
+assert(Array.isArray(list), true)
@@ -0,0 +1,119 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+let x = 10
+x += 5
+assert(x, 15)
+x -= 3
+assert(x, 12)
+x *= 2
+assert(x, 24)
+x /= 4
+assert(x, 6)
+x %= 4
+assert(x, 2)
+x **= 3
+assert(x, 8)
+x <<= 2
+assert(x, 32)
+x >>= 1
+assert(x, 16)
+x = 255
+x >>>= 4
+assert(x, 15)
+x &= 6
+assert(x, 6)
+x |= 9
+assert(x, 15)
+x ^= 5
+assert(x, 10)
+assert((x += 1), 11)
+let s = 'a'
+s += 'b'
+assert(s, 'ab')
+
+// The target is evaluated only once.
+let calls = 0
+let key = () => {
+  calls++
+  return 'n'
+}
+let obj = { n: 1 }
+obj[key()] += 1
+assert(obj.n, 2)
+assert(calls, 1)
+let ary = [1, 2, 3]
+let i = 0
+ary[i++] *= 10
+assert(ary[0], 10)
+assert(ary[1], 2)
+assert(i, 1)
+let getObj = () => {
+  calls++
+  return obj
+}
+calls = 0
+assert((getObj().n **= 2), 4)
+assert(obj.n, 4)
+assert(calls, 1)
+
+// Logical assignment
+let a = 0
+assert((a ||= 5), 5)
+assert(a, 5)
+assert((a ||= 6), 5)
+assert((a &&= 7), 7)
+a = null
+assert((a &&= 8), null)
+assert((a ??= 9), 9)
+assert((a ??= 10), 9)
+a = 0
+a ??= 11
+assert(a, 0)
+
+// Logical assignment short-circuits.
+let evaluated = false
+let rhs = () => {
+  evaluated = true
+  return 'rhs'
+}
+a = 1
+a ||= rhs()
+assert(evaluated, false)
+a = 0
+a &&= rhs()
+assert(evaluated, false)
+a = false
+a ??= rhs()
+assert(evaluated, false)
+
+// A short-circuited property is neither evaluated twice nor set.
+let setterCalls = 0
+let target = {
+  get p() {
+    return 1
+  },
+  set p(v) {
+    setterCalls++
+  }
+}
+calls = 0
+let getTarget = () => {
+  calls++
+  return target
+}
+assert((getTarget().p ||= 2), 1)
+assert((getTarget()['p'] ??= 2), 1)
+assert(calls, 2)
+assert(setterCalls, 0)
+assert((getTarget().p &&= 3), 3)
+assert(setterCalls, 1)
+let empty = {}
+empty.v ??= 'set'
+assert(empty.v, 'set')
+empty['w'] ||= 'set'
+assert(empty.w, 'set')
+for (let j = 0; j < 3; j++) {
+  empty.count ??= 0
+  empty.count += j
+}
+assert(empty.count, 3)
@@ -0,0 +1,126 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+function f(a, b = a + 1, c = b * 2) {
+  return a + b + c
+}
+assert(f(1), 1 + 2 + 4)
+assert(f(1, 5), 1 + 5 + 10)
+assert(f(1, undefined, 0), 1 + 2 + 0)
+
+// Defaults are evaluated on each call.
+let count = 0
+function g(x = ++count) {
+  return x
+}
+assert(g(), 1)
+assert(g(), 2)
+assert(g(10), 10)
+assert(g(null), null)
+assert(count, 2)
+
+// Defaults can refer to outer bindings and to 'this'.
+let outer = 'outer'
+let obj = {
+  name: 'obj',
+  method: function(a = this.name, b = outer) {
+    return a + b
+  },
+}
+assert(obj.method(), 'objouter')
+
+// Closures in defaults capture the parameters.
+function h(a, getA = () => a) {
+  a = 2
+  return getA()
+}
+assert(h(1), 2)
+
+// Rest parameters
+function withRest(a = 1, ...rest) {
+  return a + rest.length
+}
+assert(withRest(), 1)
+assert(withRest(undefined, 2, 3), 3)
+
+// Arrow functions
+let arrow = (x, y = x * 2) => x + y
+assert(arrow(3), 9)
+let asyncArrow = async (x = 1) => x
+asyncArrow().then(x => assert(x, 1))
+
+// Patterns with defaults
+function point({ x = 0, y = 0 } = {}, [z] = [3]) {
+  return x + y + z
+}
+assert(point(), 3)
+assert(point({ x: 1 }), 4)
+assert(point({ x: 1, y: 2 }, [0]), 3)
+
+// Methods and class constructors
+class Counter {
+  constructor(start = 10) {
+    this.value = start
+  }
+  add(n = 1) {
+    return this.value + n
+  }
+}
+assert(new Counter().value, 10)
+assert(new Counter(5).add(), 6)
+
+// Generators
+function* gen(a, b = a + 1) {
+  yield a
+  yield b
+}
+let it = gen(1)
+assert(it.next().value, 1)
+assert(it.next().value, 2)
+
+// length counts the parameters before the first one with a default value.
+assert(f.length, 1)
+assert(g.length, 0)
+assert(withRest.length, 0)
+assert(((a, b, ...c) => {}).length, 2)
+assert(function(a, b = 1, c) {}.length, 1)
+
+// Errors in defaults are thrown at the call.
+function thrower(a = (() => { throw 'thrown' })()) {
+  return a
+}
+let caught
+try {
+  thrower()
+} catch (e) {
+  caught = e
+}
+assert(caught, 'thrown')
+assert(thrower(1), 1)
+
+// Closures in default values don't see the variables declared in the body.
+var hidden = 'outer'
+function closure(get = () => hidden) {
+  var hidden = 'body'
+  return get() + ',' + hidden
+}
+assert(closure(), 'outer,body')
+
+function shadowed(a, get = () => a) {
+  var a = 2
+  return get() + ',' + a
+}
+assert(shadowed(1), '1,2')
+
+// Variables with the same names as parameters start with the values of them.
+function sameName(a = 1) {
+  var a
+  return a
+}
+assert(sameName(), 1)
+assert(sameName(3), 3)
+
+function pattern({ p }, get = () => p) {
+  var p = p + '!'
+  return get() + ',' + p
+}
+assert(pattern({ p: 'x' }), 'x,x!')
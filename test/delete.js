@@ -0,0 +1,122 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+let obj = { a: 1, b: 2 }
+assert(delete obj.a, true)
+assert(obj.a, undefined)
+assert("a" in obj, false)
+assert(delete obj["b"], true)
+assert("b" in obj, false)
+assert(delete obj.nothing, true)
+
+let proto = { inherited: 1 }
+let child = {}
+child.__proto__ = proto
+assert(delete child.inherited, true)
+assert(child.inherited, 1)
+assert(delete proto.inherited, true)
+assert(child.inherited, undefined)
+
+let sym = Symbol()
+obj[sym] = 1
+assert(delete obj[sym], true)
+assert(sym in obj, false)
+assert(delete Symbol.iterator, false)
+assert(typeof Symbol.iterator, "symbol")
+
+let arr = [1, 2, 3]
+assert(delete arr[1], true)
+assert(arr.length, 3)
+assert(arr[1], undefined)
+assert(1 in arr, false)
+assert(delete arr["0"], true)
+assert(0 in arr, false)
+assert(2 in arr, true)
+assert(delete arr[5], true)
+assert(delete arr.length, false)
+assert(arr.length, 3)
+
+let keys = ""
+for (let key in [4, 5, 6]) keys += key
+assert(keys, "012")
+let holey = [4, 5, 6]
+delete holey[1]
+keys = ""
+for (let key in holey) keys += key
+assert(keys, "02")
+
+let raw = (s => s)`a${1}b`
+assert(delete raw[0], false)
+assert(raw[0], "a")
+assert(delete raw.raw, false)
+
+function f() {}
+assert(delete f.prototype, false)
+assert(typeof f.prototype, "object")
+assert(delete f.name, true)
+
+class C {}
+assert(delete C.prototype, false)
+
+assert(delete "abc"[0], false)
+assert(delete "abc".length, false)
+assert(delete "abc"[3], true)
+assert(delete 1, true)
+assert(delete (1 + 2), true)
+
+let count = 0
+let key = () => { count++; return "x" }
+let o = { x: 1 }
+delete o[key()]
+assert(count, 1)
+assert("x" in o, false)
+
+function g(a) {
+  delete arguments[0]
+  arguments[0] = 2
+  return a
+}
+assert(g(1), 1)
+
+let thrown = false
+try { delete undefined.x } catch (e) { thrown = true }
+assert(thrown, true)
+
+// Bindings
+let declared = 1
+var declaredVar = 1
+assert(delete declared, false)
+assert(delete declaredVar, false)
+assert(declared, 1)
+function h(param) {
+  let local = 1
+  return [delete param, delete local, delete h].join()
+}
+assert(h(1), "false,false,false")
+undeclared = 1
+assert(delete undeclared, true)
+thrown = false
+try { undeclared + 1 } catch (e) { thrown = true }
+assert(thrown, true)
+assert(delete neverDefined, true)
+assert(delete NaN, false)
+assert(delete Math, true)
+assert("Math" in this, false)
+
+// Optional chains
+let maybe = { a: 1, b: { c: 1 } }
+assert(delete maybe?.a, true)
+assert("a" in maybe, false)
+assert(delete maybe?.["b"]?.c, true)
+assert("c" in maybe.b, false)
+assert(delete maybe.none?.a, true)
+let nothing = null
+assert(delete nothing?.a, true)
+assert(delete nothing?.a.b, true)
+function deleteArguments() {
+  return delete arguments
+}
+assert(deleteArguments(1), false)
+let arrowInFunction = function () {
+  return (() => delete arguments)()
+}
+assert(arrowInFunction(), false)
@@ -0,0 +1,203 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+// Object patterns
+let { a, b: c, d = 3, e: { f } = { f: 4 } } = { a: 1, b: 2 }
+assert(a, 1)
+assert(c, 2)
+assert(d, 3)
+assert(f, 4)
+
+var { x, 'y': y, 0: z, ...others } = { x: 'x', y: 'y', 0: 'z', p: 1, q: 2 }
+assert(x, 'x')
+assert(y, 'y')
+assert(z, 'z')
+let otherKeys = 0
+for (let key in others) otherKeys++
+assert(otherKeys, 2)
+assert(others.p, 1)
+assert(others.q, 2)
+
+// Defaults are evaluated only when the value is undefined.
+let called = false
+let mark = () => { called = true; return 'default' }
+const { g = mark(), h = mark() } = { g: null, h: undefined }
+assert(g, null)
+assert(h, 'default')
+called = false
+const { i = mark() } = { i: 0 }
+assert(called, false)
+
+// Getters are invoked once.
+let count = 0
+let { j } = { get j() { count++; return 'j' } }
+assert(j, 'j')
+assert(count, 1)
+
+// Array patterns
+let [k, , l = 10, [m, n] = [5, 6], ...rest] = [1, 2, undefined, undefined, 7, 8]
+assert(k, 1)
+assert(l, 10)
+assert(m, 5)
+assert(n, 6)
+assert(rest.length, 2)
+assert(rest[0], 7)
+assert(rest[1], 8)
+
+let [o, p, q] = 'ab'
+assert(o, 'a')
+assert(p, 'b')
+assert(q, undefined)
+
+let [...empty] = []
+assert(empty.length, 0)
+
+let [[r], { s }] = [[1], { s: 2 }]
+assert(r + s, 3)
+
+// Array patterns use the iterator protocol, and close it if it has not finished.
+let log = ''
+let iterable = {}
+iterable[Symbol.iterator] = function() {
+  let n = 0
+  let iterator = {
+    next: () => {
+      n++
+      log += n
+      return { value: n, done: n > 3 }
+    },
+  }
+  iterator['return'] = () => {
+    log += 'r'
+    return {}
+  }
+  return iterator
+}
+let [t] = iterable
+assert(t, 1)
+assert(log, '1r')
+log = ''
+let [u, v, w, ww] = iterable
+assert(ww, undefined)
+assert(log, '1234')
+log = ''
+let [...all] = iterable
+assert(all.length, 3)
+assert(log, '1234')
+
+function* gen() {
+  yield 1
+  yield 2
+  yield 3
+}
+let [g1, ...gs] = gen()
+assert(g1, 1)
+assert(gs.length, 2)
+
+// Function parameters
+function sum({ a, b = 2 }, [c, d = 4], ...[e, f]) {
+  return a + b + c + d + e + f
+}
+assert(sum({ a: 1 }, [3], 5, 6), 21)
+assert(sum({ a: 1, b: 1 }, [1, 1], 1, 1), 6)
+
+let getName = ({ name }) => name
+assert(getName({ name: 'rapidus' }), 'rapidus')
+
+let swapArgs = function([a, b]) {
+  return [b, a]
+}
+assert(swapArgs([1, 2])[0], 2)
+
+// Catch parameters
+try {
+  throw { message: 'msg', code: 42 }
+} catch ({ message, code }) {
+  assert(message, 'msg')
+  assert(code, 42)
+}
+
+// for-in and for-of bindings
+let total = 0
+for (const [key, value] of [['a', 1], ['b', 2]]) {
+  total += value
+}
+assert(total, 3)
+
+let lengths = 0
+for (var { length } in { ab: 0, cde: 0 }) {
+  lengths += length
+}
+assert(lengths, 5)
+
+let closures = []
+for (let { v } of [{ v: 1 }, { v: 2 }]) {
+  closures.push(() => v)
+}
+assert(closures[0](), 1)
+assert(closures[1](), 2)
+
+// Assignment patterns
+let first = 1, second = 2;
+[first, second] = [second, first]
+assert(first, 2)
+assert(second, 1)
+
+let obj = {}
+let arr = [];
+({ a: obj.a, b: arr[0], ...obj.rest } = { a: 'a', b: 'b', c: 'c' })
+assert(obj.a, 'a')
+assert(arr[0], 'b')
+assert(obj.rest.c, 'c')
+
+let result = ([first, second] = [3, 4])
+assert(result.length, 2)
+assert(first + second, 7)
+
+for ([first, second] of [[5, 6]]) {}
+assert(first + second, 11)
+
+// Computed keys
+let key = 'x'
+let { [key]: fromX, ['y' + 1]: fromY = 'y', [0]: fromZero } = { x: 'x', 0: 'zero' }
+assert(fromX, 'x')
+assert(fromY, 'y')
+assert(fromZero, 'zero')
+
+let sym = Symbol()
+let { [sym]: fromSym } = { [sym]: 'sym' }
+assert(fromSym, 'sym')
+
+let evaluated = 0
+let { [(evaluated++, 'p')]: fromP, ...remaining } = { p: 1, q: 2 }
+assert(fromP, 1)
+assert(evaluated, 1)
+assert(remaining.p, undefined)
+assert(remaining.q, 2);
+
+({ [key]: obj.computed } = { x: 3 })
+assert(obj.computed, 3)
+
+// Errors
+let throws = f => {
+  try {
+    f()
+  } catch (e) {
+    return true
+  }
+  return false
+}
+assert(throws(() => { let [a] = 1; }), true)
+assert(throws(() => { let [a] = {}; }), true)
+assert(throws(() => { const { a } = null; }), true)
+assert(throws(() => { const {} = undefined; }), true)
+assert(throws(() => { const [a] = undefined; }), true)
+assert(throws(() => { let a; ({ a } = null) }), true)
+assert(throws(() => { (({ a }) => a)() }), true)
+
+let message
+try {
+  let { a } = null;
+} catch (e) {
+  message = e
+}
+assert(message, 'Cannot destructure null')
@@ -0,0 +1,22 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+let total = 0, i = 1
+do {
+  total += i
+  if (i === 10) break
+  i++
+} while (i <= 20)
+assert(total, 55)
+
+let count = 0
+do count++; while (false)
+assert(count, 1)
+
+let odd = 0, j = 0
+do {
+  j++
+  let k = j
+  if (k % 2 === 0) continue
+  odd += k
+} while (j < 10)
+assert(odd, 25)
@@ -0,0 +1,68 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+let obj = { a: 1, b: 2, c: 3 }
+let sum = 0, keys = 0
+for (let k in obj) {
+  sum += obj[k]
+  keys++
+}
+assert(sum, 6)
+assert(keys, 3)
+
+let indices = ''
+for (var i in [10, 20, 30]) indices += i
+assert(indices, '012')
+assert(i, '2')
+
+let ary = [1, 2, 3]
+ary[5] = 6
+let count = 0
+for (let i in ary) count++
+assert(count, 4)
+
+function F() { this.own = 1 }
+F.prototype.inherited = 2
+let found = ''
+for (let k in new F()) found += k + ','
+assert(found, 'own,inherited,')
+
+let target = {}
+for (target.key in { x: 1 }) {}
+assert(target.key, 'x')
+
+let str_indices = ''
+for (let i in 'abc') str_indices += i
+assert(str_indices, '012')
+
+for (let k in null) throw 'err'
+for (let k in undefined) throw 'err'
+
+let fs = []
+for (let k in { p: 0, q: 0 }) fs.push(() => k)
+assert(fs[0]() + fs[1](), 'pq')
+
+let visited = 0
+outer: for (let k in { a: 0, b: 0 }) {
+  for (let l in { c: 0, d: 0 }) {
+    visited++
+    if (k === 'a') continue outer
+    break outer
+  }
+}
+assert(visited, 2)
+
+let deleted = ''
+let shrinking = { a: 1, b: 2, c: 3 }
+for (let k in shrinking) {
+  delete shrinking.b
+  deleted += k
+}
+assert(deleted, 'ac')
+
+deleted = ''
+let shadowed = Object.create({ a: 1, b: 2 })
+for (let k in shadowed) {
+  delete Object.getPrototypeOf(shadowed).b
+  deleted += k
+}
+assert(deleted, 'a')
@@ -0,0 +1,246 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+let sum = 0
+for (const x of [1, 2, 3]) sum += x
+assert(sum, 6)
+
+let s = ''
+for (let c of 'aあb') s += c + ','
+assert(s, 'a,あ,b,')
+
+// let and const declarations create a new binding for each iteration.
+let fs = []
+for (let i of [0, 1, 2]) fs.push(() => i)
+assert(fs[0]() + fs[1]() + fs[2](), 3)
+
+var v
+for (v of [4, 5]) {}
+assert(v, 5)
+
+let o = {}
+for (o.p of [6, 7]);
+assert(o.p, 7)
+
+// User-defined iterable
+let log = ''
+let iterable = {}
+iterable[Symbol.iterator] = function () {
+  let i = 0
+  let iter = {
+    next: function () {
+      i++
+      return { value: i, done: i > 5 }
+    }
+  }
+  iter['return'] = function () {
+    log += 'r'
+    return {}
+  }
+  return iter
+}
+
+let total = 0
+for (const x of iterable) total += x
+assert(total, 15)
+assert(log, '')
+
+for (const x of iterable) {
+  if (x === 2) break
+}
+assert(log, 'r')
+
+for (const x of iterable) {
+  if (x === 2) continue
+  log += x
+}
+assert(log, 'r1345')
+
+outer: for (const x of iterable) {
+  for (const y of iterable) {
+    if (y === 2) continue outer
+    if (x === 3) break outer
+  }
+}
+assert(log, 'r1345rrrr')
+
+let finallyLog = ''
+for (const x of iterable) {
+  try {
+    break
+  } finally {
+    finallyLog += log
+  }
+}
+assert(finallyLog, 'r1345rrrr')
+assert(log, 'r1345rrrrr')
+
+let iter = [8, 9][Symbol.iterator]()
+assert(iter[Symbol.iterator](), iter)
+let res = iter.next()
+assert(res.value, 8)
+assert(res.done, false)
+iter.next()
+res = iter.next()
+assert(res.value, undefined)
+assert(res.done, true)
+
+let caught = false
+try {
+  for (const x of 1) {}
+} catch (e) {
+  caught = true
+}
+assert(caught, true)
+
+let throwing = {}
+throwing[Symbol.iterator] = function () {
+  return { next: function () { throw 'boom' } }
+}
+function g() {
+  try {
+    for (const x of throwing) {}
+  } catch (e) {
+    return e
+  }
+}
+assert(g(), 'boom')
+
+// 'return' closes the iterators of the enclosing for-of statements.
+log = ''
+function first() {
+  for (const x of iterable) {
+    return x
+  }
+}
+assert(first(), 1)
+assert(log, 'r')
+
+log = ''
+function nested() {
+  for (const x of iterable) {
+    for (const k in { a: 1 }) {
+      for (const y of iterable) {
+        if (y === 2) return k + x + y
+      }
+    }
+  }
+}
+assert(nested(), 'a12')
+assert(log, 'rr')
+
+log = ''
+function inTry() {
+  try {
+    for (const x of iterable) return x
+  } finally {
+    log += 'f'
+  }
+}
+assert(inTry(), 1)
+assert(log, 'rf')
+
+let genLog = ''
+function* letters() {
+  try {
+    yield 'a'
+    yield 'b'
+  } finally {
+    genLog += 'finally'
+  }
+}
+function firstLetter() {
+  for (const c of letters()) return c
+}
+assert(firstLetter(), 'a')
+assert(genLog, 'finally')
+
+// The iterators and the finally blocks are left from the innermost one.
+log = ''
+function interleaved() {
+  try {
+    for (const x of iterable) {
+      try {
+        for (const y of iterable) {
+          try {
+            return x + y
+          } finally {
+            log += 'f1'
+          }
+        }
+      } finally {
+        log += 'f2'
+      }
+    }
+  } finally {
+    log += 'f3'
+  }
+}
+assert(interleaved(), 2)
+assert(log, 'f1rf2rf3')
+
+log = ''
+function overridden() {
+  for (const x of iterable) {
+    try {
+      return 1
+    } finally {
+      return 2
+    }
+  }
+}
+assert(overridden(), 2)
+assert(log, 'r')
+
+// Exceptions from the body close the iterator.
+log = ''
+function thrower() {
+  throw 'body'
+}
+let n = 0
+try {
+  for (const x of iterable) {
+    n = 1 + thrower()
+  }
+} catch (e) {
+  log += e
+}
+assert(log, 'rbody')
+
+log = ''
+try {
+  for (const [x] of iterable) {}
+} catch (e) {
+  log += 'caught'
+}
+assert(log, 'rcaught')
+
+// The exception from the body is thrown instead of the one from return().
+let badReturn = {}
+badReturn[Symbol.iterator] = function () {
+  return {
+    next: function () {
+      return { value: 1, done: false }
+    },
+    return: function () {
+      throw 'return'
+    },
+  }
+}
+caught = undefined
+try {
+  for (const x of badReturn) throw 'body'
+} catch (e) {
+  caught = e
+}
+assert(caught, 'body')
+
+// The loop goes on after an exception caught in the body.
+n = 0
+for (const x of [1, 2, 3]) {
+  try {
+    n = 1 + thrower()
+  } catch (e) {
+    n++
+  }
+}
+assert(n, 3)
@@ -0,0 +1,232 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+function* count(n) {
+  for (let i = 0; i < n; i++) {
+    yield i
+  }
+  return 'end'
+}
+
+let gen = count(2)
+let r = gen.next()
+assert(r.value, 0)
+assert(r.done, false)
+r = gen.next()
+assert(r.value, 1)
+assert(r.done, false)
+r = gen.next()
+assert(r.value, 'end')
+assert(r.done, true)
+r = gen.next()
+assert(r.value, undefined)
+assert(r.done, true)
+
+assert(gen[Symbol.iterator](), gen)
+assert(typeof gen, 'object')
+
+// The sent value becomes the result of 'yield'.
+let echo = function* () {
+  let log = ''
+  while (true) {
+    let x = yield log
+    if (x === undefined) return log
+    log += x
+  }
+}
+
+gen = echo()
+assert(gen.next('ignored').value, '')
+assert(gen.next('a').value, 'a')
+assert(gen.next('b').value, 'ab')
+r = gen.next()
+assert(r.value, 'ab')
+assert(r.done, true)
+
+// The body does not start until the first next().
+let started = false
+function* lazy() {
+  started = true
+  yield
+}
+gen = lazy()
+assert(started, false)
+r = gen.next()
+assert(started, true)
+assert(r.value, undefined)
+assert(r.done, false)
+
+// for-of
+let sum = 0
+for (let i of count(5)) sum += i
+assert(sum, 10)
+
+// return() runs finally blocks.
+let log = ''
+function* withFinally() {
+  try {
+    yield 1
+    yield 2
+  } finally {
+    log += 'f'
+  }
+}
+gen = withFinally()
+gen.next()
+r = gen.return(42)
+assert(r.value, 42)
+assert(r.done, true)
+assert(log, 'f')
+assert(gen.next().done, true)
+
+gen = withFinally()
+r = gen.return(1)
+assert(r.done, true)
+assert(log, 'f')
+
+log = ''
+for (let x of withFinally()) break
+assert(log, 'f')
+
+// A finally block may yield.
+function* yieldInFinally() {
+  try {
+    yield 1
+  } finally {
+    yield 2
+    log += 'F'
+  }
+}
+log = ''
+gen = yieldInFinally()
+gen.next()
+r = gen.return(3)
+assert(r.value, 2)
+assert(r.done, false)
+r = gen.next()
+assert(r.value, 3)
+assert(r.done, true)
+assert(log, 'F')
+
+// throw() is thrown at the point of 'yield'.
+function* catcher() {
+  while (true) {
+    try {
+      yield 'waiting'
+    } catch (e) {
+      log += e
+    }
+  }
+}
+log = ''
+gen = catcher()
+gen.next()
+r = gen.throw('x')
+assert(r.value, 'waiting')
+assert(log, 'x')
+gen.throw('y')
+assert(log, 'xy')
+
+let caught
+gen = count(3)
+try {
+  gen.throw('not started')
+} catch (e) {
+  caught = e
+}
+assert(caught, 'not started')
+assert(gen.next().done, true)
+
+gen = count(3)
+gen.next()
+try {
+  gen.throw('uncaught')
+} catch (e) {
+  caught = e
+}
+assert(caught, 'uncaught')
+assert(gen.next().done, true)
+
+// Exceptions thrown by the body are propagated to the caller.
+function* thrower() {
+  yield 1
+  throw 'boom'
+}
+gen = thrower()
+gen.next()
+try {
+  gen.next()
+} catch (e) {
+  caught = e
+}
+assert(caught, 'boom')
+assert(gen.next().done, true)
+
+// A generator cannot be resumed while it is running.
+function* reentrant() {
+  try {
+    gen.next()
+  } catch (e) {
+    yield 'running'
+  }
+}
+gen = reentrant()
+assert(gen.next().value, 'running')
+
+// yield*
+function* inner() {
+  let x = yield 'a'
+  yield x
+  return 'inner done'
+}
+function* outer() {
+  let ret = yield* inner()
+  yield ret
+  yield* [1, 2]
+  yield* 'cd'
+}
+let result = ''
+gen = outer()
+r = gen.next()
+while (!r.done) {
+  result += r.value + ','
+  r = gen.next('sent')
+}
+assert(result, 'a,sent,inner done,1,2,c,d,')
+
+gen = outer()
+gen.next()
+r = gen.return('early')
+assert(r.value, 'early')
+assert(r.done, true)
+
+log = ''
+function* delegateThrow() {
+  yield* catcher()
+}
+gen = delegateThrow()
+gen.next()
+r = gen.throw('z')
+assert(r.value, 'waiting')
+assert(log, 'z')
+
+function* returnFromCatch() {
+  try {
+    yield 'waiting'
+  } catch (e) {
+    return e
+  }
+}
+gen = returnFromCatch()
+gen.next()
+r = gen.throw('w')
+assert(r.value, 'w')
+assert(r.done, true)
+
+// 'this' and arguments
+let obj = {
+  x: 10,
+  gen: function* (y) {
+    yield this.x + y
+  },
+}
+assert(obj.gen(5).next().value, 15)
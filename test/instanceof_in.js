@@ -0,0 +1,66 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+function Animal(name) { this.name = name }
+function Dog(name) { this.name = name }
+Dog.prototype = new Animal("base")
+let dog = new Dog("pochi")
+assert(dog instanceof Dog, true)
+assert(dog instanceof Animal, true)
+assert(dog instanceof Object, true)
+assert(new Animal("x") instanceof Dog, false)
+assert(1 instanceof Object, false)
+assert("str" instanceof Object, false)
+assert(null instanceof Object, false)
+assert([] instanceof Array, true)
+assert([] instanceof Object, true)
+assert(Dog instanceof Function, true)
+
+class A {}
+class B extends A {}
+assert(new B() instanceof A, true)
+assert(new A() instanceof B, false)
+
+let Even = {}
+Even[Symbol.hasInstance] = function(x) { return x % 2 === 0 }
+assert(2 instanceof Even, true)
+assert(3 instanceof Even, false)
+assert(Function.prototype[Symbol.hasInstance].call(Dog, dog), true)
+
+let thrown = false
+try { dog instanceof {} } catch (e) { thrown = true }
+assert(thrown, true)
+thrown = false
+try { dog instanceof 1 } catch (e) { thrown = true }
+assert(thrown, true)
+
+let obj = { a: 1, b: undefined }
+assert("a" in obj, true)
+assert("b" in obj, true)
+assert("c" in obj, false)
+assert("toString" in obj, "toString" in Object.prototype)
+assert("name" in dog, true)
+let puppy = {}
+puppy.__proto__ = dog
+assert("name" in puppy, true)
+assert(puppy instanceof Dog, true)
+
+let arr = [1, 2, , 4]
+assert(0 in arr, true)
+assert("1" in arr, true)
+assert(2 in arr, false)
+assert(4 in arr, false)
+assert("length" in arr, true)
+assert("push" in arr, true)
+
+let sym = Symbol("s")
+let symObj = {}
+symObj[sym] = 1
+assert(sym in symObj, true)
+assert(Symbol.iterator in arr, true)
+assert(Symbol.iterator in obj, false)
+
+thrown = false
+try { "a" in "abc" } catch (e) { thrown = true }
+assert(thrown, true)
+
+for (let key in { x: "x" in obj }) assert(key, "x")
@@ -0,0 +1,80 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+let log = ''
+block: {
+  log += 'a'
+  if (log.length === 1) break block
+  log += 'b'
+}
+assert(log, 'a')
+
+log = ''
+outer: for (let i = 0; i < 3; i++) {
+  switch (i) {
+    case 0:
+      continue outer
+    case 1:
+      log += 'x'
+      break
+    default:
+      break outer
+  }
+  log += i
+}
+assert(log, 'x1')
+
+a: b: while (true) {
+  while (true) break a
+}
+
+log = ''
+for (let i = 0; i < 3; i++) {
+  try {
+    try {
+      if (i === 1) continue
+      if (i === 2) break
+      log += 't'
+    } finally {
+      log += 'f'
+    }
+  } finally {
+    log += 'F'
+  }
+  log += 'e'
+}
+assert(log, 'tfFefFfF')
+
+function f() {
+  let log = ''
+  outer: while (true) {
+    try {
+      while (true) {
+        try {
+          break outer
+        } finally {
+          log += 'inner'
+        }
+      }
+    } catch (e) {
+      log += 'NG'
+    } finally {
+      log += 'outer'
+    }
+  }
+  return log
+}
+assert(f(), 'innerouter')
+
+log = ''
+a: b: for (let i = 0; i < 3; i++) {
+  for (let j = 0; j < 3; j++) {
+    if (j === 1) continue a
+    if (i === 2) continue b
+    log += i + '' + j
+  }
+}
+assert(log, '0010')
+
+let i = 'outer'
+for (let i = 0; i < 1; i++) {}
+assert(i, 'outer')
@@ -0,0 +1,90 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+let key = "dyn"
+let obj = {
+  [key]: 1,
+  [key + "2"]: 2,
+  "quoted key": 3,
+  42: 4,
+  0.5: 5,
+  if: 6,
+  get: 7,
+  set: 8,
+  async: 9,
+}
+assert(obj.dyn, 1)
+assert(obj.dyn2, 2)
+assert(obj["quoted key"], 3)
+assert(obj[42], 4)
+assert(obj["0.5"], 5)
+assert(obj.if, 6)
+assert(obj.get + obj.set + obj.async, 24)
+
+let order = ""
+let o = {
+  [(order += "a", "x")]: (order += "b", 1),
+  [(order += "c", "y")]: (order += "d", 2),
+}
+assert(order, "abcd")
+
+let sym = Symbol("s")
+let withSym = { [sym]: 1, [Symbol.iterator]: function() { return [].values() } }
+assert(withSym[sym], 1)
+assert(sym in withSym, true)
+let keys = ""
+for (let k in withSym) keys += k
+assert(keys, "")
+
+let counter = {
+  count: 0,
+  increment() { this.count++; return this },
+  get double() { return this.count * 2 },
+  set double(v) { this.count = v / 2 },
+  [key + "Method"]() { return "computed" },
+  get [sym]() { return "symbol getter" },
+  *gen() { yield 1; yield 2 },
+  get() { return "get method" },
+}
+assert(counter.increment().increment().count, 2)
+assert(counter.double, 4)
+counter.double = 10
+assert(counter.count, 5)
+assert(counter.dynMethod(), "computed")
+assert(counter[sym], "symbol getter")
+let sum = 0
+for (let x of counter.gen()) sum += x
+assert(sum, 3)
+assert(counter.get(), "get method")
+
+let base = {
+  greet() { return "base" },
+  get name() { return "base name" },
+}
+let derived = {
+  __proto__: base,
+  greet() { return "derived+" + super.greet() },
+  get name() { return super.name + "!" },
+  arrow() { return (() => super.greet())() },
+}
+assert(derived.greet(), "derived+base")
+assert(derived.name, "base name!")
+assert(derived.arrow(), "base")
+assert(derived.__proto__ === base, true)
+
+let borrowed = derived.greet
+let other = { greet: borrowed }
+assert(other.greet(), "derived+base")
+
+let nullProto = { __proto__: null }
+assert(Object.getPrototypeOf(nullProto), null)
+assert(nullProto.__proto__, undefined)
+let ignored = { __proto__: 1 }
+assert(ignored.__proto__ !== 1, true)
+let __proto__ = base
+let shorthand = { __proto__ }
+assert(shorthand.greet, undefined)
+
+let shorthandValue = 1
+let s = { shorthandValue, key }
+assert(s.shorthandValue, 1)
+assert(s.key, "dyn")
@@ -0,0 +1,235 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+// keys, values and entries
+let o = { a: 1, b: 2 }
+assert(Object.keys(o).join(), "a,b")
+assert(Object.values(o).join(), "1,2")
+assert(Object.entries(o).length, 2)
+assert(Object.entries(o)[1].join(), "b,2")
+assert(Object.keys([5, 6]).join(), "0,1")
+assert(Object.keys("ab").join(), "0,1")
+assert(Object.values("ab").join(), "a,b")
+assert(Object.keys(1).length, 0)
+let thrown = false
+try { Object.keys(null) } catch (e) { thrown = true }
+assert(thrown, true)
+let sym = Symbol("s")
+let withSym = { x: 1 }
+withSym[sym] = 2
+assert(Object.keys(withSym).join(), "x")
+
+// Integer keys come first in ascending order, then the other keys in creation order.
+let ordered = { zeta: 1, 2: 1, alpha: 1, 1: 1, mid: 1 }
+ordered.beta = 1
+assert(Object.keys(ordered).join(), "1,2,zeta,alpha,mid,beta")
+delete ordered.zeta
+ordered.zeta = 1
+ordered.alpha = 2
+assert(Object.keys(ordered).join(), "1,2,alpha,mid,beta,zeta")
+let forInKeys = ""
+for (let k in { c: 1, a: 1, b: 1 }) forInKeys += k
+assert(forInKeys, "cab")
+
+// assign
+let target = { a: 1 }
+assert(Object.assign(target, { b: 2 }, null, { c: 3, a: 4 }), target)
+assert(target.a, 4)
+assert(target.b, 2)
+assert(target.c, 3)
+assert(Object.assign({}, withSym)[sym], 2)
+let setterLog = ""
+Object.assign({ set v(x) { setterLog += x } }, { v: "set" })
+assert(setterLog, "set")
+
+// create, getPrototypeOf and setPrototypeOf
+let proto = { greet: function () { return "hi " + this.name } }
+let child = Object.create(proto)
+child.name = "bob"
+assert(child.greet(), "hi bob")
+assert(Object.getPrototypeOf(child), proto)
+assert(Object.getPrototypeOf(Object.create(null)), null)
+assert(Object.getPrototypeOf([]), Array.prototype)
+assert(Object.getPrototypeOf("s"), String.prototype)
+let created = Object.create({}, { p: { value: 3, enumerable: true } })
+assert(created.p, 3)
+assert(Object.keys(created).join(), "p")
+thrown = false
+try { Object.create(1) } catch (e) { thrown = true }
+assert(thrown, true)
+let other = {}
+assert(Object.setPrototypeOf(other, proto), other)
+assert(other.greet !== undefined, true)
+thrown = false
+try { Object.setPrototypeOf(proto, other) } catch (e) { thrown = true }
+assert(thrown, true)
+
+// defineProperty and getOwnPropertyDescriptor
+let d = {}
+Object.defineProperty(d, "x", { value: 1 })
+assert(d.x, 1)
+let desc = Object.getOwnPropertyDescriptor(d, "x")
+assert(desc.value, 1)
+assert(desc.writable, false)
+assert(desc.enumerable, false)
+assert(desc.configurable, false)
+assert(Object.keys(d).length, 0)
+d.x = 2
+assert(d.x, 1)
+thrown = false
+try { Object.defineProperty(d, "x", { value: 2 }) } catch (e) { thrown = true }
+assert(thrown, true)
+Object.defineProperty(d, "x", { value: 1 })
+let counter = 0
+Object.defineProperty(d, "y", {
+  get: function () { return ++counter },
+  enumerable: true,
+  configurable: true
+})
+assert(d.y, 1)
+assert(d.y, 2)
+desc = Object.getOwnPropertyDescriptor(d, "y")
+assert(typeof desc.get, "function")
+assert(desc.set, undefined)
+assert("value" in desc, false)
+Object.defineProperty(d, "y", { value: "data" })
+assert(d.y, "data")
+assert(Object.getOwnPropertyDescriptor(d, "y").writable, false)
+assert(Object.getOwnPropertyDescriptor(d, "y").configurable, true)
+assert(Object.getOwnPropertyDescriptor(d, "none"), undefined)
+thrown = false
+try { Object.defineProperty(d, "z", { get: 1 }) } catch (e) { thrown = true }
+assert(thrown, true)
+thrown = false
+try { Object.defineProperty(d, "z", { get: function () {}, value: 1 }) } catch (e) { thrown = true }
+assert(thrown, true)
+thrown = false
+try { Object.defineProperty(1, "z", {}) } catch (e) { thrown = true }
+assert(thrown, true)
+Object.defineProperty(d, sym, { value: "sym", enumerable: true })
+assert(d[sym], "sym")
+Object.defineProperty(d, 3, { value: "three", writable: true })
+assert(d["3"], "three")
+assert(Object.getOwnPropertyDescriptor("ab", 1).value, "b")
+assert(Object.getOwnPropertyDescriptor("ab", "length").value, 2)
+
+// defineProperties and getOwnPropertyDescriptors
+let dp = Object.defineProperties({}, {
+  a: { value: 1, enumerable: true },
+  b: { value: 2, writable: true }
+})
+assert(Object.keys(dp).join(), "a")
+assert(dp.b, 2)
+let descs = Object.getOwnPropertyDescriptors(dp)
+assert(descs.a.enumerable, true)
+assert(descs.b.writable, true)
+assert(descs.b.enumerable, false)
+
+// getOwnPropertyNames and getOwnPropertySymbols
+assert(Object.getOwnPropertyNames(dp).join(), "a,b")
+assert(Object.getOwnPropertyNames([1]).join(), "0,length")
+assert(Object.getOwnPropertySymbols(withSym).length, 1)
+assert(Object.getOwnPropertySymbols(withSym)[0], sym)
+assert(Object.getOwnPropertySymbols({}).length, 0)
+
+// Array length
+let ary = [1, 2, 3]
+Object.defineProperty(ary, "length", { value: 1 })
+assert(ary.length, 1)
+assert(ary[1], undefined)
+Object.defineProperty(ary, "length", { writable: false })
+ary[5] = 1
+assert(ary.length, 1)
+assert(Object.getOwnPropertyDescriptor(ary, "length").writable, false)
+thrown = false
+try { Object.defineProperty([], "length", { value: -1 }) } catch (e) { thrown = true }
+assert(thrown, true)
+
+// freeze, seal and preventExtensions
+let frozen = Object.freeze({ a: 1, inner: {} })
+assert(Object.isFrozen(frozen), true)
+assert(Object.isSealed(frozen), true)
+assert(Object.isExtensible(frozen), false)
+frozen.a = 2
+frozen.b = 3
+assert(frozen.a, 1)
+assert(frozen.b, undefined)
+delete frozen.a
+assert(frozen.a, 1)
+frozen.inner.x = 1
+assert(frozen.inner.x, 1)
+assert(Object.isFrozen(frozen.inner), false)
+let frozenAry = Object.freeze([1, 2])
+frozenAry[0] = 5
+assert(frozenAry[0], 1)
+assert(Object.isFrozen(frozenAry), true)
+
+let sealed = Object.seal({ a: 1 })
+assert(Object.isSealed(sealed), true)
+assert(Object.isFrozen(sealed), false)
+sealed.a = 2
+sealed.b = 3
+assert(sealed.a, 2)
+assert(sealed.b, undefined)
+delete sealed.a
+assert(sealed.a, 2)
+
+let fixed = Object.preventExtensions({ a: 1 })
+assert(Object.isExtensible(fixed), false)
+assert(Object.isSealed(fixed), false)
+fixed.b = 1
+assert(fixed.b, undefined)
+delete fixed.a
+assert(fixed.a, undefined)
+assert(Object.isSealed(fixed), true)
+assert(Object.isFrozen(fixed), true)
+thrown = false
+try { Object.setPrototypeOf(fixed, {}) } catch (e) { thrown = true }
+assert(thrown, true)
+
+assert(Object.isFrozen(1), true)
+assert(Object.isSealed("s"), true)
+assert(Object.isExtensible(1), false)
+assert(Object.freeze(1), 1)
+assert(Object.isExtensible({}), true)
+
+// Object.prototype methods
+assert({ a: 1 }.hasOwnProperty("a"), true)
+assert(child.hasOwnProperty("greet"), false)
+assert([1].hasOwnProperty(0), true)
+assert([1].hasOwnProperty("length"), true)
+assert(withSym.hasOwnProperty(sym), true)
+assert(proto.isPrototypeOf(child), true)
+assert(Object.prototype.isPrototypeOf(child), true)
+assert(child.isPrototypeOf(proto), false)
+assert(proto.isPrototypeOf(1), false)
+assert(dp.propertyIsEnumerable("a"), true)
+assert(dp.propertyIsEnumerable("b"), false)
+assert([1].propertyIsEnumerable("length"), false)
+assert({}.toString(), "[object Object]")
+let toString = Object.prototype.toString
+assert(toString.call([]), "[object Array]")
+assert(toString.call(function () {}), "[object Function]")
+assert(toString.call(null), "[object Null]")
+assert(toString.call(undefined), "[object Undefined]")
+assert(toString.call("s"), "[object String]")
+assert(toString.call(1), "[object Number]")
+assert(toString.call(true), "[object Boolean]")
+assert(toString.call(/a/), "[object RegExp]")
+assert((function () { return toString.call(arguments) })(), "[object Arguments]")
+let tagged = {}
+tagged[Symbol.toStringTag] = "Tagged"
+assert(tagged.toString(), "[object Tagged]")
+assert(toString.call(Object.create(tagged)), "[object Tagged]")
+let getterTag = {}
+Object.defineProperty(getterTag, Symbol.toStringTag, { get: function () { return "Getter" } })
+assert(toString.call(getterTag), "[object Getter]")
+let arrayTag = []
+arrayTag[Symbol.toStringTag] = 1
+assert(toString.call(arrayTag), "[object Array]")
+class Tagged { get [Symbol.toStringTag]() { return "FromClass" } }
+assert(new Tagged().toString(), "[object FromClass]")
+assert(({ [Symbol.toStringTag]: "X" }).toString(), "[object X]")
+assert(typeof Symbol.toStringTag, "symbol")
+thrown = false
+try { Object.prototype.hasOwnProperty.call(null, "a") } catch (e) { thrown = true }
+assert(thrown, true)
@@ -25,3 +25,24 @@ assert(3 <= 3, true)
 assert(3 <= 4, true)
 assert(3 >= 3, true)
 assert(3 >= 4, false)
+assert(2 ** 10, 1024)
+assert(2 ** 3 ** 2, 512)
+assert((-2) ** 2, 4)
+assert(1 ** Infinity !== 1 ** Infinity, true)
+assert(0 || 'a', 'a')
+assert(1 || 'a', 1)
+assert(0 && 'a', 0)
+assert((1, 2), 2)
+assert(void 0, undefined)
+assert(void 'a', undefined)
+
+let i, j, sum = 0
+for (i = 0, j = 10; i < j; i++, j--) sum += j - i
+assert(sum, 30)
+assert(i, 5)
+
+let count = 0
+void count++
+assert(count, 1)
+count++, count++
+assert(count, 3)
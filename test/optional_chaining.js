@@ -0,0 +1,64 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+// Nullish coalescing
+assert(null ?? 'default', 'default')
+assert(undefined ?? 'default', 'default')
+assert(0 ?? 'default', 0)
+assert('' ?? 'default', '')
+assert(false ?? 'default', false)
+assert(null ?? undefined ?? 3, 3)
+assert((null || 0) ?? 1, 0)
+assert(null ?? (0 || 1), 1)
+let evaluated = false
+let rhs = () => {
+  evaluated = true
+  return 1
+}
+assert(2 ?? rhs(), 2)
+assert(evaluated, false)
+
+// Optional member access
+let obj = { a: { b: 1 }, f: function() { return this.a.b }, arr: [10, 20], n: null }
+assert(obj?.a.b, 1)
+assert(obj.x?.b, undefined)
+assert(obj.n?.b.c.d, undefined)
+assert(obj?.['a']?.['b'], 1)
+assert(obj.arr?.[1], 20)
+assert(obj.x?.[rhs()], undefined)
+assert(evaluated, false)
+let none = null
+assert(none?.a, undefined)
+assert(none?.a.b.c, undefined)
+assert(typeof none?.a, 'undefined')
+assert(obj.a?.b ?? 5, 1)
+assert(obj.x?.b ?? 5, 5)
+
+// Optional calls
+assert(obj.f?.(), 1)
+assert(obj.g?.(), undefined)
+assert(obj.g?.(rhs()), undefined)
+assert(evaluated, false)
+assert(obj?.f(), 1)
+assert(obj['f']?.(), 1)
+assert(none?.f(rhs()), undefined)
+assert(evaluated, false)
+let fn = () => 7
+assert(fn?.(), 7)
+let noFn = undefined
+assert(noFn?.(), undefined)
+assert(obj.a?.b.constructor, undefined)
+assert(obj?.arr.map(x => x + 1)[1], 21)
+
+// Short-circuiting in the middle of an expression
+let values = [1, none?.a.b, obj.n?.x, 4]
+assert(values.length, 4)
+assert(values[1], undefined)
+assert(values[3], 4)
+let sum = 0
+for (let i = 0; i < 3; i++) {
+  sum += obj.x?.y ?? i
+}
+assert(sum, 3)
+let add = (x, y) => x + y
+assert(add(1, none?.f(2, 3) ?? 5), 6)
+
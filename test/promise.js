@@ -0,0 +1,93 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+let log = ''
+let fail = () => { throw 'should not be called' }
+
+// Reactions are run as microtasks after the script.
+Promise.resolve(1).then(x => {
+  log += 'b'
+  assert(x, 1)
+})
+log += 'a'
+assert(log, 'a')
+
+// The executor runs synchronously.
+let executed = false
+let p = new Promise((resolve, reject) => {
+  executed = true
+  resolve(10)
+  resolve(20)
+  reject('ignored')
+})
+assert(executed, true)
+assert(typeof p, 'object')
+p.then(x => assert(x, 10))
+
+// Chaining
+Promise.resolve(1)
+  .then(x => x + 1)
+  .then(x => { throw x + 1 })
+  .then(fail)
+  .catch(e => {
+    assert(e, 3)
+    return Promise.resolve(e + 1)
+  })
+  .then(x => assert(x, 4))
+
+// An exception in the executor rejects the promise.
+new Promise(() => { throw 'thrown' }).catch(e => assert(e, 'thrown'))
+
+Promise.reject('r').then(fail, e => assert(e, 'r'))
+
+// Non-function handlers pass values through.
+Promise.resolve('through').then().then(undefined, fail).then(x => assert(x, 'through'))
+Promise.reject('rejected').then(fail).catch(e => assert(e, 'rejected'))
+
+// Thenables are followed.
+let thenable = { then: (resolve) => resolve('thenable') }
+Promise.resolve(thenable).then(x => assert(x, 'thenable'))
+new Promise(resolve => resolve(thenable)).then(x => assert(x, 'thenable'))
+
+// Promise.resolve returns the promise as it is.
+assert(Promise.resolve(p), p)
+
+// finally
+let finallyCalled = false
+Promise.resolve('f')
+  .finally(() => { finallyCalled = true; return 'ignored' })
+  .then(x => {
+    assert(x, 'f')
+    assert(finallyCalled, true)
+  })
+let finallyArgs
+Promise.reject('g')
+  .finally((...args) => { finallyArgs = args })
+  .catch(e => {
+    assert(e, 'g')
+    assert(finallyArgs.length, 0)
+  })
+Promise.resolve('h')
+  .finally(() => { throw 'from finally' })
+  .catch(e => assert(e, 'from finally'))
+
+// Promise.all
+Promise.all([1, Promise.resolve(2), new Promise(resolve => resolve(3))]).then(values => {
+  assert(values.length, 3)
+  assert(values[0], 1)
+  assert(values[1], 2)
+  assert(values[2], 3)
+})
+Promise.all([]).then(values => assert(values.length, 0))
+Promise.all([1, Promise.reject('all'), 3]).then(fail, e => assert(e, 'all'))
+
+// Promise.race
+let slow = Promise.resolve().then(() => 'slow')
+Promise.race([slow, 'fast']).then(x => assert(x, 'fast'))
+Promise.race([Promise.reject('race'), slow]).then(fail, e => assert(e, 'race'))
+
+// The order of reactions
+let order = ''
+let p1 = Promise.resolve()
+p1.then(() => { order += '1' }).then(() => { order += '3' })
+p1.then(() => { order += '2' }).then(() => { order += '4' })
+Promise.resolve().then(() => {}).then(() => {}).then(() => {}).then(() => assert(order, '1234'))
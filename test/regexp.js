@@ -0,0 +1,187 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+// Literals and division
+let a = 10, b = 2, g = 5
+assert(a / b / g, 1)
+assert(a /b/ g, 1)
+let re = /ab+c/i
+assert(typeof re, 'object')
+assert(re.source, 'ab+c')
+assert(re.flags, 'i')
+assert(re.ignoreCase, true)
+assert(re.global, false)
+assert(/[/]\//g.toString(), '/[/]\\//g')
+assert(/(?:)/.source, '(?:)')
+assert(new RegExp('').source, '(?:)')
+assert(new RegExp('a/b').source, 'a\\/b')
+assert([/x/][0].test('x'), true)
+assert((() => /y/)().test('y'), true)
+
+// A literal evaluates to a new object each time.
+let make = () => /a/g
+assert(make() !== make(), true)
+
+// test and exec
+assert(/^\d+$/.test('12345'), true)
+assert(/^\d+$/.test('123a5'), false)
+let m = /(\d+)-(\d+)?/.exec('tel: 03-')
+assert(m.length, 3)
+assert(m[0], '03-')
+assert(m[1], '03')
+assert(m[2], undefined)
+assert(m.index, 5)
+assert(m.input, 'tel: 03-')
+assert(m.groups, undefined)
+assert(/x/.exec('abc'), null)
+
+// Named groups
+let date = /(?<year>\d{4})-(?<month>\d{2})/.exec('on 2019-04')
+assert(date.groups.year, '2019')
+assert(date.groups.month, '04')
+
+// Flags
+assert(/a.c/.test('a\nc'), false)
+assert(/a.c/s.test('a\nc'), true)
+assert(/^b/.test('a\nb'), false)
+assert(/^b/m.test('a\nb'), true)
+assert(/ABC/i.test('xabcx'), true)
+assert(/\u{1F363}/u.test('🍣'), true)
+assert(/^.$/u.test('🍣'), true)
+
+// lastIndex
+let global = /o/g
+assert(global.lastIndex, 0)
+assert(global.test('foo'), true)
+assert(global.lastIndex, 2)
+assert(global.test('foo'), true)
+assert(global.lastIndex, 3)
+assert(global.test('foo'), false)
+assert(global.lastIndex, 0)
+
+let sticky = /o/y
+assert(sticky.test('foo'), false)
+sticky.lastIndex = 1
+assert(sticky.test('foo'), true)
+assert(sticky.lastIndex, 2)
+
+// Backtracking, backreferences and lookarounds
+assert(/^(a+)+b$/.test('aaab'), true)
+assert(/(\w)\1/.exec('abccd')[0], 'cc')
+assert(/\d+(?=%)/.exec('10 20%')[0], '20')
+assert(/(?<=\$)\d+/.exec('1 $2')[0], '2')
+assert(/\bfoo\b/.test('a foo b'), true)
+assert(/\bfoo\b/.test('afoob'), false)
+
+// String.prototype.match
+let matched = 'a1b22c333'.match(/\d+/g)
+assert(matched.length, 3)
+assert(matched[2], '333')
+assert('abc'.match(/\d/g), null)
+assert('abc'.match(/b/).index, 1)
+assert('a.b'.match('.')[0], 'a')
+assert('aaa'.match(/a*?/g).length, 4)
+
+// String.prototype.search
+assert('abcd'.search(/cd/), 2)
+assert('abcd'.search(/x/), -1)
+let searched = /b/g
+searched.lastIndex = 2
+assert('abc'.search(searched), 1)
+assert(searched.lastIndex, 2)
+
+// String.prototype.replace
+assert('a-b-c'.replace('-', '+'), 'a+b-c')
+assert('a-b-c'.replace(/-/, '+'), 'a+b-c')
+assert('a-b-c'.replace(/-/g, '+'), 'a+b+c')
+assert('John Smith'.replace(/(\w+)\s(\w+)/, '$2, $1'), 'Smith, John')
+assert('abc'.replace(/b/, '[$&|$`|$\'|$$]'), 'a[b|a|c|$]c')
+assert('2019-04'.replace(/(?<y>\d+)-(?<m>\d+)/, '$<m>/$<y>'), '04/2019')
+assert('abc'.replace(/b/, '$1'), 'a$1c')
+assert('abc'.replace(/(b)/, '$01$2'), 'ab$2c')
+assert('abc'.replace(/x*/g, '-'), '-a-b-c-')
+assert('a1b2'.replace(/\d/g, d => d + d), 'a11b22')
+let replacerArgs
+'xaby'.replace(/(a)(b)/, function() {
+  replacerArgs = arguments
+  return ''
+})
+assert(replacerArgs[0], 'ab')
+assert(replacerArgs[1], 'a')
+assert(replacerArgs[2], 'b')
+assert(replacerArgs[3], 1)
+assert(replacerArgs[4], 'xaby')
+
+// String.prototype.split
+let parts = 'a1b22c'.split(/\d+/)
+assert(parts.length, 3)
+assert(parts[2], 'c')
+let withCaptures = 'a1b2c'.split(/(\d)/)
+assert(withCaptures.length, 5)
+assert(withCaptures[1], '1')
+assert('abc'.split(/(?:)/).length, 3)
+assert(''.split(/x/).length, 1)
+assert(''.split(/(?:)/).length, 0)
+assert('a,b,c'.split(/,/, 2).length, 2)
+
+// Constructor
+let constructed = new RegExp('a+', 'g')
+assert(constructed.global, true)
+assert(constructed.test('caat'), true)
+assert(new RegExp(/x/g).flags, 'g')
+assert(new RegExp(/x/g, 'i').flags, 'i')
+assert(RegExp('b').test('abc'), true)
+
+let throws = f => {
+  try {
+    f()
+  } catch (e) {
+    return true
+  }
+  return false
+}
+assert(throws(() => new RegExp('(')), true)
+assert(throws(() => new RegExp('a', 'gg')), true)
+assert(throws(() => RegExp.prototype.exec.call({}, '')), true)
+
+// Indices are in UTF-16 code units, and surrogate pairs are code points only with the u flag
+let smile = '😀'
+assert((smile + 'a').search(/a/), 2)
+assert((smile + 'a').match(/a/).index, 2)
+assert((smile + 'a').replace(/a/, (m, offset) => offset), smile + '2')
+let unitRe = /a/g
+unitRe.exec(smile + 'a')
+assert(unitRe.lastIndex, 3)
+assert(/^.$/.test(smile), false)
+assert(/^.$/u.test(smile), true)
+assert(/^..$/.test(smile), true)
+assert(/^😀+$/.test(smile + String.fromCharCode(0xde00)), true)
+assert(/^[😀]$/u.test(smile), true)
+assert(/\ude00/.exec(smile).index, 1)
+assert(/\ude00/u.exec(smile), null)
+assert(smile.replace(/(?:)/g, '-').length, 5)
+assert(smile.replace(/(?:)/gu, '-'), '-' + smile + '-')
+assert(smile.split(/(?:)/).length, 2)
+assert(smile.split(/(?:)/u).length, 1)
+assert(('x' + smile + 'y').match(/./gu).length, 3)
+assert(('a' + smile + 'b').replace(smile, '[$&]'), 'a[' + smile + ']b')
+assert(('a' + smile + 'b').replace(/b/, "$`"), 'a' + smile + 'a' + smile)
+
+// Quantifiers don't recurse per iteration
+let long = 'a'.repeat(100000)
+assert(long.match(/a*/)[0].length, 100000)
+assert(/^.*b/.test(long), false)
+assert(long.match(/(a)*/)[1], 'a')
+assert(/^(?:aa)+$/.test(long), true)
+assert(('ab'.repeat(50000) + 'c').match(/(a|b)*c/)[0].length, 100001)
+assert(/^(a|b)*$/.test('ab'.repeat(50000) + 'c'), false)
+assert(('x' + long).replace(/(a)+?$/, ''), 'x')
+
+// Regular expressions can follow the heads of statements
+let matchedHead = 0
+if (long) /a/.test(long) && matchedHead++
+for (let i = 0; i < 1; i++) /^a+$/.test(long) && matchedHead++
+let count = 0
+while (count < 3) /a/g.exec(long) && count++
+assert(matchedHead, 2)
+assert(count, 3)
+assert((long.length) / 2 / 2, 25000)
@@ -0,0 +1,162 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+let throws = f => {
+  try { f() } catch (e) { return true }
+  return false
+}
+
+// Own data properties
+let obj = { a: 1 }
+obj.a = 2
+obj.b = 3
+assert(obj.a, 2)
+assert(obj.b, 3)
+assert(Object.getOwnPropertyDescriptor(obj, "b").enumerable, true)
+Object.defineProperty(obj, "c", { value: 1, enumerable: false, writable: true })
+obj.c = 2
+assert(obj.c, 2)
+assert(Object.getOwnPropertyDescriptor(obj, "c").enumerable, false)
+Object.defineProperty(obj, "ro", { value: 1 })
+obj.ro = 2
+assert(obj.ro, 1)
+
+// Inherited data properties are shadowed by an own property of the receiver
+let proto = { x: 1 }
+let child = Object.create(proto)
+child.x = 2
+assert(child.x, 2)
+assert(proto.x, 1)
+assert(child.hasOwnProperty("x"), true)
+
+// Inherited non-writable properties can't be shadowed by assignment
+Object.defineProperty(proto, "fixed", { value: "p" })
+child.fixed = "c"
+assert(child.fixed, "p")
+assert(child.hasOwnProperty("fixed"), false)
+Object.defineProperty(child, "fixed", { value: "c" })
+assert(child.fixed, "c")
+
+// Setters found on the prototype chain are called with the receiver as this
+let log = []
+let base = {
+  set v(val) { log.push(this); this._v = val },
+  get v() { return this._v }
+}
+let derived = Object.create(Object.create(base))
+derived.v = 5
+assert(log[0], derived)
+assert(derived.v, 5)
+assert(derived.hasOwnProperty("_v"), true)
+assert(derived.hasOwnProperty("v"), false)
+assert(base._v, undefined)
+
+class Point {
+  set x(val) { this._x = val * 2 }
+  get x() { return this._x }
+}
+let p = new Point()
+p.x = 2
+assert(p.x, 4)
+assert(p.hasOwnProperty("x"), false)
+
+// Accessors without a setter
+let getterOnly = { get g() { return 1 } }
+getterOnly.g = 2
+assert(getterOnly.g, 1)
+let inheritsGetter = Object.create(getterOnly)
+inheritsGetter.g = 2
+assert(inheritsGetter.g, 1)
+assert(inheritsGetter.hasOwnProperty("g"), false)
+
+// Non-extensible objects
+let fixed = Object.preventExtensions({ a: 1 })
+fixed.a = 2
+assert(fixed.a, 2)
+fixed.b = 1
+assert(fixed.b, undefined)
+let inheritsFromFixed = Object.create(fixed)
+inheritsFromFixed.b = 1
+assert(inheritsFromFixed.b, 1)
+
+// Symbol keys
+let sym = Symbol()
+let symProto = {}
+Object.defineProperty(symProto, sym, { value: 1 })
+let inheritsSym = Object.create(symProto)
+inheritsSym[sym] = 2
+assert(inheritsSym[sym], 1)
+
+// Arrays
+let ary = [1, 2, 3]
+ary[5] = 6
+assert(ary.length, 6)
+ary.length = 2
+assert(ary.join(), "1,2")
+assert(throws(() => { ary.length = -1 }), true)
+Object.defineProperty(ary, 0, { value: 0, configurable: false })
+ary.length = 0
+assert(ary.length, 1)
+Object.freeze(ary)
+assert(throws(() => { ary.push(1) }), true)
+assert(ary.length, 1)
+
+// Arguments objects stay mapped to the parameters
+function mapped(a) {
+  arguments[0] = 2
+  return a
+}
+assert(mapped(1), 2)
+
+// Primitives
+let str = "abc"
+str.x = 1
+assert(str.x, undefined)
+str[0] = "x"
+assert(str[0], "a")
+str.length = 1
+assert(str.length, 3)
+let num = 1
+num.x = 1
+assert(num.x, undefined)
+assert(throws(() => { undefined.x = 1 }), true)
+assert(throws(() => { null.x = 1 }), true)
+let received
+Object.defineProperty(String.prototype, "setterOnString", {
+  set: function (val) { received = this + val },
+  configurable: true
+})
+"abc".setterOnString = "!"
+assert(received, "abc!")
+delete String.prototype.setterOnString
+
+// Builtin functions throw when an assignment fails
+let frozen = Object.freeze({ a: 1 })
+frozen.a = 2
+frozen.b = 3
+assert(frozen.a, 1)
+assert(frozen.b, undefined)
+assert(throws(() => { Object.assign(frozen, { a: 2 }) }), true)
+
+// __proto__
+let protoA = { a: 1 }
+let withProto = {}
+withProto.__proto__ = protoA
+assert(withProto.a, 1)
+withProto.__proto__ = 5
+assert(Object.getPrototypeOf(withProto), protoA)
+assert(throws(() => { protoA.__proto__ = withProto }), true)
+assert(Object.getPrototypeOf(protoA), Object.prototype)
+Object.freeze(withProto)
+assert(throws(() => { withProto.__proto__ = {} }), true)
+assert(Object.getPrototypeOf(withProto), protoA)
+withProto.__proto__ = protoA
+
+// __proto__ is an accessor of Object.prototype, which objects without it don't inherit
+let noProto = Object.create(null)
+noProto.__proto__ = protoA
+assert(Object.getPrototypeOf(noProto), null)
+assert(noProto.__proto__, protoA)
+assert(noProto.a, undefined)
+let desc = Object.getOwnPropertyDescriptor(Object.prototype, '__proto__')
+assert(typeof desc.get, 'function')
+assert(typeof desc.set, 'function')
+assert('abc'.__proto__, String.prototype)
@@ -0,0 +1,139 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+let sum = (...args) => {
+  let total = 0
+  for (let arg of args) total += arg
+  return total
+}
+let count = (...args) => args.length
+
+// Calls
+let nums = [1, 2, 3]
+assert(sum(...nums), 6)
+assert(sum(0, ...nums, 4, ...[5]), 15)
+assert(count(...[]), 0)
+assert(count(...[, ,]), 2)
+assert(sum(...'123'.split('')), '0123')
+
+let obj = {
+  base: 10,
+  add: function(...args) {
+    return this.base + sum(...args)
+  },
+}
+assert(obj.add(...nums), 16)
+assert(obj['add'](...nums, 1), 17)
+
+// Arguments are evaluated in order.
+let log = ''
+let f = x => { log += x; return x }
+assert(count(f(1), ...f([2, 3]), f(4)), 4)
+assert(log, '12,34')
+
+// new
+function Point(x, y) {
+  this.x = x
+  this.y = y
+}
+let p = new Point(...[1, 2])
+assert(p.x, 1)
+assert(p.y, 2)
+
+class Base {
+  constructor(...args) {
+    this.args = args
+  }
+}
+class Derived extends Base {
+  constructor(...args) {
+    super(0, ...args)
+  }
+}
+assert(new Derived(1, 2).args.length, 3)
+
+// Array literals
+let a = [1, 2]
+let b = [3]
+let c = [0, ...a, ...b, 4]
+assert(c.length, 5)
+for (let i = 0; i < 5; i++) assert(c[i], i)
+assert([...[]].length, 0)
+let copy = [...a]
+assert(copy !== a, true)
+assert(copy[1], 2)
+
+let holes = [...[1, , 3]]
+assert(holes.length, 3)
+assert(holes[1], undefined)
+
+// Spread uses the iterator protocol.
+let chars = [...'ab']
+assert(chars.length, 2)
+assert(chars[1], 'b')
+
+function* gen() {
+  yield 1
+  yield 2
+}
+assert(sum(...gen()), 3)
+assert([...gen(), ...gen()].length, 4)
+
+let iterable = {}
+iterable[Symbol.iterator] = function() {
+  let n = 0
+  return { next: () => ({ value: n, done: n++ >= 3 }) }
+}
+assert(sum(...iterable), 3)
+
+let throws = f => {
+  try {
+    f()
+  } catch (e) {
+    return true
+  }
+  return false
+}
+assert(throws(() => [...1]), true)
+assert(throws(() => sum(...{})), true)
+
+// Object literals
+let o1 = { x: 1, y: 2 }
+let o2 = { ...o1, y: 3, z: 4 }
+assert(o2.x, 1)
+assert(o2.y, 3)
+assert(o2.z, 4)
+let o3 = { y: 0, ...o1 }
+assert(o3.y, 2)
+assert(o1.z, undefined)
+
+let empty = { ...null, ...undefined, ...1 }
+let keys = 0
+for (let key in empty) keys++
+assert(keys, 0)
+
+let fromArray = { ...['a', 'b'] }
+assert(fromArray[0], 'a')
+assert(fromArray[1], 'b')
+
+let fromString = { ...'ab', ...'' }
+assert(Object.keys(fromString).join(), '0,1')
+assert(fromString[0], 'a')
+assert(fromString[1], 'b')
+let { 0: first, ...restOfString } = 'xyz'
+assert(first, 'x')
+assert(Object.keys(restOfString).join(), '1,2')
+assert(restOfString[2], 'z')
+
+// Getters are invoked and copied as data properties.
+let getterCalls = 0
+let withGetter = { get g() { getterCalls++; return 'g' } }
+let copied = { ...withGetter }
+assert(getterCalls, 1)
+assert(copied.g, 'g')
+assert(getterCalls, 1)
+
+// Non-enumerable properties are not copied.
+class C {
+  m() {}
+}
+assert({ ...new C() }.m, undefined)
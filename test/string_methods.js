@@ -0,0 +1,208 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+// String and its statics
+assert(String(), "")
+assert(String(12), "12")
+assert(String(null), "null")
+assert(String(undefined), "undefined")
+assert(String(true), "true")
+assert(String([1, 2]), "1,2")
+assert(String(Symbol("desc")), "Symbol(desc)")
+assert("abc".constructor, String)
+assert(String.fromCharCode(72, 105), "Hi")
+assert(String.fromCharCode(65601), "A")
+assert(String.fromCharCode(0xD83D, 0xDE00), "😀")
+assert(String.fromCodePoint(0x1F600, 97), "😀a")
+let thrown = false
+try { String.fromCodePoint(0x110000) } catch (e) { thrown = true }
+assert(thrown, true)
+thrown = false
+try { String.fromCodePoint(1.5) } catch (e) { thrown = true }
+assert(thrown, true)
+
+// Indices are UTF-16 code units
+let s = "a😀b"
+assert(s.length, 4)
+assert(s.charAt(0), "a")
+assert(s.charAt(3), "b")
+assert(s.charAt(4), "")
+assert(s.charCodeAt(1), 0xD83D)
+assert(s.charCodeAt(2), 0xDE00)
+assert(s.charCodeAt(9) !== s.charCodeAt(9), true)
+assert(s.codePointAt(1), 0x1F600)
+assert(s.codePointAt(2), 0xDE00)
+assert(s.codePointAt(4), undefined)
+assert(s.indexOf("b"), 3)
+assert(s.slice(1, 3), "😀")
+assert(s.slice(-1), "b")
+assert(s.substring(3, 1), "😀")
+assert(s.substr(-3, 2), "😀")
+assert("日本語テキスト".indexOf("テ"), 3)
+assert("日本語テキスト".indexOf("テ", 4), -1)
+
+// Searching
+let t = "hello world hello"
+assert(t.indexOf("hello"), 0)
+assert(t.indexOf("hello", 1), 12)
+assert(t.indexOf("bye"), -1)
+assert(t.indexOf(""), 0)
+assert(t.indexOf("", 100), 17)
+assert(t.lastIndexOf("hello"), 12)
+assert(t.lastIndexOf("hello", 11), 0)
+assert(t.lastIndexOf("hello", -5), 0)
+assert(t.lastIndexOf("bye"), -1)
+assert(t.includes("world"), true)
+assert(t.includes("world", 7), false)
+assert(t.startsWith("hello"), true)
+assert(t.startsWith("world", 6), true)
+assert(t.endsWith("hello"), true)
+assert(t.endsWith("world", 11), true)
+thrown = false
+try { t.includes(/o/) } catch (e) { thrown = true }
+assert(thrown, true)
+
+// Substrings
+assert("abcdef".slice(2), "cdef")
+assert("abcdef".slice(2, -1), "cde")
+assert("abcdef".slice(4, 2), "")
+assert("abcdef".substring(4, 2), "cd")
+assert("abcdef".substring(-3, 2), "ab")
+assert("abcdef".substring(NaN, 3), "abc")
+assert("abcdef".substr(1, 3), "bcd")
+assert("abcdef".substr(-2), "ef")
+assert("abcdef".substr(2, -1), "")
+
+// Case, trimming and padding
+assert("Straße".toUpperCase(), "STRASSE")
+assert("ÀBC".toLowerCase(), "àbc")
+assert("  \t a b \n ".trim(), "a b")
+assert("　﻿a ".trimStart(), "a ")
+assert(" a ".trimEnd(), " a")
+assert("5".padStart(3, "0"), "005")
+assert("abc".padStart(8, "12"), "12121abc")
+assert("abc".padStart(2), "abc")
+assert("abc".padEnd(6), "abc   ")
+assert("abc".padEnd(6, ""), "abc")
+assert("abc".padEnd(7, "xy"), "abcxyxy")
+thrown = false
+try { "abc".padEnd(Infinity) } catch (e) { thrown = true }
+assert(thrown, true)
+thrown = false
+try { "abc".padStart(2 ** 40, "-") } catch (e) { thrown = true }
+assert(thrown, true)
+assert("abc".padStart(2 ** 40, ""), "abc")
+
+// repeat, concat, localeCompare and normalize
+assert("ab".repeat(3), "ababab")
+assert("ab".repeat(0), "")
+thrown = false
+try { "ab".repeat(-1) } catch (e) { thrown = true }
+assert(thrown, true)
+thrown = false
+try { "ab".repeat(2 ** 40) } catch (e) { thrown = true }
+assert(thrown, true)
+assert("".repeat(2 ** 40), "")
+assert("a".concat("b", 1, null), "ab1null")
+assert("a".localeCompare("b"), -1)
+assert("b".localeCompare("a"), 1)
+assert("a".localeCompare("a"), 0)
+assert("abc".normalize(), "abc")
+assert("abc".normalize("NFD"), "abc")
+assert("e\u0301".normalize(), "\u00e9")
+assert("\u00e9".normalize("NFD"), "e\u0301")
+assert("\u00e9".normalize("NFC"), "\u00e9")
+assert("\ufb01".normalize("NFC"), "\ufb01")
+assert("\ufb01".normalize("NFKC"), "fi")
+assert("\u1e9b\u0323".normalize("NFKD"), "s\u0323\u0307")
+let unpaired = "e" + String.fromCharCode(0xd800) + "\u0301"
+assert(unpaired.normalize(), unpaired)
+let smiley = String.fromCharCode(0xd83d, 0xde00)
+assert((smiley + "e\u0301").normalize(), smiley + "\u00e9")
+thrown = false
+try { "abc".normalize("NFX") } catch (e) { thrown = true }
+assert(thrown, true)
+
+// split
+assert("a,b,,c".split(",").length, 4)
+assert("a,b,,c".split(",", 2).join("|"), "a|b")
+assert("abc".split("").join("|"), "a|b|c")
+assert("".split("").length, 0)
+assert("".split(",").length, 1)
+assert("a😀".split("").length, 3)
+assert("a--b--".split("--").join("|"), "a|b|")
+
+// Methods on non-strings
+assert(String.prototype.toUpperCase.call(true), "TRUE")
+thrown = false
+try { String.prototype.trim.call(null) } catch (e) { thrown = true }
+assert(thrown, true)
+
+// Strings are sequences of UTF-16 code units, which may contain lone surrogates and NUL
+let lone = String.fromCharCode(0xD800)
+assert(lone.length, 1)
+assert(lone.charCodeAt(0), 0xD800)
+assert(("a" + lone).charCodeAt(1), 0xD800)
+assert(lone === String.fromCharCode(0xD800), true)
+assert(lone === String.fromCharCode(0xDC00), false)
+assert(lone.toUpperCase().charCodeAt(0), 0xD800)
+assert((lone + "a").toUpperCase().charCodeAt(1), 0x41)
+assert(String(lone).charCodeAt(0), 0xD800)
+let pair = "😀"
+assert(pair[0].charCodeAt(0), 0xD83D)
+assert(pair[1].charCodeAt(0), 0xDE00)
+assert(pair[0] + pair[1], pair)
+assert(pair.slice(0, 1).concat(pair.slice(1)), "😀")
+let lengths = []
+for (let c of "a😀" + lone) lengths.push(c.length)
+assert(lengths.join(), "1,2,1")
+let nul = String.fromCharCode(0)
+assert(nul.length, 1)
+assert(("a" + nul + "b").length, 3)
+assert(("a" + nul + "b").split(nul).join(), "a,b")
+assert("ab"[5], undefined)
+assert("ab"["1"], "b")
+
+// Concatenation shares buffers without changing other strings
+let base = "abc"
+let x = base + "d"
+let y = base + "e"
+assert(x, "abcd")
+assert(y, "abce")
+assert(base, "abc")
+let built = ""
+for (let i = 0; i < 100; i++) built += "ab"
+assert(built.length, 200)
+assert(built.slice(0, 2) + "c", "abc")
+assert(built.slice(198), "ab")
+
+// Lone surrogates are kept in escapes, property keys, join and RegExp sources
+let lead = '\uD800'
+let trail = '\uDC00'
+assert(lead.length, 1)
+assert(lead.charCodeAt(0), 0xD800)
+assert('😀', pair)
+assert('\u{1F600}', pair)
+assert('\x41\u{41}', "AA")
+assert(`\uD800`.charCodeAt(0), 0xD800)
+let keyed = {}
+keyed[lead] = 1
+assert(keyed[trail], undefined)
+keyed[trail] = 2
+assert(keyed[lead], 1)
+assert(keyed['\uD800'], 1)
+assert(Object.keys(keyed)[1].charCodeAt(0), 0xDC00)
+let literalKeys = { '\uD800': 1, '﷐': 2 }
+assert(literalKeys[lead], 1)
+assert(Object.keys(literalKeys)[1], '﷐')
+let { '\uDC00': missing = 3, '\uD800': found } = literalKeys
+assert(missing, 3)
+assert(found, 1)
+for (let k in literalKeys) {
+  assert(k.length, 1)
+}
+assert([lead].join('').charCodeAt(0), 0xD800)
+assert([lead, trail].join(lead).length, 3)
+assert(String([lead]).charCodeAt(0), 0xD800)
+assert(([trail] + '').charCodeAt(0), 0xDC00)
+assert(new RegExp(lead).source.charCodeAt(0), 0xD800)
+assert(String(new RegExp(lead)).charCodeAt(1), 0xD800)
@@ -0,0 +1,48 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+function f(x) {
+  let log = ''
+  switch (x) {
+    case 1:
+      log += 'a'
+    case 2:
+      log += 'b'
+      break
+    default:
+      log += 'd'
+    case 3:
+      log += 'c'
+  }
+  return log
+}
+
+assert(f(1), 'ab')
+assert(f(2), 'b')
+assert(f(3), 'c')
+assert(f(4), 'dc')
+assert(f('1'), 'dc')
+
+let x = 'outer'
+switch (1) {
+  case 1:
+    let x = 'inner'
+    assert(x, 'inner')
+}
+assert(x, 'outer')
+
+let total = 0
+for (let i = 0; i < 5; i++) {
+  switch (i % 2) {
+    case 0:
+      continue
+    case 1:
+      total += i
+      break
+  }
+  total += 100
+}
+assert(total, 204)
+
+let cnt = 0
+switch (cnt++) {}
+assert(cnt, 1)
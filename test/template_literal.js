@@ -0,0 +1,112 @@
+let assert = (x, y) => { if (x !== y) throw "err" }
+
+assert(``, '')
+assert(`abc`, 'abc')
+let a = 1, b = 'b';
+assert(`a${a}b${b}c`, 'a1bbc')
+assert(`${a}${b}`, '1b')
+assert(`${a + 1}`, '2')
+assert(`${{}.x}`, 'undefined')
+assert(`${[1, 2]}`, '1,2')
+assert(`${null} ${true}`, 'null true')
+
+// Nested templates and braces in substitutions
+assert(`x${`y${a}`}z`, 'xy1z')
+assert(`${(() => { return { v: 'v' } })().v}`, 'v')
+assert(`${`${`${a}`}`}`, '1')
+
+// Multi-line strings
+let multi = `line1
+line2`
+assert(multi, 'line1\nline2')
+assert(multi.length, 11)
+
+// Escape sequences
+assert(`\``, '`')
+assert(`\${a}`, '${a}')
+assert(`$`, '$')
+assert(`$a{a}`, '$a{a}')
+assert(`a\tb\n`, 'a\tb\n')
+assert(`\x41あ`, 'Aあ')
+assert(`a\
+b`, 'ab')
+
+// Substitutions are evaluated from left to right.
+let log = ''
+let f = x => { log += x; return x }
+assert(`${f(1)}${f(2)}${f(3)}`, '123')
+assert(log, '123')
+
+// Tagged templates
+let tag = (strings, ...values) => {
+  let s = ''
+  for (let i = 0; i < values.length; i++) s += strings[i] + '[' + values[i] + ']'
+  return s + strings[strings.length - 1]
+}
+assert(tag`a${1}b${2}c`, 'a[1]b[2]c')
+assert(tag`${1}`, '[1]')
+assert(tag``, '')
+
+let raw = strings => strings.raw[0]
+assert(raw`a\nb`, 'a\\nb')
+assert(raw`\u0041`, '\\u0041')
+let cooked = strings => strings[0]
+assert(cooked`a\nb`, 'a\nb')
+
+// The template object is frozen, and the same for each evaluation of the site.
+let objects = []
+let collect = strings => objects.push(strings)
+for (let i = 0; i < 2; i++) collect`x${i}y`
+assert(objects[0], objects[1])
+assert(objects[0].length, 2)
+objects[0][0] = 'changed'
+assert(objects[0][0], 'x')
+let keys = 0
+for (let key in objects[0]) keys++
+assert(keys, 2)
+
+let sameSource = () => collect`x${0}y`
+sameSource()
+assert(objects[2] !== objects[0], true)
+
+// Methods are called with 'this'
+let obj = {
+  prefix: 'p',
+  tag: function(strings, x) {
+    return this.prefix + strings[0] + x
+  },
+}
+assert(obj.tag`s${1}`, 'ps1')
+assert(obj['tag']`s${2}`, 'ps2')
+
+// Tags can be call expressions.
+let makeTag = prefix => strings => prefix + strings[0]
+assert(makeTag('m')`t`, 'mt')
+
+// Both the strings and the raw strings are frozen
+let frozen = objects[0]
+assert(Object.isFrozen(frozen), true)
+assert(Object.isFrozen(frozen.raw), true)
+frozen.extra = 1
+assert(frozen.extra, undefined)
+let pushed = false
+try {
+  frozen.raw.push('z')
+  pushed = true
+} catch (e) {}
+assert(pushed, false)
+assert(frozen.raw.length, 2)
+
+// Invalid escape sequences make the cooked strings undefined in tagged templates
+let both = strings => [strings[0], strings.raw[0], strings[1], strings.raw[1]]
+let invalid = both`\u{}${0}\xZ`
+assert(invalid[0], undefined)
+assert(invalid[1], '\\u{}')
+assert(invalid[2], undefined)
+assert(invalid[3], '\\xZ')
+let partly = both`\u${0}\x41\u{42}`
+assert(partly[0], undefined)
+assert(partly[1], '\\u')
+assert(partly[2], 'AB')
+assert(both`\unicode`[1], '\\unicode')
+assert(both`\u00`[0], undefined)